@@ -0,0 +1,193 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Throughput of the steps of the generation pipeline: the oracle
+//! operations, decoding, drawing random operands and formatting records.
+//!
+//! Run with `cargo bench`, e.g. `cargo bench -- arith/div` for a single
+//! operation.
+
+use std::hint::black_box;
+
+use criterion::{
+    criterion_group, criterion_main, BatchSize, Criterion, Throughput,
+};
+use rand::prelude::*;
+use rug237::{
+    generators::oracle::eval_batch, DecodedFP, Format, RoundingMode,
+    TestItem, FP237,
+};
+
+// operand sets per batch
+const BATCH: usize = 256;
+
+/// Returns `BATCH` sets of `arity` random operands with exponents in
+/// -64..=64.
+fn operands(arity: usize, seed: u64) -> Vec<Vec<FP237>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..BATCH)
+        .map(|_| {
+            (0..arity)
+                .map(|_| {
+                    FP237::random_from_exp_range_with_rng(
+                        &mut rng,
+                        &(-64..=64),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn arith(c: &mut Criterion) {
+    let mut group = c.benchmark_group("arith");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    let ops = [
+        ("add", 2),
+        ("mul", 2),
+        ("div", 2),
+        ("rem", 2),
+        ("sqrt", 1),
+        ("fma", 3),
+        ("exp", 1),
+        ("sin", 1),
+    ];
+    for (op, arity) in ops {
+        // roots of negative values are NaN
+        let items: Vec<Vec<FP237>> = operands(arity, 216)
+            .into_iter()
+            .map(|ops| ops.into_iter().map(|x| x.abs()).collect())
+            .collect();
+        group.bench_function(op, |b| {
+            b.iter(|| {
+                eval_batch(op, black_box(&items), RoundingMode::NearestEven)
+            })
+        });
+    }
+    group.bench_function("mul_scratch", |b| {
+        // results rounded into a caller-provided value
+        let items = operands(2, 216);
+        let mut z = FP237::ZERO();
+        b.iter(|| {
+            for ops in black_box(&items) {
+                z.assign_round(
+                    &ops[0].f * &ops[1].f,
+                    RoundingMode::NearestEven,
+                );
+            }
+        })
+    });
+    group.bench_function("div_all_modes", |b| {
+        let items = operands(2, 216);
+        b.iter(|| {
+            rug237::ROUNDING_MODES
+                .map(|rnd| eval_batch("div", black_box(&items), rnd))
+        })
+    });
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let values: Vec<FP237> = operands(1, 216).into_iter().flatten().collect();
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    group.bench_function("reduced", |b| {
+        b.iter(|| {
+            black_box(&values)
+                .iter()
+                .map(|x| x.decode(true))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("decoded_fp", |b| {
+        b.iter(|| {
+            black_box(&values)
+                .iter()
+                .map(DecodedFP::from)
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("to_bits", |b| {
+        b.iter(|| {
+            black_box(&values)
+                .iter()
+                .map(FP237::to_bits)
+                .collect::<Vec<_>>()
+        })
+    });
+    group.finish();
+}
+
+fn random(c: &mut Criterion) {
+    let mut group = c.benchmark_group("random");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    for (name, exp_range) in [
+        ("normal", -64..=64),
+        ("subnormal", FP237::MIN_EXP_SUBNORMAL..=FP237::EMIN - 1),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || StdRng::seed_from_u64(216),
+                |mut rng| {
+                    (0..BATCH)
+                        .map(|_| {
+                            FP237::random_from_exp_range_with_rng(
+                                &mut rng, &exp_range,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn format(c: &mut Criterion) {
+    let items: Vec<TestItem> = operands(2, 216)
+        .iter()
+        .map(|ops| {
+            TestItem::new(
+                "mul",
+                ops.iter().map(DecodedFP::from).collect(),
+                vec![DecodedFP::from(&(&ops[0] * &ops[1]))],
+            )
+        })
+        .collect();
+    let mut group = c.benchmark_group("format");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    for (name, format) in [
+        ("tsv", Format::Tsv),
+        ("csv", Format::Csv),
+        ("jsonl", Format::Jsonl),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(&items)
+                    .iter()
+                    .map(|item| item.to_record().to_line(format))
+                    .collect::<Vec<_>>()
+            })
+        });
+    }
+    let values: Vec<FP237> = operands(1, 216).into_iter().flatten().collect();
+    group.bench_function("decimal", |b| {
+        b.iter(|| {
+            black_box(&values)
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, arith, decode, random, format);
+criterion_main!(benches);
@@ -0,0 +1,17 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+// Thin wrapper kept for compatibility, use `rug237 constants` instead.
+
+use clap::Parser;
+use rug237::generators::constants::{run, Args};
+
+fn main() {
+    run(Args::parse());
+}
@@ -0,0 +1,301 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Compact binary corpus format.
+//!
+//! A corpus starts with a header
+//!
+//! | bytes | content                                           |
+//! |-------|---------------------------------------------------|
+//! | 8     | magic `RUG237` followed by 0 and the version (1)  |
+//! | 2     | number of operands per record                     |
+//! | 2     | number of results per record                      |
+//! | 2     | length of the name of the operation               |
+//! | n     | name of the operation (UTF-8)                     |
+//! | 4     | length of the info text                           |
+//! | n     | info text (UTF-8), e.g. a metadata header line    |
+//!
+//! followed by fixed-size records holding the operands and the results,
+//! each value given as its 32 byte f256 bit pattern. All integers are
+//! little-endian, the bit pattern included, i.e. the low half comes
+//! first.
+
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use crate::{DecodedFP, TestItem, FP237};
+
+pub const MAGIC: [u8; 8] = *b"RUG237\x00\x01";
+
+/// Size of a single value in a record.
+pub const VALUE_SIZE: usize = 32;
+
+fn invalid_data(msg: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidData, msg.into())
+}
+
+fn write_len<W: Write>(
+    out: &mut W,
+    len: usize,
+    n_bytes: usize,
+) -> Result<()> {
+    if n_bytes < 8 && len >> (8 * n_bytes) != 0 {
+        return Err(Error::new(ErrorKind::InvalidInput, "Length too large"));
+    }
+    out.write_all(&(len as u64).to_le_bytes()[..n_bytes])
+}
+
+fn read_len<R: Read>(inp: &mut R, n_bytes: usize) -> Result<usize> {
+    let mut buf = [0_u8; 8];
+    inp.read_exact(&mut buf[..n_bytes])?;
+    Ok(u64::from_le_bytes(buf) as usize)
+}
+
+fn read_string<R: Read>(inp: &mut R, n_bytes: usize) -> Result<String> {
+    let len = read_len(inp, n_bytes)?;
+    let mut buf = vec![0_u8; len];
+    inp.read_exact(&mut buf)?;
+    String::from_utf8(buf).map_err(|e| invalid_data(e.to_string()))
+}
+
+/// Writes a corpus header.
+pub(crate) fn write_header<W: Write>(
+    out: &mut W,
+    op: &str,
+    n_operands: usize,
+    n_results: usize,
+    info: &str,
+) -> Result<()> {
+    out.write_all(&MAGIC)?;
+    write_len(out, n_operands, 2)?;
+    write_len(out, n_results, 2)?;
+    write_len(out, op.len(), 2)?;
+    out.write_all(op.as_bytes())?;
+    write_len(out, info.len(), 4)?;
+    out.write_all(info.as_bytes())
+}
+
+/// Writes the bit pattern of the value given by `d`.
+pub(crate) fn write_value<W: Write>(
+    out: &mut W,
+    d: &DecodedFP,
+) -> Result<()> {
+    let (hi, lo) = FP237::encode(d.sign, d.exp, d.signif.into()).to_bits();
+    out.write_all(&lo.to_le_bytes())?;
+    out.write_all(&hi.to_le_bytes())
+}
+
+/// Checks that `item` fits into a record with the given number of
+/// operands and results.
+pub(crate) fn check_item(
+    item: &TestItem,
+    n_operands: usize,
+    n_results: usize,
+) -> Result<()> {
+    if !item.meta.fields().is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "The binary format holds operands and results only",
+        ));
+    }
+    if item.operands.len() != n_operands || item.expected.len() != n_results {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "All items of a binary corpus must have the same arity",
+        ));
+    }
+    Ok(())
+}
+
+/// Writes test items in the binary corpus format.
+pub struct CorpusWriter<W: Write> {
+    out: W,
+    n_operands: usize,
+    n_results: usize,
+}
+
+impl<W: Write> CorpusWriter<W> {
+    /// Writes the header of a corpus for operation `op` with the given
+    /// number of operands and results per record.
+    pub fn new(
+        mut out: W,
+        op: &str,
+        n_operands: usize,
+        n_results: usize,
+        info: &str,
+    ) -> Result<Self> {
+        write_header(&mut out, op, n_operands, n_results, info)?;
+        Ok(Self {
+            out,
+            n_operands,
+            n_results,
+        })
+    }
+
+    /// Writes the operands and results of `item`, which must not hold
+    /// additional data.
+    pub fn write_item(&mut self, item: &TestItem) -> Result<()> {
+        check_item(item, self.n_operands, self.n_results)?;
+        for d in item.operands.iter().chain(item.expected.iter()) {
+            write_value(&mut self.out, d)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Reads test items from a corpus in the binary format.
+pub struct CorpusReader<R: Read> {
+    inp: R,
+    op: String,
+    n_operands: usize,
+    n_results: usize,
+    info: String,
+}
+
+impl<R: Read> CorpusReader<R> {
+    /// Reads and checks the header of the corpus.
+    pub fn new(mut inp: R) -> Result<Self> {
+        let mut magic = [0_u8; 8];
+        inp.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("Not a binary corpus of version 1"));
+        }
+        let n_operands = read_len(&mut inp, 2)?;
+        let n_results = read_len(&mut inp, 2)?;
+        let op = read_string(&mut inp, 2)?;
+        let info = read_string(&mut inp, 4)?;
+        Ok(Self {
+            inp,
+            op,
+            n_operands,
+            n_results,
+            info,
+        })
+    }
+
+    pub fn op(&self) -> &str {
+        &self.op
+    }
+
+    pub fn n_operands(&self) -> usize {
+        self.n_operands
+    }
+
+    pub fn n_results(&self) -> usize {
+        self.n_results
+    }
+
+    pub fn info(&self) -> &str {
+        &self.info
+    }
+
+    /// Reads the next record, returns `None` at the end of the corpus.
+    pub fn read_item(&mut self) -> Result<Option<TestItem>> {
+        let n_values = self.n_operands + self.n_results;
+        let mut buf = vec![0_u8; n_values * VALUE_SIZE];
+        // distinguish a clean end from a truncated record
+        let mut n_read = 0;
+        while n_read < buf.len() {
+            match self.inp.read(&mut buf[n_read..]) {
+                Ok(0) => break,
+                Ok(n) => n_read += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        if n_read == 0 {
+            return Ok(None);
+        }
+        if n_read < buf.len() {
+            return Err(invalid_data("Truncated record"));
+        }
+        let values: Vec<DecodedFP> = buf
+            .chunks_exact(VALUE_SIZE)
+            .map(|chunk| {
+                let lo = u128::from_le_bytes(chunk[..16].try_into().unwrap());
+                let hi = u128::from_le_bytes(chunk[16..].try_into().unwrap());
+                DecodedFP::from(&FP237::from_bits((hi, lo)))
+            })
+            .collect();
+        let (operands, expected) = values.split_at(self.n_operands);
+        Ok(Some(TestItem::new(
+            &self.op,
+            operands.to_vec(),
+            expected.to_vec(),
+        )))
+    }
+}
+
+impl<R: Read> Iterator for CorpusReader<R> {
+    type Item = Result<TestItem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_item().transpose()
+    }
+}
+
+#[cfg(test)]
+mod binary_tests {
+    use super::*;
+    use crate::special_values;
+
+    #[test]
+    fn test_round_trip() {
+        let values: Vec<DecodedFP> =
+            special_values().iter().map(DecodedFP::from).collect();
+        let mut wrt =
+            CorpusWriter::new(Vec::<u8>::new(), "neg", 1, 1, "# test")
+                .unwrap();
+        for d in &values {
+            let neg = DecodedFP {
+                sign: 1 - d.sign,
+                ..*d
+            };
+            wrt.write_item(&TestItem::new("neg", vec![*d], vec![neg]))
+                .unwrap();
+        }
+        let buf = wrt.into_inner();
+        assert_eq!(buf.len(), 8 + 6 + 3 + 4 + 6 + values.len() * 64);
+        let rdr = CorpusReader::new(buf.as_slice()).unwrap();
+        assert_eq!(
+            (rdr.op(), rdr.n_operands(), rdr.n_results()),
+            ("neg", 1, 1)
+        );
+        assert_eq!(rdr.info(), "# test");
+        let items: Vec<TestItem> = rdr.map(|item| item.unwrap()).collect();
+        assert_eq!(items.len(), values.len());
+        for (item, d) in items.iter().zip(&values) {
+            assert_eq!(item.operands, [*d]);
+        }
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mut buf = Vec::<u8>::new();
+        write_header(&mut buf, "sqrt", 1, 1, "").unwrap();
+        buf.extend([0_u8; 40]);
+        let mut rdr = CorpusReader::new(buf.as_slice()).unwrap();
+        assert!(rdr.read_item().is_err());
+        assert!(CorpusReader::new(&b"RUG237"[..]).is_err());
+    }
+
+    #[test]
+    fn test_meta_rejected() {
+        let mut wrt =
+            CorpusWriter::new(Vec::<u8>::new(), "powi", 1, 1, "").unwrap();
+        let one = DecodedFP::from(&FP237::ONE());
+        let item = TestItem::new("powi", vec![one], vec![one])
+            .with_meta(crate::Record::new().int(3));
+        assert!(wrt.write_item(&item).is_err());
+    }
+}
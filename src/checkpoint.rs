@@ -0,0 +1,109 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    fs,
+    io::{Error, ErrorKind, Result},
+    path::Path,
+};
+
+/// State of an interrupted run: the number of items and bytes written to
+/// the temporary output file and the seed of the run.
+///
+/// Generators draw their data from the seed in a fixed order, so a run
+/// resumed with the same seed replays the random number stream and skips
+/// the items already written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub items: u64,
+    pub bytes: u64,
+    pub seed: Option<u64>,
+}
+
+impl Checkpoint {
+    /// Reads the checkpoint saved in `path`, returns `None` if there is
+    /// none.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        text.parse().map(Some)
+    }
+
+    /// Saves `self` to `path`, replacing the previous checkpoint only when
+    /// completely written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        let tmp = path.with_file_name(name);
+        fs::write(&tmp, self.to_string())?;
+        fs::rename(tmp, path)
+    }
+}
+
+impl std::fmt::Display for Checkpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "items={}", self.items)?;
+        writeln!(f, "bytes={}", self.bytes)?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "seed={seed}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Checkpoint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |line: &str| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid checkpoint entry \"{line}\""),
+            )
+        };
+        let mut res = Self::default();
+        for line in s.lines().filter(|l| !l.trim().is_empty()) {
+            let (key, val) =
+                line.split_once('=').ok_or_else(|| invalid(line))?;
+            let val: u64 = val.trim().parse().map_err(|_| invalid(line))?;
+            match key.trim() {
+                "items" => res.items = val,
+                "bytes" => res.bytes = val,
+                "seed" => res.seed = Some(val),
+                _ => return Err(invalid(line)),
+            }
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod checkpoint_tests {
+    use std::{env::temp_dir, process};
+
+    use super::*;
+
+    #[test]
+    fn test_save_load() {
+        let path = temp_dir().join(format!("rug237-{}.ckpt", process::id()));
+        assert_eq!(Checkpoint::load(&path).unwrap(), None);
+        let cp = Checkpoint {
+            items: 17,
+            bytes: 1234,
+            seed: Some(42),
+        };
+        cp.save(&path).unwrap();
+        assert_eq!(Checkpoint::load(&path).unwrap(), Some(cp));
+        fs::remove_file(&path).unwrap();
+        assert!("items=x".parse::<Checkpoint>().is_err());
+    }
+}
@@ -0,0 +1,184 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Complex numbers with P-bit real and imaginary parts, as oracle for
+//! Complex<f256>.
+//!
+//! Each operation rounds the real and the imaginary part of its exact
+//! result to nearest with P bits independently. The exponent range of
+//! f256 is not applied to the parts, so results near its limits, i.e.
+//! overflowing or subnormal ones, have to be avoided or rounded again via
+//! `re` and `im`.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use rug::{Assign, Complex};
+
+use crate::{RoundingMode, FP237, P};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct C237 {
+    pub c: Complex,
+}
+
+impl C237 {
+    /// Returns the complex number re + im·i.
+    pub fn new(re: &FP237, im: &FP237) -> Self {
+        Self {
+            c: Complex::with_val(P, (&re.f, &im.f)),
+        }
+    }
+
+    /// Returns the real part, rounded into the range of f256.
+    pub fn re(&self) -> FP237 {
+        FP237::with_val_round(self.c.real(), RoundingMode::NearestEven)
+    }
+
+    /// Returns the imaginary part, rounded into the range of f256.
+    pub fn im(&self) -> FP237 {
+        FP237::with_val_round(self.c.imag(), RoundingMode::NearestEven)
+    }
+
+    /// Returns the decode tuples of the real and the imaginary part, see
+    /// `FP237::decode`.
+    #[allow(clippy::type_complexity)]
+    pub fn decode(
+        &self,
+        reduce: bool,
+    ) -> ((u32, i32, (u128, u128)), (u32, i32, (u128, u128))) {
+        (self.re().decode(reduce), self.im().decode(reduce))
+    }
+
+    /// Returns |self|, rounded once.
+    pub fn abs(&self) -> FP237 {
+        FP237::with_val_round(self.c.abs_ref(), RoundingMode::NearestEven)
+    }
+
+    /// Returns the argument of `self` in [-π, π], rounded once.
+    pub fn arg(&self) -> FP237 {
+        FP237::with_val_round(self.c.arg_ref(), RoundingMode::NearestEven)
+    }
+
+    /// Returns the principal square root of `self`.
+    pub fn sqrt(&self) -> Self {
+        Self {
+            c: Complex::with_val(P, self.c.sqrt_ref()),
+        }
+    }
+
+    /// Returns e^self.
+    pub fn exp(&self) -> Self {
+        Self {
+            c: Complex::with_val(P, self.c.exp_ref()),
+        }
+    }
+
+    /// Returns the principal natural logarithm of `self`.
+    pub fn ln(&self) -> Self {
+        Self {
+            c: Complex::with_val(P, self.c.ln_ref()),
+        }
+    }
+}
+
+impl Neg for C237 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self { c: -self.c }
+    }
+}
+
+impl Add for &C237 {
+    type Output = C237;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut c = Complex::new(P);
+        c.assign(&self.c + &rhs.c);
+        C237 { c }
+    }
+}
+
+impl Sub for &C237 {
+    type Output = C237;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut c = Complex::new(P);
+        c.assign(&self.c - &rhs.c);
+        C237 { c }
+    }
+}
+
+impl Mul for &C237 {
+    type Output = C237;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut c = Complex::new(P);
+        c.assign(&self.c * &rhs.c);
+        C237 { c }
+    }
+}
+
+impl Div for &C237 {
+    type Output = C237;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let mut c = Complex::new(P);
+        c.assign(&self.c / &rhs.c);
+        C237 { c }
+    }
+}
+
+#[cfg(test)]
+mod complex_tests {
+    use rug::{float::Constant, Float};
+
+    use super::*;
+
+    #[test]
+    fn test_arith() {
+        let one = FP237::ONE();
+        let x = C237::new(&one, &one);
+        let y = C237::new(&FP237::from(3), &-FP237::from(2));
+        // (1 + i)(3 - 2i) = 5 + i
+        let z = &x * &y;
+        assert_eq!(
+            (z.re().f, z.im().f),
+            (Float::with_val(P, 5), one.f.clone())
+        );
+        assert_eq!(
+            &(&z / &y) - &x,
+            C237::new(&FP237::ZERO(), &FP237::ZERO())
+        );
+        // 1/3 is rounded once per part
+        let q = &C237::new(&one, &FP237::ZERO())
+            / &C237::new(&FP237::from(3), &FP237::ZERO());
+        assert_eq!(q.re().f, Float::with_val(P, 3).recip());
+        assert_eq!(x.decode(true), ((0, 0, (0, 1)), (0, 0, (0, 1))));
+    }
+
+    #[test]
+    fn test_functions() {
+        let x = C237::new(&FP237::ONE(), &FP237::ONE());
+        assert_eq!(x.abs().f, Float::with_val(P, 2).sqrt());
+        assert_eq!(x.arg().f, Float::with_val(P, Constant::Pi) / 4);
+        // e^(iπ) = -1
+        let z = C237::new(&FP237::ZERO(), &FP237::Pi()).exp();
+        assert_eq!(z.re().f, -1);
+        assert!(z.im().f.clone().abs() < 1e-70);
+        // ln(1 + i) = ln(2) / 2 + iπ/4
+        let w = x.ln();
+        let half_ln2 = Float::with_val(4 * P, Constant::Log2) / 2;
+        assert_eq!(w.re().f, Float::with_val(P, half_ln2));
+        assert_eq!(w.im().f, x.arg().f);
+        let i = C237::new(&FP237::ZERO(), &FP237::ONE());
+        assert_eq!(i.ln().im().f, Float::with_val(P, Constant::Pi) / 2);
+        assert_eq!((-i).sqrt().re().f, Float::with_val(P, 0.5).sqrt());
+    }
+}
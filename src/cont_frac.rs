@@ -0,0 +1,202 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Continued-fraction expansions of exact values, with their convergents
+//! and best rational approximations, for analyzing hard-to-round cases,
+//! e.g. how close multiples of binary values come to multiples of π/2.
+
+use rug::{Float, Integer, Rational};
+
+/// Simple continued fraction a₀ + 1/(a₁ + 1/(a₂ + …)) with the terms
+/// a₀, a₁, a₂, …, all but a₀ positive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContinuedFraction {
+    pub terms: Vec<Integer>,
+    /// `true` if the terms give the value expanded exactly, `false` if the
+    /// expansion has been cut off.
+    pub complete: bool,
+}
+
+impl ContinuedFraction {
+    /// Returns the expansion of `r` into at most `max_terms` terms.
+    pub fn from_rational(r: &Rational, max_terms: usize) -> Self {
+        let (mut p, mut q) = r.clone().into_numer_denom();
+        let mut terms = Vec::new();
+        while q != 0 && terms.len() < max_terms {
+            let (a, rem) = p.div_rem_floor(q.clone());
+            terms.push(a);
+            (p, q) = (q, rem);
+        }
+        Self {
+            terms,
+            complete: q == 0,
+        }
+    }
+
+    /// Returns the expansion of m·2ᵉ into at most `max_terms` terms.
+    pub fn from_scaled(m: &Integer, e: i32, max_terms: usize) -> Self {
+        let r = if e >= 0 {
+            Rational::from(Integer::from(m << e as u32))
+        } else {
+            Rational::from((m.clone(), Integer::from(1) << e.unsigned_abs()))
+        };
+        Self::from_rational(&r, max_terms)
+    }
+
+    /// Returns the expansion of the exact value of `x` into at most
+    /// `max_terms` terms. For `x` approximating a constant, only the terms
+    /// whose convergents differ from `x` by far less than its ulp are
+    /// terms of the constant.
+    ///
+    /// # Panics
+    ///
+    /// If `x` is infinite or NaN.
+    pub fn from_float(x: &Float, max_terms: usize) -> Self {
+        let r = x
+            .to_rational()
+            .unwrap_or_else(|| panic!("{x} has no rational value"));
+        Self::from_rational(&r, max_terms)
+    }
+
+    /// Returns the convergents p₀/q₀, p₁/q₁, … of the terms.
+    pub fn convergents(&self) -> Vec<Rational> {
+        let (mut p0, mut q0) = (Integer::from(1), Integer::new());
+        let (mut p1, mut q1) = (Integer::new(), Integer::from(1));
+        let mut res = Vec::with_capacity(self.terms.len());
+        for a in &self.terms {
+            let p = Integer::from(a * &p0) + &p1;
+            let q = Integer::from(a * &q0) + &q1;
+            res.push(Rational::from((p.clone(), q.clone())));
+            (p1, q1) = (p0, q0);
+            (p0, q0) = (p, q);
+        }
+        res
+    }
+
+    /// Returns the value of the terms, i.e. the last convergent.
+    pub fn value(&self) -> Option<Rational> {
+        self.convergents().pop()
+    }
+
+    /// Returns the rational number closest to the value of the terms among
+    /// those with a denominator not exceeding `max_denom`, which is either
+    /// a convergent or a semiconvergent.
+    ///
+    /// # Panics
+    ///
+    /// If `max_denom` < 1 or there are no terms.
+    pub fn best_approximation(&self, max_denom: &Integer) -> Rational {
+        assert!(*max_denom >= 1, "Invalid denominator bound");
+        let x = self.value().expect("No terms");
+        let convergents = self.convergents();
+        let k = convergents
+            .iter()
+            .rposition(|c| c.denom() <= max_denom)
+            .unwrap();
+        let best = convergents[k].clone();
+        if k + 1 == convergents.len() {
+            return best;
+        }
+        // semiconvergents (p[k-1] + j·p[k]) / (q[k-1] + j·q[k])
+        let (p0, q0) = if k == 0 {
+            (Integer::from(1), Integer::new())
+        } else {
+            let c = &convergents[k - 1];
+            (c.numer().clone(), c.denom().clone())
+        };
+        let (p1, q1) = (best.numer(), best.denom());
+        let j = Integer::from(max_denom - &q0) / q1;
+        if j == 0 {
+            return best;
+        }
+        let semi = Rational::from((
+            Integer::from(&j * p1) + p0,
+            Integer::from(&j * q1) + q0,
+        ));
+        let dist = |r: &Rational| Rational::from(r - &x).abs();
+        if dist(&semi) < dist(&best) {
+            semi
+        } else {
+            best
+        }
+    }
+}
+
+#[cfg(test)]
+mod cont_frac_tests {
+    use std::str::FromStr;
+
+    use rug::float::Constant;
+
+    use super::*;
+    use crate::{FP237, P};
+
+    #[test]
+    fn test_expansion() {
+        let cf =
+            ContinuedFraction::from_rational(&Rational::from((-7, 3)), 9);
+        assert_eq!(cf.terms, [-3, 1, 2]);
+        assert!(cf.complete);
+        assert_eq!(cf.value(), Some(Rational::from((-7, 3))));
+        // 0.375 = 3·2⁻³
+        let cf = ContinuedFraction::from_scaled(&Integer::from(3), -3, 9);
+        assert_eq!(cf.terms, [0, 2, 1, 2]);
+        let cf = ContinuedFraction::from_scaled(&Integer::from(3), 2, 9);
+        assert_eq!(cf.terms, [12]);
+        let x = &FP237::from(355) / &FP237::from(113);
+        let cf = x.to_continued_fraction(5);
+        assert_eq!(cf.terms[..3], [3, 7, 16]);
+        // 355/113 rounded is extremely close to [3; 7, 16]
+        assert!(cf.terms[3].significant_bits() > 200);
+        assert_eq!(cf.value(), x.f.to_rational());
+        assert!(cf.complete);
+        assert!(!x.to_continued_fraction(3).complete);
+    }
+
+    #[test]
+    fn test_convergents() {
+        let pi = Float::with_val(4 * P, Constant::Pi);
+        let cf = ContinuedFraction::from_float(&pi, 12);
+        assert_eq!(cf.terms[..8], [3, 7, 15, 1, 292, 1, 1, 1]);
+        let cs = cf.convergents();
+        assert_eq!(cs[1], Rational::from((22, 7)));
+        assert_eq!(cs[3], Rational::from((355, 113)));
+        let best = |d: u32| cf.best_approximation(&Integer::from(d));
+        assert_eq!(best(1), Rational::from(3));
+        assert_eq!(best(7), Rational::from((22, 7)));
+        // semiconvergent 311/99 is better than 22/7
+        assert_eq!(best(100), Rational::from((311, 99)));
+        assert_eq!(best(113), Rational::from((355, 113)));
+        assert_eq!(best(30000), Rational::from((94053, 29938)));
+    }
+
+    #[test]
+    fn test_best_rational() {
+        let pi = FP237::with_val_round(
+            Float::with_val(P, Constant::Pi),
+            crate::RoundingMode::NearestEven,
+        );
+        let best = |x: &FP237, d: u32| x.best_rational(&Integer::from(d));
+        assert_eq!(best(&pi, 113), Rational::from((355, 113)));
+        assert_eq!(best(&pi, 100), Rational::from((311, 99)));
+        assert_eq!(best(&-pi.clone(), 113), Rational::from((-355, 113)));
+        let x = FP237::from_str("0.375").unwrap();
+        assert_eq!(best(&x, 1000), Rational::from((3, 8)));
+        assert_eq!(best(&x, 3), Rational::from((1, 3)));
+        // all rationals with smaller denominators are farther from π
+        let r = best(&pi, 1000);
+        let exact = pi.f.to_rational().unwrap();
+        let dist = |r: &Rational| Rational::from(r - &exact).abs();
+        for q in 1..=1000_u32 {
+            let (p, _) =
+                Rational::from(&exact * q).round().into_numer_denom();
+            assert!(dist(&Rational::from((p, q))) >= dist(&r));
+        }
+    }
+}
@@ -0,0 +1,432 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{collections::HashSet, io::Write, ops::RangeInclusive, thread};
+
+use rand::{prelude::*, rngs::StdRng};
+use rug::Float;
+
+use crate::{
+    verify, DecodedFP, RecordWriter, RoundingMode, Sign, TestItem, FP237,
+    MIN_EXP_SUBNORMAL, P, PM1,
+};
+
+/// Returns the special values ±0, ±1, ±MIN_GT_ZERO, ±MIN_POSITIVE, ±MAX,
+/// ±∞ and NaN.
+pub fn special_values() -> Vec<FP237> {
+    let pos = [
+        FP237::ZERO(),
+        FP237::ONE(),
+        FP237::MIN_GT_ZERO(),
+        FP237::MIN_POSITIVE(),
+        FP237::MAX(),
+        FP237::INFINITY(),
+    ];
+    let mut res = pos.to_vec();
+    res.extend(pos.into_iter().map(|f| -f));
+    res.push(FP237::NAN());
+    res
+}
+
+/// Returns the smallest and the largest value (both signs) of the lowest
+/// and the highest binade in `exp_range`, i.e. 2ᵗ and 2ᵗ⁺¹ - ulp for
+/// t = start and t = end.
+pub fn boundary_values(exp_range: &RangeInclusive<i32>) -> Vec<FP237> {
+    let mut res = Vec::<FP237>::new();
+    let mut exps = vec![*exp_range.start()];
+    if exp_range.end() != exp_range.start() {
+        exps.push(*exp_range.end());
+    }
+    for t in exps {
+        let t = t.max(MIN_EXP_SUBNORMAL);
+        let ulp_exp = (t - PM1).max(MIN_EXP_SUBNORMAL);
+        let lower = Float::with_val(P, Float::i_exp(1, t));
+        let upper = Float::with_val(P, Float::i_exp(1, t + 1))
+            - Float::with_val(P, Float::i_exp(1, ulp_exp));
+        for f in [lower, upper] {
+            res.push(FP237::new(f.clone()));
+            res.push(FP237::new(-f));
+        }
+    }
+    res
+}
+
+/// Creates an operand set from a random number generator.
+type Gen<'a> = Box<dyn Fn(&mut StdRng) -> Vec<FP237> + Sync + 'a>;
+
+/// Operand sets of a corpus, in the order they were added.
+enum Part<'a> {
+    Items(Vec<Vec<FP237>>),
+    /// `n` operand sets created by `gen`, with operands of sign `sign`
+    Random {
+        n: u32,
+        sign: Sign,
+        gen: Gen<'a>,
+    },
+}
+
+/// Assembles the operand sets of a test corpus from random items, boundary
+/// values and special values. Duplicate operand sets (compared by bit
+/// pattern) are removed and the remaining ones are shuffled, all driven by
+/// a single seed, so that a given seed always gives the same corpus.
+pub struct CorpusBuilder<'a> {
+    arity: usize,
+    rng: StdRng,
+    parts: Vec<Part<'a>>,
+    jobs: usize,
+    verify: Option<Vec<RoundingMode>>,
+    sign: Sign,
+}
+
+/// Number of operand sets generated or evaluated by a thread in one go.
+const CHUNK_SIZE: usize = 256;
+
+/// Runs `f` on each of `chunks`, on up to `jobs` threads at a time, and
+/// returns the results in the order of `chunks`.
+fn run_chunks<C, R, F>(chunks: &[C], jobs: usize, f: F) -> Vec<R>
+where
+    C: Sync,
+    R: Send,
+    F: Fn(&C) -> R + Sync,
+{
+    if jobs == 1 {
+        return chunks.iter().map(&f).collect();
+    }
+    let f = &f;
+    chunks
+        .chunks(jobs)
+        .flat_map(|batch| {
+            thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .map(|chunk| scope.spawn(move || f(chunk)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().unwrap())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect()
+}
+
+impl<'a> CorpusBuilder<'a> {
+    pub fn new(arity: usize, seed: u64) -> Self {
+        Self {
+            arity,
+            rng: StdRng::seed_from_u64(seed),
+            parts: Vec::new(),
+            jobs: 1,
+            verify: None,
+            sign: Sign::Both,
+        }
+    }
+
+    /// Sets the number of threads used to create the operand sets added by
+    /// `random` and to evaluate the operand sets in `write`. The random
+    /// operand sets are created in chunks, each from a generator seeded
+    /// with its own seed drawn from the single seed, and the chunks as well
+    /// as the results are concatenated in order, so the corpus doesn't
+    /// depend on the number of threads.
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    /// Makes `write` check each result against the result recomputed at
+    /// higher precision, aborting on a mismatch. The results are expected
+    /// to be rounded by the modes in `rnd`, one result per mode for each
+    /// value of the operation.
+    pub fn verify(mut self, rnd: &[RoundingMode]) -> Self {
+        self.verify = Some(rnd.to_vec());
+        self
+    }
+
+    /// Gives the operands of the operand sets added by `random` afterwards
+    /// the sign selected by `sign`.
+    pub fn sign(mut self, sign: Sign) -> Self {
+        self.sign = sign;
+        self
+    }
+
+    /// Adds a part of `n` operand sets to be created by `gen`, see `sign`.
+    /// The part is lazy: its operand sets are generated only when `build`
+    /// or `write` runs, in chunks each seeded with its own seed, see
+    /// `jobs`.
+    pub fn random<F>(mut self, n: u32, gen: F) -> Self
+    where
+        F: Fn(&mut StdRng) -> Vec<FP237> + Sync + 'a,
+    {
+        self.parts.push(Part::Random {
+            n,
+            sign: self.sign,
+            gen: Box::new(gen),
+        });
+        self
+    }
+
+    /// Returns `n` operand sets created by `gen`, chunk by chunk, see
+    /// `jobs`.
+    fn create(&mut self, n: u32, sign: Sign, gen: &Gen) -> Vec<Vec<FP237>> {
+        let n = n as usize;
+        let chunks: Vec<(u64, usize)> = (0..n)
+            .step_by(CHUNK_SIZE)
+            .map(|start| (self.rng.gen(), CHUNK_SIZE.min(n - start)))
+            .collect();
+        let arity = self.arity;
+        run_chunks(&chunks, self.jobs, |&(seed, len)| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..len)
+                .map(|_| {
+                    let item: Vec<FP237> = gen(&mut rng)
+                        .into_iter()
+                        .map(|f| sign.apply(f))
+                        .collect();
+                    assert_eq!(item.len(), arity);
+                    item
+                })
+                .collect::<Vec<_>>()
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Adds the operand sets `items` as they are.
+    pub fn items(mut self, items: Vec<Vec<FP237>>) -> Self {
+        assert!(items.iter().all(|item| item.len() == self.arity));
+        self.parts.push(Part::Items(items));
+        self
+    }
+
+    /// Adds all combinations of the boundary values of `exp_range`.
+    pub fn boundaries(self, exp_range: &RangeInclusive<i32>) -> Self {
+        let values = boundary_values(exp_range);
+        self.combinations(&values)
+    }
+
+    /// Adds all combinations of the special values.
+    pub fn specials(self) -> Self {
+        let values = special_values();
+        self.combinations(&values)
+    }
+
+    fn combinations(self, values: &[FP237]) -> Self {
+        let mut combs: Vec<Vec<FP237>> = vec![vec![]];
+        for _i in 0..self.arity {
+            combs = combs
+                .iter()
+                .flat_map(|c| {
+                    values.iter().map(move |v| {
+                        let mut c = c.clone();
+                        c.push(v.clone());
+                        c
+                    })
+                })
+                .collect();
+        }
+        self.items(combs)
+    }
+
+    /// Returns the deduplicated and shuffled operand sets.
+    pub fn build(mut self) -> Vec<Vec<FP237>> {
+        let mut seen = HashSet::<Vec<(u128, u128)>>::new();
+        let mut items = Vec::new();
+        for part in std::mem::take(&mut self.parts) {
+            let part_items = match part {
+                Part::Items(items) => items,
+                Part::Random { n, sign, gen } => self.create(n, sign, &gen),
+            };
+            items.extend(part_items.into_iter().filter(|item| {
+                seen.insert(item.iter().map(FP237::to_bits).collect())
+            }));
+        }
+        items.shuffle(&mut self.rng);
+        items
+    }
+
+    /// Writes one test item for operation `op` per operand set, holding
+    /// the operands and the results returned from `eval`. Operand sets for
+    /// which `eval` returns `None` are skipped.
+    ///
+    /// # Panics
+    ///
+    /// If verification is requested and a result doesn't match the result
+    /// recomputed at higher precision, or `op` can't be recomputed.
+    pub fn write<W, F>(
+        mut self,
+        out: &mut RecordWriter<W>,
+        op: &str,
+        eval: F,
+    ) -> std::io::Result<()>
+    where
+        W: Write,
+        F: Fn(&[FP237]) -> Option<Vec<FP237>> + Sync,
+    {
+        let jobs = self.jobs;
+        let verify = self.verify.take();
+        if verify.is_some() {
+            assert!(
+                verify::is_supported(op),
+                "Results of {op} can't be verified"
+            );
+        }
+        let items = self.build();
+        let eval_chunk = |chunk: &[Vec<FP237>]| -> Vec<Option<TestItem>> {
+            chunk
+                .iter()
+                .map(|item| {
+                    eval(item).map(|res| {
+                        if let Some(rnd) = &verify {
+                            if let Err(msg) =
+                                verify::check(op, item, &res, rnd)
+                            {
+                                panic!("{msg}");
+                            }
+                        }
+                        TestItem::new(
+                            op,
+                            item.iter().map(DecodedFP::from).collect(),
+                            res.iter().map(DecodedFP::from).collect(),
+                        )
+                    })
+                })
+                .collect()
+        };
+        // written batch by batch, so that progress is reported early
+        let chunks: Vec<&[Vec<FP237>]> = items.chunks(CHUNK_SIZE).collect();
+        for batch in chunks.chunks(jobs) {
+            let results = run_chunks(batch, jobs, |chunk| eval_chunk(chunk));
+            for test_item in results.iter().flatten().flatten() {
+                out.write_item(test_item)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod corpus_builder_tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_roundtrip() {
+        for f in special_values()
+            .iter()
+            .chain(boundary_values(&(MIN_EXP_SUBNORMAL..=-3)).iter())
+        {
+            let g = FP237::from_bits(f.to_bits());
+            if f.f.is_nan() {
+                assert!(g.f.is_nan());
+            } else {
+                assert_eq!(g.f, f.f);
+                assert_eq!(g.f.is_sign_negative(), f.f.is_sign_negative());
+            }
+        }
+    }
+
+    #[test]
+    fn test_bits_of_extremes() {
+        assert_eq!(FP237::ONE().to_bits(), (0x3ffff_u128 << 108, 0));
+        assert_eq!(FP237::MIN_GT_ZERO().to_bits(), (0, 1));
+        assert_eq!(FP237::MIN_POSITIVE().to_bits(), (1_u128 << 108, 0));
+        assert_eq!(
+            FP237::MAX().to_bits(),
+            ((1_u128 << 127) - (1_u128 << 108) - 1, u128::MAX)
+        );
+    }
+
+    #[test]
+    fn test_dedup_and_determinism() {
+        let gen = |rng: &mut StdRng| {
+            vec![FP237::random_from_exp_range_with_rng(rng, &(-5..=5))]
+        };
+        let a = CorpusBuilder::new(1, 7)
+            .random(20, gen)
+            .specials()
+            .specials()
+            .build();
+        let b = CorpusBuilder::new(1, 7)
+            .random(20, gen)
+            .specials()
+            .specials()
+            .build();
+        assert_eq!(a.len(), 20 + special_values().len());
+        assert_eq!(
+            a.iter().map(|i| i[0].to_bits()).collect::<Vec<_>>(),
+            b.iter().map(|i| i[0].to_bits()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_sign() {
+        let gen = |rng: &mut StdRng| {
+            vec![FP237::random_from_exp_range_with_rng(rng, &(-5..=5))]
+        };
+        let signs = |sign: Sign| {
+            CorpusBuilder::new(1, 3)
+                .sign(sign)
+                .random(50, gen)
+                .build()
+                .iter()
+                .map(|i| i[0].f.is_sign_negative())
+                .collect::<Vec<_>>()
+        };
+        assert!(signs(Sign::Pos).iter().all(|neg| !neg));
+        assert!(signs(Sign::Neg).iter().all(|neg| *neg));
+        let both = signs(Sign::Both);
+        assert!(both.contains(&true) && both.contains(&false));
+    }
+
+    #[test]
+    fn test_jobs() {
+        let written = |jobs: usize| {
+            let mut out =
+                RecordWriter::new(Vec::<u8>::new(), crate::Format::Tsv);
+            CorpusBuilder::new(2, 11)
+                .random(1000, |rng| {
+                    (0..2)
+                        .map(|_| {
+                            FP237::random_from_exp_range_with_rng(
+                                rng,
+                                &(-20..=20),
+                            )
+                        })
+                        .collect()
+                })
+                .jobs(jobs)
+                .write(&mut out, "div", |ops| Some(vec![&ops[0] / &ops[1]]))
+                .unwrap();
+            out.into_inner().unwrap()
+        };
+        assert_eq!(written(1), written(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Result 0 of sqrt")]
+    fn test_verify() {
+        let write = |eval: fn(&FP237) -> FP237| {
+            let mut out =
+                RecordWriter::new(Vec::<u8>::new(), crate::Format::Tsv);
+            CorpusBuilder::new(1, 5)
+                .random(100, |rng| {
+                    vec![FP237::random_from_exp_range_with_rng(
+                        rng,
+                        &(-20..=20),
+                    )
+                    .abs()]
+                })
+                .verify(&[RoundingMode::NearestEven])
+                .write(&mut out, "sqrt", |ops| Some(vec![eval(&ops[0])]))
+                .unwrap();
+        };
+        write(|x| x.sqrt_round(RoundingMode::NearestEven));
+        write(|x| x.sqrt_round(RoundingMode::TowardZero));
+    }
+}
@@ -0,0 +1,251 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Exact decimal values, as ground truth for parsing and formatting: each
+//! finite f256 value and each decimal literal is held without rounding,
+//! and decimal strings are derived by integer arithmetic only, without
+//! relying on rug's decimal printer.
+
+use std::{cmp::Ordering, str::FromStr};
+
+use rug::{ops::Pow, Integer, Rational};
+
+use crate::{RoundingMode, FP237};
+
+/// Exact value r·10^exp. Zero is unsigned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecimalExact {
+    pub r: Rational,
+    pub exp: i32,
+}
+
+/// Returns 10^k.
+fn pow10(k: i32) -> Rational {
+    let p = Integer::from(10).pow(k.unsigned_abs());
+    if k >= 0 {
+        Rational::from(p)
+    } else {
+        Rational::from((1, p))
+    }
+}
+
+/// Returns ⌊log₁₀(v)⌋ for v > 0.
+fn log10_floor(v: &Rational) -> i32 {
+    let bits = v.numer().significant_bits() as i64
+        - v.denom().significant_bits() as i64;
+    // a first guess, off by at most one
+    let mut k = (bits as f64 * std::f64::consts::LOG10_2).floor() as i32;
+    while pow10(k) > *v {
+        k -= 1;
+    }
+    while pow10(k + 1) <= *v {
+        k += 1;
+    }
+    k
+}
+
+/// Returns `v` rounded to an integer, ties to even.
+fn round_half_even(v: &Rational) -> Integer {
+    let (mut q, r) = v.numer().clone().div_rem_floor(v.denom().clone());
+    match (Integer::from(&r << 1)).cmp(v.denom()) {
+        Ordering::Greater => q += 1,
+        Ordering::Equal if q.is_odd() => q += 1,
+        _ => {}
+    }
+    q
+}
+
+/// Returns the digits of `q` in scientific notation with exponent `k`,
+/// the first digit followed by the others, if any, after a point.
+fn sci_string(neg: bool, q: &Integer, k: i32) -> String {
+    let sign = if neg { "-" } else { "" };
+    let digits = q.to_string();
+    let (first, rest) = digits.split_at(1);
+    if rest.is_empty() {
+        format!("{sign}{first}e{k}")
+    } else {
+        format!("{sign}{first}.{rest}e{k}")
+    }
+}
+
+impl DecimalExact {
+    pub fn new(r: Rational, exp: i32) -> Self {
+        Self { r, exp }
+    }
+
+    /// Returns the exact value of `x`.
+    ///
+    /// # Panics
+    ///
+    /// If `x` is infinite or NaN.
+    pub fn from_fp237(x: &FP237) -> Self {
+        let (m, e) =
+            x.f.to_integer_exp()
+                .unwrap_or_else(|| panic!("{x} has no decimal value"));
+        if e >= 0 {
+            Self::new(Rational::from(m << e as u32), 0)
+        } else {
+            // m·2ᵉ = m·5⁻ᵉ·10ᵉ
+            let f = Integer::from(5).pow(e.unsigned_abs());
+            Self::new(Rational::from(m * f), e)
+        }
+    }
+
+    /// Returns the value r·10^exp.
+    pub fn value(&self) -> Rational {
+        &self.r * pow10(self.exp)
+    }
+
+    /// Returns `self` rounded once to f256 according to `rnd`.
+    pub fn to_fp237(&self, rnd: RoundingMode) -> FP237 {
+        FP237::with_val_round(&self.value(), rnd)
+    }
+
+    /// Returns `self` in scientific notation with `n` fractional digits,
+    /// rounded half to even, like "-1.250e-7".
+    pub fn to_sci_string(&self, n: usize) -> String {
+        let v = self.r.clone().abs();
+        if v == 0 {
+            return if n == 0 {
+                "0e0".to_string()
+            } else {
+                format!("0.{}e0", "0".repeat(n))
+            };
+        }
+        let mut k = log10_floor(&v);
+        let mut q = round_half_even(&(&v * pow10(n as i32 - k)));
+        // rounding up may carry into another digit
+        if q == Integer::from(10).pow(n as u32 + 1) {
+            q /= 10;
+            k += 1;
+        }
+        sci_string(self.r < 0, &q, k + self.exp)
+    }
+
+    /// Returns all digits of `self` in scientific notation, without
+    /// trailing zeros, like "-1.25e-7", or `None` if the value has no
+    /// finite decimal expansion.
+    pub fn to_exact_string(&self) -> Option<String> {
+        let v = self.value().abs();
+        if v == 0 {
+            return Some("0e0".to_string());
+        }
+        let mut d = v.denom().clone();
+        let twos = d.find_one(0).unwrap();
+        d >>= twos;
+        let fives = d.remove_factor_mut(&Integer::from(5));
+        if d != 1 {
+            return None;
+        }
+        let s = twos.max(fives) as i32;
+        let (q, _) = (&v * pow10(s)).into_numer_denom();
+        let digits = q.to_string();
+        let trimmed = digits.trim_end_matches('0');
+        let k = digits.len() as i32 - 1 - s;
+        let q = Integer::from_str(trimmed).unwrap();
+        Some(sci_string(self.r < 0, &q, k))
+    }
+}
+
+impl From<&FP237> for DecimalExact {
+    fn from(x: &FP237) -> Self {
+        Self::from_fp237(x)
+    }
+}
+
+impl FromStr for DecimalExact {
+    type Err = String;
+
+    /// Parses a decimal literal like `-12.5e-3` exactly.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let t = s.trim();
+        let (neg, u) = match t.strip_prefix('-') {
+            Some(u) => (true, u),
+            None => (false, t.strip_prefix('+').unwrap_or(t)),
+        };
+        let (m, e) = u.split_once(['e', 'E']).unwrap_or((u, "0"));
+        let (int_digits, fract_digits) = m.split_once('.').unwrap_or((m, ""));
+        let digits = format!("{int_digits}{fract_digits}");
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("Invalid decimal literal {s}"));
+        }
+        let exp = e
+            .parse::<i32>()
+            .map_err(|e| format!("Invalid exponent in {s}: {e}"))?;
+        let mut r = Rational::from(Integer::from_str(&digits).unwrap());
+        if neg {
+            r = -r;
+        }
+        Ok(Self::new(r, exp - fract_digits.len() as i32))
+    }
+}
+
+#[cfg(test)]
+mod decimal_tests {
+    use rand::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let d = DecimalExact::from_str("-12.50e-3").unwrap();
+        assert_eq!((d.r.clone(), d.exp), (Rational::from(-1250), -5));
+        assert_eq!(d.value(), Rational::from((-1, 80)));
+        assert_eq!(d.to_exact_string().unwrap(), "-1.25e-2");
+        assert_eq!(d.to_sci_string(0), "-1e-2");
+        assert_eq!(d.to_sci_string(4), "-1.2500e-2");
+        let ne = RoundingMode::NearestEven;
+        for lit in ["0.1", "1e-78984", "7.3e78913", "+5e-324"] {
+            let d = DecimalExact::from_str(lit).unwrap();
+            assert_eq!(
+                d.to_fp237(ne).to_bits(),
+                FP237::from_str(lit).unwrap().to_bits(),
+                "{lit}"
+            );
+        }
+        assert!(DecimalExact::from_str("1.e").is_err());
+        assert!(DecimalExact::from_str("0x1p3").is_err());
+        assert!(DecimalExact::from_str(".").is_err());
+    }
+
+    #[test]
+    fn test_from_fp237() {
+        let x = DecimalExact::from(&FP237::from_str("0x1p-3").unwrap());
+        assert_eq!(x.to_exact_string().unwrap(), "1.25e-1");
+        let third = DecimalExact::new(Rational::from((1, 3)), 0);
+        assert!(third.to_exact_string().is_none());
+        assert_eq!(third.to_sci_string(2), "3.33e-1");
+        let mut rng = StdRng::seed_from_u64(203);
+        for _ in 0..100 {
+            let f = FP237::random_from_exp_range_with_rng(
+                &mut rng,
+                &(-900..=900),
+            );
+            let d = DecimalExact::from_fp237(&f);
+            assert_eq!(d.to_fp237(RoundingMode::Up).to_bits(), f.to_bits());
+            let s = d.to_exact_string().unwrap();
+            let e = DecimalExact::from_str(&s).unwrap();
+            assert_eq!(e.value(), d.value());
+        }
+    }
+
+    #[test]
+    fn test_sci_rounding() {
+        let d = DecimalExact::from_str("9.995").unwrap();
+        assert_eq!(d.to_sci_string(2), "1.00e1");
+        assert_eq!(d.to_sci_string(3), "9.995e0");
+        // ties to even
+        let d = DecimalExact::from_str("2.5e7").unwrap();
+        assert_eq!(d.to_sci_string(0), "2e7");
+        assert_eq!(
+            DecimalExact::from_str("0").unwrap().to_sci_string(2),
+            "0.00e0"
+        );
+    }
+}
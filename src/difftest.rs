@@ -0,0 +1,327 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Differential testing of an implementation of f256 operations against
+//! the results recomputed here at 2P bits.
+//!
+//! The implementation under test is given as a closure mapping the
+//! operands to its results, e.g. by calling into f256 or by parsing the
+//! output of a program. It is run over a corpus or a stream of random
+//! operands, collecting the errors in ulps: their maximum and mean, the
+//! failures, i.e. results not rounded correctly, with some examples, and
+//! statistics per exponent of the first operand.
+
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    ops::RangeInclusive,
+};
+
+use rand::prelude::*;
+
+use crate::{
+    ulp_err,
+    verify::{is_supported, reference, VERIFY_PREC},
+    DecodedFP, RoundingMode, TestItem, FP237,
+};
+
+/// A result not rounded correctly.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Failure {
+    pub operands: Vec<DecodedFP>,
+    pub result: DecodedFP,
+    pub expected: DecodedFP,
+    /// Error of the result in ulps, ∞ for non-finite results differing
+    /// from the expected ones
+    pub ulps: f64,
+}
+
+/// Error statistics of a set of results.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ErrStats {
+    pub n_results: u64,
+    pub n_failures: u64,
+    pub max_ulps: f64,
+    pub sum_ulps: f64,
+}
+
+impl ErrStats {
+    fn add(&mut self, ulps: f64, failed: bool) {
+        self.n_results += 1;
+        self.n_failures += failed as u64;
+        self.max_ulps = self.max_ulps.max(ulps);
+        self.sum_ulps += ulps;
+    }
+
+    /// Returns the mean error in ulps.
+    pub fn mean_ulps(&self) -> f64 {
+        if self.n_results == 0 {
+            0.0
+        } else {
+            self.sum_ulps / self.n_results as f64
+        }
+    }
+}
+
+/// Result of a differential test.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiffReport {
+    pub op: String,
+    pub total: ErrStats,
+    /// Statistics per bucket of exponents of the first operand, keyed by
+    /// the smallest exponent of the bucket, `None` for zeros, infinities
+    /// and NaN
+    pub buckets: BTreeMap<Option<i32>, ErrStats>,
+    pub examples: Vec<Failure>,
+}
+
+impl DiffReport {
+    /// Writes a summary, followed by the statistics per exponent bucket
+    /// and the failure examples, all lines starting with `#` except the
+    /// examples.
+    pub fn print<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let t = &self.total;
+        writeln!(
+            w,
+            "# {}: {} results, {} failures, max {:.3} ulp, mean {:.3} ulp",
+            self.op,
+            t.n_results,
+            t.n_failures,
+            t.max_ulps,
+            t.mean_ulps()
+        )?;
+        writeln!(w, "# exp\tresults\tfailures\tmax ulp\tmean ulp")?;
+        for (key, s) in &self.buckets {
+            let exp = key.map_or("-".to_string(), |e| e.to_string());
+            writeln!(
+                w,
+                "# {exp}\t{}\t{}\t{:.3}\t{:.3}",
+                s.n_results,
+                s.n_failures,
+                s.max_ulps,
+                s.mean_ulps()
+            )?;
+        }
+        for f in &self.examples {
+            writeln!(
+                w,
+                "{}{:?} = {:?}, expected {:?}: {:.3} ulp",
+                self.op, f.operands, f.result, f.expected, f.ulps
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Differential test of operation `op`, rounded according to a single
+/// rounding mode.
+#[derive(Clone, Debug)]
+pub struct DiffTest {
+    op: String,
+    rnd: RoundingMode,
+    max_examples: usize,
+    bucket_width: i32,
+    exp_range: RangeInclusive<i32>,
+}
+
+impl DiffTest {
+    /// Returns a test of `op`, rounded to nearest, ties to even.
+    ///
+    /// # Panics
+    ///
+    /// If the results of `op` can't be recomputed.
+    pub fn new(op: &str) -> Self {
+        assert!(is_supported(op), "Results of {op} can't be recomputed");
+        Self {
+            op: op.to_string(),
+            rnd: RoundingMode::NearestEven,
+            max_examples: 10,
+            bucket_width: 64,
+            exp_range: -64..=64,
+        }
+    }
+
+    /// Sets the rounding mode the results are expected in.
+    pub fn rounding(mut self, rnd: RoundingMode) -> Self {
+        self.rnd = rnd;
+        self
+    }
+
+    /// Sets the maximal number of failures kept as examples.
+    pub fn max_examples(mut self, n: usize) -> Self {
+        self.max_examples = n;
+        self
+    }
+
+    /// Sets the number of exponents per bucket.
+    ///
+    /// # Panics
+    ///
+    /// If `width` < 1.
+    pub fn bucket_width(mut self, width: i32) -> Self {
+        assert!(width >= 1, "Invalid bucket width");
+        self.bucket_width = width;
+        self
+    }
+
+    /// Sets the exponent range of random operands.
+    pub fn exp_range(mut self, exp_range: RangeInclusive<i32>) -> Self {
+        self.exp_range = exp_range;
+        self
+    }
+
+    /// Runs `eval` over the operand sets `items`, comparing its results
+    /// to the results recomputed here.
+    ///
+    /// # Panics
+    ///
+    /// If `eval` returns an unexpected number of results.
+    pub fn run<I, F>(&self, items: I, mut eval: F) -> DiffReport
+    where
+        I: IntoIterator<Item = Vec<FP237>>,
+        F: FnMut(&[FP237]) -> Vec<FP237>,
+    {
+        let mut report = DiffReport {
+            op: self.op.clone(),
+            ..Default::default()
+        };
+        for ops in items {
+            let results = eval(&ops);
+            let values = reference(&self.op, &ops, VERIFY_PREC);
+            assert_eq!(
+                results.len(),
+                values.len(),
+                "Unexpected number of results of {}",
+                self.op
+            );
+            let key = ops[0]
+                .f
+                .get_exp()
+                .map(|e| e.div_euclid(self.bucket_width) * self.bucket_width);
+            for (res, v) in results.iter().zip(&values) {
+                let expected = v.to_fp237(self.rnd);
+                // compare bit patterns like `verify::check`
+                let failed = res.to_bits() != expected.to_bits();
+                let other = if failed { f64::INFINITY } else { 0.0 };
+                let ulps = ulp_err(res, &v.f).map_or(other, |e| e.to_f64());
+                report.total.add(ulps, failed);
+                report.buckets.entry(key).or_default().add(ulps, failed);
+                if failed && report.examples.len() < self.max_examples {
+                    report.examples.push(Failure {
+                        operands: ops.iter().map(DecodedFP::from).collect(),
+                        result: DecodedFP::from(res),
+                        expected: DecodedFP::from(&expected),
+                        ulps,
+                    });
+                }
+            }
+        }
+        report
+    }
+
+    /// Runs `eval` over the operands of the items of `items` for the
+    /// operation under test, skipping all others.
+    pub fn run_test_items<I, F>(&self, items: I, eval: F) -> DiffReport
+    where
+        I: IntoIterator<Item = TestItem>,
+        F: FnMut(&[FP237]) -> Vec<FP237>,
+    {
+        let ops =
+            items
+                .into_iter()
+                .filter(|item| item.op == self.op)
+                .map(|item| {
+                    item.operands
+                        .iter()
+                        .map(|d| {
+                            FP237::encode(d.sign, d.exp, d.signif.into())
+                        })
+                        .collect()
+                });
+        self.run(ops, eval)
+    }
+
+    /// Runs `eval` over `n` random sets of `arity` operands with exponents
+    /// in the range set by `exp_range`, drawn from a generator seeded with
+    /// `seed`.
+    pub fn run_random<F>(
+        &self,
+        n: usize,
+        arity: usize,
+        seed: u64,
+        eval: F,
+    ) -> DiffReport
+    where
+        F: FnMut(&[FP237]) -> Vec<FP237>,
+    {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let exp_range = self.exp_range.clone();
+        let ops = (0..n).map(move |_| {
+            (0..arity)
+                .map(|_| {
+                    FP237::random_from_exp_range_with_rng(
+                        &mut rng, &exp_range,
+                    )
+                })
+                .collect()
+        });
+        self.run(ops, eval)
+    }
+}
+
+#[cfg(test)]
+mod difftest_tests {
+    use super::*;
+
+    #[test]
+    fn test_correct() {
+        let test = DiffTest::new("mul");
+        let report =
+            test.run_random(50, 2, 213, |ops| vec![&ops[0] * &ops[1]]);
+        assert_eq!(report.total.n_results, 50);
+        assert_eq!(report.total.n_failures, 0);
+        assert!(report.total.max_ulps <= 0.5);
+        assert!(report.examples.is_empty());
+        let n: u64 = report.buckets.values().map(|s| s.n_results).sum();
+        assert_eq!(n, 50);
+    }
+
+    #[test]
+    fn test_failures() {
+        let test = DiffTest::new("sqrt").max_examples(3).bucket_width(1);
+        let items = [1, 2, 3, 4, 5].map(|i| vec![FP237::from(i)]);
+        let report = test.run(items, |ops| vec![ops[0].sqrt().next_up()]);
+        let t = &report.total;
+        assert_eq!((t.n_results, t.n_failures), (5, 5));
+        assert!(t.max_ulps >= 1.0 && t.max_ulps < 1.5);
+        assert!(t.mean_ulps() > 0.5);
+        assert_eq!(report.examples.len(), 3);
+        // exponents of 1, 2 and 3, 4 and 5
+        let keys: Vec<_> = report.buckets.keys().copied().collect();
+        assert_eq!(keys, [Some(1), Some(2), Some(3)]);
+        let mut out = Vec::new();
+        report.print(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("# sqrt: 5 results, 5 failures"));
+        assert_eq!(text.lines().count(), 2 + 3 + 3);
+    }
+
+    #[test]
+    fn test_test_items() {
+        let one = DecodedFP::from(&FP237::ONE());
+        let items = vec![
+            TestItem::new("add", vec![one, one], vec![]),
+            TestItem::new("sub", vec![one, one], vec![]),
+        ];
+        let report = DiffTest::new("add")
+            .run_test_items(items, |ops| vec![&ops[0] + &ops[1]]);
+        assert_eq!(report.total.n_results, 1);
+        assert_eq!(report.total.max_ulps, 0.0);
+    }
+}
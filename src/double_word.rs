@@ -0,0 +1,209 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Double-word arithmetic on pairs of f256 values, as a reference for the
+//! intermediate results of f256's internal double-word computations.
+//!
+//! The algorithms are those of Joldes, Muller and Popescu, "Tight and
+//! rigorous error bounds for basic building blocks of double-word
+//! arithmetic" (2017), with every operation rounded to nearest in f256:
+//! addition by AccurateDWPlusDW, multiplication by DWTimesDW3 and division
+//! by DWDivDW2 (with the relative error bounded by 3u², 4u² and 15u² +
+//! 56u³ respectively, u = 2⁻ᴾ), barring underflow and overflow.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use rug::Float;
+
+use crate::{RoundingMode, FP237, P};
+
+const RN: RoundingMode = RoundingMode::NearestEven;
+
+/// Unevaluated sum hi + lo of two f256 values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DW237 {
+    pub hi: FP237,
+    pub lo: FP237,
+}
+
+/// Returns (s, t) with s = a + b rounded to nearest and t the rounding
+/// error, provided that the exponent of a is not less than that of b.
+fn fast_two_sum(a: &FP237, b: &FP237) -> (FP237, FP237) {
+    let s = a.add_round(b, RN);
+    let z = s.sub_round(a, RN);
+    let t = b.sub_round(&z, RN);
+    (s, t)
+}
+
+impl DW237 {
+    pub fn new(hi: FP237, lo: FP237) -> Self {
+        Self { hi, lo }
+    }
+
+    /// Returns `x` rounded to nearest as hi and the rest rounded to
+    /// nearest as lo.
+    pub fn from_float(x: &Float) -> Self {
+        let hi = FP237::with_val_round(x, RN);
+        let lo = FP237::with_val_round(Float::with_val(P, x - &hi.f), RN);
+        Self { hi, lo }
+    }
+
+    /// Returns the exact value hi + lo.
+    pub fn value(&self) -> Float {
+        match (self.hi.f.get_exp(), self.lo.f.get_exp()) {
+            (Some(eh), Some(el)) => {
+                let prec = (eh - el).unsigned_abs() + P;
+                Float::with_val(prec, &self.hi.f) + &self.lo.f
+            }
+            _ => Float::with_val(P, &self.hi.f + &self.lo.f),
+        }
+    }
+
+    /// Returns hi + lo rounded once according to `rnd`.
+    pub fn to_fp237(&self, rnd: RoundingMode) -> FP237 {
+        FP237::with_val_round(self.value(), rnd)
+    }
+
+    /// Returns self + y (DWPlusFP).
+    pub fn add_fp(&self, y: &FP237) -> Self {
+        let (sh, sl) = self.hi.two_sum(y);
+        let v = self.lo.add_round(&sl, RN);
+        let (hi, lo) = fast_two_sum(&sh, &v);
+        Self { hi, lo }
+    }
+
+    /// Returns self * y (DWTimesFP3).
+    pub fn mul_fp(&self, y: &FP237) -> Self {
+        let (ch, cl1) = self.hi.two_prod(y);
+        let cl3 = self.lo.fma_round(y, &cl1, RN);
+        let (hi, lo) = fast_two_sum(&ch, &cl3);
+        Self { hi, lo }
+    }
+}
+
+impl From<FP237> for DW237 {
+    fn from(hi: FP237) -> Self {
+        Self {
+            hi,
+            lo: FP237::ZERO(),
+        }
+    }
+}
+
+impl Neg for DW237 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Add for &DW237 {
+    type Output = DW237;
+
+    /// AccurateDWPlusDW
+    fn add(self, rhs: Self) -> Self::Output {
+        let (sh, sl) = self.hi.two_sum(&rhs.hi);
+        let (th, tl) = self.lo.two_sum(&rhs.lo);
+        let c = sl.add_round(&th, RN);
+        let (vh, vl) = fast_two_sum(&sh, &c);
+        let w = tl.add_round(&vl, RN);
+        let (hi, lo) = fast_two_sum(&vh, &w);
+        DW237 { hi, lo }
+    }
+}
+
+impl Sub for &DW237 {
+    type Output = DW237;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + &-rhs.clone()
+    }
+}
+
+impl Mul for &DW237 {
+    type Output = DW237;
+
+    /// DWTimesDW3
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (ch, cl1) = self.hi.two_prod(&rhs.hi);
+        let tl0 = self.lo.mul_round(&rhs.lo, RN);
+        let tl1 = self.hi.fma_round(&rhs.lo, &tl0, RN);
+        let cl2 = self.lo.fma_round(&rhs.hi, &tl1, RN);
+        let cl3 = cl1.add_round(&cl2, RN);
+        let (hi, lo) = fast_two_sum(&ch, &cl3);
+        DW237 { hi, lo }
+    }
+}
+
+impl Div for &DW237 {
+    type Output = DW237;
+
+    /// DWDivDW2
+    fn div(self, rhs: Self) -> Self::Output {
+        let th = self.hi.div_round(&rhs.hi, RN);
+        let r = rhs.mul_fp(&th);
+        let pi_h = self.hi.sub_round(&r.hi, RN);
+        let delta_l = self.lo.sub_round(&r.lo, RN);
+        let delta = pi_h.add_round(&delta_l, RN);
+        let tl = delta.div_round(&rhs.hi, RN);
+        let (hi, lo) = fast_two_sum(&th, &tl);
+        DW237 { hi, lo }
+    }
+}
+
+#[cfg(test)]
+mod double_word_tests {
+    use rand::prelude::*;
+
+    use super::*;
+
+    /// Returns |approx / exact - 1| in units of 2⁻²ᴾ.
+    fn rel_err(approx: &DW237, exact: &Float) -> f64 {
+        let err = Float::with_val(4 * P, approx.value() - exact) / exact;
+        (err.abs() << (2 * P)).to_f64()
+    }
+
+    fn random_dw(rng: &mut StdRng) -> DW237 {
+        let x = FP237::random_from_exp_range_with_rng(rng, &(-20..=20));
+        let y = FP237::random_from_exp_range_with_rng(rng, &(0..=0));
+        DW237::from_float(&Float::with_val(4 * P, &x.f * &y.f))
+    }
+
+    #[test]
+    fn test_conversions() {
+        let third = Float::with_val(4 * P, 3).recip();
+        let x = DW237::from_float(&third);
+        assert_eq!(x.hi.f, Float::with_val(P, &third));
+        assert!(rel_err(&x, &third) <= 0.5);
+        assert_eq!(x.to_fp237(RN).f, x.hi.f);
+        assert_eq!(DW237::from(FP237::ONE()).value(), 1);
+    }
+
+    #[test]
+    fn test_arith() {
+        let mut rng = StdRng::seed_from_u64(237);
+        for _ in 0..200 {
+            let (x, y) = (random_dw(&mut rng), random_dw(&mut rng));
+            let (a, b) = (x.value(), y.value());
+            let wp = 8 * P;
+            let sum = Float::with_val(wp, &a + &b);
+            assert!(rel_err(&(&x + &y), &sum) <= 3.0);
+            let diff = Float::with_val(wp, &a - &b);
+            assert!(rel_err(&(&x - &y), &diff) <= 3.0);
+            let prod = Float::with_val(wp, &a * &b);
+            assert!(rel_err(&(&x * &y), &prod) <= 4.0);
+            let quot = Float::with_val(wp, &a / &b);
+            assert!(rel_err(&(&x / &y), &quot) <= 15.0);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Floating-point values with a precision chosen at runtime, for
+//! recomputing results with more bits than f256 has, escalating the
+//! precision as needed.
+
+use std::cmp::Ordering;
+
+use rug::{float::Round, ops::AssignRound, Float};
+
+use crate::{RoundingMode, FP237, P};
+
+/// Floating-point value with runtime precision and unbounded exponent
+/// range, together with the ternary of its rounding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FPDyn {
+    pub f: Float,
+    pub o: Ordering,
+}
+
+impl FPDyn {
+    /// Returns `val` rounded to `prec` bits according to `rnd`.
+    pub fn with_val_round<T>(prec: u32, val: T, rnd: Round) -> Self
+    where
+        Float: AssignRound<T, Round = Round, Ordering = Ordering>,
+    {
+        let (f, o) = Float::with_val_round(prec, val, rnd);
+        Self { f, o }
+    }
+
+    /// Returns `val` rounded to odd at `prec` bits, i.e. rounded toward
+    /// zero with the last bit of an inexact value set. A value rounded to
+    /// odd at P + 2 or more bits can be rounded a second time without
+    /// double rounding error, in any rounding mode.
+    pub fn with_val_odd<T>(prec: u32, val: T) -> Self
+    where
+        Float: AssignRound<T, Round = Round, Ordering = Ordering>,
+    {
+        Self::with_val_round(prec, val, Round::Zero).to_odd()
+    }
+
+    /// Returns `self`, which has to be rounded toward zero, rounded to odd
+    /// instead.
+    pub fn to_odd(self) -> Self {
+        let Self { mut f, o } = self;
+        if o != Ordering::Equal
+            && f.is_finite()
+            && f.to_integer_exp().unwrap().0.is_even()
+        {
+            if f.is_sign_positive() {
+                f.next_up();
+            } else {
+                f.next_down();
+            }
+        }
+        Self { f, o }
+    }
+
+    /// Returns `x` with `prec` bits, which is exact for `prec` >= P.
+    pub fn from_fp237(x: &FP237, prec: u32) -> Self {
+        Self::with_val_round(prec, &x.f, Round::Nearest)
+    }
+
+    pub fn prec(&self) -> u32 {
+        self.f.prec()
+    }
+
+    /// Returns `self` rounded to f256 according to `rnd`.
+    pub fn to_fp237(&self, rnd: RoundingMode) -> FP237 {
+        FP237::with_val_round(&self.f, rnd)
+    }
+
+    /// Returns the precisions to be tried in turn, starting with `start`
+    /// times P bits: `start`·P, (`start` + 1)·P, ...
+    pub fn precisions(start: u32) -> impl Iterator<Item = u32> {
+        (start..).map(|n| n * P)
+    }
+}
+
+#[cfg(test)]
+mod fp_dyn_tests {
+    use super::*;
+
+    #[test]
+    fn test_round_odd() {
+        let third = Float::with_val(8 * P, 3).recip();
+        let x = FPDyn::with_val_odd(P + 2, &third);
+        assert_eq!((x.prec(), x.o), (P + 2, Ordering::Less));
+        assert!(x.f.to_integer_exp().unwrap().0.is_odd());
+        for rnd in crate::ROUNDING_MODES {
+            assert_eq!(
+                x.to_fp237(rnd).to_bits(),
+                FP237::with_val_round(&third, rnd).to_bits()
+            );
+        }
+        let two = FP237::from(2);
+        let y = FPDyn::from_fp237(&two, 2 * P);
+        assert_eq!((&y.f, y.o), (&two.f, Ordering::Equal));
+        assert_eq!(y.to_fp237(RoundingMode::Down).to_bits(), two.to_bits());
+    }
+
+    #[test]
+    fn test_precisions() {
+        let precs: Vec<u32> = FPDyn::precisions(2).take(3).collect();
+        assert_eq!(precs, [2 * P, 3 * P, 4 * P]);
+    }
+}
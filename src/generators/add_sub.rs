@@ -0,0 +1,107 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{cmp::max, ops::RangeInclusive};
+
+use clap::Parser;
+
+use super::{
+    CommonArgs, ExpRangeArgs, RoundArgs, NORMAL_EXP_RANGE,
+    SUBNORMAL_EXP_LOWER_BOUND, SUBNORMAL_EXP_RANGE,
+};
+use crate::{CorpusBuilder, Header, EMIN, FP237, P};
+
+const MIXED_EXP_UPPER_BOUND: i32 = EMIN + 2;
+
+// f256::MIN_GT_ZERO <= |f| < 4 * MIN_POSITIVE
+const MIXED_EXP_RANGE: RangeInclusive<i32> =
+    SUBNORMAL_EXP_LOWER_BOUND..=MIXED_EXP_UPPER_BOUND;
+
+/// Generates test data for addition and subtraction.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    #[command(flatten)]
+    rounding: RoundArgs,
+
+    /// Add the boundary values of the exponent ranges
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    let normal_exp_range = args.exp.exp_range(&NORMAL_EXP_RANGE);
+    let subnormal_exp_range = args.exp.exp_range(&SUBNORMAL_EXP_RANGE);
+    let n_sub_normal = args.common.n_test_data / 20;
+    let n_normal = args.common.n_test_data - n_sub_normal;
+    let seed = args.common.seed();
+    let rnd = args.rounding.round;
+
+    let mut builder = CorpusBuilder::new(2, seed)
+        .sign(args.common.sign)
+        .random(n_normal, |rng| {
+            let x =
+                FP237::random_from_exp_range_with_rng(rng, &normal_exp_range);
+            let (_, e, _) = x.decode(false);
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &(max(e - P as i32, SUBNORMAL_EXP_LOWER_BOUND)
+                    ..=e + P as i32),
+            );
+            vec![x, y]
+        })
+        .random(n_sub_normal, |rng| {
+            let x =
+                FP237::random_from_exp_range_with_rng(rng, &MIXED_EXP_RANGE);
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            );
+            vec![x, y]
+        });
+    if args.boundaries {
+        builder = builder
+            .boundaries(&normal_exp_range)
+            .boundaries(&subnormal_exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        builder = builder.verify(&[rnd]);
+    }
+    let header = Header::new("add").seed(seed).rounding(rnd).exp_ranges(&[
+        &normal_exp_range,
+        &subnormal_exp_range,
+        &MIXED_EXP_RANGE,
+    ]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "add", |ops| {
+            Some(vec![ops[0].add_round(&ops[1], rnd)])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
@@ -0,0 +1,1608 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    cmp::Ordering,
+    io::{stdout, Write},
+};
+
+use clap::{Parser, ValueEnum};
+use rug::{
+    float::{Constant, Round},
+    ops::Pow,
+    Float, Integer, Rational,
+};
+
+use super::{constants::exact_math_constants, verify_table::max_ulp_error};
+use crate::{
+    max_10_exp, min_10_exp,
+    tables::{ConstWriter, TableWriter, Target, Value},
+    RoundingMode, EMAX, EMIN, FP237, P,
+};
+
+/// Tables to be calculated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Table {
+    /// Gain K of the CORDIC iterations and its reciprocal P
+    CordicGain,
+    /// atan(2⁻ⁱ) for the CORDIC iterations
+    Atans,
+    /// Gain K_h of the hyperbolic CORDIC iterations and its reciprocal P_h
+    HyperbolicGain,
+    /// atanh(2⁻ⁱ) for the hyperbolic CORDIC iterations, with the indices
+    /// 4, 13, 40, … repeated
+    Atanhs,
+    /// π and π/2
+    Pi,
+    /// Chebyshev series of --func over [--lo, --hi], economized to
+    /// --degree
+    Chebyshev,
+    /// Taylor series of --func around 0 up to --degree, with bounds of the
+    /// truncation error over [--lo, --hi]
+    Taylor,
+    /// Mathematical constants of f256's `consts` module (always P bits)
+    Consts,
+    /// Layout constants of f256 (exponent bias and masks, decimal limits,
+    /// EPSILON), derived from P and EMAX
+    Layout,
+    /// --consts split into --terms f256 values with the exact sum
+    /// matching the constant to --terms · P bits
+    Split,
+    /// Seeds of --width bits approximating 1/x over 2^--index-bits
+    /// subintervals of [1, 2), for Newton–Raphson division
+    Recip,
+    /// Seeds of --width bits approximating 1/√x over 2^--index-bits
+    /// subintervals of [1, 4), for Newton–Raphson sqrt and rsqrt
+    Rsqrt,
+    /// 2^(k/2^m) for k in 0..2^m, m = --index-bits, split into hi + lo,
+    /// for table-driven exp
+    Exp2,
+    /// ln(1 + k/2^m) for k in 0..2^m, m = --index-bits, split into hi +
+    /// lo, for table-driven ln
+    Ln,
+    /// log2(1 + k/2^m) for k in 0..2^m, m = --index-bits, split into hi +
+    /// lo, for table-driven log2
+    Log2,
+    /// 10^k for k in --k-min..=--k-max as (exp, hi, lo) with 10^k ≈
+    /// (hi·2¹²⁸ + lo)·2^exp, for decimal conversion
+    Pow10,
+    /// --base^k for k in --k-min..=--k-max as 256-bit significand and
+    /// binary exponent, with error bounds, for shortest float printing
+    CachedPowers,
+    /// Coefficients of the Lanczos approximation of Γ for --g with
+    /// --degree + 1 terms, with the error sampled over [--lo, --hi]
+    Lanczos,
+    /// Coefficients of the Stirling series of ln Γ with --degree terms,
+    /// with the error bound for z ≥ --lo
+    Stirling,
+    /// Bernoulli numbers B₂ₖ and Euler–Maclaurin coefficients B₂ₖ / (2k)!
+    /// for k = 1..=--degree, each split into hi + lo
+    Bernoulli,
+    /// Chebyshev series of erf, or of erfc(x)·e^(x²) for --func erfc, of
+    /// --degree over each segment between consecutive --breaks, with the
+    /// maximum error in ulps per segment
+    Erf,
+}
+
+/// Bases of cached powers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Base {
+    #[value(name = "5")]
+    Five,
+    #[value(name = "10")]
+    Ten,
+}
+
+impl Base {
+    fn value(self) -> u32 {
+        match self {
+            Base::Five => 5,
+            Base::Ten => 10,
+        }
+    }
+}
+
+/// Functions to be approximated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Func {
+    Exp,
+    Exp2,
+    Ln,
+    Ln1p,
+    Sin,
+    Cos,
+    Tan,
+    Atan,
+    Sqrt,
+    Erf,
+    Erfc,
+    /// erfc(x)·e^(x²)
+    Erfcx,
+}
+
+impl Func {
+    pub(crate) fn eval(self, x: Float) -> Float {
+        match self {
+            Func::Exp => x.exp(),
+            Func::Exp2 => x.exp2(),
+            Func::Ln => x.ln(),
+            Func::Ln1p => x.ln_1p(),
+            Func::Sin => x.sin(),
+            Func::Cos => x.cos(),
+            Func::Tan => x.tan(),
+            Func::Atan => x.atan(),
+            Func::Sqrt => x.sqrt(),
+            Func::Erf => x.erf(),
+            Func::Erfc => x.erfc(),
+            Func::Erfcx => {
+                let e = Float::with_val(x.prec(), x.square_ref()).exp();
+                x.erfc() * e
+            }
+        }
+    }
+
+    pub(crate) fn name(self) -> String {
+        format!("{self:?}").to_lowercase()
+    }
+
+    /// Returns the coefficient of xᵏ of the Taylor series around 0.
+    fn taylor_coeff(self, k: u32, wp: u32) -> Float {
+        let fact = Float::with_val(wp, Float::factorial(k));
+        // (-1)ʲ for the j-th non-zero term of sin, cos and atan
+        let alt = if (k / 2).is_multiple_of(2) { 1 } else { -1 };
+        match self {
+            Func::Exp => fact.recip(),
+            Func::Exp2 => Float::with_val(wp, Constant::Log2).pow(k) / fact,
+            Func::Sin if k % 2 == 1 => fact.recip() * alt,
+            Func::Cos if k.is_multiple_of(2) => fact.recip() * alt,
+            Func::Atan if k % 2 == 1 => Float::with_val(wp, alt) / k,
+            Func::Ln1p if k > 0 => {
+                Float::with_val(wp, if k % 2 == 1 { 1 } else { -1 }) / k
+            }
+            Func::Sin | Func::Cos | Func::Atan | Func::Ln1p => Float::new(wp),
+            _ => panic!("Taylor series of {} not supported", self.name()),
+        }
+    }
+
+    /// Returns a bound of the error of the Taylor series truncated after
+    /// the term of degree `k`, for |x| ≤ `r`, i.e. the Lagrange remainder
+    /// for exp, exp2, sin and cos and the geometric tail of the series for
+    /// atan and ln1p.
+    fn taylor_remainder(self, k: u32, r: &Float, wp: u32) -> Float {
+        let fact = Float::with_val(wp, Float::factorial(k + 1));
+        let rk = Float::with_val(wp, r.pow(k + 1));
+        match self {
+            Func::Exp => Float::with_val(wp, r.exp_ref()) * rk / fact,
+            Func::Exp2 => {
+                let ln2 = Float::with_val(wp, Constant::Log2);
+                Float::with_val(wp, r.exp2_ref()) * rk * ln2.pow(k + 1) / fact
+            }
+            Func::Sin | Func::Cos => rk / fact,
+            Func::Atan | Func::Ln1p => {
+                assert!(*r < 1, "Series of {} diverges at {r}", self.name());
+                let q = if self == Func::Atan {
+                    Float::with_val(wp, r.square_ref())
+                } else {
+                    Float::with_val(wp, r)
+                };
+                rk / (k + 1) / (1 - q)
+            }
+            _ => panic!("Taylor series of {} not supported", self.name()),
+        }
+    }
+}
+
+/// Returns the layout constants of f256 derived from P, EMIN and EMAX as
+/// (name, value) pairs.
+fn layout() -> Vec<(&'static str, Value)> {
+    let exp_bits = (2 * EMAX as u32 + 1).ilog2() + 1;
+    let fraction_bits = 255 - exp_bits;
+    assert_eq!(fraction_bits, P - 1, "Exponent and significand overlap");
+    let hi_fraction_bits = fraction_bits - 128;
+    let hi_fraction_mask = (1_u128 << hi_fraction_bits) - 1;
+    let hi_exp_mask = ((1_u128 << exp_bits) - 1) << hi_fraction_bits;
+    // ⌊(P - 1)·log₁₀ 2⌋ decimal digits survive a round trip through f256
+    let log10_2 = Float::with_val(128, 2).log10();
+    let digits = Float::with_val(128, &log10_2 * (P - 1)).floor();
+    let digits = digits.to_integer().unwrap();
+    vec![
+        ("RADIX", Value::int("u32", 2)),
+        ("MANTISSA_DIGITS", Value::int("u32", P)),
+        ("FRACTION_BITS", Value::int("u32", fraction_bits)),
+        ("HI_FRACTION_BITS", Value::int("u32", hi_fraction_bits)),
+        ("EXP_BITS", Value::int("u32", exp_bits)),
+        ("EXP_BIAS", Value::int("u32", EMAX)),
+        ("EXP_MAX", Value::int("u32", 2 * EMAX)),
+        ("MIN_EXP", Value::int("i32", EMIN + 1)),
+        ("MAX_EXP", Value::int("i32", EMAX + 1)),
+        ("DIGITS", Value::int("u32", digits)),
+        ("MIN_10_EXP", Value::int("i32", min_10_exp())),
+        ("MAX_10_EXP", Value::int("i32", max_10_exp())),
+        ("HI_SIGN_MASK", Value::hex("u128", 1_u128 << 127)),
+        ("HI_EXP_MASK", Value::hex("u128", hi_exp_mask)),
+        ("HI_FRACTION_MASK", Value::hex("u128", hi_fraction_mask)),
+    ]
+}
+
+/// Returns the seeds ⌊2ʷ·y⌉ for the subintervals [a, b) between
+/// consecutive `bounds`, with y = 2 / (g(a) + g(b)) minimizing
+/// max |1 - g(x)·y| over [a, b) for increasing g, each together with the
+/// maximum relative error |1 - g(x)·seed / 2ʷ| over [a, b).
+fn seeds(
+    bounds: &[Float],
+    w: u32,
+    g: fn(Float) -> Float,
+) -> Vec<(Integer, Float)> {
+    bounds
+        .windows(2)
+        .map(|ab| {
+            let wp = ab[0].prec();
+            let (ga, gb) = (g(ab[0].clone()), g(ab[1].clone()));
+            let seed = (Float::with_val(wp, &ga + &gb).recip() << (w + 1))
+                .to_integer()
+                .unwrap();
+            // the error is linear in g(x), so its maximum is at a or b
+            let y = Float::with_val(wp, &seed) >> w;
+            let err = |gx: &Float| {
+                let gxy = Float::with_val(wp, gx * &y);
+                Float::with_val(wp, 1 - gxy).abs()
+            };
+            let err = err(&ga).max(&err(&gb));
+            (seed, err)
+        })
+        .collect()
+}
+
+/// Returns the seeds approximating 1/x for the 2ᵏ subintervals of [1, 2).
+fn recip_seeds(k: u32, w: u32) -> Vec<(Integer, Float)> {
+    let wp = 2 * (k + w) + 64;
+    let bounds: Vec<Float> = (0..=1_u32 << k)
+        .map(|i| Float::with_val(wp, (1 << k) + i) >> k)
+        .collect();
+    seeds(&bounds, w, |x| x)
+}
+
+/// Returns the seeds approximating 1/√x for 2ᵏ subintervals of [1, 4),
+/// indexed by the lowest bit of the exponent and the leading k - 1
+/// fraction bits of x, i.e. 2ᵏ⁻¹ subintervals of [1, 2) followed by 2ᵏ⁻¹
+/// subintervals of [2, 4).
+fn rsqrt_seeds(k: u32, w: u32) -> Vec<(Integer, Float)> {
+    let wp = 2 * (k + w) + 64;
+    let h = 1_u32 << (k - 1);
+    let bounds: Vec<Float> = (0..h)
+        .map(|i| Float::with_val(wp, h + i) / h)
+        .chain((0..=h).map(|i| Float::with_val(wp, h + i) / h * 2))
+        .collect();
+    seeds(&bounds, w, Float::sqrt)
+}
+
+/// Adds the table `name` of `seeds` of `w` bits to `tw`, preceded by the
+/// worst relative error |1 - `gx`·y| and the number of Newton–Raphson
+/// iterations needed to get P bits, with `step` mapping the error of one
+/// iteration to the error of the next one.
+fn write_seeds(
+    tw: &mut TableWriter,
+    name: &str,
+    w: u32,
+    seeds: &[(Integer, Float)],
+    gx: &str,
+    step: fn(f64) -> f64,
+) {
+    let worst = seeds
+        .iter()
+        .fold(Float::with_val(64, 0), |acc, (_, e)| acc.max(e));
+    let worst = worst.to_f64();
+    let mut iters = 0;
+    let mut err = worst;
+    while err > (-(P as f64)).exp2() {
+        err = step(err);
+        iters += 1;
+    }
+    tw.comment(&format!(
+        "max |1 - {gx}·y| = 2^{:.2}, i.e. {:.2} correct bits, {iters} \
+         Newton–Raphson iterations for {P} bits",
+        worst.log2(),
+        -worst.log2()
+    ));
+    let ty = match w {
+        8 => "u8",
+        16 => "u16",
+        32 => "u32",
+        _ => "u64",
+    };
+    let rows: Vec<(Vec<Value>, String)> = seeds
+        .iter()
+        .enumerate()
+        .map(|(i, (seed, err))| {
+            let note = format!(
+                "{i}: max |1 - {gx}·y| = 2^{:.2}",
+                err.clone().log2().to_f64()
+            );
+            (vec![Value::hex(ty, seed)], note)
+        })
+        .collect();
+    tw.value_array(name, &rows);
+}
+
+/// Returns g(k/2ᵐ) for k in 0..2ᵐ, each with 4·P bits and split into
+/// hi + lo.
+fn segments(m: u32, g: fn(Float) -> Float) -> Vec<(Float, FP237, FP237)> {
+    assert!(
+        (1..=16).contains(&m),
+        "Index bits must be in 1..=16, got {m}"
+    );
+    (0..1_u32 << m)
+        .map(|k| {
+            let f = g(Float::with_val(4 * P, k) >> m);
+            let [hi, lo]: [FP237; 2] =
+                FP237::split_hi_lo(&f, 2).try_into().unwrap();
+            (f, hi, lo)
+        })
+        .collect()
+}
+
+/// Returns 2^(k/2ᵐ) for k in 0..2ᵐ, each split into hi + lo.
+pub(crate) fn exp2_segments(m: u32) -> Vec<(Float, FP237, FP237)> {
+    segments(m, Float::exp2)
+}
+
+/// Returns ln(1 + k/2ᵐ) for k in 0..2ᵐ, each split into hi + lo.
+fn ln_segments(m: u32) -> Vec<(Float, FP237, FP237)> {
+    segments(m, Float::ln_1p)
+}
+
+/// Returns log₂(1 + k/2ᵐ) for k in 0..2ᵐ, each split into hi + lo.
+fn log2_segments(m: u32) -> Vec<(Float, FP237, FP237)> {
+    segments(m, |x| {
+        let ln_2 = Float::with_val(x.prec(), Constant::Log2);
+        x.ln_1p() / ln_2
+    })
+}
+
+/// Adds the table `name` of `segments` approximating `expr` to `tw`, each
+/// entry preceded by a comment giving its value and the error of hi + lo.
+fn write_segments(
+    tw: &mut TableWriter,
+    name: &str,
+    expr: &str,
+    segments: &[(Float, FP237, FP237)],
+) {
+    let errs: Vec<Float> = segments
+        .iter()
+        .map(|(f, hi, lo)| {
+            (Float::with_val(f.prec(), f - &hi.f) - &lo.f).abs()
+        })
+        .collect();
+    let worst = errs
+        .iter()
+        .fold(Float::with_val(4 * P, 0), |acc, err| acc.max(err));
+    tw.comment(&format!("{expr} ≈ {name}[k].0 + {name}[k].1"));
+    tw.comment(&format!(
+        "max |{expr} - (hi + lo)| ≤ {}",
+        upper_bound(&worst)
+    ));
+    let rows: Vec<(Vec<Value>, String)> = segments
+        .iter()
+        .zip(&errs)
+        .enumerate()
+        .map(|(k, ((f, hi, lo), err))| {
+            let note = format!(
+                "{k}: {}, error ≤ {}",
+                f.to_string_radix(10, Some(72)),
+                upper_bound(err)
+            );
+            (vec![Value::F256(hi.clone()), Value::F256(lo.clone())], note)
+        })
+        .collect();
+    tw.value_array(name, &rows);
+}
+
+/// Returns an upper bound of `f` with 6 significant digits.
+fn upper_bound(f: &Float) -> String {
+    // compensate for the rounding errors of calculating `f`
+    let eps =
+        Float::with_val(f.prec(), Float::i_exp(1, 16 - f.prec() as i32));
+    let f = Float::with_val(f.prec(), f * (1 + eps));
+    f.to_string_radix_round(10, Some(6), Round::Up)
+}
+
+/// Returns the gain of `n` CORDIC iterations, ∏ √(1 + 2⁻²ⁱ) for i in 0..n.
+fn cordic_gain(prec: u32, n: u32) -> Float {
+    let mut k = Float::with_val(prec, 1);
+    for i in 0..n {
+        let f = Float::with_val(prec, Float::i_exp(1, -2 * i as i32));
+        k *= (f + 1_u32).sqrt();
+    }
+    k
+}
+
+/// Returns the bounds Rᵢ for i in 0..=n of the residual angle before
+/// iteration i of the n circular CORDIC iterations with angles `atans`,
+/// Rᵢ being the sum of atan(2⁻ʲ) for j in i..n plus atan(2⁻⁽ⁿ⁻¹⁾). The
+/// iterations converge for |z₀| ≤ R₀, as |zᵢ| ≤ Rᵢ implies
+/// |zᵢ₊₁| = ||zᵢ| - atan(2⁻ⁱ)| ≤ max(Rᵢ₊₁, atan(2⁻ⁱ)) = Rᵢ₊₁.
+fn cordic_residuals(atans: &[Float]) -> Vec<Float> {
+    let mut residuals = vec![atans.last().unwrap().clone()];
+    for atan in atans.iter().rev() {
+        let r =
+            Float::with_val(atan.prec(), residuals.last().unwrap() + atan);
+        residuals.push(r);
+    }
+    residuals.reverse();
+    residuals
+}
+
+/// Returns 10ᵏ rounded to nearest from its exact value.
+///
+/// # Panics
+///
+/// If 10ᵏ is beyond the range of normal f256 values.
+fn pow10(k: i32) -> FP237 {
+    let p = Integer::from(10).pow(k.unsigned_abs());
+    let r = if k < 0 {
+        Rational::from((1, p))
+    } else {
+        Rational::from(p)
+    };
+    let f = FP237::with_val_round(&r, RoundingMode::NearestEven);
+    assert!(
+        f.f.is_normal() && f.ilogb() >= EMIN && f.ilogb() <= EMAX,
+        "10^{k} beyond the range of normal f256 values"
+    );
+    f
+}
+
+/// Returns bᵏ rounded to nearest (ties away from zero) as m·2ᵉ with
+/// 2²⁵⁵ ≤ m < 2²⁵⁶, together with the exact error (m·2ᵉ - bᵏ) / 2ᵉ in
+/// ulps.
+fn cached_power(b: u32, k: i32) -> (i32, Integer, Rational) {
+    let q = Integer::from(b).pow(k.unsigned_abs());
+    // bᵏ = n / 2ˢ with n having at least 256 significant bits
+    let (n, s) = if k < 0 {
+        let s = 255 + q.significant_bits();
+        (Rational::from((Integer::from(1) << s, q)), s as i32)
+    } else {
+        let s = 256 - q.significant_bits() as i32;
+        let n = if s >= 0 {
+            Rational::from(q << s as u32)
+        } else {
+            Rational::from((q, Integer::from(1) << -s as u32))
+        };
+        (n, s)
+    };
+    let mut m = Integer::from(n.round_ref());
+    let mut e = -s;
+    if m.significant_bits() > 256 {
+        // n rounded up to 2²⁵⁶
+        m >>= 1;
+        e += 1;
+        return (e, m.clone(), Rational::from(m) - n / 2);
+    }
+    let err = Rational::from(&m) - n;
+    (e, m, err)
+}
+
+/// Returns the solution x of `a`·x = `b`, by Gaussian elimination with
+/// partial pivoting.
+fn solve(mut a: Vec<Vec<Float>>, mut b: Vec<Float>) -> Vec<Float> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].cmp_abs(&a[j][col]).unwrap())
+            .unwrap();
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let (upper, lower) = a.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (i, cur) in lower.iter_mut().enumerate() {
+            let wp = cur[col].prec();
+            let f = Float::with_val(wp, &cur[col] / &pivot_row[col]);
+            for (x, p) in cur[col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= Float::with_val(wp, &f * p);
+            }
+            let d = Float::with_val(wp, &f * &b[col]);
+            b[col + 1 + i] -= d;
+        }
+    }
+    let mut x: Vec<Float> = vec![Float::new(b[0].prec()); n];
+    for row in (0..n).rev() {
+        let mut sum = b[row].clone();
+        for k in row + 1..n {
+            sum -= Float::with_val(sum.prec(), &a[row][k] * &x[k]);
+        }
+        x[row] = sum / &a[row][row];
+    }
+    x
+}
+
+/// Returns √(2π)·t^(z + ½)·e⁻ᵗ with t = z + g + ½, i.e. Γ(z + 1) divided by
+/// the Lanczos sum.
+fn lanczos_factor(z: &Float, g: &Float) -> Float {
+    let wp = z.prec();
+    let sqrt_2pi = (Float::with_val(wp, Constant::Pi) << 1_u32).sqrt();
+    let t: Float = Float::with_val(wp, z + g) + 0.5;
+    let e: Float = Float::with_val(wp, z) + 0.5;
+    let p = Float::with_val(wp, (&t).pow(&e));
+    sqrt_2pi * p * (-t).exp()
+}
+
+/// Returns the coefficients c₀, …, cₙ of the Lanczos sum
+/// c₀ + Σ cₖ / (z + k), k = 1..=n, making
+/// Γ(z + 1) = √(2π)·t^(z + ½)·e⁻ᵗ·(c₀ + Σ cₖ / (z + k)), t = z + g + ½,
+/// exact for z = 0, …, n.
+fn lanczos(g: &Float, n: usize) -> Vec<Float> {
+    let wp = g.prec();
+    let mut a = Vec::with_capacity(n + 1);
+    let mut b = Vec::with_capacity(n + 1);
+    for j in 0..=n {
+        let z = Float::with_val(wp, j);
+        let gamma = Float::with_val(wp, Float::factorial(j as u32));
+        b.push(gamma / lanczos_factor(&z, g));
+        let mut row = vec![Float::with_val(wp, 1)];
+        row.extend((1..=n).map(|k| Float::with_val(wp, j + k).recip()));
+        a.push(row);
+    }
+    solve(a, b)
+}
+
+/// Returns Γ(x) approximated by the Lanczos sum with coefficients `c`.
+fn lanczos_gamma(c: &[Float], g: &Float, x: &Float) -> Float {
+    let z = Float::with_val(x.prec(), x - 1_u32);
+    let mut sum = c[0].clone();
+    for (k, c) in c.iter().enumerate().skip(1) {
+        sum += Float::with_val(
+            x.prec(),
+            c / Float::with_val(x.prec(), &z + k as u32),
+        );
+    }
+    lanczos_factor(&z, g) * sum
+}
+
+/// Returns the Bernoulli numbers B₀, …, Bₙ.
+fn bernoulli(n: usize) -> Vec<Rational> {
+    let mut bs: Vec<Rational> = Vec::with_capacity(n + 1);
+    for m in 0..=n {
+        if m == 0 {
+            bs.push(Rational::from(1));
+            continue;
+        }
+        let mut sum = Rational::new();
+        for (j, b) in bs.iter().enumerate() {
+            sum += Integer::from(Integer::binomial_u(m as u32 + 1, j as u32))
+                * b.clone();
+        }
+        bs.push(-sum / (m as u32 + 1));
+    }
+    bs
+}
+
+/// Returns the coefficients B₂ₖ / (2k·(2k - 1)) of z^-(2k-1), k = 1..=n, in
+/// the Stirling series
+/// ln Γ(z) ≈ (z - ½)·ln z - z + ½·ln(2π) + Σ B₂ₖ / (2k·(2k - 1)·z^(2k-1))
+/// and the coefficient of the first omitted term, which bounds the error
+/// for real z > 0.
+fn stirling(n: usize) -> (Vec<Rational>, Rational) {
+    let bs = bernoulli(2 * n + 2);
+    let coeff =
+        |k: usize| bs[2 * k].clone() / (2 * k as u32 * (2 * k as u32 - 1));
+    ((1..=n).map(coeff).collect(), coeff(n + 1))
+}
+
+/// Returns the Bernoulli numbers B₂ₖ and the coefficients B₂ₖ / (2k)! of
+/// the Euler–Maclaurin formula
+/// Σ f(n), n ≥ N ≈ ∫ f(x) dx over [N, ∞) + f(N) / 2 - Σ B₂ₖ / (2k)! ·
+/// f⁽²ᵏ⁻¹⁾(N) for k = 1..=n.
+fn euler_maclaurin(n: usize) -> (Vec<Rational>, Vec<Rational>) {
+    let bs = bernoulli(2 * n);
+    (1..=n)
+        .map(|k| {
+            let fact = Integer::from(Integer::factorial(2 * k as u32));
+            (bs[2 * k].clone(), bs[2 * k].clone() / fact)
+        })
+        .unzip()
+}
+
+/// Returns `r` rounded to nearest to `prec` bits and the rest rounded to
+/// nearest, so that hi + lo matches `r` to about 2·`prec` bits.
+fn split_rational(r: &Rational, prec: u32) -> (Float, Float) {
+    let hi = Float::with_val(prec, r);
+    let rest = r - hi.to_rational().unwrap();
+    let lo = Float::with_val(prec, &rest);
+    (hi, lo)
+}
+
+/// Returns the indices i of the hyperbolic CORDIC iterations for 1..=n,
+/// with 4, 13, 40, …, k, 3k + 1, … repeated as needed for convergence.
+fn hyperbolic_indices(n: u32) -> Vec<u32> {
+    let mut indices = Vec::new();
+    let mut repeat = 4;
+    for i in 1..=n {
+        indices.push(i);
+        if i == repeat {
+            indices.push(i);
+            repeat = 3 * repeat + 1;
+        }
+    }
+    indices
+}
+
+/// Returns the gain of the hyperbolic CORDIC iterations with `indices`,
+/// ∏ √(1 - 2⁻²ⁱ) for i in indices.
+fn hyperbolic_gain(prec: u32, indices: &[u32]) -> Float {
+    let mut k = Float::with_val(prec, 1);
+    for &i in indices {
+        let f = Float::with_val(prec, Float::i_exp(1, -2 * i as i32));
+        k *= (1_u32 - f).sqrt();
+    }
+    k
+}
+
+/// Returns the first `n` coefficients cⱼ of the Chebyshev series
+/// Σ cⱼ·Tⱼ(t) of `func` over [`lo`, `hi`], with x = mid + t·(hi - lo) / 2,
+/// interpolated at `nodes` Chebyshev nodes and calculated with `wp` bits.
+fn chebyshev(
+    func: Func,
+    lo: &Float,
+    hi: &Float,
+    n: usize,
+    nodes: usize,
+    wp: u32,
+) -> Vec<Float> {
+    let mid: Float = Float::with_val(wp, hi + lo) / 2;
+    let half_width: Float = Float::with_val(wp, hi - lo) / 2;
+    let pi = Float::with_val(wp, Constant::Pi);
+    let thetas: Vec<Float> = (0..nodes)
+        .map(|k| pi.clone() * (2 * k + 1) as u32 / (2 * nodes) as u32)
+        .collect();
+    let fxs: Vec<Float> = thetas
+        .iter()
+        .map(|theta| {
+            let x = mid.clone() + half_width.clone() * theta.clone().cos();
+            let fx = func.eval(x.clone());
+            assert!(fx.is_finite(), "{}({x}) is not finite", func.name());
+            fx
+        })
+        .collect();
+    (0..n)
+        .map(|j| {
+            let mut c = Float::with_val(wp, 0);
+            for (theta, fx) in thetas.iter().zip(&fxs) {
+                c += fx.clone() * (theta.clone() * j as u32).cos();
+            }
+            c = c * 2_u32 / nodes as u32;
+            if j == 0 {
+                c /= 2;
+            }
+            c
+        })
+        .collect()
+}
+
+/// Returns Σ cⱼ·Tⱼ(`t`), evaluated by Clenshaw's recurrence.
+pub(crate) fn clenshaw(c: &[Float], t: &Float) -> Float {
+    let wp = t.prec();
+    let two_t = Float::with_val(wp, t * 2_u32);
+    let (mut b1, mut b2) = (Float::new(wp), Float::new(wp));
+    for cj in c[1..].iter().rev() {
+        let b0 = Float::with_val(wp, &two_t * &b1) - &b2 + cj;
+        b2 = std::mem::replace(&mut b1, b0);
+    }
+    Float::with_val(wp, t * &b1) - &b2 + &c[0]
+}
+
+/// Returns the coefficients of the Chebyshev series of `func` of
+/// `degree` over [`lo`, `hi`], rounded to `prec` bits, together with the
+/// maximum error of the rounded series in ulps of `func`, sampled at
+/// `samples` + 1 equidistant points, calculated with `wp` bits.
+fn erf_segment(
+    func: Func,
+    lo: &Float,
+    hi: &Float,
+    degree: usize,
+    prec: u32,
+    samples: u32,
+    wp: u32,
+) -> (Vec<Float>, Float) {
+    let n = 2 * (degree + 1);
+    let coeffs: Vec<Float> = chebyshev(func, lo, hi, n, 2 * n, wp)
+        .into_iter()
+        .take(degree + 1)
+        .map(|c| Float::with_val(prec, c))
+        .collect();
+    let (worst, _) = max_ulp_error(
+        func,
+        |x| {
+            let t = (Float::with_val(wp, x * 2_u32) - hi - lo)
+                / Float::with_val(wp, hi - lo);
+            clenshaw(&coeffs, &t)
+        },
+        lo,
+        hi,
+        prec,
+        samples,
+        wp,
+    );
+    (coeffs, worst)
+}
+
+/// Calculates constants and tables used in the implementation of f256,
+/// printed as Rust source.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Table to be calculated
+    #[arg(short, long, value_enum)]
+    table: Table,
+
+    /// Number of significant bits of the values (type FP<PREC>)
+    #[arg(short, long, default_value_t = 255)]
+    prec: u32,
+
+    /// Maximum absolute exponent of the values
+    #[arg(short, long, default_value_t = EMAX)]
+    emax: i32,
+
+    /// Function to be approximated (chebyshev, taylor, erf)
+    #[arg(short, long, value_enum, default_value_t = Func::Exp)]
+    func: Func,
+
+    /// Lower bound of the interval (chebyshev, taylor, lanczos, stirling)
+    /// [default: -1, 1 for lanczos, 8 for stirling]
+    #[arg(long, allow_negative_numbers = true)]
+    lo: Option<String>,
+
+    /// Upper bound of the interval (chebyshev, taylor, lanczos) [default:
+    /// 1, 64 for lanczos]
+    #[arg(long, allow_negative_numbers = true)]
+    hi: Option<String>,
+
+    /// Parameter g of the Lanczos approximation (lanczos only)
+    #[arg(short, long, default_value = "4.7421875")]
+    g: String,
+
+    /// Degree of the series, i.e. number of terms of the Lanczos sum less
+    /// one, number of terms of the Stirling series or number of Bernoulli
+    /// numbers (chebyshev, taylor, lanczos, stirling, bernoulli, erf)
+    #[arg(short, long, default_value_t = 16)]
+    degree: usize,
+
+    /// Name of the emitted array (chebyshev, taylor, recip, rsqrt, exp2,
+    /// ln, log2, pow10, cached-powers, lanczos, stirling), prefix of the
+    /// emitted arrays (erf) [default: CHEB_<FUNC>, TAYLOR_<FUNC>,
+    /// RECIP_SEEDS, RSQRT_SEEDS, EXP2_TABLE, LN_TABLE, LOG2_TABLE, POW10,
+    /// CACHED_POW<BASE>, LANCZOS_COEFFS, STIRLING_COEFFS or <FUNC>]
+    #[arg(short, long)]
+    name: Option<String>,
+
+    /// Names of the constants to be split, as given by --table consts
+    /// (split only)
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        default_value = "FRAC_PI_2,LN_2,LN_10"
+    )]
+    consts: Vec<String>,
+
+    /// Number of terms per constant, 2 or 3 (split only)
+    #[arg(long, default_value_t = 2)]
+    terms: usize,
+
+    /// Number of CORDIC iterations, i.e. of distinct indices i for the
+    /// hyperbolic ones (cordic-gain, atans, hyperbolic-gain, atanhs)
+    /// [default: PREC]
+    #[arg(short, long)]
+    iterations: Option<u32>,
+
+    /// Base of the cached powers (cached-powers only)
+    #[arg(long, value_enum, default_value_t = Base::Five)]
+    base: Base,
+
+    /// Smallest exponent k of the powers (pow10, cached-powers)
+    #[arg(long, default_value_t = -32, allow_negative_numbers = true)]
+    k_min: i32,
+
+    /// Largest exponent k of the powers (pow10, cached-powers)
+    #[arg(long, default_value_t = 128, allow_negative_numbers = true)]
+    k_max: i32,
+
+    /// Output target
+    #[arg(long, value_enum, default_value_t = Target::Rust)]
+    target: Target,
+
+    /// Name of the type of the emitted values [default: FP<PREC>]
+    #[arg(long)]
+    type_name: Option<String>,
+
+    /// Number of bits of x indexing the table: leading fraction bits for
+    /// recip, exp2, ln and log2, lowest exponent bit and leading fraction
+    /// bits for rsqrt (recip, rsqrt, exp2, ln, log2)
+    #[arg(long, default_value_t = 8)]
+    index_bits: u32,
+
+    /// Number of bits of the seeds, i.e. of the unsigned integer type
+    /// holding them: 8, 16, 32 or 64 (recip, rsqrt)
+    #[arg(long, default_value_t = 16)]
+    width: u32,
+
+    /// Increasing bounds of the segments (erf only)
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "0,0.5,1,1.5,2,3,4,6",
+        allow_negative_numbers = true
+    )]
+    breaks: Vec<String>,
+}
+
+/// Returns `s` parsed with `wp` bits.
+fn parse_float(s: &str, wp: u32) -> Float {
+    Float::with_val(
+        wp,
+        Float::parse(s).unwrap_or_else(|_| panic!("Invalid number: {s}")),
+    )
+}
+
+impl Args {
+    /// Returns --lo and --hi, `default` if not given.
+    fn bounds<'a>(
+        &'a self,
+        default: (&'a str, &'a str),
+    ) -> (&'a str, &'a str) {
+        (
+            self.lo.as_deref().unwrap_or(default.0),
+            self.hi.as_deref().unwrap_or(default.1),
+        )
+    }
+
+    /// Returns --lo and --hi with `wp` bits, `default` if not given.
+    fn interval(&self, wp: u32, default: (&str, &str)) -> (Float, Float) {
+        let (lo, hi) = self.bounds(default);
+        let (lo, hi) = (parse_float(lo, wp), parse_float(hi, wp));
+        assert!(lo < hi, "Empty interval [{lo}, {hi}]");
+        (lo, hi)
+    }
+}
+
+pub fn run(args: Args) {
+    let mut wrt = ConstWriter::new(args.prec, args.emax);
+    if let Some(name) = &args.type_name {
+        wrt = wrt.with_type_name(name);
+    }
+    let mut tw = TableWriter::new(wrt, args.target);
+    if let Some(table) = args.table.to_possible_value() {
+        tw.meta("table", table.get_name());
+    }
+    let prec = args.prec;
+    let n = args.iterations.unwrap_or(prec);
+    // work with at least twice the target precision
+    let wp = 2 * prec.max(P);
+    match args.table {
+        Table::CordicGain => {
+            let k = cordic_gain(prec, n);
+            tw.comment(&format!("{n} iterations"));
+            tw.constant("K", &k);
+            tw.constant("P", &k.recip());
+        }
+        Table::Atans => {
+            let atans: Vec<Float> = (0..n)
+                .map(|i| {
+                    Float::with_val(wp, Float::i_exp(1, -(i as i32))).atan()
+                })
+                .collect();
+            let residuals = cordic_residuals(&atans);
+            let margin: Float = Float::with_val(wp, &residuals[0])
+                - Float::with_val(wp, Constant::Pi) / 4;
+            tw.comment(&format!(
+                "{n} iterations converge for |z| ≤ {}, i.e. π/4 + {}",
+                residuals[0].to_string_radix(10, Some(6)),
+                margin.to_string_radix(10, Some(6))
+            ));
+            let atans: Vec<(Float, String)> = atans
+                .into_iter()
+                .zip(&residuals[1..])
+                .map(|(f, r)| (f, format!(", residual ≤ {}", upper_bound(r))))
+                .collect();
+            tw.annotated_array("ATANS", &atans);
+        }
+        Table::HyperbolicGain => {
+            let indices = hyperbolic_indices(n);
+            let k = hyperbolic_gain(wp, &indices);
+            tw.comment(&format!("{} iterations", indices.len()));
+            tw.constant("K_H", &k);
+            tw.constant("P_H", &k.recip());
+        }
+        Table::Atanhs => {
+            let atanhs: Vec<(Float, String)> = hyperbolic_indices(n)
+                .into_iter()
+                .map(|i| {
+                    let f = Float::with_val(wp, Float::i_exp(1, -(i as i32)));
+                    (f.atanh(), format!(" (i = {i})"))
+                })
+                .collect();
+            tw.annotated_array("ATANHS", &atanhs);
+        }
+        Table::Pi => {
+            let pi = Float::with_val(prec, Constant::Pi);
+            tw.constant("PI", &pi);
+            tw.constant("FRAC_PI_2", &(pi / 2));
+        }
+        Table::Chebyshev => {
+            let (lo, hi) = args.interval(wp, ("-1", "1"));
+            // calculate the series up to twice the target degree and drop
+            // the higher terms, bounding the error by their sum, since
+            // |Tⱼ(t)| ≤ 1 on [-1, 1]
+            let n = 2 * (args.degree + 1);
+            let mut coeffs = chebyshev(args.func, &lo, &hi, n, 2 * n, wp);
+            // coefficients vanishing by symmetry come out as rounding noise
+            let max = coeffs
+                .iter()
+                .fold(Float::with_val(wp, 0), |acc, c| acc.max(&c.as_abs()));
+            let noise =
+                max * Float::with_val(wp, Float::i_exp(1, 32 - wp as i32));
+            for c in coeffs.iter_mut() {
+                if c.as_abs().lt(&noise) {
+                    *c = Float::new(wp);
+                }
+            }
+            let dropped = coeffs.split_off(args.degree + 1);
+            let bound = dropped
+                .iter()
+                .fold(Float::with_val(wp, 0), |acc, c| acc + c.clone().abs());
+            let name = args.name.clone().unwrap_or_else(|| {
+                format!("CHEB_{}", args.func.name().to_uppercase())
+            });
+            let (lo, hi) = args.bounds(("-1", "1"));
+            tw.meta("func", &args.func.name());
+            tw.meta("lo", lo);
+            tw.meta("hi", hi);
+            tw.comment(&format!(
+                "Chebyshev coefficients of {}(x) over [{lo}, {hi}], degree \
+                 {}",
+                args.func.name(),
+                args.degree
+            ));
+            tw.comment("x = (hi + lo) / 2 + t·(hi - lo) / 2, t ∈ [-1, 1]");
+            tw.comment(&format!(
+                "truncation error ≤ {}",
+                upper_bound(&bound)
+            ));
+            tw.array(&name, &coeffs);
+        }
+        Table::Consts => {
+            for (name, f) in exact_math_constants() {
+                tw.f256_constant(name, &f);
+            }
+        }
+        Table::Layout => {
+            tw.comment(&format!(
+                "f256 layout derived from P = {P}, EMAX = {EMAX}"
+            ));
+            for (name, value) in layout() {
+                tw.value_constant(name, &value);
+            }
+            let eps = Float::with_val(P, Float::i_exp(1, 1 - P as i32));
+            tw.f256_constant("EPSILON", &eps);
+        }
+        Table::Split => {
+            let suffixes: &[&str] = match args.terms {
+                2 => &["HI", "LO"],
+                3 => &["HI", "MID", "LO"],
+                n => panic!("Number of terms must be 2 or 3, got {n}"),
+            };
+            let consts = exact_math_constants();
+            for name in &args.consts {
+                let Some((_, exact)) = consts.iter().find(|(n, _)| n == name)
+                else {
+                    panic!("Unkown constant: {name}");
+                };
+                // each term is the rounded rest of the previous ones
+                let mut rest = exact.clone();
+                for (suffix, t) in
+                    suffixes.iter().zip(FP237::split_hi_lo(exact, args.terms))
+                {
+                    let name = format!("{name}_{suffix}");
+                    tw.f256_constant(&name, &rest);
+                    rest -= &t.f;
+                }
+                tw.comment(&format!(
+                    "|{name} - ({})| ≤ {}",
+                    suffixes
+                        .iter()
+                        .map(|s| format!("{name}_{s}"))
+                        .collect::<Vec<_>>()
+                        .join(" + "),
+                    upper_bound(&rest.abs())
+                ));
+            }
+        }
+        Table::Recip | Table::Rsqrt => {
+            let (k, w) = (args.index_bits, args.width);
+            assert!(
+                [8, 16, 32, 64].contains(&w),
+                "Width must be 8, 16, 32 or 64, got {w}"
+            );
+            assert!(
+                (1..w).contains(&k),
+                "Index bits must be in 1..{w}, got {k}"
+            );
+            if args.table == Table::Recip {
+                let name = args.name.unwrap_or_else(|| "RECIP_SEEDS".into());
+                tw.comment(&format!(
+                    "1/x ≈ {name}[i] / 2^{w} for x ∈ [1 + i/2^{k}, 1 + \
+                     (i+1)/2^{k})"
+                ));
+                // e' = e²
+                let seeds = recip_seeds(k, w);
+                write_seeds(&mut tw, &name, w, &seeds, "x", |e| e * e);
+            } else {
+                let name = args.name.unwrap_or_else(|| "RSQRT_SEEDS".into());
+                let h = 1 << (k - 1);
+                tw.comment(&format!(
+                    "1/√x ≈ {name}[i] / 2^{w} for x ∈ [1 + i/{h}, 1 + \
+                     (i+1)/{h}), i < {h}, and x ∈ [2·(1 + (i-{h})/{h}), \
+                     2·(1 + (i-{h}+1)/{h})), i ≥ {h}"
+                ));
+                // y' = y·(3 - x·y²) / 2 gives e' = -e²·(3 + e) / 2
+                let seeds = rsqrt_seeds(k, w);
+                write_seeds(&mut tw, &name, w, &seeds, "√x", |e| {
+                    e * e * (3.0 + e) / 2.0
+                });
+            }
+        }
+        Table::Exp2 => {
+            let m = args.index_bits;
+            let name = args.name.unwrap_or_else(|| "EXP2_TABLE".into());
+            let expr = format!("2^(k/2^{m})");
+            write_segments(&mut tw, &name, &expr, &exp2_segments(m));
+        }
+        Table::Ln => {
+            let m = args.index_bits;
+            let name = args.name.unwrap_or_else(|| "LN_TABLE".into());
+            let expr = format!("ln(1 + k/2^{m})");
+            write_segments(&mut tw, &name, &expr, &ln_segments(m));
+        }
+        Table::Log2 => {
+            let m = args.index_bits;
+            let name = args.name.unwrap_or_else(|| "LOG2_TABLE".into());
+            let expr = format!("log2(1 + k/2^{m})");
+            write_segments(&mut tw, &name, &expr, &log2_segments(m));
+        }
+        Table::Pow10 => {
+            let (k_min, k_max) = (args.k_min, args.k_max);
+            assert!(k_min <= k_max, "Empty range {k_min}..={k_max}");
+            let name = args.name.unwrap_or_else(|| "POW10".into());
+            tw.comment(&format!(
+                "10^k ≈ (hi·2^128 + lo)·2^exp = {name}[k - ({k_min})] for k \
+                 in {k_min}..={k_max}"
+            ));
+            let rows: Vec<(Vec<Value>, String)> = (k_min..=k_max)
+                .map(|k| {
+                    let f = pow10(k);
+                    let note = match f.o {
+                        Ordering::Less => "rounded down, ternary -1",
+                        Ordering::Equal => "exact",
+                        Ordering::Greater => "rounded up, ternary 1",
+                    };
+                    let (_, exp, (hi, lo)) = f.decode(false);
+                    let values = vec![
+                        Value::int("i32", exp),
+                        Value::hex("u128", hi),
+                        Value::hex("u128", lo),
+                    ];
+                    (values, format!("10^{k}: {note}"))
+                })
+                .collect();
+            tw.value_array(&name, &rows);
+        }
+        Table::CachedPowers => {
+            let (k_min, k_max) = (args.k_min, args.k_max);
+            assert!(k_min <= k_max, "Empty range {k_min}..={k_max}");
+            let b = args.base.value();
+            let name = args.name.unwrap_or_else(|| format!("CACHED_POW{b}"));
+            tw.comment(&format!(
+                "{b}^k ≈ (hi·2^128 + lo)·2^exp = {name}[k - ({k_min})] for \
+                 k in {k_min}..={k_max}, 2^255 ≤ hi·2^128 + lo < 2^256"
+            ));
+            let mut worst = Rational::new();
+            let mut rows = Vec::new();
+            for k in k_min..=k_max {
+                let (e, m, err) = cached_power(b, k);
+                let bound = upper_bound(&Float::with_val(64, &err).abs());
+                let note = match err.cmp0() {
+                    Ordering::Less => {
+                        format!("rounded down, |error| ≤ {bound} ulp")
+                    }
+                    Ordering::Equal => "exact".to_string(),
+                    Ordering::Greater => {
+                        format!("rounded up, |error| ≤ {bound} ulp")
+                    }
+                };
+                worst = worst.max(err.abs());
+                let hi = Integer::from(&m >> 128);
+                let lo = m.keep_bits(128);
+                let values = vec![
+                    Value::int("i32", e),
+                    Value::hex("u128", hi),
+                    Value::hex("u128", lo),
+                ];
+                rows.push((values, format!("{b}^{k}: {note}")));
+            }
+            tw.comment(&format!(
+                "max |error| ≤ {} ulp",
+                upper_bound(&Float::with_val(64, &worst))
+            ));
+            tw.value_array(&name, &rows);
+        }
+        Table::Lanczos => {
+            let wp = 4 * prec.max(P);
+            let g = parse_float(&args.g, wp);
+            let (lo, hi) = args.interval(wp, ("1", "64"));
+            assert!(lo > 0, "Domain must be positive");
+            let c = lanczos(&g, args.degree);
+            // relative error, sampled at 256 points
+            let steps = 255_u32;
+            let worst = (0..=steps).fold(Float::with_val(64, 0), |acc, i| {
+                let x = Float::with_val(wp, &hi - &lo) * i / steps + &lo;
+                let gamma = Float::with_val(wp, x.gamma_ref());
+                let err = (lanczos_gamma(&c, &g, &x) / gamma - 1_u32).abs();
+                acc.max(&Float::with_val(64, err))
+            });
+            let (lo, hi) = args.bounds(("1", "64"));
+            let name =
+                args.name.clone().unwrap_or_else(|| "LANCZOS_COEFFS".into());
+            tw.comment(&format!(
+                "Γ(z + 1) ≈ √(2π)·t^(z + ½)·e^-t·({name}[0] + Σ {name}[k] / \
+                 (z + k)), t = z + g + ½, k = 1..={}",
+                args.degree
+            ));
+            tw.comment(&format!(
+                "max |Γ(x) / approx - 1| for x ∈ [{lo}, {hi}], sampled at \
+                 {} points: 2^{:.2}",
+                steps + 1,
+                worst.log2().to_f64()
+            ));
+            tw.constant("LANCZOS_G", &g);
+            tw.array(&name, &c);
+        }
+        Table::Stirling => {
+            let (lo, _) = args.bounds(("8", ""));
+            let z = parse_float(lo, wp);
+            assert!(z > 0, "Domain must be positive");
+            let (coeffs, next) = stirling(args.degree);
+            let bound = Float::with_val(wp, next).abs()
+                / z.pow(2 * args.degree as u32 + 1);
+            let name = args
+                .name
+                .clone()
+                .unwrap_or_else(|| "STIRLING_COEFFS".into());
+            tw.comment(&format!(
+                "ln Γ(z) ≈ (z - ½)·ln z - z + ½·ln(2π) + Σ {name}[k-1] · \
+                 z^-(2k-1), k = 1..={}",
+                args.degree
+            ));
+            tw.comment(&format!(
+                "|error| ≤ {} for real z ≥ {lo}",
+                upper_bound(&bound)
+            ));
+            let coeffs: Vec<(Float, String)> = coeffs
+                .iter()
+                .map(|c| (Float::with_val(wp, c), format!(" = {c}")))
+                .collect();
+            tw.annotated_array(&name, &coeffs);
+        }
+        Table::Bernoulli => {
+            let (bs, coeffs) = euler_maclaurin(args.degree);
+            tw.comment(&format!(
+                "B₂ₖ ≈ BERNOULLI_HI[k-1] + BERNOULLI_LO[k-1], k = 1..={}",
+                args.degree
+            ));
+            tw.comment(
+                "Σ f(n), n ≥ N ≈ ∫ f(x) dx over [N, ∞) + f(N) / 2 - Σ \
+                 EULER_MACLAURIN[k-1] · f⁽²ᵏ⁻¹⁾(N) with EULER_MACLAURIN = \
+                 EULER_MACLAURIN_HI + EULER_MACLAURIN_LO = B₂ₖ / (2k)!",
+            );
+            for (name, values) in
+                [("BERNOULLI", bs), ("EULER_MACLAURIN", coeffs)]
+            {
+                let (hi, lo): (Vec<(Float, String)>, Vec<Float>) = values
+                    .iter()
+                    .map(|r| {
+                        let (hi, lo) = split_rational(r, prec);
+                        ((hi, format!(" ≈ {r}")), lo)
+                    })
+                    .unzip();
+                tw.annotated_array(&format!("{name}_HI"), &hi);
+                tw.array(&format!("{name}_LO"), &lo);
+            }
+        }
+        Table::Erf => {
+            // erfc is fitted scaled by e^(x²), which keeps the relative
+            // error under control where erfc decays
+            let fitted = match args.func {
+                Func::Erf | Func::Erfcx => args.func,
+                Func::Erfc => Func::Erfcx,
+                f => panic!(
+                    "Erf table of {} not supported, use --func erf, erfc or \
+                     erfcx",
+                    f.name()
+                ),
+            };
+            let breaks: Vec<Float> =
+                args.breaks.iter().map(|b| parse_float(b, wp)).collect();
+            assert!(breaks.len() >= 2, "At least two breaks needed");
+            assert!(
+                breaks.windows(2).all(|w| w[0] < w[1]),
+                "Breaks must be increasing"
+            );
+            let prefix = args
+                .name
+                .clone()
+                .unwrap_or_else(|| args.func.name().to_uppercase());
+            let samples = 256;
+            let segments: Vec<(Vec<Float>, Float)> = breaks
+                .windows(2)
+                .map(|w| {
+                    erf_segment(
+                        fitted,
+                        &w[0],
+                        &w[1],
+                        args.degree,
+                        prec,
+                        samples,
+                        wp,
+                    )
+                })
+                .collect();
+            let worst = segments
+                .iter()
+                .fold(Float::with_val(64, 0), |acc, (_, e)| acc.max(e));
+            tw.meta("func", &fitted.name());
+            tw.meta("breaks", &args.breaks.join(","));
+            tw.comment(&format!(
+                "Chebyshev coefficients of {}(x), degree {}, over \
+                 [{prefix}_BREAKS[k], {prefix}_BREAKS[k + 1]] in {prefix}_k",
+                fitted.name(),
+                args.degree
+            ));
+            if fitted == Func::Erfcx {
+                tw.comment("erfc(x) = erfcx(x)·e^(-x²)");
+            }
+            tw.comment(
+                "x = (hi + lo) / 2 + t·(hi - lo) / 2, t ∈ [-1, 1], \
+                 evaluated by Clenshaw's recurrence",
+            );
+            tw.comment(&format!(
+                "error in ulps of {}(x) of the rounded coefficients, \
+                 sampled at {} points per segment, max {}",
+                fitted.name(),
+                samples + 1,
+                upper_bound(&worst)
+            ));
+            tw.array(&format!("{prefix}_BREAKS"), &breaks);
+            for (k, ((coeffs, err), b)) in
+                segments.iter().zip(args.breaks.windows(2)).enumerate()
+            {
+                tw.comment(&format!(
+                    "[{}, {}]: error ≤ {} ulp",
+                    b[0],
+                    b[1],
+                    upper_bound(err)
+                ));
+                tw.array(&format!("{prefix}_{k}"), coeffs);
+            }
+        }
+        Table::Taylor => {
+            let (lo, hi) = args.interval(wp, ("-1", "1"));
+            let r = Float::with_val(wp, lo.abs_ref()).max(&hi.as_abs());
+            let degree = args.degree as u32;
+            // only the non-zero terms are emitted, each annotated with the
+            // error of the series truncated after it
+            let terms: Vec<(u32, Float)> = (0..=degree)
+                .map(|k| (k, args.func.taylor_coeff(k, wp)))
+                .filter(|(_, c)| !c.is_zero())
+                .collect();
+            let powers: Vec<String> =
+                terms.iter().map(|(k, _)| k.to_string()).collect();
+            tw.meta("func", &args.func.name());
+            tw.meta("powers", &powers.join(","));
+            let terms: Vec<(Float, String)> = terms
+                .into_iter()
+                .map(|(k, c)| {
+                    let bound = args.func.taylor_remainder(k, &r, wp);
+                    (c, format!(" (x^{k}), |R| ≤ {}", upper_bound(&bound)))
+                })
+                .collect();
+            let name = args.name.clone().unwrap_or_else(|| {
+                format!("TAYLOR_{}", args.func.name().to_uppercase())
+            });
+            tw.comment(&format!(
+                "Taylor coefficients of {}(x) around 0 up to degree {}",
+                args.func.name(),
+                args.degree
+            ));
+            let (lo, hi) = args.bounds(("-1", "1"));
+            tw.meta("lo", lo);
+            tw.meta("hi", hi);
+            tw.comment(&format!(
+                "|R|: truncation error for x ∈ [{lo}, {hi}] when truncated \
+                 after the term"
+            ));
+            tw.annotated_array(&name, &terms);
+        }
+    }
+    stdout().write_all(&tw.finish()).unwrap();
+}
+
+#[cfg(test)]
+mod calc_tables_tests {
+    use super::*;
+    use crate::{HI_EXP_MASK, HI_FRACTION_MASK, PM1};
+
+    #[test]
+    fn test_chebyshev() {
+        // cⱼ of exp over [-1, 1] are 2·Iⱼ(1), c₀ = I₀(1)
+        let (lo, hi) = (Float::with_val(474, -1), Float::with_val(474, 1));
+        let coeffs = chebyshev(Func::Exp, &lo, &hi, 3, 40, 474);
+        let exp = [
+            "1.266065877752008335598244625214717537607670311354962206808",
+            "1.130318207984970054415392055219726614657799243242184018960",
+            "0.271495339534076562365705139989981845899742136225556375695",
+        ];
+        for (c, e) in coeffs.iter().zip(exp) {
+            let e = Float::with_val(474, Float::parse(e).unwrap());
+            assert!((c.clone() - e).abs() < 1e-55, "{c}");
+        }
+    }
+
+    #[test]
+    fn test_clenshaw() {
+        // T₀ + 2·T₁ + 3·T₂ = 1 + 2t + 3·(2t² - 1)
+        let c: Vec<Float> =
+            (1..=3).map(|j| Float::with_val(128, j)).collect();
+        let t = Float::with_val(128, 0.5);
+        assert_eq!(clenshaw(&c, &t), 0.5);
+    }
+
+    #[test]
+    fn test_erf_segment() {
+        let (lo, hi) = (Float::with_val(474, 0), Float::with_val(474, 1));
+        let (coeffs, err) = erf_segment(Func::Erf, &lo, &hi, 24, 53, 64, 474);
+        assert_eq!(coeffs.len(), 25);
+        assert!(coeffs.iter().all(|c| c.prec() == 53));
+        assert!(err < 2, "{err}");
+        // scaled erfc stays within a few ulps where erfc is tiny
+        let (lo, hi) = (Float::with_val(474, 4), Float::with_val(474, 6));
+        let (_, err) = erf_segment(Func::Erfcx, &lo, &hi, 24, 53, 64, 474);
+        assert!(err < 2, "{err}");
+        // a too short series shows up in the error
+        let (_, err) = erf_segment(Func::Erf, &lo, &hi, 2, 53, 64, 474);
+        assert!(err > 1000, "{err}");
+    }
+
+    #[test]
+    fn test_layout() {
+        let consts = layout();
+        let get = |name: &str| {
+            consts.iter().find(|(n, _)| *n == name).unwrap().1.to_rust()
+        };
+        assert_eq!(get("EXP_BITS"), "19");
+        assert_eq!(get("FRACTION_BITS"), "236");
+        assert_eq!(get("DIGITS"), "71");
+        assert_eq!(get("MIN_10_EXP"), "-78912");
+        assert_eq!(get("MAX_10_EXP"), "78913");
+        assert_eq!(get("HI_EXP_MASK"), format!("0x{:032x}", HI_EXP_MASK));
+        assert_eq!(
+            get("HI_FRACTION_MASK"),
+            format!("0x{:032x}", HI_FRACTION_MASK)
+        );
+    }
+
+    #[test]
+    fn test_taylor() {
+        let c = Func::Sin.taylor_coeff(3, 474);
+        assert_eq!(c, Float::with_val(474, -1) / 6);
+        assert!(Func::Cos.taylor_coeff(3, 474).is_zero());
+        assert_eq!(
+            Func::Cos.taylor_coeff(4, 474),
+            Float::with_val(474, 24).recip()
+        );
+        assert_eq!(Func::Ln1p.taylor_coeff(2, 474), -0.5);
+        // |exp(x) - (1 + x)| < e^0.5 · 0.5² / 2 on [-0.5, 0.5]
+        let r = Float::with_val(474, 0.5);
+        let bound = Func::Exp.taylor_remainder(1, &r, 474);
+        let exp = Float::with_val(474, r.exp_ref()) / 8;
+        assert_eq!(bound, exp);
+        let err = Float::with_val(474, r.exp_ref()) - 1.5;
+        assert!(err < bound);
+    }
+
+    #[test]
+    fn test_recip_seeds() {
+        let seeds = recip_seeds(2, 8);
+        let vals: Vec<u32> =
+            seeds.iter().map(|(s, _)| s.to_u32().unwrap()).collect();
+        assert_eq!(vals, [228, 186, 158, 137]);
+        // 1.25·228/256 - 1 at the upper end of [1, 1.25)
+        assert_eq!(seeds[0].1, Float::with_val(64, 29) / 256);
+        // (b - a) / (a + b) < 2⁻⁹ plus the rounding of the seeds
+        let bound = Float::with_val(64, Float::i_exp(1, -9))
+            + Float::with_val(64, Float::i_exp(1, -16));
+        for (_, err) in recip_seeds(8, 16) {
+            assert!(err < bound, "{err}");
+        }
+    }
+
+    #[test]
+    fn test_rsqrt_seeds() {
+        let seeds = rsqrt_seeds(1, 8);
+        let vals: Vec<u32> =
+            seeds.iter().map(|(s, _)| s.to_u32().unwrap()).collect();
+        // 2^9 / (1 + √2) and 2^9 / (√2 + 2)
+        assert_eq!(vals, [212, 150]);
+        // (√b - √a) / (√a + √b) < 2⁻⁹ plus the rounding of the seeds
+        let bound = Float::with_val(64, Float::i_exp(1, -9))
+            + Float::with_val(64, Float::i_exp(1, -16));
+        for (_, err) in rsqrt_seeds(8, 16) {
+            assert!(err < bound, "{err}");
+        }
+    }
+
+    #[test]
+    fn test_exp2_segments() {
+        let segments = exp2_segments(2);
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[0].1.f, 1);
+        assert!(segments[0].2.f.is_zero());
+        // 2^(2/4) = √2
+        let sqrt_2 = Float::with_val(4 * P, 2).sqrt();
+        let [hi, lo]: [FP237; 2] =
+            FP237::split_hi_lo(&sqrt_2, 2).try_into().unwrap();
+        assert_eq!((&segments[2].1, &segments[2].2), (&hi, &lo));
+    }
+
+    #[test]
+    fn test_log_segments() {
+        let segments = ln_segments(1);
+        assert!(segments[0].1.f.is_zero() && segments[0].2.f.is_zero());
+        // ln(1.5)
+        let f = Float::with_val(4 * P, 1.5).ln();
+        assert_eq!(segments[1].0, f);
+        assert_eq!(segments[1].1.f, Float::with_val(P, &f));
+        // log₂(1 + 2/4) = log₂(3) - 1
+        let segments = log2_segments(2);
+        let f = Float::with_val(4 * P, 3).log2() - 1;
+        let rest = Float::with_val(4 * P, &f - &segments[2].1.f);
+        let rest = rest - &segments[2].2.f;
+        assert!(rest.abs() < Float::with_val(P, Float::i_exp(1, -2 * PM1)));
+    }
+
+    #[test]
+    fn test_hyperbolic() {
+        let mut exp: Vec<u32> = (1..=14).collect();
+        exp.insert(13, 13);
+        exp.insert(4, 4);
+        assert_eq!(hyperbolic_indices(14), exp);
+        let k = hyperbolic_gain(128, &hyperbolic_indices(64));
+        let k_h = Float::with_val(64, 0.828159360960215_f64);
+        assert!((k - k_h).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_args() {
+        use clap::CommandFactory;
+        Args::command().debug_assert();
+    }
+
+    #[test]
+    fn test_cordic_residuals() {
+        let atans: Vec<Float> = (0..3)
+            .map(|i| Float::with_val(64, Float::i_exp(1, -i)).atan())
+            .collect();
+        let residuals = cordic_residuals(&atans);
+        assert_eq!(residuals.len(), 4);
+        assert_eq!(residuals[3], atans[2]);
+        assert_eq!(residuals[2], Float::with_val(64, &atans[2] * 2));
+        let sum = atans.iter().fold(Float::with_val(64, 0), |s, a| s + a);
+        let diff = Float::with_val(64, &residuals[0] - sum) - &atans[2];
+        assert!(diff.abs() < 1e-18);
+    }
+
+    #[test]
+    fn test_pow10() {
+        // 5¹⁰² < 2²³⁷ < 5¹⁰³
+        let f = pow10(102);
+        assert_eq!(f.o, Ordering::Equal);
+        assert_eq!(f.f, Float::with_val(4 * P, 10).pow(102));
+        assert_ne!(pow10(103).o, Ordering::Equal);
+        let f = pow10(-1);
+        assert_ne!(f.o, Ordering::Equal);
+        assert_eq!(f.f, Float::with_val(P, Float::parse("0.1").unwrap()));
+        assert_eq!(pow10(0).decode(false), (0, -PM1, (1 << 108, 0)));
+    }
+
+    #[test]
+    fn test_cached_power() {
+        let one = Integer::from(1);
+        assert_eq!(
+            cached_power(5, 0),
+            (-255, one.clone() << 255, Rational::new())
+        );
+        assert_eq!(
+            cached_power(10, 1),
+            (-252, Integer::from(5) << 253, Rational::new())
+        );
+        // 5⁻¹ = 2²⁵⁸/5 · 2⁻²⁵⁸ with 2²⁵⁸ ≡ 4 mod 5, i.e. rounded up
+        let (e, m, err) = cached_power(5, -1);
+        assert_eq!(e, -258);
+        assert_eq!(m, (one.clone() << 258) / 5 + 1);
+        assert_eq!(err, Rational::from((1, 5)));
+        for k in -300..=300 {
+            let (e, m, err) = cached_power(10, k);
+            assert_eq!(m.significant_bits(), 256, "{k}");
+            assert!(err.clone().abs() <= Rational::from((1, 2)), "{k}");
+            let exact = if k < 0 {
+                Rational::from((1, Integer::from(10).pow(-k as u32)))
+            } else {
+                Rational::from(Integer::from(10).pow(k as u32))
+            };
+            let approx = if e < 0 {
+                Rational::from((m, one.clone() << -e as u32))
+            } else {
+                Rational::from(m << e as u32)
+            };
+            assert_eq!(approx - exact, err * Rational::from(2).pow(e), "{k}");
+        }
+    }
+
+    #[test]
+    fn test_lanczos() {
+        let g = Float::with_val(256, 4.7421875);
+        let c = lanczos(&g, 14);
+        // exact at the fitted points
+        let x = Float::with_val(256, 6);
+        let err = (lanczos_gamma(&c, &g, &x) - 120_u32).abs();
+        assert!(err < 1e-60, "{err}");
+        // Γ(½) = √π
+        let x = Float::with_val(256, 0.5);
+        let sqrt_pi = Float::with_val(256, Constant::Pi).sqrt();
+        let err = (lanczos_gamma(&c, &g, &x) / sqrt_pi - 1_u32).abs();
+        assert!(err < 1e-14, "{err}");
+    }
+
+    #[test]
+    fn test_stirling() {
+        let bs = bernoulli(4);
+        let exp = [(1, 1), (-1, 2), (1, 6), (0, 1), (-1, 30)];
+        assert_eq!(bs, exp.map(Rational::from));
+        let (coeffs, next) = stirling(2);
+        assert_eq!(
+            coeffs,
+            [Rational::from((1, 12)), Rational::from((-1, 360))]
+        );
+        assert_eq!(next, Rational::from((1, 1260)));
+    }
+
+    #[test]
+    fn test_euler_maclaurin() {
+        let (bs, coeffs) = euler_maclaurin(3);
+        let exp = [(1, 6), (-1, 30), (1, 42)];
+        assert_eq!(bs, exp.map(Rational::from));
+        let exp = [(1, 12), (-1, 720), (1, 30240)];
+        assert_eq!(coeffs, exp.map(Rational::from));
+        let (hi, lo) = split_rational(&bs[0], P);
+        assert_eq!(hi, Float::with_val(P, &bs[0]));
+        let rest = &bs[0] - hi.to_rational().unwrap();
+        let rest = rest - lo.to_rational().unwrap();
+        // |rest| ≤ ½ ulp(lo)
+        let ulp =
+            Float::with_val(P, Float::i_exp(1, lo.get_exp().unwrap())) >> P;
+        assert!(rest.abs() <= ulp.to_rational().unwrap() / 2);
+    }
+}
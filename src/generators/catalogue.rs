@@ -0,0 +1,409 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Machine-readable description of the operations the generators produce
+//! test data for, printed by `rug237 --list-ops`.
+//!
+//! Each column group is given by its name and its kind:
+//!
+//! * fp: reduced decode tuple, four integral columns sign, exp, hi, lo
+//! * fp_unreduced: non-reduced decode tuple, four integral columns
+//! * bits: bit pattern of an f256, two integral columns hi, lo
+//! * int, token, str: a single column
+//!
+//! The columns of a corpus are the operands, followed by the expected
+//! results, followed by the additional data. With `per_mode` set and the
+//! rounding of the corpus being all, the expected results and the
+//! additional data are given for each rounding mode in the order ne tz up
+//! dn na.
+
+use crate::record::json_quoted;
+
+type Columns = &'static [(&'static str, &'static str)];
+
+/// Schema of the test data written by a generator for some operations.
+struct Entry {
+    command: &'static str,
+    ops: &'static [&'static str],
+    rounding: &'static [&'static str],
+    per_mode: bool,
+    test_item: bool,
+    operands: Columns,
+    expected: Columns,
+    meta: Columns,
+    note: &'static str,
+}
+
+const MODES: &[&str] = &["ne", "tz", "up", "dn", "na"];
+const ALL: &[&str] = &["all"];
+const NE: &[&str] = &["ne"];
+
+const X: Columns = &[("x", "fp")];
+const XY: Columns = &[("x", "fp"), ("y", "fp")];
+const XYA: Columns = &[("x", "fp"), ("y", "fp"), ("a", "fp")];
+const Z: Columns = &[("z", "fp")];
+const NONE: Columns = &[];
+
+/// Returns an entry for test items with operands `operands` and expected
+/// results `expected`, rounded to nearest, ties to even.
+const fn item(
+    command: &'static str,
+    ops: &'static [&'static str],
+    operands: Columns,
+    expected: Columns,
+) -> Entry {
+    Entry {
+        command,
+        ops,
+        rounding: NE,
+        per_mode: false,
+        test_item: true,
+        operands,
+        expected,
+        meta: NONE,
+        note: "",
+    }
+}
+
+impl Entry {
+    const fn rounding(mut self, rounding: &'static [&'static str]) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    const fn per_mode(mut self) -> Self {
+        self.per_mode = true;
+        self
+    }
+
+    const fn record(mut self) -> Self {
+        self.test_item = false;
+        self
+    }
+
+    const fn meta(mut self, meta: Columns) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    const fn note(mut self, note: &'static str) -> Self {
+        self.note = note;
+        self
+    }
+
+    fn to_json(&self, op: &str) -> String {
+        let strs = |ss: &[&str]| {
+            let elems: Vec<String> =
+                ss.iter().map(|s| json_quoted(s)).collect();
+            format!("[{}]", elems.join(","))
+        };
+        let columns = |cols: Columns| {
+            let elems: Vec<String> = cols
+                .iter()
+                .map(|(name, kind)| {
+                    format!(
+                        "{{\"name\":{},\"kind\":{}}}",
+                        json_quoted(name),
+                        json_quoted(kind)
+                    )
+                })
+                .collect();
+            format!("[{}]", elems.join(","))
+        };
+        let mut res = format!(
+            "{{\"op\":{},\"command\":{},\"arity\":{},\"rounding\":{},\"\
+             per_mode\":{},\"test_item\":{},\"operands\":{},\"expected\":{},\
+             \"meta\":{}",
+            json_quoted(op),
+            json_quoted(self.command),
+            self.operands.len(),
+            strs(self.rounding),
+            self.per_mode,
+            self.test_item,
+            columns(self.operands),
+            columns(self.expected),
+            columns(self.meta)
+        );
+        if !self.note.is_empty() {
+            res += &format!(",\"note\":{}", json_quoted(self.note));
+        }
+        res + "}"
+    }
+}
+
+const ENTRIES: &[Entry] = &[
+    item("addsub", &["add"], XY, Z).rounding(MODES),
+    item("mul", &["mul"], XY, Z).rounding(MODES),
+    item("div", &["div"], XY, Z).rounding(MODES),
+    item("rem", &["rem"], XY, Z).rounding(MODES),
+    item(
+        "euclid",
+        &["div_rem_euclid"],
+        XY,
+        &[("q", "fp"), ("r", "fp")],
+    ),
+    item("remquo", &["remquo"], XY, &[("r", "fp")]).meta(&[("q", "int")]),
+    item("sqrt", &["sqrt"], X, Z)
+        .rounding(&["ne", "tz", "up", "dn", "na", "all"])
+        .per_mode(),
+    item("cbrt", &["cbrt"], X, Z),
+    item("fma", &["fma"], XYA, Z).rounding(MODES),
+    item(
+        "fms-fnma",
+        &["fms_fnma_fnms"],
+        XYA,
+        &[("fms", "fp"), ("fnma", "fp"), ("fnms", "fp")],
+    ),
+    item("sos", &["sos"], XY, Z),
+    item("hypot", &["hypot"], XY, Z),
+    item("dot", &["dot"], XY, Z)
+        .note("x and y are repeated for each of the terms"),
+    item(
+        "linsys",
+        &["solve"],
+        &[("a", "fp"), ("b", "fp")],
+        &[("x", "fp")],
+    )
+    .note(
+        "a is repeated n*n times row by row, b and x n times, n given by \
+         --dim",
+    ),
+    item(
+        "two-sum",
+        &["two_sum", "two_prod"],
+        XY,
+        &[("hi", "fp"), ("lo", "fp")],
+    ),
+    item("powi", &["powi"], X, Z).meta(&[("n", "int")]),
+    item("trig", &["sin", "cos", "tan", "cot"], X, Z),
+    item(
+        "trig-reduction",
+        &["reduce_pi_2"],
+        X,
+        &[("hi", "fp"), ("lo", "fp"), ("sin", "fp"), ("cos", "fp")],
+    )
+    .meta(&[("k", "int")]),
+    item("log", &["ln", "log2", "log10", "ln_1p"], X, Z),
+    item(
+        "expm1-ln1p",
+        &["exp_m1", "ln_1p"],
+        X,
+        &[("naive", "fp"), ("fused", "fp")],
+    ),
+    item("gamma", &["gamma", "ln_gamma"], X, Z),
+    item("frexp-ldexp", &["frexp"], X, &[("m", "fp")]).meta(&[("e", "int")]),
+    item("frexp-ldexp", &["ldexp"], X, Z).meta(&[
+        ("n", "int"),
+        ("overflow", "int"),
+        ("underflow", "int"),
+        ("inexact", "int"),
+    ]),
+    item("frexp-ldexp", &["ilogb"], X, NONE)
+        .meta(&[("e", "int"), ("invalid", "int")]),
+    item("frexp-ldexp", &["logb"], X, Z).meta(&[("div_by_zero", "int")]),
+    item(
+        "round-to-int",
+        &[
+            "trunc",
+            "floor",
+            "ceil",
+            "round",
+            "round_ties_even",
+            "fract",
+        ],
+        X,
+        Z,
+    ),
+    item("rounding-mode", &["add", "sub", "mul", "div"], XY, Z)
+        .rounding(ALL)
+        .per_mode(),
+    item("rounding-mode", &["sqrt"], X, Z)
+        .rounding(ALL)
+        .per_mode(),
+    item("rounding-mode", &["fma"], XYA, Z)
+        .rounding(ALL)
+        .per_mode(),
+    item("subnormal-edge", &["add", "sub", "mul", "div"], XY, Z)
+        .rounding(MODES),
+    item("subnormal-edge", &["fma"], XYA, Z).rounding(MODES),
+    item("overflow-underflow", &["add", "sub", "mul", "div"], XY, Z)
+        .rounding(ALL)
+        .per_mode()
+        .meta(&[
+            ("overflow", "int"),
+            ("underflow", "int"),
+            ("inexact", "int"),
+        ]),
+    item("overflow-underflow", &["fma"], XYA, Z)
+        .rounding(ALL)
+        .per_mode()
+        .meta(&[
+            ("overflow", "int"),
+            ("underflow", "int"),
+            ("inexact", "int"),
+        ]),
+    item(
+        "copysign",
+        &["copysign"],
+        &[("x", "bits"), ("y", "bits")],
+        &[("z", "bits")],
+    )
+    .record(),
+    item(
+        "copysign",
+        &["signum", "abs"],
+        &[("x", "bits")],
+        &[("z", "bits")],
+    )
+    .record(),
+    item("raw-bits", &["classify"], &[("x", "bits")], NONE)
+        .record()
+        .meta(&[
+            ("category", "token"),
+            ("sign", "int"),
+            ("signaling", "int"),
+            ("canonical", "bits"),
+        ]),
+    item(
+        "raw-bits",
+        &["total_cmp"],
+        &[("x", "bits"), ("y", "bits")],
+        &[("ord", "int")],
+    )
+    .record(),
+    item(
+        "f64-f32",
+        &["to_f64", "to_f32"],
+        &[("x", "bits")],
+        &[("z", "int")],
+    )
+    .rounding(ALL)
+    .per_mode()
+    .record()
+    .note("z is the bit pattern of the f64 or f32"),
+    item(
+        "f64-f32",
+        &["from_f64", "from_f32"],
+        &[("f", "int")],
+        &[("x", "bits")],
+    )
+    .rounding(ALL)
+    .record()
+    .note("f is the bit pattern of the f64 or f32, converted exactly"),
+    item("int-conv", &["to_int"], X, NONE)
+        .rounding(ALL)
+        .meta(&[
+            ("rounding", "token"),
+            ("i", "int"),
+            ("invalid", "int"),
+            ("inexact", "int"),
+        ])
+        .note("one row per rounding mode, i is - if out of range"),
+    item("int-conv", &["from_int"], NONE, Z)
+        .rounding(ALL)
+        .meta(&[("i", "int"), ("rounding", "token"), ("inexact", "int")])
+        .note("one row per rounding mode"),
+    item("parse", &["from_str"], NONE, &[("x", "fp")])
+        .meta(&[("lit", "str")]),
+    item("format", &["to_string"], X, NONE).meta(&[("lit", "str")]),
+    item("format-sci", &["to_sci"], &[("x", "fp_unreduced")], NONE)
+        .meta(&[("p", "int"), ("lit", "str")]),
+    item("fixed", &["to_fixed"], &[("x", "fp_unreduced")], NONE)
+        .meta(&[("p", "int"), ("lit", "str")]),
+    item("shortest", &["to_shortest_string"], X, NONE)
+        .meta(&[("lit", "str")]),
+    item("str-round-trip", &["from_str"], NONE, &[("x", "fp")]).meta(&[
+        ("lit", "str"),
+        ("shortest", "str"),
+        ("exact", "str"),
+    ]),
+    item(
+        "complex",
+        &["add", "sub", "mul", "div"],
+        &[
+            ("x_re", "fp"),
+            ("x_im", "fp"),
+            ("y_re", "fp"),
+            ("y_im", "fp"),
+        ],
+        &[("z_re", "fp"), ("z_im", "fp")],
+    ),
+    item("complex", &["abs"], &[("x_re", "fp"), ("x_im", "fp")], Z),
+    item(
+        "complex",
+        &["sqrt"],
+        &[("x_re", "fp"), ("x_im", "fp")],
+        &[("z_re", "fp"), ("z_im", "fp")],
+    ),
+    item("constants", &["constants"], NONE, NONE)
+        .record()
+        .meta(&[("name", "token"), ("f", "fp_unreduced"), ("f", "fp")]),
+    item(
+        "search-hard-cases",
+        &[
+            "sqrt", "cbrt", "ln", "log2", "log10", "ln_1p", "exp", "exp_m1",
+            "sin", "cos", "tan", "cot",
+        ],
+        X,
+        Z,
+    )
+    .meta(&[("log2_distance", "token"), ("boundary", "token")])
+    .note(
+        "distance of the exact result from the closest rounding boundary in \
+         ulps",
+    ),
+    item("exp2-table", &["exp2_table"], NONE, NONE)
+        .record()
+        .meta(&[("k", "int"), ("hi", "fp"), ("lo", "fp")])
+        .note("2^(k/2^m) = hi + lo, m given by --index-bits"),
+];
+
+/// Returns the catalogue of operations as JSON array, one object per
+/// operation and generator.
+pub fn to_json() -> String {
+    let elems: Vec<String> = ENTRIES
+        .iter()
+        .flat_map(|entry| entry.ops.iter().map(|op| entry.to_json(op)))
+        .collect();
+    format!("[\n{}\n]", elems.join(",\n"))
+}
+
+#[cfg(test)]
+mod catalogue_tests {
+    use super::*;
+    use crate::{
+        generators::oracle::arity as arity_of,
+        reader::{parse_json, Json},
+    };
+
+    #[test]
+    fn test_to_json() {
+        let Ok(Json::Arr(ops)) = parse_json(&to_json()) else {
+            panic!("Not a JSON array");
+        };
+        assert!(ops.len() > ENTRIES.len());
+        for op in &ops {
+            let (Some(Json::Str(name)), Some(Json::Num(arity))) =
+                (op.get("op"), op.get("arity"))
+            else {
+                panic!("Missing op or arity");
+            };
+            let is_real = matches!(
+                op.get("command"),
+                Some(Json::Str(cmd)) if cmd != "complex"
+            );
+            assert!(matches!(op.get("per_mode"), Some(Json::Bool(_))));
+            if let Some(n) = arity_of(name) {
+                if is_real {
+                    assert_eq!(arity.parse::<usize>().unwrap(), n, "{name}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use clap::Parser;
+use rug::Float;
+
+use super::{
+    CommonArgs, ExpRangeArgs, NORMAL_EXP_RANGE, SUBNORMAL_EXP_RANGE,
+};
+use crate::{CorpusBuilder, Header, RoundingMode, FP237, P};
+
+// The root z of x rounded to nearest lies within half the distance to
+// its neighbours, so x lies between the cubes of the midpoints between z
+// and its neighbours. The midpoints have P + 1 significant bits, so their
+// cubes are exact at 3·(P + 1) bits. Roots of subnormal values are normal,
+// so the neighbours of z are never subnormal.
+fn check_test_item(x: &FP237, z: &FP237) {
+    if !z.f.is_normal() {
+        return;
+    }
+    let cube = |neighbour: &Float| {
+        let mid = Float::with_val(P + 1, neighbour + &z.f) / 2;
+        Float::with_val(3 * (P + 1), &mid * &mid) * &mid
+    };
+    let mut below = z.f.clone();
+    below.next_down();
+    let mut above = z.f.clone();
+    above.next_up();
+    assert!(
+        cube(&below) <= x.f && x.f <= cube(&above),
+        "Cube root {:?} of {:?} not rounded to nearest.",
+        z.decode(false),
+        x.decode(false)
+    );
+}
+
+/// Generates test data for the cube root.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    /// Add the boundary values of the exponent ranges
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    let normal_exp_range = args.exp.exp_range(&NORMAL_EXP_RANGE);
+    let subnormal_exp_range = args.exp.exp_range(&SUBNORMAL_EXP_RANGE);
+    let n_sub_normal = args.common.n_test_data / 100 + 1;
+    let n_normal = args.common.n_test_data - n_sub_normal;
+    let seed = args.common.seed();
+
+    let mut builder = CorpusBuilder::new(1, seed)
+        .sign(args.common.sign)
+        .random(n_normal, |rng| {
+            vec![FP237::random_from_exp_range_with_rng(
+                rng,
+                &normal_exp_range,
+            )]
+        })
+        .random(n_sub_normal, |rng| {
+            vec![FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            )]
+        });
+    if args.boundaries {
+        builder = builder
+            .boundaries(&normal_exp_range)
+            .boundaries(&subnormal_exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        builder = builder.verify(&[RoundingMode::NearestEven]);
+    }
+    let header = Header::new("cbrt")
+        .seed(seed)
+        .exp_ranges(&[&normal_exp_range, &subnormal_exp_range]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "cbrt", |ops| {
+            let x = &ops[0];
+            let z = x.cbrt();
+            check_test_item(x, &z);
+            Some(vec![z])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
+
+#[cfg(test)]
+mod cbrt_tests {
+    use super::*;
+
+    #[test]
+    fn test_check_test_item() {
+        for x in [FP237::MIN_GT_ZERO(), FP237::new(Float::with_val(P, -2))] {
+            check_test_item(&x, &x.cbrt());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not rounded to nearest")]
+    fn test_check_test_item_off() {
+        let x = FP237::new(Float::with_val(P, -2));
+        let mut z = x.cbrt().f;
+        z.next_up();
+        check_test_item(&x, &FP237::new(z));
+    }
+}
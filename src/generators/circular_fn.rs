@@ -0,0 +1,75 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use clap::Parser;
+use rug::{ops::CompleteRound, Float};
+
+use super::CommonArgs;
+use crate::{CorpusBuilder, Header, FP237, P, PM1};
+
+const EXP_UPPER_BOUND: i32 = 2 * PM1 + 14;
+const EXP_LOWER_BOUND: i32 = -PM1 / 2 - 4;
+
+/// Generates test data for the circular functions.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// circular function: sin cos tan cot
+    #[arg(short, long, default_value = "sin")]
+    func: String,
+    /// Range of input value f: C = 0..2π S = 2π..T L = T..
+    #[arg(long, default_value_t = 'C')]
+    range: char,
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+pub fn run(args: Args) {
+    let pi = Float::with_val(P + 1, rug::float::Constant::Pi);
+    let tau = FP237::new(Float::with_val(P, 2 * pi));
+    let lower_limit =
+        FP237::new(Float::u_exp(2, EXP_LOWER_BOUND).complete(P));
+    let fast_limit = FP237::new(Float::u_exp(2, 20).complete(P));
+    let upper_limit =
+        FP237::new(Float::u_exp(2, EXP_UPPER_BOUND).complete(P));
+
+    let func = match args.func.as_str() {
+        "sin" => FP237::sin,
+        "cos" => FP237::cos,
+        "tan" => FP237::tan,
+        "cot" => FP237::cot,
+        _ => panic!("Unkown func"),
+    };
+    let range = match args.range {
+        'C' => lower_limit..tau,
+        'S' => tau..fast_limit,
+        'L' => fast_limit..upper_limit,
+        _ => panic!("Unkown range"),
+    };
+    let exp_low = range.start.decode(false).1 + PM1;
+    let exp_high = range.end.decode(false).1 + PM1;
+    let exp_range = exp_low..=exp_high;
+
+    let seed = args.common.seed();
+
+    let header = Header::new(&args.func).seed(seed).exp_ranges(&[&exp_range]);
+    let mut out = args.common.output.writer(&header);
+    CorpusBuilder::new(1, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| loop {
+            let a = FP237::random_from_exp_range_with_rng(rng, &exp_range);
+            if range.contains(&a) {
+                break vec![a];
+            }
+        })
+        .jobs(args.common.jobs)
+        .write(&mut out, &args.func, |ops| Some(vec![func(&ops[0])]))
+        .unwrap();
+    out.finish().unwrap();
+}
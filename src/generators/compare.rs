@@ -0,0 +1,169 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{collections::BTreeMap, io::BufRead, path::PathBuf, process};
+
+use clap::{error::ErrorKind, Parser};
+use rug::Integer;
+
+use super::{
+    open_input,
+    oracle::{eval, parse_values, select},
+    usage_error,
+};
+use crate::{ulp_diff, DecodedFP, FP237};
+
+/// Returns the number of f256 values from `x` to `y`, or `None` if only one
+/// of them is NaN.
+fn ulp_distance(x: &FP237, y: &FP237) -> Option<Integer> {
+    if x.f.is_nan() && y.f.is_nan() {
+        Some(Integer::new())
+    } else {
+        ulp_diff(x, y).map(Integer::abs)
+    }
+}
+
+/// Returns the index of the histogram bucket for a distance of `d` ulps:
+/// 0 for 0, 1 for 1, k for 2ᵏ⁻² < d <= 2ᵏ⁻¹.
+fn bucket(d: &Integer) -> u32 {
+    if *d == 0 {
+        0
+    } else {
+        Integer::from(d - 1).significant_bits() + 1
+    }
+}
+
+/// Returns the range of distances covered by bucket `b`, given as powers of
+/// two for large distances.
+fn bucket_label(b: u32) -> String {
+    match b {
+        0..=2 => b.to_string(),
+        3..=32 => format!("{}-{}", (1_u64 << (b - 2)) + 1, 1_u64 << (b - 1)),
+        _ => format!("2^{}+1-2^{}", b - 2, b - 1),
+    }
+}
+
+/// Compares results computed by f256 to the results computed here.
+///
+/// Reads one line per test case from FILE (or stdin), holding the operands
+/// followed by the results, one per rounding mode, given as decode tuples
+/// or as literals. Empty lines and lines starting with `#` are skipped.
+/// Each mismatch is reported with its distance in ulps, followed by a
+/// histogram of the distances. Exits with status 1 if there are
+/// mismatches.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Operation: add sub mul div rem sqrt fma (all rounding modes), cbrt
+    /// hypot sos ln log2 log10 ln_1p exp exp_m1 sin cos tan cot gamma
+    /// ln_gamma (ne only)
+    #[arg(short, long)]
+    op: String,
+
+    /// Rounding mode of the results: ne tz up dn na, or all (one result
+    /// per mode, in this order)
+    #[arg(short, long, default_value = "ne")]
+    rounding: String,
+
+    /// File holding the test cases (default: stdin), decompressed if it
+    /// ends with .gz
+    file: Option<PathBuf>,
+}
+
+pub fn run(args: Args) {
+    let op = args.op.as_str();
+    let (arity, modes) = select(op, &args.rounding)
+        .unwrap_or_else(|e| usage_error(ErrorKind::InvalidValue, e));
+    let inp = open_input(args.file.as_deref());
+    let mut histogram = BTreeMap::<u32, u64>::new();
+    let (mut n_results, mut n_nan) = (0_u64, 0_u64);
+    for (n, line) in inp.lines().enumerate() {
+        let line = line.unwrap();
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let values = parse_values(line, arity + modes.len())
+            .unwrap_or_else(|e| panic!("Line {}: {e}", n + 1));
+        let (ops, results) = values.split_at(arity);
+        let operands: Vec<DecodedFP> =
+            ops.iter().map(DecodedFP::from).collect();
+        for (res, rnd) in results.iter().zip(&modes) {
+            n_results += 1;
+            let expected = eval(op, ops, *rnd);
+            let dist = ulp_distance(res, &expected);
+            if dist.as_ref().is_some_and(|d| *d == 0) {
+                *histogram.entry(0).or_default() += 1;
+                continue;
+            }
+            let dist = match dist {
+                Some(d) => {
+                    *histogram.entry(bucket(&d)).or_default() += 1;
+                    format!("{d} ulp")
+                }
+                None => {
+                    n_nan += 1;
+                    "NaN".to_string()
+                }
+            };
+            println!(
+                "line {}: {op}{operands:?} ({rnd}) = {:?}, expected {:?}: \
+                 {dist}",
+                n + 1,
+                DecodedFP::from(res),
+                DecodedFP::from(&expected)
+            );
+        }
+    }
+    let n_mismatches = n_results - histogram.get(&0).copied().unwrap_or(0);
+    println!("# {op}: {n_results} results, {n_mismatches} mismatches");
+    println!("# ulp\tcount");
+    for (b, count) in &histogram {
+        println!("# {}\t{count}", bucket_label(*b));
+    }
+    if n_nan > 0 {
+        println!("# NaN\t{n_nan}");
+    }
+    if n_mismatches > 0 {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod compare_tests {
+    use super::*;
+
+    #[test]
+    fn test_ulp_distance() {
+        let one = FP237::ONE();
+        let d = |x: &FP237, y: &FP237| ulp_distance(x, y).unwrap();
+        assert_eq!(d(&one, &one.next_up().next_up()), 2);
+        assert_eq!(d(&FP237::ZERO(), &-FP237::ZERO()), 1);
+        assert_eq!(d(&FP237::MIN_GT_ZERO(), &-FP237::MIN_GT_ZERO()), 3);
+        assert_eq!(d(&FP237::MAX(), &FP237::INFINITY()), 1);
+        assert_eq!(d(&FP237::NAN(), &FP237::NAN()), 0);
+        assert!(ulp_distance(&FP237::NAN(), &one).is_none());
+    }
+
+    #[test]
+    fn test_buckets() {
+        let labels: Vec<String> = [0, 1, 2, 3, 4, 5, 8, 9]
+            .iter()
+            .map(|d| bucket_label(bucket(&Integer::from(*d))))
+            .collect();
+        assert_eq!(
+            labels,
+            ["0", "1", "2", "3-4", "3-4", "5-8", "5-8", "9-16"]
+        );
+        assert_eq!(
+            bucket_label(bucket(&(Integer::from(1) << 40))),
+            "2^39+1-2^40"
+        );
+    }
+}
@@ -0,0 +1,111 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::Parser;
+use rand::prelude::*;
+
+use super::CommonArgs;
+use crate::{CorpusBuilder, Header, C237, FP237, PM1};
+
+// 2⁻¹⁰⁰⁰ <= |f| < 2¹⁰⁰⁰, keeping all results far from the range limits, so
+// that rounding each component to P bits gives the f256 result
+const EXP_RANGE: RangeInclusive<i32> = -1000..=999;
+// exponent differences of the components of a skewed operand
+const SKEW_EXP_RANGE: RangeInclusive<i32> = 100..=300;
+
+/// Returns the real and the imaginary part of `c`.
+fn components(c: &C237) -> Vec<FP237> {
+    vec![c.re(), c.im()]
+}
+
+/// Returns the real and the imaginary part of a random complex number,
+/// which is skewed, i.e. has one part far smaller than the other, if `skew`
+/// is true.
+fn random_parts(rng: &mut StdRng, skew: bool) -> Vec<FP237> {
+    let re = FP237::random_from_exp_range_with_rng(rng, &EXP_RANGE);
+    let im = if skew {
+        let (_, e, _) = re.decode(false);
+        let t =
+            (e + PM1 - rng.gen_range(SKEW_EXP_RANGE)).max(*EXP_RANGE.start());
+        FP237::random_from_exp_range_with_rng(rng, &(t..=t))
+    } else {
+        FP237::random_from_exp_range_with_rng(rng, &EXP_RANGE)
+    };
+    if skew && rng.gen() {
+        vec![im, re]
+    } else {
+        vec![re, im]
+    }
+}
+
+/// Generates test data for complex arithmetic.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Operation: add sub mul div abs sqrt
+    #[arg(short, long, default_value = "mul")]
+    op: String,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+pub fn run(args: Args) {
+    let op = args.op.as_str();
+    // each complex operand is given by its real and imaginary part
+    let arity = match op {
+        "abs" | "sqrt" => 2,
+        "add" | "sub" | "mul" | "div" => 4,
+        _ => panic!("Unkown op"),
+    };
+    let n_skewed = args.common.n_test_data / 4;
+    let n_random = args.common.n_test_data - n_skewed;
+    let seed = args.common.seed();
+
+    let gen = |rng: &mut StdRng, skew: bool| {
+        let mut parts = random_parts(rng, skew);
+        if arity == 4 {
+            let skew = skew && rng.gen();
+            parts.extend(random_parts(rng, skew));
+        }
+        parts
+    };
+    let builder = CorpusBuilder::new(arity, seed)
+        .sign(args.common.sign)
+        .random(n_random, |rng| gen(rng, false))
+        .random(n_skewed, |rng| gen(rng, true));
+    let header = Header::new(op)
+        .seed(seed)
+        .exp_ranges(&[&EXP_RANGE, &SKEW_EXP_RANGE]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, op, |ops| {
+            let x = C237::new(&ops[0], &ops[1]);
+            if arity == 2 {
+                return Some(match op {
+                    "abs" => vec![x.abs()],
+                    _ => components(&x.sqrt()),
+                });
+            }
+            let y = C237::new(&ops[2], &ops[3]);
+            let z = match op {
+                "add" => &x + &y,
+                "sub" => &x - &y,
+                "mul" => &x * &y,
+                _ => &x / &y,
+            };
+            // real and imaginary part of the result
+            Some(components(&z))
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
@@ -0,0 +1,159 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use clap::Parser;
+use rug::{float::Constant, Float};
+
+use super::OutputArgs;
+use crate::{Header, Record, RoundingMode, FP237, P};
+
+// working precision, leaving plenty of guard bits for the final rounding
+const WP: u32 = 4 * P;
+
+/// Returns the named mathematical constants of f256 at working precision.
+pub(crate) fn exact_math_constants() -> Vec<(&'static str, Float)> {
+    let pi = Float::with_val(WP, Constant::Pi);
+    let ln_2 = Float::with_val(WP, Constant::Log2);
+    let ln_10 = Float::with_val(WP, 10).ln();
+    let e = Float::with_val(WP, 1).exp();
+    let sqrt_2 = Float::with_val(WP, 2).sqrt();
+    let sqrt_pi = pi.clone().sqrt();
+    vec![
+        ("E", e.clone()),
+        ("PI", pi.clone()),
+        ("TAU", pi.clone() * 2),
+        ("FRAC_PI_2", pi.clone() / 2),
+        ("FRAC_PI_3", pi.clone() / 3),
+        ("FRAC_PI_4", pi.clone() / 4),
+        ("FRAC_PI_6", pi.clone() / 6),
+        ("FRAC_PI_8", pi.clone() / 8),
+        ("FRAC_1_PI", pi.clone().recip()),
+        ("FRAC_2_PI", 2 / pi.clone()),
+        ("FRAC_2_SQRT_PI", 2 / sqrt_pi),
+        ("SQRT_2", sqrt_2.clone()),
+        ("FRAC_1_SQRT_2", sqrt_2.recip()),
+        ("LN_2", ln_2.clone()),
+        ("LN_10", ln_10.clone()),
+        ("LOG2_E", ln_2.clone().recip()),
+        ("LOG2_10", ln_10.clone() / &ln_2),
+        ("LOG10_E", ln_10.clone().recip()),
+        ("LOG10_2", ln_2 / &ln_10),
+        ("EULER_GAMMA", Float::with_val(WP, Constant::Euler)),
+        ("CATALAN", Float::with_val(WP, Constant::Catalan)),
+    ]
+}
+
+/// Returns the named mathematical constants of f256, each rounded to
+/// nearest from its value at working precision.
+fn math_constants() -> Vec<(&'static str, FP237)> {
+    exact_math_constants()
+        .into_iter()
+        .map(|(name, f)| {
+            (name, FP237::with_val_round(f, RoundingMode::NearestEven))
+        })
+        .collect()
+}
+
+/// Returns the limits of f256.
+fn limits() -> Vec<(&'static str, FP237)> {
+    let epsilon = FP237::ONE().next_up();
+    let epsilon = FP237::new(Float::with_val(P, &epsilon.f - 1));
+    vec![
+        ("MAX", FP237::MAX()),
+        ("MIN", -FP237::MAX()),
+        ("MIN_POSITIVE", FP237::MIN_POSITIVE()),
+        ("MIN_GT_ZERO", FP237::MIN_GT_ZERO()),
+        ("EPSILON", epsilon),
+    ]
+}
+
+/// Returns the terms of the constants split into a sum of f256 values, as
+/// used for argument reduction.
+fn split_constants() -> Vec<(String, FP237)> {
+    let splits: [(&str, Vec<FP237>); 3] = [
+        ("FRAC_2_PI", FP237::FRAC_2_PI_SPLIT2().into()),
+        ("FRAC_PI_2", FP237::FRAC_PI_2_SPLIT3().into()),
+        ("LN_2", FP237::LN_2_SPLIT3().into()),
+    ];
+    splits
+        .into_iter()
+        .flat_map(|(name, terms)| {
+            let suffixes: &[&str] = match terms.len() {
+                2 => &["HI", "LO"],
+                _ => &["HI", "MID", "LO"],
+            };
+            suffixes
+                .iter()
+                .zip(terms)
+                .map(move |(suffix, t)| (format!("{name}_{suffix}"), t))
+        })
+        .collect()
+}
+
+/// Generates the decode tuples of the f256 constants.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Also emit the next-down and next-up neighbors of each constant and
+    /// the ternary of its rounding (-1: rounded down, 0: exact, 1: rounded
+    /// up), enclosing the exact value
+    #[arg(long)]
+    enclosure: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Returns the record of the constant `f` named `name`: its non-reduced
+/// and reduced decode tuples, followed by the decode tuples of its
+/// neighbors and its ternary if `enclosure` is set.
+fn record(name: &str, f: &FP237, enclosure: bool) -> Record {
+    let rec = Record::new().token(name).fp_unreduced(f).fp(f);
+    if enclosure {
+        rec.fp(&f.next_down()).fp(&f.next_up()).int(f.o as i32)
+    } else {
+        rec
+    }
+}
+
+pub fn run(args: Args) {
+    let mut header = Header::new("constants");
+    if args.enclosure {
+        header = header.param("enclosure", true);
+    }
+    let mut out = args.output.meta_writer(&header);
+    for (name, f) in math_constants().iter().chain(limits().iter()) {
+        out.write(&record(name, f, args.enclosure)).unwrap();
+    }
+    // the two-term splits are the leading terms of the three-term ones
+    for (name, f) in &split_constants() {
+        out.write(&record(name, f, args.enclosure)).unwrap();
+    }
+    out.finish().unwrap();
+}
+
+#[cfg(test)]
+mod constants_tests {
+    use super::*;
+
+    #[test]
+    fn test_enclosure() {
+        let exact = exact_math_constants();
+        for (name, f) in math_constants() {
+            let (_, x) = exact.iter().find(|(n, _)| *n == name).unwrap();
+            let (lo, hi) = (f.next_down(), f.next_up());
+            assert!(lo.f < *x && *x < hi.f, "{name}");
+            assert_eq!(f.f.partial_cmp(x), Some(f.o), "{name}");
+        }
+        // name, 2 resp. 4 decode tuples, ternary
+        let rec = record("PI", &FP237::Pi(), true);
+        assert_eq!(rec.fields().len(), 18);
+        assert_eq!(record("PI", &FP237::Pi(), false).fields().len(), 9);
+    }
+}
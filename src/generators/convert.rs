@@ -0,0 +1,116 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    io::Result,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+
+use super::{open_input, oracle::arity, OutputArgs};
+use crate::{
+    CorpusReader, Field, Format, Header, RecordReader, TestItem,
+    ROUNDING_MODES,
+};
+
+/// Returns the format of the corpus at `path` derived from its extension
+/// (ignoring a trailing .gz), TSV if it has none of the known ones.
+fn format_of(path: &Path) -> Format {
+    let path = match path.extension() {
+        Some(ext) if ext == "gz" => path.with_extension(""),
+        _ => path.to_path_buf(),
+    };
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => Format::Csv,
+        Some("jsonl" | "json") => Format::Jsonl,
+        Some("rs") => Format::Rust,
+        Some("bin") => Format::Bin,
+        _ => Format::Tsv,
+    }
+}
+
+/// Converts a corpus from one format to another, e.g. binary to TSV or
+/// TSV to Rust source. Each decode tuple is checked to denote a value
+/// representable as f256 on the way. The header of the corpus is kept.
+///
+/// Except for binary corpora and test items in JSON Lines format the
+/// number of operands and results per test case is not recorded in the
+/// corpus. It defaults to the number of operands of the operation named in
+/// the header and one result per rounding mode named there.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// File holding the corpus, decompressed if it ends with .gz
+    file: PathBuf,
+
+    /// Format of the corpus (default: derived from the extension of FILE)
+    #[arg(long, value_enum)]
+    from: Option<Format>,
+
+    /// Number of operands per test case
+    #[arg(short = 'n', long)]
+    operands: Option<usize>,
+
+    /// Number of results per test case
+    #[arg(short = 'm', long)]
+    results: Option<usize>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+pub fn run(args: Args) {
+    let path = &args.file;
+    let inp = open_input(Some(path));
+    let from = args.from.unwrap_or_else(|| format_of(path));
+    let (header, items): (
+        Header,
+        Box<dyn Iterator<Item = Result<TestItem>>>,
+    ) = if from == Format::Bin {
+        let rdr = CorpusReader::new(inp)
+            .unwrap_or_else(|e| panic!("Can't read {}: {e}", path.display()));
+        let header = Header::from_line(rdr.info())
+            .unwrap_or_else(|| Header::new(rdr.op()));
+        (header, Box::new(rdr))
+    } else {
+        let mut rdr = RecordReader::new(inp, from)
+            .unwrap_or_else(|e| panic!("Can't read {}: {e}", path.display()));
+        let header = rdr.header().cloned();
+        let op = header.as_ref().map_or("", Header::op);
+        let n_operands = args.operands.or_else(|| arity(op));
+        let n_results = args.results.or_else(|| {
+            header.as_ref().map(|hdr| match hdr.get("rounding") {
+                Some(Field::Token(rnd)) if rnd == "all" => {
+                    ROUNDING_MODES.len()
+                }
+                _ => 1,
+            })
+        });
+        match (n_operands, n_results) {
+            (Some(n_operands), Some(n_results)) => {
+                rdr = rdr.with_arity(n_operands, n_results)
+            }
+            // test items in JSON Lines format are self-describing
+            _ if from == Format::Jsonl => {}
+            _ => panic!(
+                "Number of operands and results unknown, use -n and -m to \
+                 give them"
+            ),
+        }
+        let header = header.unwrap_or_else(|| Header::new(""));
+        (header, Box::new(rdr))
+    };
+    let mut out = args.output.writer(&header);
+    for item in items {
+        let item = item.unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        out.write_item(&item).unwrap();
+    }
+    out.finish().unwrap();
+}
@@ -0,0 +1,152 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::{Parser, ValueEnum};
+use rand::prelude::*;
+
+use super::CommonArgs;
+use crate::{
+    special_values, Header, Record, EMAX, FP237, HI_EXP_MASK,
+    HI_FRACTION_MASK, HI_QUIET_NAN_BIT, HI_SIGN_BIT, MIN_EXP_SUBNORMAL,
+};
+
+// f256::MIN_GT_ZERO <= |f| <= f256::MAX
+const EXP_RANGE: RangeInclusive<i32> = MIN_EXP_SUBNORMAL..=EMAX;
+
+/// Returns the bit pattern of a NaN with sign `s`, the quiet bit set or not
+/// and a random payload (which is not zero for a signaling NaN).
+fn nan_bits(rng: &mut StdRng, s: bool, quiet: bool) -> (u128, u128) {
+    let mut hi = HI_EXP_MASK | rng.gen_range(0..=HI_FRACTION_MASK);
+    let mut lo = rng.gen::<u128>();
+    if quiet {
+        hi |= HI_QUIET_NAN_BIT;
+    } else {
+        hi &= !HI_QUIET_NAN_BIT;
+        if hi & HI_FRACTION_MASK == 0 && lo == 0 {
+            lo = 1;
+        }
+    }
+    if s {
+        hi |= HI_SIGN_BIT;
+    }
+    (hi, lo)
+}
+
+fn is_nan((hi, lo): (u128, u128)) -> bool {
+    hi & HI_EXP_MASK == HI_EXP_MASK && (hi & HI_FRACTION_MASK != 0 || lo != 0)
+}
+
+fn abs((hi, lo): (u128, u128)) -> (u128, u128) {
+    (hi & !HI_SIGN_BIT, lo)
+}
+
+fn copysign((hi, lo): (u128, u128), (y_hi, _): (u128, u128)) -> (u128, u128) {
+    ((hi & !HI_SIGN_BIT) | (y_hi & HI_SIGN_BIT), lo)
+}
+
+/// NaN is returned unchanged, any other value gives ±1 with the sign of the
+/// value, including ±0 and ±∞.
+fn signum(x: (u128, u128)) -> (u128, u128) {
+    if is_nan(x) {
+        x
+    } else {
+        copysign(FP237::ONE().to_bits(), x)
+    }
+}
+
+/// Functions to generate test data for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Func {
+    Copysign,
+    Signum,
+    Abs,
+}
+
+impl Func {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Copysign => "copysign",
+            Self::Signum => "signum",
+            Self::Abs => "abs",
+        }
+    }
+}
+
+/// Generates test data for copysign, signum and abs.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Function
+    #[arg(short, long, value_enum, default_value_t = Func::Copysign)]
+    func: Func,
+
+    // the number of test data is the number of random values added to the
+    // special values
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+pub fn run(args: Args) {
+    let seed = args.common.seed();
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    // the special values (without the canonical NaN), NaNs of both signs
+    // with random payloads, both quiet and signaling, and random values
+    let mut values: Vec<(u128, u128)> = special_values()
+        .iter()
+        .filter(|f| !f.f.is_nan())
+        .map(|f| f.to_bits())
+        .collect();
+    for s in [false, true] {
+        for quiet in [false, true] {
+            values.push(nan_bits(&mut rng, s, quiet));
+        }
+    }
+    for _ in 0..args.common.n_test_data {
+        values.push(
+            FP237::random_from_exp_range_with_rng(&mut rng, &EXP_RANGE)
+                .to_bits(),
+        );
+    }
+
+    let header = Header::new(args.func.name())
+        .seed(seed)
+        .exp_ranges(&[&EXP_RANGE]);
+
+    let mut out = args.common.output.meta_writer(&header);
+    for x in &values {
+        match args.func {
+            Func::Copysign => {
+                for y in &values {
+                    let z = copysign(*x, *y);
+                    let rec = Record::new()
+                        .int(x.0)
+                        .int(x.1)
+                        .int(y.0)
+                        .int(y.1)
+                        .int(z.0)
+                        .int(z.1);
+                    out.write(&rec).unwrap();
+                }
+            }
+            Func::Signum | Func::Abs => {
+                let z = if args.func == Func::Abs {
+                    abs(*x)
+                } else {
+                    signum(*x)
+                };
+                let rec = Record::new().int(x.0).int(x.1).int(z.0).int(z.1);
+                out.write(&rec).unwrap();
+            }
+        }
+    }
+    out.finish().unwrap();
+}
@@ -0,0 +1,99 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::Float;
+
+use super::CommonArgs;
+use crate::{CorpusBuilder, Header, RoundingMode, FP237, P};
+
+/// Returns a random value with an exponent of exactly `e`.
+fn random_with_exp(rng: &mut StdRng, e: i32) -> FP237 {
+    FP237::random_from_exp_range_with_rng(rng, &(e..=e))
+}
+
+/// Returns the operands x₁, y₁, x₂, y₂, … of an `n`-term dot product whose
+/// terms cancel over `c` bits (following the GenDot scheme of Ogita, Rump
+/// and Oishi): the first half of the terms have exponents spread over c/2
+/// bits, the second half is chosen so that the running sum shrinks step by
+/// step.
+fn gen_dot(rng: &mut StdRng, n: usize, c: i32) -> Vec<FP237> {
+    let n2 = n / 2;
+    let half = c / 2;
+    let mut xs: Vec<FP237> = Vec::with_capacity(n);
+    let mut ys: Vec<FP237> = Vec::with_capacity(n);
+    for i in 0..n2 {
+        let e = if i == 0 {
+            half
+        } else {
+            rng.gen_range(0..=half)
+        };
+        xs.push(random_with_exp(rng, e / 2));
+        ys.push(random_with_exp(rng, e - e / 2));
+    }
+    for i in n2..n {
+        let e = half - ((i - n2 + 1) as i32 * c) / (n - n2) as i32;
+        let x = random_with_exp(rng, e / 2);
+        let r = random_with_exp(rng, e);
+        let d = FP237::dot(&xs, &ys);
+        let y = Float::with_val(2 * P, &r.f - &d.f) / &x.f;
+        ys.push(FP237::with_val_round(y, RoundingMode::NearestEven));
+        xs.push(x);
+    }
+    xs.into_iter().zip(ys).flat_map(|(x, y)| [x, y]).collect()
+}
+
+/// Generates test data for the correctly rounded dot product.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Number of terms of the dot product
+    #[arg(short, long, default_value_t = 4)]
+    terms: usize,
+
+    /// Number of bits cancelled out
+    #[arg(short, long, default_value_t = 200)]
+    cancellation: i32,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    assert!(args.terms >= 2, "At least 2 terms needed");
+    let (n, c) = (args.terms, args.cancellation);
+    let seed = args.common.seed();
+
+    let header = Header::new("dot").seed(seed);
+
+    let mut out = args.common.output.writer(&header);
+    let mut builder = CorpusBuilder::new(2 * n, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| gen_dot(rng, n, c));
+    if args.verify {
+        builder = builder.verify(&[RoundingMode::NearestEven]);
+    }
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "dot", |ops| {
+            let (xs, ys): (Vec<FP237>, Vec<FP237>) = ops
+                .chunks(2)
+                .map(|xy| (xy[0].clone(), xy[1].clone()))
+                .unzip();
+            Some(vec![FP237::dot(&xs, &ys)])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
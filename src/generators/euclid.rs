@@ -0,0 +1,146 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    cmp::{max, min},
+    ops::RangeInclusive,
+};
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::Float;
+
+use super::{
+    CommonArgs, ExpRangeArgs, NORMAL_EXP_RANGE, SUBNORMAL_EXP_UPPER_BOUND,
+};
+use crate::{
+    CorpusBuilder, Header, EMAX, EMIN, FP237, MIN_EXP_SUBNORMAL, P, PM1,
+};
+
+const SUBNORMAL_EXP_LOWER_BOUND: i32 = MIN_EXP_SUBNORMAL + 1;
+
+// f256::MIN_GT_ZERO <= |f| < MIN_POSITIVE
+const SUBNORMAL_EXP_RANGE: RangeInclusive<i32> =
+    SUBNORMAL_EXP_LOWER_BOUND..=SUBNORMAL_EXP_UPPER_BOUND;
+
+// 2⁻¹⁰⁰⁰ <= |f| < 2¹⁰⁰⁰
+const MODERATE_EXP_RANGE: RangeInclusive<i32> = -1000..=999;
+// 1 <= k < 2⁶⁴
+const MULTIPLIER_EXP_RANGE: RangeInclusive<i32> = 0..=63;
+
+/// Generates test data for div_euclid and rem_euclid.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    /// Add the boundary values of the exponent ranges
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+}
+
+pub fn run(args: Args) {
+    let normal_exp_range = args.exp.exp_range(&NORMAL_EXP_RANGE);
+    let subnormal_exp_range = args.exp.exp_range(&SUBNORMAL_EXP_RANGE);
+    let n_sub_normal = args.common.n_test_data / 100 + 1;
+    let n_near_zero = args.common.n_test_data / 2;
+    let n_normal = args.common.n_test_data - n_sub_normal - n_near_zero;
+    let seed = args.common.seed();
+
+    let mut builder = CorpusBuilder::new(2, seed)
+        .sign(args.common.sign)
+        .random(n_normal, |rng| {
+            let x =
+                FP237::random_from_exp_range_with_rng(rng, &normal_exp_range);
+            let (_, e, _) = x.decode(false);
+            let e = e + PM1;
+            // quotients from below 1 up to some hundred integer bits
+            let lower_limit = max(EMIN, e - 300);
+            let upper_limit = min(EMAX, e + 2);
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &(lower_limit..=upper_limit),
+            );
+            vec![x, y]
+        })
+        .random(n_near_zero, |rng| {
+            // x close to an integer multiple of y, so that the truncated
+            // remainder is tiny and of either sign, giving a euclidean
+            // remainder near zero or near |y|
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &MODERATE_EXP_RANGE,
+            );
+            let k = FP237::random_from_exp_range_with_rng(
+                rng,
+                &MULTIPLIER_EXP_RANGE,
+            )
+            .trunc();
+            let mut x = FP237::new(Float::with_val(P, &k.f * &y.f));
+            for _ in 0..rng.gen_range(0..=2) {
+                x = if rng.gen() {
+                    x.next_up()
+                } else {
+                    x.next_down()
+                };
+            }
+            vec![x, y]
+        })
+        .random(n_sub_normal, |rng| {
+            let x =
+                FP237::random_from_exp_range_with_rng(rng, &normal_exp_range);
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            );
+            vec![x, y]
+        })
+        .random(n_sub_normal, |rng| {
+            let x = FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            );
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            );
+            vec![x, y]
+        });
+    if args.boundaries {
+        builder = builder
+            .boundaries(&normal_exp_range)
+            .boundaries(&subnormal_exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    let header = Header::new("div_rem_euclid").seed(seed).exp_ranges(&[
+        &normal_exp_range,
+        &subnormal_exp_range,
+        &MODERATE_EXP_RANGE,
+        &MULTIPLIER_EXP_RANGE,
+    ]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "div_rem_euclid", |ops| {
+            let (x, y) = (&ops[0], &ops[1]);
+            Some(vec![x.div_euclid(y), x.rem_euclid(y)])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
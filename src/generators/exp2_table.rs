@@ -0,0 +1,39 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use clap::Parser;
+
+use super::{calc_tables::exp2_segments, OutputArgs};
+use crate::{Header, Record};
+
+/// Generates the entries of the table 2^(k/2^m), k in 0..2^m, split into
+/// hi + lo, for validating the table of f256's exp against the one emitted
+/// by `rug237 tables --table exp2`.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Number of leading fraction bits indexing the table (m)
+    #[arg(long, default_value_t = 8)]
+    index_bits: u32,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+pub fn run(args: Args) {
+    let header = Header::new("exp2_table");
+    let mut out = args.output.meta_writer(&header);
+    // k, hi, lo
+    for (k, (_, hi, lo)) in exp2_segments(args.index_bits).iter().enumerate()
+    {
+        let rec = Record::new().int(k).fp(hi).fp(lo);
+        out.write(&rec).unwrap();
+    }
+    out.finish().unwrap();
+}
@@ -0,0 +1,71 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::Parser;
+
+use super::CommonArgs;
+use crate::{CorpusBuilder, Header, FP237};
+
+const TINY_EXP_LOWER_BOUND: i32 = -300;
+const TINY_EXP_UPPER_BOUND: i32 = -2;
+
+// 2⁻³⁰⁰ <= |f| < 2⁻¹
+const TINY_EXP_RANGE: RangeInclusive<i32> =
+    TINY_EXP_LOWER_BOUND..=TINY_EXP_UPPER_BOUND;
+
+type Func = fn(&FP237) -> FP237;
+
+/// Generates test data for exp_m1 and ln_1p.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// function: exp_m1 ln_1p
+    #[arg(short, long, default_value = "exp_m1")]
+    func: String,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Add the boundary values of the exponent range
+    #[arg(short, long)]
+    boundaries: bool,
+}
+
+pub fn run(args: Args) {
+    // the naive variant rounds the intermediate result, the fused one
+    // doesn't; for tiny arguments they differ by catastrophic cancellation
+    let (naive, fused): (Func, Func) = match args.func.as_str() {
+        "exp_m1" => (|x| &x.exp() - &FP237::ONE(), FP237::exp_m1),
+        "ln_1p" => (|x| (x + &FP237::ONE()).ln(), FP237::ln_1p),
+        _ => panic!("Unkown func"),
+    };
+    let seed = args.common.seed();
+
+    let mut builder = CorpusBuilder::new(1, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            vec![FP237::random_from_exp_range_with_rng(rng, &TINY_EXP_RANGE)]
+        });
+    if args.boundaries {
+        builder = builder.boundaries(&TINY_EXP_RANGE);
+    }
+    let header = Header::new(&args.func)
+        .seed(seed)
+        .exp_ranges(&[&TINY_EXP_RANGE]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, &args.func, |ops| {
+            Some(vec![naive(&ops[0]), fused(&ops[0])])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
@@ -0,0 +1,186 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::Float;
+
+use super::CommonArgs;
+use crate::{
+    CorpusBuilder, Header, Record, RoundingMode, FP113, FP237, P,
+    ROUNDING_MODES,
+};
+
+// f64::MIN_POSITIVE / 2⁵⁵ <= |f| < f64::MAX * 2², i.e. including values
+// which underflow to zero or overflow to infinity
+const F64_EXP_RANGE: RangeInclusive<i32> = -1022 - 52 - 3..=1023 + 2;
+// f32::MIN_POSITIVE / 2²⁶ <= |f| < f32::MAX * 2²
+const F32_EXP_RANGE: RangeInclusive<i32> = -126 - 23 - 3..=127 + 2;
+// f128::MIN_POSITIVE / 2¹¹⁵ <= |f| < f128::MAX * 2²
+const F128_EXP_RANGE: RangeInclusive<i32> = -16382 - 112 - 3..=16383 + 2;
+
+/// Binary formats converted from and to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BinFormat {
+    F64,
+    F32,
+    F128,
+}
+
+impl BinFormat {
+    fn exp_range(self) -> &'static RangeInclusive<i32> {
+        match self {
+            Self::F64 => &F64_EXP_RANGE,
+            Self::F32 => &F32_EXP_RANGE,
+            Self::F128 => &F128_EXP_RANGE,
+        }
+    }
+
+    /// Returns the bit pattern of `x` rounded to the format according to
+    /// `rnd`.
+    fn to_bits(self, x: &FP237, rnd: RoundingMode) -> u128 {
+        match self {
+            Self::F64 => x.to_f64_round(rnd).to_bits() as u128,
+            Self::F32 => x.to_f32_round(rnd).to_bits() as u128,
+            Self::F128 => FP113::with_val_round(&x.f, rnd).to_bits(),
+        }
+    }
+
+    /// Returns the value of a random bit pattern of the format.
+    fn random(self, rng: &mut StdRng) -> Float {
+        match self {
+            Self::F64 => Float::with_val(P, f64::from_bits(rng.gen())),
+            Self::F32 => Float::with_val(P, f32::from_bits(rng.gen())),
+            Self::F128 => Float::with_val(P, FP113::from_bits(rng.gen()).f),
+        }
+    }
+
+    /// Returns zero, one, the smallest normal and subnormal and the largest
+    /// value of the format.
+    fn specials(self) -> [Float; 5] {
+        match self {
+            Self::F64 => [0.0, 1.0, f64::MIN_POSITIVE, 5e-324, f64::MAX]
+                .map(|f| Float::with_val(P, f)),
+            Self::F32 => [0.0, 1.0, f32::MIN_POSITIVE, 1e-45, f32::MAX]
+                .map(|f| Float::with_val(P, f)),
+            Self::F128 => [
+                FP113::ZERO(),
+                FP113::ONE(),
+                FP113::MIN_POSITIVE(),
+                FP113::MIN_GT_ZERO(),
+                FP113::MAX(),
+            ]
+            .map(|f| Float::with_val(P, f.f)),
+        }
+    }
+}
+
+/// Generates test data for conversions from and to f64, f32 and f128.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Target / source format: f64 f32 f128
+    #[arg(short = 'f', long, default_value = "f64")]
+    float_format: String,
+
+    /// Direction of conversion: T = f256 to f64/f32/f128, F = f64/f32/f128
+    /// to f256
+    #[arg(short, long, default_value_t = 'T')]
+    direction: char,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Add the boundary values of the exponent range
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+}
+
+pub fn run(args: Args) {
+    let format = match args.float_format.as_str() {
+        "f64" => BinFormat::F64,
+        "f32" => BinFormat::F32,
+        "f128" => BinFormat::F128,
+        _ => panic!("Unkown format"),
+    };
+    let exp_range = format.exp_range();
+    let seed = args.common.seed();
+    let op = if args.direction == 'T' {
+        format!("to_{}", args.float_format)
+    } else {
+        format!("from_{}", args.float_format)
+    };
+    let header = Header::new(&op)
+        .seed(seed)
+        .rounding("all")
+        .exp_ranges(&[exp_range]);
+    let mut out = args.common.output.meta_writer(&header);
+
+    match args.direction {
+        'T' => {
+            let mut builder = CorpusBuilder::new(1, seed)
+                .sign(args.common.sign)
+                .random(args.common.n_test_data, |rng| {
+                    vec![FP237::random_from_exp_range_with_rng(
+                        rng, exp_range,
+                    )]
+                });
+            if args.boundaries {
+                builder = builder.boundaries(exp_range);
+            }
+            if args.specials {
+                builder = builder.specials();
+            }
+            // source bit pattern, then the target bit pattern for each
+            // rounding mode in the order ne tz up dn na
+            for item in builder.build() {
+                let (hi, lo) = item[0].to_bits();
+                let rec = ROUNDING_MODES
+                    .iter()
+                    .fold(Record::new().int(hi).int(lo), |rec, rnd| {
+                        rec.int(format.to_bits(&item[0], *rnd))
+                    });
+                out.write(&rec).unwrap();
+            }
+        }
+        'F' => {
+            // every f64 / f32 / f128 is exactly representable in f256
+            let builder = CorpusBuilder::new(1, seed)
+                .sign(args.common.sign)
+                .random(args.common.n_test_data, |rng| {
+                    vec![FP237::new(format.random(rng))]
+                });
+            let mut items: Vec<FP237> =
+                builder.build().into_iter().flatten().collect();
+            if args.specials {
+                for f in format.specials() {
+                    items.push(FP237::new(f.clone()));
+                    items.push(FP237::new(-f));
+                }
+                items.push(FP237::INFINITY());
+                items.push(-FP237::INFINITY());
+                items.push(FP237::NAN());
+            }
+            let rnd = ROUNDING_MODES[0];
+            for x in items {
+                let (hi, lo) = x.to_bits();
+                let rec = Record::new().int(format.to_bits(&x, rnd));
+                out.write(&rec.int(hi).int(lo)).unwrap();
+            }
+        }
+        _ => panic!("Unkown direction"),
+    }
+    out.finish().unwrap();
+}
@@ -0,0 +1,100 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    cmp::{max, min},
+    ops::RangeInclusive,
+};
+
+use clap::Parser;
+
+use super::{CommonArgs, ExpRangeArgs, RoundArgs, EXP_UPPER_BOUND};
+use crate::{
+    CorpusBuilder, Header, EMAX, EMIN, FP237, MIN_EXP_SUBNORMAL, PM1,
+};
+
+const EXP_LOWER_BOUND: i32 = MIN_EXP_SUBNORMAL;
+
+// f256::MIN_GT_ZERO <= |f| <= f256::MAX
+const EXP_RANGE: RangeInclusive<i32> = EXP_LOWER_BOUND..=EXP_UPPER_BOUND;
+
+/// Generates test data for fused multiply-add.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    #[command(flatten)]
+    rounding: RoundArgs,
+
+    /// Add the boundary values of the exponent ranges
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    let exp_range = args.exp.exp_range(&EXP_RANGE);
+    let seed = args.common.seed();
+    let rnd = args.rounding.round;
+
+    let mut builder = CorpusBuilder::new(3, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            let x = FP237::random_from_exp_range_with_rng(rng, &exp_range);
+            let (_, e, _) = x.decode(false);
+            let lower_limit = max(EMIN - PM1, EMIN - PM1 - e);
+            let upper_limit = min(EMAX - PM1, EMAX - PM1 - e);
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &(lower_limit..=upper_limit),
+            );
+            let a = FP237::random_from_exp_range_with_rng(rng, &exp_range);
+            vec![x, y, a]
+        });
+    if args.boundaries {
+        builder = builder.boundaries(&exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        builder = builder.verify(&[rnd]);
+    }
+    let header = Header::new("fma")
+        .seed(seed)
+        .rounding(rnd)
+        .exp_ranges(&[&exp_range]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "fma", |ops| {
+            let (x, y, a) = (&ops[0], &ops[1], &ops[2]);
+            let z = x.fma_round(y, a, rnd);
+            let mut t = x.mul_round(y, rnd);
+            t.add_assign_round(a, rnd);
+            // only cases where the fused result differs from the unfused
+            // one are of interest
+            (z != t).then(|| vec![z])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
@@ -0,0 +1,154 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    cmp::{max, min},
+    ops::RangeInclusive,
+};
+
+use clap::{Parser, ValueEnum};
+use rand::prelude::*;
+use rug::Float;
+
+use super::{CommonArgs, ExpRangeArgs, EXP_UPPER_BOUND};
+use crate::{
+    CorpusBuilder, Header, RoundingMode, EMAX, EMIN, FP237,
+    MIN_EXP_SUBNORMAL, P, PM1,
+};
+
+const EXP_LOWER_BOUND: i32 = MIN_EXP_SUBNORMAL;
+
+// f256::MIN_GT_ZERO <= |f| <= f256::MAX
+const EXP_RANGE: RangeInclusive<i32> = EXP_LOWER_BOUND..=EXP_UPPER_BOUND;
+// 2⁻¹⁰⁰⁰ <= |f| < 2¹⁰⁰⁰, so that products with powers of two stay exact
+const MODERATE_EXP_RANGE: RangeInclusive<i32> = -1000..=1000;
+
+/// Kinds of test data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Kind {
+    /// Random operands
+    #[value(name = "R")]
+    Random,
+    /// Exact zero results
+    #[value(name = "Z")]
+    Zero,
+}
+
+/// Generates test data for fms, fnma and fnms.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Kind of test data
+    #[arg(short, long, value_enum, default_value_t = Kind::Random)]
+    kind: Kind,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    /// Add the boundary values of the exponent range
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    let exp_range = args.exp.exp_range(&EXP_RANGE);
+    let kind = args.kind;
+    let seed = args.common.seed();
+
+    let mut builder = CorpusBuilder::new(3, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            match kind {
+                Kind::Random => {
+                    let x = FP237::random_from_exp_range_with_rng(
+                        rng, &exp_range,
+                    );
+                    let (_, e, _) = x.decode(false);
+                    let lower_limit = max(EMIN - PM1, EMIN - PM1 - e);
+                    let upper_limit = min(EMAX - PM1, EMAX - PM1 - e);
+                    let y = FP237::random_from_exp_range_with_rng(
+                        rng,
+                        &(lower_limit..=upper_limit),
+                    );
+                    let a = FP237::random_from_exp_range_with_rng(
+                        rng, &exp_range,
+                    );
+                    vec![x, y, a]
+                }
+                Kind::Zero => {
+                    // either x * y = ±a exactly or x * y and a both zero,
+                    // so that one of the variants gives an exact zero
+                    if rng.gen() {
+                        let k = rng.gen_range(-100..=100);
+                        let mut x = Float::with_val(P, Float::i_exp(1, k));
+                        if rng.gen() {
+                            x = -x;
+                        }
+                        let x = FP237::new(x);
+                        let y = FP237::random_from_exp_range_with_rng(
+                            rng,
+                            &MODERATE_EXP_RANGE,
+                        );
+                        let a = &x * &y;
+                        vec![x, y, if rng.gen() { -a } else { a }]
+                    } else {
+                        let x = FP237::random_from_exp_range_with_rng(
+                            rng,
+                            &MODERATE_EXP_RANGE,
+                        );
+                        let y = if rng.gen() {
+                            FP237::ZERO()
+                        } else {
+                            -FP237::ZERO()
+                        };
+                        let a = if rng.gen() {
+                            FP237::ZERO()
+                        } else {
+                            -FP237::ZERO()
+                        };
+                        vec![x, y, a]
+                    }
+                }
+            }
+        });
+    if args.boundaries {
+        builder = builder.boundaries(&exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        builder = builder.verify(&[RoundingMode::NearestEven]);
+    }
+    // one result column per variant: fms fnma fnms
+    let header = Header::new("fms_fnma_fnms")
+        .seed(seed)
+        .exp_ranges(&[&exp_range, &MODERATE_EXP_RANGE]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "fms_fnma_fnms", |ops| {
+            let (x, y, a) = (&ops[0], &ops[1], &ops[2]);
+            Some(vec![x.fms(y, a), x.fnma(y, a), x.fnms(y, a)])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
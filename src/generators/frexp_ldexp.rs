@@ -0,0 +1,151 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::{Parser, ValueEnum};
+use rand::prelude::*;
+use rug::Float;
+
+use super::{CommonArgs, NORMAL_EXP_RANGE, SUBNORMAL_EXP_RANGE};
+use crate::{
+    CorpusBuilder, DecodedFP, Header, Record, TestItem, EMAX, FP237,
+    MIN_EXP_SUBNORMAL, P,
+};
+
+// scale factors moving any finite value across the whole range and beyond
+const SCALE_RANGE: RangeInclusive<i32> =
+    MIN_EXP_SUBNORMAL - EMAX - 2..=EMAX - MIN_EXP_SUBNORMAL + 2;
+
+/// Functions to generate test data for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Func {
+    Frexp,
+    Ldexp,
+    Ilogb,
+    Logb,
+}
+
+impl Func {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Frexp => "frexp",
+            Self::Ldexp => "ldexp",
+            Self::Ilogb => "ilogb",
+            Self::Logb => "logb",
+        }
+    }
+}
+
+/// Ranges of the input value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Range {
+    /// Subnormal values
+    #[value(name = "S")]
+    Subnormal,
+    /// Normal values
+    #[value(name = "N")]
+    Normal,
+}
+
+/// Generates test data for frexp, ldexp, ilogb and logb.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Function
+    #[arg(short, long, value_enum, default_value_t = Func::Frexp)]
+    func: Func,
+
+    /// Range of input value f
+    #[arg(long, value_enum, default_value_t = Range::Normal)]
+    range: Range,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+}
+
+pub fn run(args: Args) {
+    let exp_range = match args.range {
+        Range::Subnormal => &SUBNORMAL_EXP_RANGE,
+        Range::Normal => &NORMAL_EXP_RANGE,
+    };
+    let seed = args.common.seed();
+    let header = Header::new(args.func.name())
+        .seed(seed)
+        .exp_ranges(&[&NORMAL_EXP_RANGE, &SUBNORMAL_EXP_RANGE]);
+    let mut out = args.common.output.meta_writer(&header);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut builder = CorpusBuilder::new(1, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            vec![FP237::random_from_exp_range_with_rng(rng, exp_range)]
+        });
+    if args.specials {
+        builder = builder.specials();
+    }
+    for item in builder.build() {
+        let x = &item[0];
+        let (expected, meta) = match args.func {
+            Func::Frexp => {
+                let (m, e) = x.frexp();
+                (vec![DecodedFP::from(&m)], Record::new().int(e))
+            }
+            Func::Ldexp => {
+                // scale factors near the distance to the thresholds hit
+                // overflow and underflow more often than arbitrary ones
+                let t = x.ilogb().clamp(MIN_EXP_SUBNORMAL, EMAX);
+                let n = match rng.gen_range(0..3) {
+                    0 => EMAX - t + rng.gen_range(-2..=2),
+                    1 => MIN_EXP_SUBNORMAL - t + rng.gen_range(-2..=2),
+                    _ => rng.gen_range(SCALE_RANGE),
+                };
+                let z = x.ldexp(n);
+                let exact = Float::with_val(P, &x.f << n);
+                let inexact = z.f != exact && !z.f.is_nan();
+                let overflow = z.f.is_infinite() && x.f.is_finite();
+                let underflow =
+                    inexact && exact.clone().abs() < FP237::MIN_POSITIVE().f;
+                (
+                    vec![DecodedFP::from(&z)],
+                    Record::new()
+                        .int(n)
+                        .int(overflow as u32)
+                        .int(underflow as u32)
+                        .int(inexact as u32),
+                )
+            }
+            Func::Ilogb => {
+                // invalid for zero, infinities and NaN
+                let invalid = !x.f.is_normal();
+                (vec![], Record::new().int(x.ilogb()).int(invalid as u32))
+            }
+            Func::Logb => {
+                // division by zero for zero
+                let div_by_zero = x.f.is_zero();
+                (
+                    vec![DecodedFP::from(&x.logb())],
+                    Record::new().int(div_by_zero as u32),
+                )
+            }
+        };
+        let item = TestItem::new(
+            args.func.name(),
+            vec![DecodedFP::from(x)],
+            expected,
+        )
+        .with_meta(meta);
+        out.write_item(&item).unwrap();
+    }
+    out.finish().unwrap();
+}
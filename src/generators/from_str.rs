@@ -0,0 +1,189 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{io::Write, ops::RangeInclusive, str::FromStr};
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::{Float, Integer};
+
+use super::OutputArgs;
+use crate::{
+    max_10_exp, max_exact_digits, max_sig_digits, min_10_exp,
+    min_subnormal_10_exp, DecimalExact, DecodedFP, Header, Record,
+    RecordWriter, RoundingMode, TestItem, EMAX, FP237, MIN_EXP_SUBNORMAL,
+};
+
+const FAST_EXACT_EXP_RANGE: RangeInclusive<i32> = -102..=102;
+const FAST_APPROX_EXP_RANGE: RangeInclusive<i32> = -512..=512;
+const NORMAL_EXP_RANGE: RangeInclusive<i32> = -1024..=1024;
+
+const FAST_EXACT_MAX_N_DIGITS: u32 = 71;
+const SLOW_MAX_N_DIGITS: u32 = 80;
+
+// binary exponents of hex literals, including values rounding to zero or
+// overflowing
+const HEX_EXP_RANGE: RangeInclusive<i32> = MIN_EXP_SUBNORMAL - 4..=EMAX + 1;
+// 59 hex digits hold P bits, more digits need rounding
+const HEX_MAX_N_DIGITS: u32 = 72;
+
+const DIGITS: &[u8] = b"0123456789";
+const HEX_DIGITS: &[u8] = b"0123456789abcdefABCDEF";
+
+fn print_test_item(out: &mut RecordWriter<impl Write>, lit: &str, f: FP237) {
+    let item = TestItem::new("from_str", vec![], vec![DecodedFP::from(&f)])
+        .with_meta(Record::new().str(lit));
+    out.write_item(&item).unwrap();
+}
+
+fn gen_number_str(
+    rng: &mut StdRng,
+    exp_range: &RangeInclusive<i32>,
+    max_n_digits: u32,
+) -> String {
+    let sign: &str = match rng.gen_range(0..=2) {
+        0 => "+",
+        1 => "-",
+        _ => "",
+    };
+    let n_digits: u32 = rng.gen_range(1..=max_n_digits);
+    let mut n_fract_digits: u32 = rng.gen_range(0..n_digits);
+    let n_int_digits: u32 = n_digits - n_fract_digits;
+    let mut signif_digit = false;
+    let int_digits: String = (0..n_int_digits)
+        .map(|_| {
+            let idx = rng.gen_range(0..DIGITS.len());
+            if idx > 0 {
+                signif_digit = true;
+            }
+            DIGITS[idx] as char
+        })
+        .collect();
+    let mut fract_digits: String = (0..n_fract_digits)
+        .map(|_| {
+            let idx = rng.gen_range(0..DIGITS.len());
+            if idx > 0 {
+                signif_digit = true;
+            }
+            DIGITS[idx] as char
+        })
+        .collect();
+    if !signif_digit {
+        fract_digits.push('7');
+        n_fract_digits += 1;
+    }
+    let exp: i32 = rng.gen_range(exp_range.clone()) + n_fract_digits as i32;
+    if exp == 0 {
+        format!("{}{}.{}", sign, int_digits, fract_digits)
+    } else {
+        if n_fract_digits == 0 {
+            fract_digits = "0".to_string();
+        }
+        format!("{}{}.{}e{}", sign, int_digits, fract_digits, exp)
+    }
+}
+
+/// Returns a hex-float literal like `-0x1f.8p-3` together with its
+/// correctly rounded value.
+fn gen_hex_number_str(rng: &mut StdRng) -> (String, FP237) {
+    let sign: &str = match rng.gen_range(0..=2) {
+        0 => "+",
+        1 => "-",
+        _ => "",
+    };
+    let n_digits: u32 = rng.gen_range(1..=HEX_MAX_N_DIGITS);
+    let n_fract_digits: u32 = rng.gen_range(0..n_digits);
+    let n_int_digits: u32 = n_digits - n_fract_digits;
+    let mut digits: String = (0..n_digits)
+        .map(|_| HEX_DIGITS[rng.gen_range(0..HEX_DIGITS.len())] as char)
+        .collect();
+    let mut m = Integer::from_str_radix(&digits, 16).unwrap();
+    if m == 0 {
+        digits.pop();
+        digits.push('1');
+        m += 1;
+    }
+    let (int_digits, fract_digits) = digits.split_at(n_int_digits as usize);
+    // the value's exponent is roughly the literal's exponent plus four
+    // times the number of integral digits
+    let exp: i32 =
+        rng.gen_range(HEX_EXP_RANGE.clone()) - 4 * n_int_digits as i32;
+    let lit = if n_fract_digits == 0 {
+        format!("{}0x{}p{}", sign, int_digits, exp)
+    } else {
+        format!("{}0x{}.{}p{}", sign, int_digits, fract_digits, exp)
+    };
+    let mut v = Float::with_val(4 * n_digits, m);
+    v <<= exp - 4 * n_fract_digits as i32;
+    if sign == "-" {
+        v = -v;
+    }
+    (lit, FP237::with_val_round(v, RoundingMode::NearestEven))
+}
+
+/// Generates test data for parsing literals.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Type of number: E = fast exact A = fast approx N = normal,
+    /// S = subnormal, X = extreme, H = hex literal
+    #[arg(short, long, default_value_t = 'E')]
+    type_of_num: char,
+
+    /// Number of test data to generate
+    #[arg(short, long, default_value_t = 10)]
+    n_test_data: u32,
+
+    /// Seed for the random number generator
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+pub fn run(args: Args) {
+    let seed = args.output.seed(args.seed);
+    let header = Header::new("from_str").seed(seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (exp_range, max_n_digits) = match args.type_of_num {
+        'E' => (FAST_EXACT_EXP_RANGE, FAST_EXACT_MAX_N_DIGITS),
+        'A' => (FAST_APPROX_EXP_RANGE, max_sig_digits()),
+        'N' => (NORMAL_EXP_RANGE, SLOW_MAX_N_DIGITS),
+        'X' => (min_10_exp()..=max_10_exp(), max_exact_digits()),
+        'S' => (min_subnormal_10_exp()..=min_10_exp(), SLOW_MAX_N_DIGITS),
+        'H' => {
+            let header = header.exp_ranges(&[&HEX_EXP_RANGE]);
+            let mut out = args.output.meta_writer(&header);
+            for _i in 0..args.n_test_data {
+                let (s, f) = gen_hex_number_str(&mut rng);
+                print_test_item(&mut out, &s, f);
+            }
+            out.finish().unwrap();
+            return;
+        }
+        _ => panic!("Unkown type of number"),
+    };
+
+    let header = header.exp_ranges(&[&exp_range]);
+    let mut out = args.output.meta_writer(&header);
+    for _i in 0..args.n_test_data {
+        let s = gen_number_str(&mut rng, &exp_range, max_n_digits);
+        // parsing extreme literals takes long, so skip those already
+        // written by an interrupted run
+        if out.skip_next() {
+            continue;
+        }
+        let f = DecimalExact::from_str(&s)
+            .unwrap()
+            .to_fp237(RoundingMode::NearestEven);
+        print_test_item(&mut out, &s, f);
+    }
+    out.finish().unwrap();
+}
@@ -0,0 +1,119 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::Float;
+
+use super::CommonArgs;
+use crate::{CorpusBuilder, Header, RoundingMode, EMAX, FP237, P, PM1};
+
+const NEAR_EXP_LOWER_BOUND: i32 = -PM1 - 2;
+const NEAR_EXP_UPPER_BOUND: i32 = -1;
+
+// 2⁻²³⁸ <= |d| < 2⁻¹ for f = n + d
+const NEAR_EXP_RANGE: RangeInclusive<i32> =
+    NEAR_EXP_LOWER_BOUND..=NEAR_EXP_UPPER_BOUND;
+// 0 <= n < 2¹⁵ for poles at -n, Γ underflows for n > ≈18000
+const POLE_EXP_RANGE: RangeInclusive<i32> = 0..=14;
+// 2¹⁰ <= f < 2¹⁵, Γ overflows for f > ≈18000
+const GAMMA_LARGE_EXP_RANGE: RangeInclusive<i32> = 10..=14;
+// 2²⁶²¹⁰³ <= f <= f256::MAX, ln Γ overflows for f > ≈2²⁶²¹²⁶
+const LN_GAMMA_LARGE_EXP_RANGE: RangeInclusive<i32> = EMAX - 40..=EMAX;
+
+/// Generates test data for gamma and ln_gamma.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// gamma function: gamma ln_gamma
+    #[arg(short, long, default_value = "gamma")]
+    func: String,
+
+    /// Range of input value f: P = near poles (non-positive integers),
+    /// O = near 1 and 2, L = large
+    #[arg(long, default_value_t = 'P')]
+    range: char,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    let (func, large_exp_range) = match args.func.as_str() {
+        "gamma" => {
+            (FP237::gamma as fn(&FP237) -> FP237, &GAMMA_LARGE_EXP_RANGE)
+        }
+        "ln_gamma" => (
+            FP237::ln_gamma as fn(&FP237) -> FP237,
+            &LN_GAMMA_LARGE_EXP_RANGE,
+        ),
+        _ => panic!("Unkown func"),
+    };
+    let range = args.range;
+    let seed = args.common.seed();
+
+    let mut builder = CorpusBuilder::new(1, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            let x = match range {
+                'P' | 'O' => {
+                    let n = match range {
+                        'P' => {
+                            let k = rng.gen_range(POLE_EXP_RANGE);
+                            -rng.gen_range(0..1_i32 << k)
+                        }
+                        _ => rng.gen_range(1..=2),
+                    };
+                    let d = FP237::random_from_exp_range_with_rng(
+                        rng,
+                        &NEAR_EXP_RANGE,
+                    );
+                    FP237::with_val_round(
+                        Float::with_val(P, n) + &d.f,
+                        RoundingMode::NearestEven,
+                    )
+                }
+                'L' => FP237::random_from_exp_range_with_rng(
+                    rng,
+                    large_exp_range,
+                )
+                .abs(),
+                _ => panic!("Unkown range"),
+            };
+            vec![x]
+        });
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        builder = builder.verify(&[RoundingMode::NearestEven]);
+    }
+    let header =
+        Header::new(&args.func).seed(seed).exp_ranges(&match range {
+            'L' => vec![large_exp_range],
+            _ => vec![&NEAR_EXP_RANGE],
+        });
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, &args.func, |ops| Some(vec![func(&ops[0])]))
+        .unwrap();
+    out.finish().unwrap();
+}
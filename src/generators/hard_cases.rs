@@ -0,0 +1,350 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Search for hard-to-round inputs: for randomly drawn operands the exact
+//! result is compared to the rounding boundaries of f256, i.e. to the
+//! values representable as f256 (boundaries of the directed roundings)
+//! and to the midpoints between them (boundaries of the roundings to
+//! nearest). The operands with the results closest to a boundary are kept
+//! per function.
+//!
+//! The distance is given in units of 2⁻ᴾ·ulp, so a distance below 1 means
+//! that more than 2P bits of the result are needed to round it correctly.
+//! A random search only finds distances of about 2ᴾ / n for n candidates;
+//! it documents the inputs tested, it doesn't prove a bound.
+
+use std::{
+    cmp::Ordering,
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use clap::{Parser, ValueEnum};
+use rand::prelude::*;
+use rug::{float::Round, Float};
+
+use super::oracle;
+use crate::{tables::f256_literal, FPDyn, RoundingMode, FP237, P};
+
+/// Functions supported, all searched by default.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Func {
+    Sqrt,
+    Cbrt,
+    Ln,
+    Log2,
+    Log10,
+    #[value(name = "ln_1p")]
+    Ln1p,
+    Exp,
+    #[value(name = "exp_m1")]
+    ExpM1,
+    Sin,
+    Cos,
+    Tan,
+    Cot,
+}
+
+impl Func {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Sqrt => "sqrt",
+            Self::Cbrt => "cbrt",
+            Self::Ln => "ln",
+            Self::Log2 => "log2",
+            Self::Log10 => "log10",
+            Self::Ln1p => "ln_1p",
+            Self::Exp => "exp",
+            Self::ExpM1 => "exp_m1",
+            Self::Sin => "sin",
+            Self::Cos => "cos",
+            Self::Tan => "tan",
+            Self::Cot => "cot",
+        }
+    }
+}
+
+/// Returns the exact value of `func` at `x`, rounded toward zero to `wp`
+/// bits, together with the ternary value of the rounding.
+pub(crate) fn exact(func: Func, x: &Float, wp: u32) -> FPDyn {
+    let rz = Round::Zero;
+    match func {
+        Func::Sqrt => FPDyn::with_val_round(wp, x.sqrt_ref(), rz),
+        Func::Cbrt => FPDyn::with_val_round(wp, x.cbrt_ref(), rz),
+        Func::Ln => FPDyn::with_val_round(wp, x.ln_ref(), rz),
+        Func::Log2 => FPDyn::with_val_round(wp, x.log2_ref(), rz),
+        Func::Log10 => FPDyn::with_val_round(wp, x.log10_ref(), rz),
+        Func::Ln1p => FPDyn::with_val_round(wp, x.ln_1p_ref(), rz),
+        Func::Exp => FPDyn::with_val_round(wp, x.exp_ref(), rz),
+        Func::ExpM1 => FPDyn::with_val_round(wp, x.exp_m1_ref(), rz),
+        Func::Sin => FPDyn::with_val_round(wp, x.sin_ref(), rz),
+        Func::Cos => FPDyn::with_val_round(wp, x.cos_ref(), rz),
+        Func::Tan => FPDyn::with_val_round(wp, x.tan_ref(), rz),
+        Func::Cot => FPDyn::with_val_round(wp, x.cot_ref(), rz),
+    }
+}
+
+/// Kind of the rounding boundary closest to an exact result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Boundary {
+    /// A value representable as f256
+    Representable,
+    /// The midpoint between two consecutive f256 values
+    Midpoint,
+}
+
+/// Returns the distance of `v` from the closest rounding boundary of a
+/// normal f256 in units of 2⁻ᴾ·ulp, together with the kind of that
+/// boundary, or `None` if `v` is zero or not finite.
+pub(crate) fn boundary_distance(v: &Float) -> Option<(Float, Boundary)> {
+    let exp = v.get_exp()?;
+    // |v| in units of ½ ulp, ulp = 2^(exp - P)
+    let s = v.clone().abs() << (P as i32 + 1 - exp);
+    let k = Float::with_val(v.prec(), s.round_ref());
+    let kind = if k.is_integer() && k.to_integer().unwrap().is_odd() {
+        Boundary::Midpoint
+    } else {
+        Boundary::Representable
+    };
+    // ½ ulp = 2^(P-1) units of 2⁻ᴾ·ulp
+    let d = Float::with_val(v.prec(), s - k).abs() << (P - 1);
+    Some((d, kind))
+}
+
+/// A hard-to-round input found for some function.
+#[derive(Clone, Debug)]
+pub(crate) struct HardCase {
+    pub x: FP237,
+    pub distance: Float,
+    pub boundary: Boundary,
+}
+
+/// Returns the distance of the exact value of `func` at `x` from the
+/// closest rounding boundary, see `boundary_distance`, or `None` if the
+/// result is exact, zero or not finite. The working precision is raised
+/// from 3P bits by P bits at a time until the distance is resolved.
+pub(crate) fn hardness(func: Func, x: &FP237) -> Option<(Float, Boundary)> {
+    for wp in FPDyn::precisions(3) {
+        let v = exact(func, &x.f, wp);
+        let (d, kind) = boundary_distance(&v.f)?;
+        if !d.is_zero() {
+            return Some((d, kind));
+        }
+        if v.o == Ordering::Equal {
+            return None;
+        }
+    }
+    unreachable!()
+}
+
+/// Returns the `keep` hardest of `n` inputs of `func` drawn with `rng`
+/// from the positive values with exponents in `exp_range`, closest first,
+/// together with the number of inputs tested.
+pub(crate) fn search<R: Rng + ?Sized>(
+    func: Func,
+    n: u32,
+    keep: usize,
+    exp_range: &std::ops::RangeInclusive<i32>,
+    rng: &mut R,
+) -> (Vec<HardCase>, u32) {
+    let mut cases: Vec<HardCase> = Vec::new();
+    let mut tested = 0;
+    for _ in 0..n {
+        let x = FP237::random_from_exp_range_with_rng(rng, exp_range).abs();
+        let Some((distance, boundary)) = hardness(func, &x) else {
+            continue;
+        };
+        tested += 1;
+        let pos = cases.partition_point(|c| c.distance <= distance);
+        if pos < keep {
+            cases.insert(
+                pos,
+                HardCase {
+                    x,
+                    distance,
+                    boundary,
+                },
+            );
+            cases.truncate(keep);
+        }
+    }
+    (cases, tested)
+}
+
+/// Returns `d` as power of two, e.g. 2^12.34.
+fn as_pow2(d: &Float) -> String {
+    format!("2^{:.2}", Float::with_val(64, d.log2_ref()).to_f64())
+}
+
+/// Searches the hardest-to-round inputs of some functions among random
+/// operands and emits them as Rust arrays of test cases on the output
+/// and as a report.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Functions to be searched
+    #[arg(
+        short,
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = Func::value_variants().to_vec()
+    )]
+    func: Vec<Func>,
+
+    /// Number of random inputs per function
+    #[arg(short, long, default_value_t = 10000)]
+    n_test_data: u32,
+
+    /// Number of hardest inputs kept per function
+    #[arg(short, long, default_value_t = 8)]
+    keep: usize,
+
+    /// Seed for the random number generator
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Lower bound of the exponent range of the inputs
+    #[arg(long, default_value_t = -8, allow_negative_numbers = true)]
+    exp_min: i32,
+
+    /// Upper bound of the exponent range of the inputs
+    #[arg(long, default_value_t = 8, allow_negative_numbers = true)]
+    exp_max: i32,
+
+    /// Output file of the Rust source (default: stdout)
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Output file of the report (default: stderr)
+    #[arg(long)]
+    report: Option<PathBuf>,
+}
+
+/// Returns a writer to `path` or, if not given, to `default`.
+fn create(path: &Option<PathBuf>, default: Box<dyn Write>) -> Box<dyn Write> {
+    match path {
+        Some(path) => Box::new(File::create(path).unwrap_or_else(|e| {
+            panic!("Can't create {}: {e}", path.display())
+        })),
+        None => default,
+    }
+}
+
+pub fn run(args: Args) {
+    let (exp_min, exp_max) = (args.exp_min, args.exp_max);
+    assert!(exp_min <= exp_max, "Empty exponent range");
+    let seed = args.seed.unwrap_or_else(|| thread_rng().gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut src = create(&args.output, Box::new(io::stdout()));
+    let mut report = create(&args.report, Box::new(io::stderr()));
+    let write = |w: &mut Box<dyn Write>, s: String| {
+        writeln!(w, "{s}").unwrap();
+    };
+    write(
+        &mut src,
+        format!(
+            "// Hardest-to-round inputs among {} random ones with exponents \
+             in {exp_min}..={exp_max}, seed {seed}\n// distance of the \
+             exact result from the closest rounding boundary in units of \
+             2^-{P} ulp",
+            args.n_test_data
+        ),
+    );
+    write(
+        &mut report,
+        format!(
+            "hard-to-round inputs, seed {seed}, exponents \
+             {exp_min}..={exp_max}, distances in units of 2^-{P} \
+             ulp\n\n{:<8} {:>8} {:>16} {:>13}  boundary",
+            "func", "tested", "x", "distance"
+        ),
+    );
+    for &func in &args.func {
+        let (cases, tested) = search(
+            func,
+            args.n_test_data,
+            args.keep,
+            &(exp_min..=exp_max),
+            &mut rng,
+        );
+        let func_name = func.name();
+        let name = format!("HARD_CASES_{}", func_name.to_uppercase());
+        let mut lines = vec![format!(
+            "pub(crate) const {name}: [(f256, f256); {}] = [",
+            cases.len()
+        )];
+        for c in &cases {
+            let res = oracle::eval(
+                func_name,
+                std::slice::from_ref(&c.x),
+                RoundingMode::NearestEven,
+            );
+            lines.push(format!(
+                "    // {func_name}({}): distance {} ({:?})\n    ({}, {}),",
+                c.x,
+                as_pow2(&c.distance),
+                c.boundary,
+                f256_literal(&c.x),
+                f256_literal(&res)
+            ));
+            write(
+                &mut report,
+                format!(
+                    "{func_name:<8} {tested:>8} {:>16} {:>13}  {:?}",
+                    c.x.f.to_string_radix(10, Some(8)),
+                    as_pow2(&c.distance),
+                    c.boundary
+                ),
+            );
+        }
+        lines.push("];".into());
+        write(&mut src, lines.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod hard_cases_tests {
+    use super::*;
+
+    #[test]
+    fn test_boundary_distance() {
+        let wp = 3 * P;
+        // 1 + 2⁻²³⁷ is the midpoint between 1 and 1 + 2⁻²³⁶
+        let mid = Float::with_val(wp, 1) + Float::with_val(wp, -237).exp2();
+        let (d, kind) = boundary_distance(&mid).unwrap();
+        assert!(d.is_zero());
+        assert_eq!(kind, Boundary::Midpoint);
+        // 2⁻⁴⁰⁰ above it: 2⁻⁴⁰⁰ / 2⁻²³⁶ · 2²³⁷ = 2⁷³
+        let v = mid + Float::with_val(wp, -400).exp2();
+        let (d, kind) = boundary_distance(&v).unwrap();
+        assert_eq!(d, Float::with_val(wp, 73).exp2());
+        assert_eq!(kind, Boundary::Midpoint);
+        // a quarter ulp below 3 is as far from 3 as from the midpoint
+        let ulp = Float::with_val(wp, -235).exp2();
+        let v = Float::with_val(wp, 3) - ulp / 4;
+        let (d, _) = boundary_distance(&v).unwrap();
+        assert_eq!(d, Float::with_val(wp, P - 2).exp2());
+        assert!(boundary_distance(&Float::new(wp)).is_none());
+    }
+
+    #[test]
+    fn test_search() {
+        // exact results are skipped
+        assert!(hardness(Func::Sqrt, &FP237::from(4)).is_none());
+        let mut rng = StdRng::seed_from_u64(237);
+        let (cases, tested) = search(Func::Exp, 64, 4, &(-2..=2), &mut rng);
+        assert_eq!(tested, 64);
+        assert_eq!(cases.len(), 4);
+        assert!(cases.windows(2).all(|w| w[0].distance <= w[1].distance));
+        let (d, _) = hardness(Func::Exp, &cases[0].x).unwrap();
+        assert_eq!(d, cases[0].distance);
+    }
+}
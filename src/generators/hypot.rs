@@ -0,0 +1,119 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    cmp::{max, min},
+    ops::RangeInclusive,
+};
+
+use clap::{Parser, ValueEnum};
+
+use super::CommonArgs;
+use crate::{
+    CorpusBuilder, Header, RoundingMode, EMAX, EMIN, FP237,
+    MIN_EXP_SUBNORMAL, P, PM1,
+};
+
+const OVERFLOW_EXP_LOWER_BOUND: i32 = EMAX / 2 + 1;
+const OVERFLOW_EXP_UPPER_BOUND: i32 = EMAX;
+const UNDERFLOW_EXP_LOWER_BOUND: i32 = MIN_EXP_SUBNORMAL;
+const UNDERFLOW_EXP_UPPER_BOUND: i32 = EMIN / 2 - 1;
+
+// f² > f256::MAX
+const OVERFLOW_EXP_RANGE: RangeInclusive<i32> =
+    OVERFLOW_EXP_LOWER_BOUND..=OVERFLOW_EXP_UPPER_BOUND;
+// f² < MIN_POSITIVE
+const UNDERFLOW_EXP_RANGE: RangeInclusive<i32> =
+    UNDERFLOW_EXP_LOWER_BOUND..=UNDERFLOW_EXP_UPPER_BOUND;
+
+/// Ranges of the input values x, y.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Range {
+    /// x² + y² overflows
+    #[value(name = "O")]
+    Overflow,
+    /// x² + y² underflows
+    #[value(name = "U")]
+    Underflow,
+    /// x² overflows and y² underflows
+    #[value(name = "M")]
+    Mixed,
+}
+
+/// Generates test data for hypot.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Range of input values x, y
+    #[arg(long, value_enum, default_value_t = Range::Overflow)]
+    range: Range,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Add the boundary values of the exponent ranges
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    let (x_range, y_range) = match args.range {
+        Range::Overflow => (&OVERFLOW_EXP_RANGE, &OVERFLOW_EXP_RANGE),
+        Range::Underflow => (&UNDERFLOW_EXP_RANGE, &UNDERFLOW_EXP_RANGE),
+        Range::Mixed => (&OVERFLOW_EXP_RANGE, &UNDERFLOW_EXP_RANGE),
+    };
+    let seed = args.common.seed();
+
+    let mut builder = CorpusBuilder::new(2, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            let x = FP237::random_from_exp_range_with_rng(rng, x_range);
+            let y = if x_range == y_range {
+                // keep y within reach of x, so that both squares
+                // contribute to the result
+                let (_, e, _) = x.decode(false);
+                let lower_limit = max(*y_range.start(), e + PM1 - P as i32);
+                let upper_limit = min(*y_range.end(), e + PM1 + P as i32);
+                FP237::random_from_exp_range_with_rng(
+                    rng,
+                    &(lower_limit..=upper_limit),
+                )
+            } else {
+                FP237::random_from_exp_range_with_rng(rng, y_range)
+            };
+            vec![x, y]
+        });
+    if args.boundaries {
+        builder = builder.boundaries(x_range).boundaries(y_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        builder = builder.verify(&[RoundingMode::NearestEven]);
+    }
+    let header = Header::new("hypot")
+        .seed(seed)
+        .exp_ranges(&[x_range, y_range]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "hypot", |ops| Some(vec![ops[0].hypot(&ops[1])]))
+        .unwrap();
+    out.finish().unwrap();
+}
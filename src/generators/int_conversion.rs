@@ -0,0 +1,156 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::{Float, Integer};
+
+use super::CommonArgs;
+use crate::{
+    CorpusBuilder, Header, Record, RoundingMode, TestItem, FP237, P,
+    ROUNDING_MODES,
+};
+
+/// Returns the number of bits and the signedness of the integer type named
+/// `name`.
+fn int_type(name: &str) -> (u32, bool) {
+    match name {
+        "i32" => (32, true),
+        "i64" => (64, true),
+        "i128" => (128, true),
+        "u64" => (64, false),
+        "u128" => (128, false),
+        "u256" => (256, false),
+        _ => panic!("Unkown int type"),
+    }
+}
+
+/// Returns the range of values of the integer type with `n_bits` bits.
+fn int_range(n_bits: u32, signed: bool) -> (Integer, Integer) {
+    if signed {
+        let lim = Integer::from(1) << (n_bits - 1);
+        (-lim.clone(), lim - 1)
+    } else {
+        (Integer::ZERO, (Integer::from(1) << n_bits) - 1)
+    }
+}
+
+/// Generates test data for conversions from and to integers.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Integer type: i32 i64 i128 u64 u128 u256
+    #[arg(short, long, default_value = "i64")]
+    int_type: String,
+
+    /// Direction of conversion: T = f256 to int, F = int to f256
+    #[arg(short, long, default_value_t = 'T')]
+    direction: char,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+}
+
+pub fn run(args: Args) {
+    let (n_bits, signed) = int_type(&args.int_type);
+    let (min, max) = int_range(n_bits, signed);
+    let seed = args.common.seed();
+    let op = if args.direction == 'T' {
+        "to_int"
+    } else {
+        "from_int"
+    };
+    let header = Header::new(op)
+        .seed(seed)
+        .rounding("all")
+        .param("int_type", &args.int_type);
+    let mut out = args.common.output.meta_writer(&header);
+
+    match args.direction {
+        'T' => {
+            // values near ±2ᵏ, offset by multiples of 1/4 to hit the ties,
+            // mixed with arbitrary values of the same magnitude
+            let mut builder = CorpusBuilder::new(1, seed)
+                .sign(args.common.sign)
+                .random(args.common.n_test_data, |rng| {
+                    let k = rng.gen_range(0..=n_bits as i32);
+                    let x = if rng.gen_ratio(1, 4) {
+                        FP237::random_from_exp_range_with_rng(rng, &(-4..=k))
+                    } else {
+                        let d = rng.gen_range(-8..=8) as f64 / 4.0;
+                        let f = Float::with_val(P, Float::i_exp(1, k)) + d;
+                        FP237::with_val_round(f, RoundingMode::NearestEven)
+                    };
+                    vec![if rng.gen() { -x } else { x }]
+                });
+            if args.specials {
+                builder = builder.specials();
+            }
+            // value, then rounding mode, result, invalid flag and inexact
+            // flag
+            for item in builder.build() {
+                let x = &item[0];
+                for rnd in ROUNDING_MODES {
+                    let r = x.round_to_int(rnd);
+                    let i =
+                        r.f.to_integer().filter(|i| *i >= min && *i <= max);
+                    let meta = Record::new().token(rnd);
+                    let meta = match i {
+                        Some(i) => {
+                            meta.int(i).int(0).int((r.f != x.f) as u32)
+                        }
+                        None => meta.token("-").int(1).int(0),
+                    };
+                    let item =
+                        TestItem::new("to_int", vec![x.into()], vec![])
+                            .with_meta(meta);
+                    out.write_item(&item).unwrap();
+                }
+            }
+        }
+        'F' => {
+            // integers near ±2ᵏ and arbitrary integers with k bits
+            let mut rng = StdRng::seed_from_u64(seed);
+            for _ in 0..args.common.n_test_data {
+                let k = rng.gen_range(1..=n_bits);
+                let mut i = if rng.gen() {
+                    Integer::from(1) << (k - 1)
+                } else {
+                    let (h, l) = (rng.gen::<u128>(), rng.gen::<u128>());
+                    ((Integer::from(h) << 128) + l) >> (256 - k)
+                };
+                i += rng.gen_range(-3..=3);
+                if signed && rng.gen() {
+                    i = -i;
+                }
+                if i < min || i > max {
+                    continue;
+                }
+                // value, then int, rounding mode and inexact flag
+                for rnd in ROUNDING_MODES {
+                    let x = FP237::with_val_round(&i, rnd);
+                    let meta = Record::new()
+                        .int(&i)
+                        .token(rnd)
+                        .int((x.f != i) as u32);
+                    let item =
+                        TestItem::new("from_int", vec![], vec![(&x).into()])
+                            .with_meta(meta);
+                    out.write_item(&item).unwrap();
+                }
+            }
+        }
+        _ => panic!("Unkown direction"),
+    }
+    out.finish().unwrap();
+}
@@ -0,0 +1,128 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::{Parser, ValueEnum};
+use rand::prelude::*;
+
+use super::CommonArgs;
+use crate::{CorpusBuilder, Header, Matrix237, Vector237, FP237};
+
+// exponents of the elements of the matrices and the right-hand sides
+const EXP_RANGE: RangeInclusive<i32> = -8..=8;
+
+/// Returns a random element of a matrix or a right-hand side.
+fn random_elem(rng: &mut StdRng) -> FP237 {
+    FP237::random_from_exp_range_with_rng(rng, &EXP_RANGE)
+}
+
+/// Returns a random value with an exponent of exactly `e`.
+fn random_with_exp(rng: &mut StdRng, e: i32) -> FP237 {
+    FP237::random_from_exp_range_with_rng(rng, &(e..=e))
+}
+
+/// Returns the elements of a random `n` x `n` matrix whose last row is a
+/// combination of the others, perturbed by about 2⁻ᶜ relative to the
+/// elements, so that its condition number is about 2ᶜ.
+fn near_singular(rng: &mut StdRng, n: usize, c: i32) -> Vec<FP237> {
+    let mut elems: Vec<FP237> =
+        (0..(n - 1) * n).map(|_| random_elem(rng)).collect();
+    let coeffs: Vec<FP237> = (0..n - 1)
+        .map(|_| FP237::random_from_exp_range_with_rng(rng, &(-2..=2)))
+        .chain([FP237::ONE()])
+        .collect();
+    for j in 0..n {
+        let col: Vec<FP237> = (0..n - 1)
+            .map(|i| elems[i * n + j].clone())
+            .chain([random_with_exp(rng, *EXP_RANGE.start() - c)])
+            .collect();
+        elems.push(FP237::dot(&coeffs, &col));
+    }
+    elems
+}
+
+/// Kinds of matrices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Kind {
+    Hilbert,
+    NearSingular,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Hilbert => "hilbert",
+            Self::NearSingular => "near-singular",
+        }
+    }
+}
+
+/// Generates small ill-conditioned linear systems a·x = b with their exact
+/// solutions, each element rounded once.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Kind of matrix
+    #[arg(short, long, value_enum, default_value_t = Kind::Hilbert)]
+    kind: Kind,
+
+    /// Dimension of the systems
+    #[arg(short, long, default_value_t = 4)]
+    dim: usize,
+
+    /// Number of bits lost to the condition of near-singular matrices
+    #[arg(short, long, default_value_t = 100)]
+    cancellation: i32,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+pub fn run(args: Args) {
+    let n = args.dim;
+    assert!((2..=8).contains(&n), "Dimension must be in 2..=8");
+    let c = args.cancellation;
+    let kind = args.kind;
+    let hilbert = match kind {
+        Kind::Hilbert => Matrix237::hilbert(n).elems,
+        Kind::NearSingular => vec![],
+    };
+    let seed = args.common.seed();
+
+    // operands: the elements of a row by row, followed by b
+    let gen = |rng: &mut StdRng| {
+        let mut ops = if hilbert.is_empty() {
+            near_singular(rng, n, c)
+        } else {
+            hilbert.clone()
+        };
+        ops.extend((0..n).map(|_| random_elem(rng)));
+        ops
+    };
+    // the signs are part of the structure of the matrices, so --sign
+    // doesn't apply
+    let builder = CorpusBuilder::new(n * n + n, seed)
+        .random(args.common.n_test_data, gen);
+    let header = Header::new("solve")
+        .seed(seed)
+        .param("kind", kind.name())
+        .param("dim", n)
+        .exp_ranges(&[&EXP_RANGE]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "solve", |ops| {
+            let a = Matrix237::new(n, n, ops[..n * n].to_vec());
+            let b = Vector237::new(ops[n * n..].to_vec());
+            a.solve(&b).map(|x| x.elems)
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
@@ -0,0 +1,117 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::{error::ErrorKind, Parser};
+
+use super::{usage_error, CommonArgs, NORMAL_EXP_RANGE, SUBNORMAL_EXP_RANGE};
+use crate::{
+    boundary_values, CorpusBuilder, Header, RoundingMode, Sign, FP237, PM1,
+};
+
+const NEAR_ONE_EXP_LOWER_BOUND: i32 = -PM1 - 2;
+const NEAR_ONE_EXP_UPPER_BOUND: i32 = -1;
+
+// 2⁻²³⁸ <= |d| < 2⁻¹ for f = 1 + d
+const NEAR_ONE_EXP_RANGE: RangeInclusive<i32> =
+    NEAR_ONE_EXP_LOWER_BOUND..=NEAR_ONE_EXP_UPPER_BOUND;
+
+/// Generates test data for the logarithms.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// logarithmic function: ln log2 log10 ln_1p
+    #[arg(short, long, default_value = "ln")]
+    func: String,
+
+    /// Range of input value f: O = near 1 (ln_1p: near 0),
+    /// S = subnormal, N = normal
+    #[arg(long, default_value_t = 'O')]
+    range: char,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Add the arguments derived from the boundary values of the exponent
+    /// range like the random ones
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    let func = match args.func.as_str() {
+        "ln" => FP237::ln,
+        "log2" => FP237::log2,
+        "log10" => FP237::log10,
+        "ln_1p" => FP237::ln_1p,
+        _ => panic!("Unkown func"),
+    };
+    let is_ln_1p = args.func == "ln_1p";
+    if !is_ln_1p && args.common.sign == Sign::Neg {
+        usage_error(
+            ErrorKind::ArgumentConflict,
+            format!("--sign neg gives NaN only for {}", args.func),
+        );
+    }
+    let exp_range = match args.range {
+        'O' => &NEAR_ONE_EXP_RANGE,
+        'S' => &SUBNORMAL_EXP_RANGE,
+        'N' => &NORMAL_EXP_RANGE,
+        _ => panic!("Unkown range"),
+    };
+    let near_one = args.range == 'O';
+    let seed = args.common.seed();
+
+    // near 1 ln_1p takes the distance d to 1 as argument, the other
+    // functions 1 + d, outside that range all functions get positive
+    // arguments
+    let arg = move |d: FP237| match (near_one, is_ln_1p) {
+        (true, false) => &FP237::ONE() + &d,
+        (true, true) => d,
+        (false, _) => d.abs(),
+    };
+
+    let mut builder = CorpusBuilder::new(1, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, move |rng| {
+            let d = FP237::random_from_exp_range_with_rng(rng, exp_range);
+            vec![arg(d)]
+        });
+    if args.boundaries {
+        builder = builder.items(
+            boundary_values(exp_range)
+                .into_iter()
+                .map(|d| vec![arg(d)])
+                .collect(),
+        );
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        builder = builder.verify(&[RoundingMode::NearestEven]);
+    }
+    let header = Header::new(&args.func).seed(seed).exp_ranges(&[exp_range]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, &args.func, |ops| Some(vec![func(&ops[0])]))
+        .unwrap();
+    out.finish().unwrap();
+}
@@ -0,0 +1,174 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use serde::Deserialize;
+
+use super::Command;
+
+/// A single generator run, e.g.
+///
+/// ```toml
+/// [[job]]
+/// command = "sqrt"
+/// n_test_data = 1000
+/// seed = 42
+/// output = "sqrt.tsv.gz"
+/// args = ["-b", "-x"]
+/// ```
+///
+/// `args` holds the generator specific options. A relative output path is
+/// taken relative to the directory of the manifest.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Job {
+    pub command: String,
+    pub n_test_data: Option<u32>,
+    pub seed: Option<u64>,
+    pub format: Option<String>,
+    pub output: Option<PathBuf>,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// A test suite, i.e. the list of jobs to be run in order.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    pub job: Vec<Job>,
+}
+
+#[derive(Parser, Debug)]
+#[command(no_binary_name = true)]
+struct JobCli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+impl Job {
+    /// Returns the command line equivalent to `self`.
+    pub fn to_args(&self, base_dir: &Path) -> Vec<String> {
+        let mut res = vec![self.command.clone()];
+        if let Some(n) = self.n_test_data {
+            res.extend(["-n".to_string(), n.to_string()]);
+        }
+        if let Some(seed) = self.seed {
+            res.extend(["-s".to_string(), seed.to_string()]);
+        }
+        if let Some(format) = &self.format {
+            res.extend(["--format".to_string(), format.clone()]);
+        }
+        if let Some(output) = &self.output {
+            let path = base_dir.join(output);
+            res.extend(["--output".to_string(), path.display().to_string()]);
+        }
+        res.extend(self.args.iter().cloned());
+        res
+    }
+}
+
+impl Manifest {
+    /// Parses and checks all jobs, so that a typo in the last job doesn't
+    /// show up only after all others have run.
+    pub fn commands(&self, base_dir: &Path) -> Result<Vec<Command>, String> {
+        self.job
+            .iter()
+            .enumerate()
+            .map(|(i, job)| {
+                JobCli::try_parse_from(job.to_args(base_dir))
+                    .map(|cli| cli.command)
+                    .map_err(|e| format!("job {}: {e}", i + 1))
+            })
+            .collect()
+    }
+}
+
+/// Runs all jobs listed in a TOML manifest.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Path of the manifest
+    manifest: PathBuf,
+}
+
+pub fn run(args: Args) {
+    let text = fs::read_to_string(&args.manifest).unwrap_or_else(|e| {
+        panic!("Can't read {}: {e}", args.manifest.display())
+    });
+    let manifest: Manifest = toml::from_str(&text)
+        .unwrap_or_else(|e| panic!("Invalid manifest: {e}"));
+    let base_dir = args.manifest.parent().unwrap_or(Path::new(""));
+    let commands = manifest
+        .commands(base_dir)
+        .unwrap_or_else(|e| panic!("Invalid manifest: {e}"));
+    for (i, cmd) in commands.into_iter().enumerate() {
+        eprintln!("job {}: {}", i + 1, manifest.job[i].command);
+        cmd.run();
+    }
+}
+
+#[cfg(test)]
+mod manifest_tests {
+    use super::*;
+
+    #[test]
+    fn test_commands() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            [[job]]
+            command = "addsub"
+            n_test_data = 3
+            seed = 7
+            output = "add_sub.tsv"
+            args = ["-b"]
+
+            [[job]]
+            command = "constants"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.job[0].to_args(Path::new("suite")),
+            [
+                "addsub",
+                "-n",
+                "3",
+                "-s",
+                "7",
+                "--output",
+                Path::new("suite").join("add_sub.tsv").to_str().unwrap(),
+                "-b"
+            ]
+        );
+        assert_eq!(manifest.commands(Path::new("")).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_job() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            [[job]]
+            command = "sqrt"
+
+            [[job]]
+            command = "sqrt"
+            args = ["--no-such-option"]
+            "#,
+        )
+        .unwrap();
+        let err = manifest.commands(Path::new("")).unwrap_err();
+        assert!(err.starts_with("job 2:"));
+    }
+}
@@ -0,0 +1,536 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Test data generators, one module per subcommand of the `rug237` binary.
+//!
+//! Each module provides an `Args` struct and a `run` function. The former
+//! `gen_*` binaries are thin wrappers around these.
+
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{stdin, BufRead, BufReader},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use clap::{error::ErrorKind, Subcommand};
+use flate2::read::GzDecoder;
+use rand::prelude::*;
+
+use crate::{
+    Checkpoint, Format, Header, Literals, Output, Progress, RecordWriter,
+    RoundingMode, Sign, EMAX, EMIN, MIN_EXP_SUBNORMAL,
+};
+
+pub mod add_sub;
+pub mod calc_tables;
+pub mod catalogue;
+pub mod cbrt;
+pub mod circular_fn;
+pub mod compare;
+pub mod complex;
+pub mod constants;
+pub mod convert;
+pub mod copysign_signum;
+pub mod div;
+pub mod dot_product;
+pub mod euclid;
+pub mod exp2_table;
+pub mod expm1_ln1p;
+pub mod f64_f32_conversion;
+pub mod fixed_format;
+pub mod fma;
+pub mod fms_fnma;
+pub mod format_scientific;
+pub mod frexp_ldexp;
+pub mod from_str;
+pub mod gamma_lgamma;
+pub mod hard_cases;
+pub mod hypot;
+pub mod int_conversion;
+pub mod linsys;
+pub mod log;
+pub mod manifest;
+pub mod mul;
+pub mod mul_div;
+pub mod oracle;
+pub mod overflow_underflow;
+pub mod powi;
+pub mod raw_bits;
+pub mod rem;
+pub mod remquo;
+pub mod round_to_int;
+pub mod rounding_mode;
+pub mod sample;
+pub mod search_hard_cases;
+pub mod sos;
+pub mod sqrt;
+pub mod stats;
+pub mod str_round_trip;
+pub mod subnormal_edge;
+pub mod to_str;
+pub mod to_str_shortest;
+pub mod trig_reduction;
+pub mod two_sum_two_prod;
+pub mod verify_table;
+
+pub(crate) const SUBNORMAL_EXP_LOWER_BOUND: i32 = MIN_EXP_SUBNORMAL;
+pub(crate) const SUBNORMAL_EXP_UPPER_BOUND: i32 = EMIN - 1;
+pub(crate) const NORMAL_EXP_LOWER_BOUND: i32 = EMIN;
+pub(crate) const EXP_UPPER_BOUND: i32 = EMAX;
+
+// f256::MIN_GT_ZERO <= |f| < MIN_POSITIVE
+pub(crate) const SUBNORMAL_EXP_RANGE: RangeInclusive<i32> =
+    SUBNORMAL_EXP_LOWER_BOUND..=SUBNORMAL_EXP_UPPER_BOUND;
+
+// MIN_POSITIVE <= |f| <= f256::MAX
+pub(crate) const NORMAL_EXP_RANGE: RangeInclusive<i32> =
+    NORMAL_EXP_LOWER_BOUND..=EXP_UPPER_BOUND;
+
+/// Predefined exponent ranges of the operands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExpPreset {
+    /// Subnormal values only
+    Subnormal,
+    /// Normal values only
+    Normal,
+    /// The whole exponent range, from the smallest subnormal to the
+    /// largest normal values
+    Extreme,
+}
+
+impl ExpPreset {
+    pub fn range(self) -> RangeInclusive<i32> {
+        match self {
+            Self::Subnormal => SUBNORMAL_EXP_RANGE,
+            Self::Normal => NORMAL_EXP_RANGE,
+            Self::Extreme => SUBNORMAL_EXP_LOWER_BOUND..=EXP_UPPER_BOUND,
+        }
+    }
+}
+
+/// Exits with the usage error `msg` of kind `kind`, like clap does for the
+/// arguments it rejects itself.
+pub fn usage_error(kind: ErrorKind, msg: impl Display) -> ! {
+    clap::Error::raw(kind, format!("{msg}\n")).exit()
+}
+
+/// Options shared by all generators.
+#[derive(clap::Args, Debug)]
+pub struct CommonArgs {
+    /// Number of test data to generate
+    #[arg(short, long, default_value_t = 25)]
+    pub n_test_data: u32,
+
+    /// Seed for the random number generator
+    #[arg(short, long)]
+    pub seed: Option<u64>,
+
+    /// Number of threads evaluating the test data (doesn't change the
+    /// output)
+    #[arg(short, long, default_value_t = 1)]
+    pub jobs: usize,
+
+    /// Sign of the randomly drawn operands
+    #[arg(long, value_enum, default_value_t = Sign::Both)]
+    pub sign: Sign,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+impl CommonArgs {
+    /// Returns the seed to be used, see `OutputArgs::seed`.
+    pub fn seed(&self) -> u64 {
+        self.output.seed(self.seed)
+    }
+}
+
+/// Exponent range options of the generators drawing operands from ranges
+/// spanning the subnormal or normal values.
+#[derive(clap::Args, Debug)]
+pub struct ExpRangeArgs {
+    /// Exponent range of the randomly drawn operands, replacing the ranges
+    /// spanning the subnormal or normal values
+    #[arg(long, value_enum)]
+    pub exp_preset: Option<ExpPreset>,
+
+    /// Lower bound of the exponent range, overriding the one of the preset
+    #[arg(
+        long,
+        allow_negative_numbers = true,
+        value_parser = exp_parser()
+    )]
+    pub exp_min: Option<i32>,
+
+    /// Upper bound of the exponent range, overriding the one of the preset
+    #[arg(
+        long,
+        allow_negative_numbers = true,
+        value_parser = exp_parser()
+    )]
+    pub exp_max: Option<i32>,
+}
+
+/// Returns the parser of exponents within the whole exponent range.
+fn exp_parser() -> clap::builder::RangedI64ValueParser<i32> {
+    clap::value_parser!(i32).range(MIN_EXP_SUBNORMAL as i64..=EMAX as i64)
+}
+
+impl ExpRangeArgs {
+    /// Returns true if any of the exponent range options is given.
+    pub fn is_given(&self) -> bool {
+        self.exp_preset.is_some()
+            || self.exp_min.is_some()
+            || self.exp_max.is_some()
+    }
+
+    /// Returns the exponent range to draw operands from instead of
+    /// `default`, see `exp_preset`, `exp_min` and `exp_max`. Without a
+    /// preset the bounds override the ones of the whole exponent range.
+    /// Exits with a usage error if the resulting range is empty.
+    pub fn exp_range(
+        &self,
+        default: &RangeInclusive<i32>,
+    ) -> RangeInclusive<i32> {
+        if !self.is_given() {
+            return default.clone();
+        }
+        let preset = self.exp_preset.unwrap_or(ExpPreset::Extreme).range();
+        let lower = self.exp_min.unwrap_or(*preset.start());
+        let upper = self.exp_max.unwrap_or(*preset.end());
+        if lower > upper {
+            usage_error(
+                ErrorKind::ValueValidation,
+                format!("Empty exponent range {lower}..={upper}"),
+            );
+        }
+        lower..=upper
+    }
+}
+
+/// Rounding option of the generators supporting all rounding modes.
+#[derive(clap::Args, Debug)]
+pub struct RoundArgs {
+    /// Rounding mode of the results: ne tz up dn na
+    #[arg(short, long, default_value_t = RoundingMode::NearestEven)]
+    pub round: RoundingMode,
+}
+
+/// Output options shared by all generators.
+#[derive(clap::Args, Debug)]
+pub struct OutputArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Tsv)]
+    pub format: Format,
+
+    /// Output file (default: stdout), compressed if it ends with .gz
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+
+    /// Omit the header line describing how the data was produced
+    #[arg(long)]
+    pub no_header: bool,
+
+    /// Skip test data equal to data written before
+    #[arg(long)]
+    pub dedup: bool,
+
+    /// Add a decimal literal for each operand and result of the test
+    /// items: shortest (round-tripping) or rounded to the given number of
+    /// significant digits
+    #[arg(long, value_name = "DIGITS")]
+    pub literals: Option<Literals>,
+
+    /// Don't report progress on stderr
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Save the state of the run to this file periodically and resume an
+    /// interrupted run from it (requires --output, uncompressed tsv, csv or
+    /// jsonl)
+    #[arg(long)]
+    pub checkpoint: Option<PathBuf>,
+}
+
+impl OutputArgs {
+    /// Returns the checkpoint of an interrupted run, if any.
+    fn resumed(&self) -> Option<Checkpoint> {
+        let path = self.checkpoint.as_ref()?;
+        Checkpoint::load(path).unwrap_or_else(|e| {
+            panic!("Can't read checkpoint {}: {e}", path.display())
+        })
+    }
+
+    /// Returns the given seed or, when resuming an interrupted run, the
+    /// seed of that run or, if neither is given, a random one. A random
+    /// seed is echoed on stderr, so that the run can be reproduced even
+    /// without the header.
+    pub fn seed(&self, seed: Option<u64>) -> u64 {
+        seed.or_else(|| self.resumed().and_then(|cp| cp.seed))
+            .unwrap_or_else(|| {
+                let seed = thread_rng().gen();
+                eprintln!("seed: {seed}");
+                seed
+            })
+    }
+
+    /// Returns a writer emitting records in the selected format to the
+    /// selected output, starting with `header` unless suppressed.
+    ///
+    /// If an interrupted run is resumed, the output written so far is kept
+    /// and the header is not written again.
+    pub fn writer(&self, header: &Header) -> RecordWriter<Output> {
+        let header = &match self.literals {
+            Some(literals) => {
+                if self.format == Format::Bin {
+                    usage_error(
+                        ErrorKind::ArgumentConflict,
+                        "The binary format can't hold literals",
+                    );
+                }
+                header.clone().param("literals", literals)
+            }
+            None => header.clone(),
+        };
+        let resumed = match &self.checkpoint {
+            Some(cp_path) => {
+                let Some(path) = &self.output else {
+                    usage_error(
+                        ErrorKind::ArgumentConflict,
+                        "--checkpoint requires --output",
+                    );
+                };
+                if !matches!(
+                    self.format,
+                    Format::Tsv | Format::Csv | Format::Jsonl
+                ) || path.extension().is_some_and(|ext| ext == "gz")
+                {
+                    usage_error(
+                        ErrorKind::ArgumentConflict,
+                        "--checkpoint requires uncompressed tsv, csv or jsonl",
+                    );
+                }
+                let resumed = self.resumed();
+                let state = resumed.unwrap_or(Checkpoint {
+                    seed: header.get_seed(),
+                    ..Checkpoint::default()
+                });
+                if state.seed != header.get_seed() {
+                    usage_error(
+                        ErrorKind::ArgumentConflict,
+                        format!(
+                            "Seed doesn't match the one of checkpoint {}",
+                            cp_path.display()
+                        ),
+                    );
+                }
+                Some((cp_path, resumed.is_some(), state))
+            }
+            None => None,
+        };
+        let out = match (&self.output, &resumed) {
+            (Some(path), Some((_, true, state))) => {
+                Output::resume(path, state.bytes).unwrap_or_else(|e| {
+                    panic!("Can't resume {}: {e}", path.display())
+                })
+            }
+            (Some(path), _) => Output::create(path).unwrap_or_else(|e| {
+                panic!("Can't create {}: {e}", path.display())
+            }),
+            (None, _) => Output::stdout(),
+        };
+        let mut wrt = RecordWriter::new(out, self.format);
+        if self.dedup {
+            wrt = wrt.dedup();
+        }
+        if let Some(literals) = self.literals {
+            wrt = wrt.with_literals(literals);
+        }
+        if !self.quiet {
+            wrt = wrt.with_progress(Progress::new(header.op()));
+        }
+        let is_resumed = match resumed {
+            Some((cp_path, is_resumed, state)) => {
+                wrt = wrt.with_checkpoint(cp_path, state);
+                is_resumed
+            }
+            None => false,
+        };
+        if !self.no_header && !is_resumed {
+            wrt.write_header(header).unwrap();
+        }
+        wrt
+    }
+
+    /// Like `writer`, for generators emitting meta columns or plain
+    /// records, which the binary format can't hold. Exits with a usage
+    /// error if it is selected, before anything is written.
+    pub fn meta_writer(&self, header: &Header) -> RecordWriter<Output> {
+        if self.format == Format::Bin {
+            usage_error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "The binary format can't hold the additional data of {}",
+                    header.op()
+                ),
+            );
+        }
+        self.writer(header)
+    }
+}
+
+/// Returns a reader for the file at `path` (decompressed if it ends with
+/// .gz) or for stdin.
+fn open_input(path: Option<&Path>) -> Box<dyn BufRead> {
+    match path {
+        Some(path) => {
+            let file = File::open(path).unwrap_or_else(|e| {
+                panic!("Can't open {}: {e}", path.display())
+            });
+            if path.extension().is_some_and(|ext| ext == "gz") {
+                Box::new(BufReader::new(GzDecoder::new(file)))
+            } else {
+                Box::new(BufReader::new(file))
+            }
+        }
+        None => Box::new(stdin().lock()),
+    }
+}
+
+/// Returns true if `line` is a header line in TSV, CSV or JSON Lines
+/// format (or any other comment line).
+fn is_header(line: &str) -> bool {
+    line.starts_with('#') || line.starts_with("{\"header\"")
+}
+
+/// The subcommands of the `rug237` binary.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[command(name = "addsub")]
+    AddSub(add_sub::Args),
+    Mul(mul::Args),
+    Div(div::Args),
+    Rem(rem::Args),
+    Euclid(euclid::Args),
+    Remquo(remquo::Args),
+    Sqrt(sqrt::Args),
+    Cbrt(cbrt::Args),
+    Fma(fma::Args),
+    FmsFnma(fms_fnma::Args),
+    Sos(sos::Args),
+    Hypot(hypot::Args),
+    #[command(name = "dot")]
+    DotProduct(dot_product::Args),
+    Linsys(linsys::Args),
+    #[command(name = "two-sum")]
+    TwoSumTwoProd(two_sum_two_prod::Args),
+    Powi(powi::Args),
+    #[command(name = "trig")]
+    CircularFn(circular_fn::Args),
+    TrigReduction(trig_reduction::Args),
+    Log(log::Args),
+    Expm1Ln1p(expm1_ln1p::Args),
+    #[command(name = "gamma")]
+    GammaLgamma(gamma_lgamma::Args),
+    FrexpLdexp(frexp_ldexp::Args),
+    RoundToInt(round_to_int::Args),
+    RoundingMode(rounding_mode::Args),
+    SubnormalEdge(subnormal_edge::Args),
+    OverflowUnderflow(overflow_underflow::Args),
+    #[command(name = "copysign")]
+    CopysignSignum(copysign_signum::Args),
+    RawBits(raw_bits::Args),
+    #[command(name = "f64-f32")]
+    F64F32Conversion(f64_f32_conversion::Args),
+    #[command(name = "int-conv")]
+    IntConversion(int_conversion::Args),
+    #[command(name = "parse")]
+    FromStr(from_str::Args),
+    #[command(name = "format")]
+    ToStr(to_str::Args),
+    #[command(name = "format-sci")]
+    FormatScientific(format_scientific::Args),
+    #[command(name = "fixed")]
+    FixedFormat(fixed_format::Args),
+    #[command(name = "shortest")]
+    ToStrShortest(to_str_shortest::Args),
+    StrRoundTrip(str_round_trip::Args),
+    Complex(complex::Args),
+    Constants(constants::Args),
+    Tables(calc_tables::Args),
+    VerifyTable(verify_table::Args),
+    Exp2Table(exp2_table::Args),
+    HardCases(hard_cases::Args),
+    SearchHardCases(search_hard_cases::Args),
+    Oracle(oracle::Args),
+    Compare(compare::Args),
+    Stats(stats::Args),
+    Sample(sample::Args),
+    Convert(convert::Args),
+    RunManifest(manifest::Args),
+}
+
+impl Command {
+    /// Runs the generator selected by `self`.
+    pub fn run(self) {
+        match self {
+            Self::AddSub(args) => add_sub::run(args),
+            Self::Mul(args) => mul::run(args),
+            Self::Div(args) => div::run(args),
+            Self::Rem(args) => rem::run(args),
+            Self::Euclid(args) => euclid::run(args),
+            Self::Remquo(args) => remquo::run(args),
+            Self::Sqrt(args) => sqrt::run(args),
+            Self::Cbrt(args) => cbrt::run(args),
+            Self::Fma(args) => fma::run(args),
+            Self::FmsFnma(args) => fms_fnma::run(args),
+            Self::Sos(args) => sos::run(args),
+            Self::Hypot(args) => hypot::run(args),
+            Self::DotProduct(args) => dot_product::run(args),
+            Self::Linsys(args) => linsys::run(args),
+            Self::TwoSumTwoProd(args) => two_sum_two_prod::run(args),
+            Self::Powi(args) => powi::run(args),
+            Self::CircularFn(args) => circular_fn::run(args),
+            Self::TrigReduction(args) => trig_reduction::run(args),
+            Self::Log(args) => log::run(args),
+            Self::Expm1Ln1p(args) => expm1_ln1p::run(args),
+            Self::GammaLgamma(args) => gamma_lgamma::run(args),
+            Self::FrexpLdexp(args) => frexp_ldexp::run(args),
+            Self::RoundToInt(args) => round_to_int::run(args),
+            Self::RoundingMode(args) => rounding_mode::run(args),
+            Self::SubnormalEdge(args) => subnormal_edge::run(args),
+            Self::OverflowUnderflow(args) => overflow_underflow::run(args),
+            Self::CopysignSignum(args) => copysign_signum::run(args),
+            Self::RawBits(args) => raw_bits::run(args),
+            Self::F64F32Conversion(args) => f64_f32_conversion::run(args),
+            Self::IntConversion(args) => int_conversion::run(args),
+            Self::FromStr(args) => from_str::run(args),
+            Self::ToStr(args) => to_str::run(args),
+            Self::FormatScientific(args) => format_scientific::run(args),
+            Self::FixedFormat(args) => fixed_format::run(args),
+            Self::ToStrShortest(args) => to_str_shortest::run(args),
+            Self::StrRoundTrip(args) => str_round_trip::run(args),
+            Self::Complex(args) => complex::run(args),
+            Self::Constants(args) => constants::run(args),
+            Self::Tables(args) => calc_tables::run(args),
+            Self::VerifyTable(args) => verify_table::run(args),
+            Self::Exp2Table(args) => exp2_table::run(args),
+            Self::HardCases(args) => hard_cases::run(args),
+            Self::SearchHardCases(args) => search_hard_cases::run(args),
+            Self::Oracle(args) => oracle::run(args),
+            Self::Compare(args) => compare::run(args),
+            Self::Stats(args) => stats::run(args),
+            Self::Sample(args) => sample::run(args),
+            Self::Convert(args) => convert::run(args),
+            Self::RunManifest(args) => manifest::run(args),
+        }
+    }
+}
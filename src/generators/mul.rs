@@ -0,0 +1,121 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::cmp::{max, min};
+
+use clap::Parser;
+
+use super::{
+    mul_div::Kind, CommonArgs, ExpRangeArgs, RoundArgs, NORMAL_EXP_RANGE,
+    SUBNORMAL_EXP_RANGE,
+};
+use crate::{CorpusBuilder, Header, EMAX, EMIN, FP237, PM1};
+
+/// Generates test data for multiplication.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Kind of test data
+    #[arg(short, long, value_enum, default_value_t = Kind::Random)]
+    kind: Kind,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    #[command(flatten)]
+    rounding: RoundArgs,
+
+    /// Add the boundary values of the exponent ranges
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    args.kind.check_exp_args(&args.exp);
+    let normal_exp_range = args.exp.exp_range(&NORMAL_EXP_RANGE);
+    let subnormal_exp_range = args.exp.exp_range(&SUBNORMAL_EXP_RANGE);
+    let n_sub_normal = args.common.n_test_data / 20;
+    let n_normal = args.common.n_test_data - n_sub_normal;
+    let seed = args.common.seed();
+    let rnd = args.rounding.round;
+
+    let builder = CorpusBuilder::new(2, seed).sign(args.common.sign);
+    let mut builder = if args.kind == Kind::Random {
+        builder
+            .random(n_normal, |rng| {
+                let x = FP237::random_from_exp_range_with_rng(
+                    rng,
+                    &normal_exp_range,
+                );
+                let (_, e, _) = x.decode(false);
+                let lower_limit = max(EMIN - PM1, EMIN - PM1 - e);
+                let upper_limit = min(EMAX - PM1, EMAX - PM1 - e);
+                let y = FP237::random_from_exp_range_with_rng(
+                    rng,
+                    &(lower_limit..=upper_limit),
+                );
+                vec![x, y]
+            })
+            .random(n_sub_normal, |rng| {
+                let x = FP237::random_from_exp_range_with_rng(
+                    rng,
+                    &normal_exp_range,
+                );
+                let y = FP237::random_from_exp_range_with_rng(
+                    rng,
+                    &subnormal_exp_range,
+                );
+                vec![x, y]
+            })
+    } else {
+        args.kind.add_edge_cases(
+            builder,
+            args.common.n_test_data,
+            true,
+            &subnormal_exp_range,
+        )
+    };
+    if args.boundaries {
+        builder = builder
+            .boundaries(&normal_exp_range)
+            .boundaries(&subnormal_exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        builder = builder.verify(&[rnd]);
+    }
+    let header = Header::new("mul").seed(seed).rounding(rnd).exp_ranges(
+        &match args.kind {
+            Kind::Random => vec![&normal_exp_range, &subnormal_exp_range],
+            kind => kind.exp_ranges(&subnormal_exp_range),
+        },
+    );
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "mul", |ops| {
+            Some(vec![ops[0].mul_round(&ops[1], rnd)])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
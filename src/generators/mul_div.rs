@@ -0,0 +1,257 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Edge cases of products and quotients, shared by the mul and div
+//! generators.
+
+use std::ops::RangeInclusive;
+
+use clap::{error::ErrorKind, ValueEnum};
+use rand::{prelude::*, rngs::StdRng};
+use rug::{Float, Integer};
+
+use super::{usage_error, ExpRangeArgs};
+use crate::{CorpusBuilder, RoundingMode, EMAX, EMIN, FP237, P, PM1};
+
+const EXACT_EXP_LOWER_BOUND: i32 = EMIN / 2;
+const EXACT_EXP_UPPER_BOUND: i32 = EMAX / 2;
+
+// exponents of operands with exact product or quotient
+const EXACT_EXP_RANGE: RangeInclusive<i32> =
+    EXACT_EXP_LOWER_BOUND..=EXACT_EXP_UPPER_BOUND;
+// 1 <= |y| < 2²³⁶
+const GT_ONE_EXP_RANGE: RangeInclusive<i32> = 0..=PM1;
+// 2⁻²³⁶ <= |y| < 1
+const LT_ONE_EXP_RANGE: RangeInclusive<i32> = -PM1..=-1;
+
+/// Returns a random value with a significand of exactly `n_bits` bits and
+/// an exponent in `exp_range`.
+fn random_short(
+    rng: &mut StdRng,
+    n_bits: u32,
+    exp_range: &RangeInclusive<i32>,
+) -> FP237 {
+    let (h, l) = (rng.gen::<u128>(), rng.gen::<u128>());
+    let mut m: Integer = ((Integer::from(h) << 128) + l) >> (256 - n_bits);
+    m.set_bit(n_bits - 1, true);
+    let t = rng.gen_range(exp_range.clone()) - n_bits as i32 + 1;
+    let f = Float::with_val(P, m) << t;
+    FP237::new(if rng.gen() { -f } else { f })
+}
+
+/// Returns values at and one ulp / half an ulp beside the overflow
+/// threshold (MAX) or the underflow thresholds (MIN_POSITIVE and
+/// MIN_GT_ZERO). The values beyond the thresholds are not representable
+/// as f256, they are held at extended exponent range.
+fn thresholds(overflow: bool) -> Vec<Float> {
+    if overflow {
+        let max = FP237::MAX().f;
+        let ulp = Float::with_val(P, Float::i_exp(1, EMAX - PM1));
+        vec![
+            Float::with_val(P, &max - &ulp),
+            max.clone(),
+            Float::with_val(2 * P, &max + Float::with_val(P, &ulp >> 1)),
+            Float::with_val(P, &max + &ulp),
+        ]
+    } else {
+        let min_pos = FP237::MIN_POSITIVE().f;
+        let ulp = FP237::MIN_GT_ZERO().f;
+        vec![
+            Float::with_val(P, &min_pos - &ulp),
+            min_pos.clone(),
+            Float::with_val(P, &min_pos + &ulp),
+            Float::with_val(P, &ulp >> 1),
+            ulp.clone(),
+            Float::with_val(P, &ulp * 3) >> 1,
+        ]
+    }
+}
+
+/// Returns operands x, y so that x * y (if `is_mul`) or x / y lands at or
+/// a few ulps beside one of `targets`, with |y| drawn from `y_range`.
+fn near_threshold(
+    rng: &mut StdRng,
+    targets: &[Float],
+    y_range: &RangeInclusive<i32>,
+    is_mul: bool,
+) -> Vec<FP237> {
+    let z = FP237::new(targets.choose(rng).unwrap().clone());
+    // with y being a power of two the result hits the target exactly,
+    // otherwise it lands a few ulps beside
+    let y = if rng.gen() {
+        let t = rng.gen_range(y_range.clone());
+        FP237::new(Float::with_val(P, Float::i_exp(1, t)))
+    } else {
+        FP237::random_from_exp_range_with_rng(rng, y_range)
+    };
+    let x = if is_mul { &z / &y } else { &z * &y };
+    // x may itself be subnormal, so it has to be rounded to the precision
+    // available at its exponent
+    let x = FP237::with_val_round(x.f, RoundingMode::NearestEven);
+    if rng.gen() {
+        vec![-x, y]
+    } else {
+        vec![x, y]
+    }
+}
+
+/// Kinds of test data of the mul and div generators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Kind {
+    /// Random operands from the normal and subnormal exponent ranges
+    #[value(name = "R")]
+    Random,
+    /// Exact results
+    #[value(name = "E")]
+    Exact,
+    /// Results near overflow
+    #[value(name = "O")]
+    Overflow,
+    /// Results near underflow
+    #[value(name = "U")]
+    Underflow,
+    /// A subnormal and a normal operand
+    #[value(name = "S")]
+    Subnormal,
+}
+
+impl Kind {
+    /// Exits with a usage error if exponent range options are given for a
+    /// kind drawing its operands from fixed ranges.
+    pub(crate) fn check_exp_args(self, exp: &ExpRangeArgs) {
+        if matches!(self, Self::Exact | Self::Overflow | Self::Underflow)
+            && exp.is_given()
+        {
+            usage_error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "--exp-preset, --exp-min and --exp-max don't apply to \
+                     kind {}",
+                    self.to_possible_value().unwrap().get_name()
+                ),
+            );
+        }
+    }
+
+    /// Returns the exponent ranges the operands of the edge cases of this
+    /// kind are drawn from.
+    pub(crate) fn exp_ranges<'a>(
+        self,
+        subnormal_exp_range: &'a RangeInclusive<i32>,
+    ) -> Vec<&'a RangeInclusive<i32>> {
+        match self {
+            Self::Exact => vec![&EXACT_EXP_RANGE],
+            Self::Overflow | Self::Underflow => {
+                vec![&GT_ONE_EXP_RANGE, &LT_ONE_EXP_RANGE]
+            }
+            _ => vec![subnormal_exp_range],
+        }
+    }
+
+    /// Adds `n` edge cases of this kind for products (if `is_mul`) or
+    /// quotients to `builder`. Not to be called for `Random`.
+    pub(crate) fn add_edge_cases<'a>(
+        self,
+        builder: CorpusBuilder<'a>,
+        n: u32,
+        is_mul: bool,
+        subnormal_exp_range: &'a RangeInclusive<i32>,
+    ) -> CorpusBuilder<'a> {
+        match self {
+            Self::Random => unreachable!("Random is not an edge case"),
+            Self::Exact => builder.random(n, move |rng| {
+                // the result of a * b is exact as long as the significands
+                // of a and b together have no more than P bits
+                let n_bits = rng.gen_range(1..P);
+                let a = random_short(rng, n_bits, &EXACT_EXP_RANGE);
+                let b = random_short(rng, P - n_bits, &EXACT_EXP_RANGE);
+                if is_mul {
+                    vec![a, b]
+                } else {
+                    vec![&a * &b, b]
+                }
+            }),
+            Self::Overflow | Self::Underflow => {
+                let overflow = self == Self::Overflow;
+                let targets = thresholds(overflow);
+                // y must be chosen so that x does not overflow / underflow
+                // itself
+                let y_range = if overflow == is_mul {
+                    &GT_ONE_EXP_RANGE
+                } else {
+                    &LT_ONE_EXP_RANGE
+                };
+                builder.random(n, move |rng| {
+                    near_threshold(rng, &targets, y_range, is_mul)
+                })
+            }
+            Self::Subnormal => builder.random(n, move |rng| {
+                let x = FP237::random_from_exp_range_with_rng(
+                    rng,
+                    subnormal_exp_range,
+                );
+                // y must be chosen so that the result does not overflow
+                match (is_mul, rng.gen::<bool>()) {
+                    (true, swap) => {
+                        let y = FP237::random_from_exp_range_with_rng(
+                            rng,
+                            &(0..=P as i32 + PM1),
+                        );
+                        if swap {
+                            vec![y, x]
+                        } else {
+                            vec![x, y]
+                        }
+                    }
+                    (false, false) => {
+                        let y = FP237::random_from_exp_range_with_rng(
+                            rng,
+                            &(-(P as i32) - PM1..=0),
+                        );
+                        vec![x, y]
+                    }
+                    (false, true) => {
+                        let y = FP237::random_from_exp_range_with_rng(
+                            rng,
+                            &(EMIN..=EMIN + PM1),
+                        );
+                        vec![y, x]
+                    }
+                }
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod mul_div_tests {
+    use super::*;
+
+    #[test]
+    fn test_near_threshold_operands_representable() {
+        let mut rng = StdRng::seed_from_u64(237);
+        for overflow in [false, true] {
+            let targets = thresholds(overflow);
+            for is_mul in [false, true] {
+                let y_range = if overflow == is_mul {
+                    &GT_ONE_EXP_RANGE
+                } else {
+                    &LT_ONE_EXP_RANGE
+                };
+                for _ in 0..1000 {
+                    for x in
+                        near_threshold(&mut rng, &targets, y_range, is_mul)
+                    {
+                        assert_eq!(FP237::from_bits(x.to_bits()).f, x.f);
+                    }
+                }
+            }
+        }
+    }
+}
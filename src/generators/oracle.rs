@@ -0,0 +1,336 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    io::{self, stdin, BufRead},
+    process,
+    str::FromStr,
+};
+
+use clap::{error::ErrorKind, Parser};
+
+use super::{usage_error, OutputArgs};
+use crate::{
+    DecodedFP, Header, Interval237, RoundingMode, TestItem, FP237,
+    ROUNDING_MODES,
+};
+
+/// Returns the number of operands of `op` and whether its results can be
+/// given for all rounding modes, or `None` for an unknown operation.
+fn signature(op: &str) -> Option<(usize, bool)> {
+    match op {
+        "sqrt" => Some((1, true)),
+        "add" | "sub" | "mul" | "div" | "rem" => Some((2, true)),
+        "fma" => Some((3, true)),
+        "cbrt" | "ln" | "log2" | "log10" | "ln_1p" | "exp" | "exp_m1"
+        | "sin" | "cos" | "tan" | "cot" | "gamma" | "ln_gamma" => {
+            Some((1, false))
+        }
+        "hypot" | "sos" => Some((2, false)),
+        _ => None,
+    }
+}
+
+/// Returns the number of operands of `op`, or `None` for an unknown
+/// operation.
+pub(super) fn arity(op: &str) -> Option<usize> {
+    signature(op).map(|(arity, _)| arity)
+}
+
+/// Returns the result of `op` applied to `ops`, rounded according to
+/// `rnd`, which must be ties to even for operations not supporting all
+/// rounding modes.
+pub(super) fn eval(op: &str, ops: &[FP237], rnd: RoundingMode) -> FP237 {
+    match op {
+        "sqrt" => ops[0].sqrt_round(rnd),
+        "add" => ops[0].add_round(&ops[1], rnd),
+        "sub" => ops[0].sub_round(&ops[1], rnd),
+        "mul" => ops[0].mul_round(&ops[1], rnd),
+        "div" => ops[0].div_round(&ops[1], rnd),
+        "rem" => ops[0].rem_round(&ops[1], rnd),
+        "fma" => ops[0].fma_round(&ops[1], &ops[2], rnd),
+        "cbrt" => ops[0].cbrt(),
+        "ln" => ops[0].ln(),
+        "log2" => ops[0].log2(),
+        "log10" => ops[0].log10(),
+        "ln_1p" => ops[0].ln_1p(),
+        "exp" => ops[0].exp(),
+        "exp_m1" => ops[0].exp_m1(),
+        "sin" => ops[0].sin(),
+        "cos" => ops[0].cos(),
+        "tan" => ops[0].tan(),
+        "cot" => ops[0].cot(),
+        "gamma" => ops[0].gamma(),
+        "ln_gamma" => ops[0].ln_gamma(),
+        "hypot" => ops[0].hypot(&ops[1]),
+        "sos" => ops[0].sos(&ops[1]),
+        _ => panic!("Unknown op: {op}"),
+    }
+}
+
+/// Returns the results of `op` applied to each of the operand sets `items`,
+/// rounded according to `rnd`, see `eval`.
+///
+/// # Panics
+///
+/// If `op` is unknown or an operand set has less than its arity of
+/// operands.
+pub fn eval_batch(
+    op: &str,
+    items: &[Vec<FP237>],
+    rnd: RoundingMode,
+) -> Vec<FP237> {
+    let arity = arity(op).unwrap_or_else(|| panic!("Unknown op: {op}"));
+    items
+        .iter()
+        .map(|ops| {
+            assert!(ops.len() >= arity, "{op} needs {arity} operands");
+            eval(op, ops, rnd)
+        })
+        .collect()
+}
+
+/// Returns the enclosure of the exact result of `op` applied to `ops`, or
+/// `None` if `op` has no interval counterpart.
+pub(super) fn enclose(op: &str, ops: &[FP237]) -> Option<Interval237> {
+    let xs: Vec<Interval237> = ops.iter().map(Interval237::point).collect();
+    Some(match op {
+        "add" => &xs[0] + &xs[1],
+        "sub" => &xs[0] - &xs[1],
+        "mul" => &xs[0] * &xs[1],
+        "div" => &xs[0] / &xs[1],
+        "sqrt" => xs[0].sqrt(),
+        "cbrt" => xs[0].cbrt(),
+        "ln" => xs[0].ln(),
+        "log2" => xs[0].log2(),
+        "log10" => xs[0].log10(),
+        "ln_1p" => xs[0].ln_1p(),
+        "exp" => xs[0].exp(),
+        "exp_m1" => xs[0].exp_m1(),
+        "sin" => xs[0].sin(),
+        "cos" => xs[0].cos(),
+        "tan" => xs[0].tan(),
+        _ => return None,
+    })
+}
+
+/// Returns the number of operands of `op` and the rounding modes selected
+/// by `rounding`: ne tz up dn na, or all for all of them in this order.
+pub(super) fn select(
+    op: &str,
+    rounding: &str,
+) -> Result<(usize, Vec<RoundingMode>), String> {
+    let Some((arity, all_modes)) = signature(op) else {
+        return Err(format!("Unknown op: {op}"));
+    };
+    let modes: Vec<RoundingMode> = match rounding {
+        "all" => ROUNDING_MODES.to_vec(),
+        s => vec![RoundingMode::from_str(s)?],
+    };
+    if !all_modes && modes != [RoundingMode::NearestEven] {
+        return Err(format!("{op} is supported for rounding mode ne only"));
+    }
+    Ok((arity, modes))
+}
+
+/// Parses `n` values from `line`, given either as decode tuples (four
+/// integers per value) or as literals (one per value, possibly quoted).
+/// The fields may be separated by tabs, commas or spaces. Decode tuples
+/// must be canonical, i.e. reduced or non-reduced as returned by
+/// `FP237::decode`.
+pub(super) fn parse_values(
+    line: &str,
+    n: usize,
+) -> Result<Vec<FP237>, String> {
+    let fields: Vec<&str> = line
+        .split(['\t', ',', ' '])
+        .filter(|s| !s.is_empty())
+        .collect();
+    if fields.len() == 4 * n {
+        fields
+            .chunks(4)
+            .map(|t| {
+                let sign = t[0].parse::<u32>().map_err(|e| e.to_string())?;
+                let exp = t[1].parse::<i32>().map_err(|e| e.to_string())?;
+                let hi = t[2].parse::<u128>().map_err(|e| e.to_string())?;
+                let lo = t[3].parse::<u128>().map_err(|e| e.to_string())?;
+                if sign > 1 {
+                    return Err(format!("Invalid sign {sign}"));
+                }
+                let tuple = (sign, exp, (hi, lo));
+                let f = FP237::encode(sign, exp, (hi, lo));
+                if f.decode(true) != tuple && f.decode(false) != tuple {
+                    return Err(format!(
+                        "Non-canonical decode tuple {tuple:?}"
+                    ));
+                }
+                Ok(f)
+            })
+            .collect()
+    } else if fields.len() == n {
+        fields
+            .iter()
+            .map(|s| {
+                let lit = s.trim_matches('"');
+                FP237::from_str(lit)
+                    .map_err(|e| format!("Invalid literal {lit}: {e}"))
+            })
+            .collect()
+    } else {
+        Err(format!(
+            "Expected {n} literals or {} integers, found {} fields",
+            4 * n,
+            fields.len()
+        ))
+    }
+}
+
+/// Computes the results for operands read from stdin, one operand set per
+/// line, so that inputs generated elsewhere can be checked against this
+/// oracle. Empty lines and lines starting with `#` are skipped, malformed
+/// lines are reported on stderr and skipped, making the run fail.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Operation: add sub mul div rem sqrt fma (all rounding modes), cbrt
+    /// hypot sos ln log2 log10 ln_1p exp exp_m1 sin cos tan cot gamma
+    /// ln_gamma (ne only)
+    #[arg(short, long)]
+    op: String,
+
+    /// Rounding mode: ne tz up dn na, or all (giving one result per mode,
+    /// in this order)
+    #[arg(short, long, default_value = "ne")]
+    rounding: String,
+
+    /// Give the bounds of an interval enclosing the exact result, rounded
+    /// outward, instead of the rounded result: add sub mul div sqrt cbrt
+    /// ln log2 log10 ln_1p exp exp_m1 sin cos tan
+    #[arg(long, conflicts_with = "rounding")]
+    enclosure: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+pub fn run(args: Args) {
+    let op = args.op.as_str();
+    let (arity, modes) = select(op, &args.rounding)
+        .unwrap_or_else(|e| usage_error(ErrorKind::InvalidValue, e));
+    let mut header = Header::new(op).rounding(&args.rounding);
+    if args.enclosure {
+        if enclose(op, &vec![FP237::ONE(); arity]).is_none() {
+            usage_error(
+                ErrorKind::ArgumentConflict,
+                format!("{op} has no enclosure"),
+            );
+        }
+        header = header.param("enclosure", true);
+    }
+    let mut out = args.output.writer(&header);
+    let mut n_malformed = 0_u64;
+    for (n, line) in stdin().lock().lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                eprintln!("Line {}: {e}", n + 1);
+                n_malformed += 1;
+                continue;
+            }
+            Err(e) => panic!("Reading stdin failed: {e}"),
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let ops = match parse_values(line, arity) {
+            Ok(ops) => ops,
+            Err(e) => {
+                eprintln!("Line {}: {e}", n + 1);
+                n_malformed += 1;
+                continue;
+            }
+        };
+        let results = if args.enclosure {
+            let Interval237 { lo, hi } = enclose(op, &ops).unwrap();
+            vec![DecodedFP::from(&lo), DecodedFP::from(&hi)]
+        } else {
+            modes
+                .iter()
+                .map(|rnd| DecodedFP::from(&eval(op, &ops, *rnd)))
+                .collect()
+        };
+        let item = TestItem::new(
+            op,
+            ops.iter().map(DecodedFP::from).collect(),
+            results,
+        );
+        out.write_item(&item).unwrap();
+    }
+    out.finish().unwrap();
+    if n_malformed > 0 {
+        eprintln!("{n_malformed} malformed lines skipped");
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod oracle_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_values() {
+        let one = DecodedFP::from(&FP237::ONE());
+        let ops = parse_values("0\t0\t0\t1\t1,0,0,1", 2).unwrap();
+        assert_eq!(DecodedFP::from(&ops[0]), one);
+        assert_eq!(DecodedFP::from(&-ops[1].clone()), one);
+        let ops = parse_values("\"0.5e1\" -0x1p-1", 2).unwrap();
+        assert_eq!(
+            DecodedFP::from(&ops[0]),
+            DecodedFP::from(&FP237::from(5))
+        );
+        assert_eq!(DecodedFP::from(&ops[1]), (1, -1, (0, 1)).into());
+        assert!(parse_values("1 2 3", 2).is_err());
+        assert!(parse_values("2 0 0 1", 1).is_err());
+        // 1 reduced, non-reduced and as 2·2⁻¹
+        assert!(parse_values("0 0 0 1", 1).is_ok());
+        let hi = 1_u128 << 108;
+        assert!(parse_values(&format!("0 -236 {hi} 0"), 1).is_ok());
+        assert!(parse_values("0 -1 0 2", 1).is_err());
+        // significand beyond P bits
+        let hi = (1_u128 << 127) + 1;
+        assert!(parse_values(&format!("0 0 {hi} 0"), 1).is_err());
+        // NaN with a payload
+        assert!(parse_values("0 262144 0 1", 1).is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        assert_eq!(select("fma", "all").unwrap().0, 3);
+        assert!(select("sinh", "ne").is_err());
+        assert!(select("add", "xx").is_err());
+        assert!(select("ln", "up").is_err());
+    }
+
+    #[test]
+    fn test_eval() {
+        let ops = [FP237::ONE(), FP237::from(3)];
+        let up = eval("div", &ops, RoundingMode::Up);
+        let dn = eval("div", &ops, RoundingMode::Down);
+        assert_eq!(up.to_bits(), dn.next_up().to_bits());
+        let sos = eval("sos", &ops, RoundingMode::NearestEven);
+        assert_eq!(sos.to_bits(), FP237::from(10).to_bits());
+        let div = enclose("div", &ops).unwrap();
+        assert_eq!(
+            (div.lo.to_bits(), div.hi.to_bits()),
+            (dn.to_bits(), up.to_bits())
+        );
+        assert!(enclose("gamma", &ops[..1]).is_none());
+    }
+}
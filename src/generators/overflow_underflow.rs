@@ -0,0 +1,251 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{cmp::Ordering, ops::RangeInclusive};
+
+use clap::{Parser, ValueEnum};
+use rand::prelude::*;
+use rug::{float::Round, Float};
+
+use super::CommonArgs;
+use crate::{
+    CorpusBuilder, DecodedFP, Header, Record, RoundingMode, TestItem, EMAX,
+    EMIN, FP237, MIN_EXP_SUBNORMAL, P, PM1, ROUNDING_MODES,
+};
+
+// f256::MAX / 2² <= |f| <= f256::MAX
+const NEAR_EMAX_EXP_RANGE: RangeInclusive<i32> = EMAX - 2..=EMAX;
+// MIN_POSITIVE <= |f| < MIN_POSITIVE * 2⁴
+const NEAR_EMIN_EXP_RANGE: RangeInclusive<i32> = EMIN..=EMIN + 3;
+// f256::MIN_GT_ZERO <= |f| < f256::MIN_GT_ZERO * 2⁴
+const NEAR_MIN_GT_ZERO_EXP_RANGE: RangeInclusive<i32> =
+    MIN_EXP_SUBNORMAL..=MIN_EXP_SUBNORMAL + 3;
+// 2 <= |f| < 2¹⁰⁰, keeping r / f below f256::MAX
+const OVERFLOW_FACTOR_EXP_RANGE: RangeInclusive<i32> = 1..=99;
+// 2⁻⁴⁰⁰ <= |f| < 2⁻²⁴⁰, keeping r / f above MIN_POSITIVE
+const UNDERFLOW_FACTOR_EXP_RANGE: RangeInclusive<i32> = -400..=-241;
+
+/// Rounds the exact value `f` to P bits only, so that operands derived
+/// from a target result keep their full precision.
+fn round_p(f: Float) -> FP237 {
+    FP237::new(Float::with_val(P, f))
+}
+
+/// Returns a random multiple of 1/2 between -4 and 4 or a random value
+/// strictly between -4 and 4, i.e. an offset from a threshold measured in
+/// ulps, hitting exact values and ties as well as arbitrary ones.
+fn random_ulp_offset(rng: &mut StdRng) -> f64 {
+    if rng.gen() {
+        rng.gen_range(-8..=8) as f64 / 2.0
+    } else {
+        rng.gen_range(-4.0..4.0)
+    }
+}
+
+/// Operations to generate test data for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Fma,
+}
+
+impl Op {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Sub => "sub",
+            Self::Mul => "mul",
+            Self::Div => "div",
+            Self::Fma => "fma",
+        }
+    }
+}
+
+/// Thresholds the results are near to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Threshold {
+    /// Result within a few ulps of f256::MAX
+    #[value(name = "O")]
+    Overflow,
+    /// Result within a few ulps of f256::MIN_GT_ZERO
+    #[value(name = "U")]
+    Underflow,
+}
+
+/// Generates test data for results near MAX or MIN_GT_ZERO.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Operation
+    #[arg(short, long, value_enum, default_value_t = Op::Mul)]
+    op: Op,
+
+    /// Threshold
+    #[arg(short, long, value_enum, default_value_t = Threshold::Overflow)]
+    threshold: Threshold,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+pub fn run(args: Args) {
+    let op = args.op;
+    let arity = match op {
+        Op::Fma => 3,
+        _ => 2,
+    };
+    let overflow = args.threshold == Threshold::Overflow;
+    let seed = args.common.seed();
+    let header = Header::new(op.name())
+        .seed(seed)
+        .rounding("all")
+        .exp_ranges(&[
+            &NEAR_EMAX_EXP_RANGE,
+            &NEAR_EMIN_EXP_RANGE,
+            &NEAR_MIN_GT_ZERO_EXP_RANGE,
+        ]);
+    let mut out = args.common.output.meta_writer(&header);
+
+    let builder = CorpusBuilder::new(arity, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            // target result r = ±(2²³⁷ - 1 + d) * 2^(EMAX - 236) or
+            // ±d * 2^MIN_EXP_SUBNORMAL
+            let d = random_ulp_offset(rng);
+            let r = if overflow {
+                let m = Float::with_val(2 * P, Float::i_exp(1, P as i32));
+                Float::with_val(2 * P, m - 1 + d) << (EMAX - PM1)
+            } else {
+                // sums of f256 values are multiples of MIN_GT_ZERO
+                let d = if matches!(op, Op::Add | Op::Sub) {
+                    d.abs().round().max(1.0)
+                } else {
+                    d.abs().max(0.5)
+                };
+                Float::with_val(2 * P, d) << MIN_EXP_SUBNORMAL
+            };
+            let r = if rng.gen() { r } else { -r };
+            let mut rnd = |range: &RangeInclusive<i32>| {
+                FP237::random_from_exp_range_with_rng(rng, range)
+            };
+            let factor_range = if overflow {
+                &OVERFLOW_FACTOR_EXP_RANGE
+            } else {
+                &UNDERFLOW_FACTOR_EXP_RANGE
+            };
+            match op {
+                Op::Add | Op::Sub => {
+                    let x = if overflow {
+                        // same sign as r, so that |r - x| <= f256::MAX
+                        let x = rnd(&NEAR_EMAX_EXP_RANGE).abs();
+                        if r.is_sign_negative() {
+                            -x
+                        } else {
+                            x
+                        }
+                    } else {
+                        rnd(&NEAR_EMIN_EXP_RANGE)
+                    };
+                    let y = if op == Op::Add {
+                        Float::with_val(4 * P, &r - &x.f)
+                    } else {
+                        Float::with_val(4 * P, &x.f - &r)
+                    };
+                    // near MIN_GT_ZERO the difference is exact
+                    vec![x, round_p(y)]
+                }
+                Op::Mul => {
+                    let x = rnd(factor_range);
+                    let y = round_p(Float::with_val(2 * P, &r / &x.f));
+                    vec![x, y]
+                }
+                Op::Div => {
+                    let (lo, hi) =
+                        (*factor_range.start(), *factor_range.end());
+                    let y = rnd(&(-hi..=-lo));
+                    let x = round_p(Float::with_val(2 * P, &r * &y.f));
+                    vec![x, y]
+                }
+                _ => {
+                    let a = if overflow {
+                        let a = rnd(&NEAR_EMAX_EXP_RANGE).abs();
+                        if r.is_sign_negative() {
+                            -a
+                        } else {
+                            a
+                        }
+                    } else {
+                        rnd(&NEAR_MIN_GT_ZERO_EXP_RANGE)
+                    };
+                    let x = rnd(factor_range);
+                    let p = Float::with_val(4 * P, &r - &a.f);
+                    let y = round_p(Float::with_val(2 * P, &p / &x.f));
+                    vec![x, y, a]
+                }
+            }
+        });
+    let max = FP237::MAX().f;
+    let min_positive = FP237::MIN_POSITIVE().f;
+    for item in builder.build() {
+        // exact result rounded to P bits with unbounded exponent range
+        let unbounded = |rnd: Round| match op {
+            Op::Add => Float::with_val_round(P, &item[0].f + &item[1].f, rnd),
+            Op::Sub => Float::with_val_round(P, &item[0].f - &item[1].f, rnd),
+            Op::Mul => Float::with_val_round(P, &item[0].f * &item[1].f, rnd),
+            Op::Div => Float::with_val_round(P, &item[0].f / &item[1].f, rnd),
+            _ => Float::with_val_round(
+                P,
+                item[0].f.mul_add_ref(&item[1].f, &item[2].f),
+                rnd,
+            ),
+        };
+        // the exact result is tiny if rounding it towards zero gives a
+        // value below MIN_POSITIVE
+        let (t, _) = unbounded(Round::Zero);
+        let tiny = !t.is_zero() && t.abs() < min_positive;
+        let mut expected = vec![];
+        let mut meta = Record::new();
+        for rnd in ROUNDING_MODES {
+            let z = match op {
+                Op::Add => item[0].add_round(&item[1], rnd),
+                Op::Sub => item[0].sub_round(&item[1], rnd),
+                Op::Mul => item[0].mul_round(&item[1], rnd),
+                Op::Div => item[0].div_round(&item[1], rnd),
+                _ => item[0].fma_round(&item[1], &item[2], rnd),
+            };
+            // ties away from zero and to even only differ in crossing
+            // f256::MAX for the tie above it, which overflows in both
+            let round = match rnd {
+                RoundingMode::TowardZero => Round::Zero,
+                RoundingMode::Up => Round::Up,
+                RoundingMode::Down => Round::Down,
+                _ => Round::Nearest,
+            };
+            let (u, o) = unbounded(round);
+            let inexact = o != Ordering::Equal || z.f != u;
+            let overflow = u.abs() > max;
+            let underflow = tiny && inexact;
+            expected.push((&z).into());
+            meta = meta
+                .int(overflow as u32)
+                .int(underflow as u32)
+                .int(inexact as u32);
+        }
+        // operands, results for each rounding mode in the order ne tz up
+        // dn na, then overflow, underflow and inexact flag for each of them
+        let operands = item.iter().map(DecodedFP::from).collect();
+        let item =
+            TestItem::new(op.name(), operands, expected).with_meta(meta);
+        out.write_item(&item).unwrap();
+    }
+    out.finish().unwrap();
+}
@@ -0,0 +1,136 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::Float;
+
+use super::{CommonArgs, ExpRangeArgs};
+use crate::{
+    special_values, CorpusBuilder, Header, Record, TestItem, EMAX, EMIN,
+    FP237, MIN_EXP_SUBNORMAL, P,
+};
+
+const N_MAX: i32 = 1000;
+
+// |n| for the exponents sampled at random
+const EXPONENT_RANGE: RangeInclusive<i32> = 2..=N_MAX;
+// f256::MIN_GT_ZERO <= |f| <= f256::MAX
+const EXP_RANGE: RangeInclusive<i32> = MIN_EXP_SUBNORMAL..=EMAX;
+// exponents combined with the special values
+const SPECIAL_EXPONENTS: [i32; 7] = [-3, -2, -1, 0, 1, 2, 3];
+
+/// The exponent n is carried as a second, integral operand, so that the
+/// corpus builder keeps it together with the base.
+fn exp_op(n: i32) -> FP237 {
+    FP237::new(Float::with_val(P, n))
+}
+
+fn random_exponent(rng: &mut StdRng, negative: bool) -> i32 {
+    let m = rng.gen_range(EXPONENT_RANGE);
+    if negative {
+        -m
+    } else {
+        m
+    }
+}
+
+/// Generates test data for integer powers.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    /// Add negative exponents, with results down to underflow
+    #[arg(short = 'g', long)]
+    negative: bool,
+
+    /// Add rows with exponents 0, 1 and -1
+    #[arg(short, long)]
+    units: bool,
+
+    /// Add deliberately overflowing cases
+    #[arg(short, long)]
+    overflow: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+}
+
+pub fn run(args: Args) {
+    let exp_range = args.exp.exp_range(&EXP_RANGE);
+    let n = args.common.n_test_data;
+    let seed = args.common.seed();
+    let header = Header::new("powi").seed(seed).exp_ranges(&[&exp_range]);
+    let mut out = args.common.output.meta_writer(&header);
+
+    // for 2ᵗ <= |x| < 2ᵗ⁺¹ and n > 0: 2ⁿᵗ <= |xⁿ| < 2ⁿ⁽ᵗ⁺¹⁾
+    let mut builder = CorpusBuilder::new(2, seed)
+        .sign(args.common.sign)
+        .random(n, |rng| {
+            // finite normal results
+            let n = random_exponent(rng, false);
+            let t_range = EMIN / n..=(EMAX + 1) / n - 1;
+            let x = FP237::random_from_exp_range_with_rng(rng, &t_range);
+            vec![x, exp_op(n)]
+        });
+    if args.negative {
+        builder = builder.random(n, |rng| {
+            // results from near f256::MAX down to below MIN_GT_ZERO
+            let n = random_exponent(rng, true);
+            let m = -n;
+            let t_range = -EMAX / m..=-MIN_EXP_SUBNORMAL / m + 1;
+            let x = FP237::random_from_exp_range_with_rng(rng, &t_range);
+            vec![x, exp_op(n)]
+        });
+    }
+    if args.units {
+        builder = builder.random(n / 10 + 1, |rng| {
+            let x = FP237::random_from_exp_range_with_rng(rng, &exp_range);
+            vec![x, exp_op(rng.gen_range(-1..=1))]
+        });
+    }
+    if args.overflow {
+        builder = builder.random(n / 10 + 1, |rng| {
+            // |xⁿ| >= 2^(EMAX + 1)
+            let negative = args.negative && rng.gen();
+            let n = random_exponent(rng, negative);
+            let m = n.abs();
+            let t_range = if negative {
+                MIN_EXP_SUBNORMAL..=-((EMAX + m) / m) - 1
+            } else {
+                (EMAX + 1) / m + 1..=EMAX
+            };
+            let x = FP237::random_from_exp_range_with_rng(rng, &t_range);
+            vec![x, exp_op(n)]
+        });
+    }
+    if args.specials {
+        let items = special_values().into_iter().flat_map(|x| {
+            SPECIAL_EXPONENTS.map(|n| vec![x.clone(), exp_op(n)])
+        });
+        builder = builder.items(items.collect());
+    }
+    for item in builder.build() {
+        let x = &item[0];
+        let n = item[1].f.to_i32_saturating().unwrap();
+        let item =
+            TestItem::new("powi", vec![x.into()], vec![(&x.powi(n)).into()])
+                .with_meta(Record::new().int(n));
+        out.write_item(&item).unwrap();
+    }
+    out.finish().unwrap();
+}
@@ -0,0 +1,151 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::cmp::Ordering;
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::Integer;
+
+use super::CommonArgs;
+use crate::{
+    special_values, Header, Record, Sign, EMAX, FP237, HI_EXP_MASK,
+    HI_FRACTION_MASK, HI_QUIET_NAN_BIT, HI_SIGN_BIT,
+};
+
+// biased exponents next to the reserved ones
+const NEAR_RESERVED_BIASED_EXPS: [u128; 4] =
+    [1, 2, 2 * EMAX as u128 - 1, 2 * EMAX as u128];
+
+/// Returns a random bit pattern, drawn from the whole range of patterns or
+/// with one of the reserved exponents (zero / subnormal, infinity / NaN)
+/// or an exponent next to them.
+fn random_bits(rng: &mut StdRng) -> (u128, u128) {
+    let (hi, lo) = (rng.gen::<u128>(), rng.gen::<u128>());
+    let biased_exp = match rng.gen_range(0..4) {
+        0 => return (hi, lo),
+        1 => 0,
+        2 => HI_EXP_MASK >> 108,
+        _ => *NEAR_RESERVED_BIASED_EXPS.choose(rng).unwrap(),
+    };
+    let mut hi = (hi & !HI_EXP_MASK) | (biased_exp << 108);
+    // small fractions hit zero, infinity and NaNs with tiny payloads
+    if rng.gen_range(0..4) == 0 {
+        hi &= !HI_FRACTION_MASK;
+        return (hi, lo & rng.gen_range(0..=1));
+    }
+    (hi, lo)
+}
+
+/// Returns the category like that of core::num::FpCategory.
+fn category((hi, lo): (u128, u128)) -> &'static str {
+    let zero_fraction = hi & HI_FRACTION_MASK == 0 && lo == 0;
+    match (hi & HI_EXP_MASK, zero_fraction) {
+        (0, true) => "Zero",
+        (0, false) => "Subnormal",
+        (HI_EXP_MASK, true) => "Infinite",
+        (HI_EXP_MASK, false) => "Nan",
+        _ => "Normal",
+    }
+}
+
+fn is_signaling((hi, lo): (u128, u128)) -> bool {
+    category((hi, lo)) == "Nan" && hi & HI_QUIET_NAN_BIT == 0
+}
+
+/// Orders bit patterns according to IEEE 754 totalOrder, i.e. -NaN < -∞ <
+/// … < -0 < +0 < … < +∞ < +NaN, NaNs ordered by payload.
+fn total_cmp(x: (u128, u128), y: (u128, u128)) -> Ordering {
+    let key = |(hi, lo): (u128, u128)| {
+        let m: Integer = (Integer::from(hi & !HI_SIGN_BIT) << 128) + lo;
+        if hi & HI_SIGN_BIT != 0 {
+            -m - 1
+        } else {
+            m
+        }
+    };
+    key(x).cmp(&key(y))
+}
+
+/// Generates test data for raw bit patterns.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// function: classify total_cmp
+    #[arg(short, long, default_value = "classify")]
+    func: String,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+}
+
+pub fn run(args: Args) {
+    let seed = args.common.seed();
+    let header = Header::new(&args.func).seed(seed);
+    let mut out = args.common.output.meta_writer(&header);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut values: Vec<(u128, u128)> = (0..args.common.n_test_data)
+        .map(|_| {
+            let (hi, lo) = random_bits(&mut rng);
+            match args.common.sign {
+                Sign::Pos => (hi & !HI_SIGN_BIT, lo),
+                Sign::Neg => (hi | HI_SIGN_BIT, lo),
+                Sign::Both => (hi, lo),
+            }
+        })
+        .collect();
+    if args.specials {
+        values.extend(special_values().iter().map(FP237::to_bits));
+    }
+
+    match args.func.as_str() {
+        "classify" => {
+            for x in values {
+                // canonicalization maps every NaN to the positive quiet NaN
+                // without payload and leaves all other patterns unchanged
+                let c = FP237::from_bits(x).to_bits();
+                let rec = Record::new()
+                    .int(x.0)
+                    .int(x.1)
+                    .token(category(x))
+                    .int((x.0 & HI_SIGN_BIT != 0) as u32)
+                    .int(is_signaling(x) as u32)
+                    .int(c.0)
+                    .int(c.1);
+                out.write(&rec).unwrap();
+            }
+        }
+        "total_cmp" => {
+            for x in &values {
+                // an arbitrary other pattern, the same pattern or the one
+                // with the opposite sign
+                let y = match rng.gen_range(0..4) {
+                    0 => *x,
+                    1 => (x.0 ^ HI_SIGN_BIT, x.1),
+                    _ => *values.choose(&mut rng).unwrap(),
+                };
+                let ord = total_cmp(*x, y) as i32;
+                let rec = Record::new()
+                    .int(x.0)
+                    .int(x.1)
+                    .int(y.0)
+                    .int(y.1)
+                    .int(ord);
+                out.write(&rec).unwrap();
+            }
+        }
+        _ => panic!("Unkown func"),
+    }
+    out.finish().unwrap();
+}
@@ -0,0 +1,115 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    cmp::{max, min},
+    ops::RangeInclusive,
+};
+
+use clap::Parser;
+
+use super::{
+    CommonArgs, ExpRangeArgs, RoundArgs, NORMAL_EXP_RANGE,
+    SUBNORMAL_EXP_UPPER_BOUND,
+};
+use crate::{
+    CorpusBuilder, Header, EMAX, EMIN, FP237, MIN_EXP_SUBNORMAL, PM1,
+};
+
+const SUBNORMAL_EXP_LOWER_BOUND: i32 = MIN_EXP_SUBNORMAL + 1;
+
+// f256::MIN_GT_ZERO <= |f| < MIN_POSITIVE
+const SUBNORMAL_EXP_RANGE: RangeInclusive<i32> =
+    SUBNORMAL_EXP_LOWER_BOUND..=SUBNORMAL_EXP_UPPER_BOUND;
+
+/// Generates test data for the remainder of truncating division.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    #[command(flatten)]
+    rounding: RoundArgs,
+
+    /// Add the boundary values of the exponent ranges
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+}
+
+pub fn run(args: Args) {
+    let normal_exp_range = args.exp.exp_range(&NORMAL_EXP_RANGE);
+    let subnormal_exp_range = args.exp.exp_range(&SUBNORMAL_EXP_RANGE);
+    let n_sub_normal = args.common.n_test_data / 100 + 1;
+    let n_normal = args.common.n_test_data - n_sub_normal;
+    let seed = args.common.seed();
+    let rnd = args.rounding.round;
+
+    let mut builder = CorpusBuilder::new(2, seed)
+        .sign(args.common.sign)
+        .random(n_normal, |rng| {
+            let x =
+                FP237::random_from_exp_range_with_rng(rng, &normal_exp_range);
+            let (_, e, _) = x.decode(false);
+            let lower_limit = max(EMIN - PM1, e - PM1 - EMAX + 2);
+            let upper_limit = min(EMAX - PM1, e - PM1 - EMIN - 2);
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &(lower_limit..=upper_limit),
+            );
+            vec![x, y]
+        })
+        .random(n_sub_normal, |rng| {
+            let x =
+                FP237::random_from_exp_range_with_rng(rng, &normal_exp_range);
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            );
+            vec![x, y]
+        })
+        .random(n_sub_normal, |rng| {
+            let x = FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            );
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            );
+            vec![x, y]
+        });
+    if args.boundaries {
+        builder = builder
+            .boundaries(&normal_exp_range)
+            .boundaries(&subnormal_exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    let header = Header::new("rem")
+        .seed(seed)
+        .rounding(rnd)
+        .exp_ranges(&[&normal_exp_range, &subnormal_exp_range]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "rem", |ops| {
+            Some(vec![ops[0].rem_round(&ops[1], rnd)])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
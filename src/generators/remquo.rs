@@ -0,0 +1,140 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    cmp::{max, min},
+    ops::RangeInclusive,
+};
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::Float;
+
+use super::{
+    CommonArgs, ExpRangeArgs, NORMAL_EXP_RANGE, SUBNORMAL_EXP_UPPER_BOUND,
+};
+use crate::{
+    CorpusBuilder, Header, Record, TestItem, EMAX, EMIN, FP237,
+    MIN_EXP_SUBNORMAL, P, PM1,
+};
+
+const SUBNORMAL_EXP_LOWER_BOUND: i32 = MIN_EXP_SUBNORMAL + 1;
+
+// f256::MIN_GT_ZERO <= |f| < MIN_POSITIVE
+const SUBNORMAL_EXP_RANGE: RangeInclusive<i32> =
+    SUBNORMAL_EXP_LOWER_BOUND..=SUBNORMAL_EXP_UPPER_BOUND;
+
+// 2⁻¹⁰⁰⁰ <= |f| < 2¹⁰⁰⁰
+const MODERATE_EXP_RANGE: RangeInclusive<i32> = -1000..=999;
+// bits left free in the divisor of a tie, so that (k + ½) * y is exact
+const TIE_BITS: u32 = 10;
+
+/// Generates test data for the IEEE remainder with quotient bits.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    /// Add the boundary values of the exponent ranges
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+}
+
+pub fn run(args: Args) {
+    let normal_exp_range = args.exp.exp_range(&NORMAL_EXP_RANGE);
+    let subnormal_exp_range = args.exp.exp_range(&SUBNORMAL_EXP_RANGE);
+    let n_sub_normal = args.common.n_test_data / 100 + 1;
+    let n_ties = args.common.n_test_data / 10 + 1;
+    let n_normal = args.common.n_test_data - n_sub_normal - n_ties;
+    let seed = args.common.seed();
+    let header = Header::new("remquo").seed(seed).exp_ranges(&[
+        &normal_exp_range,
+        &subnormal_exp_range,
+        &MODERATE_EXP_RANGE,
+    ]);
+    let mut out = args.common.output.meta_writer(&header);
+
+    let mut builder = CorpusBuilder::new(2, seed)
+        .sign(args.common.sign)
+        .random(n_normal, |rng| {
+            let x =
+                FP237::random_from_exp_range_with_rng(rng, &normal_exp_range);
+            let (_, e, _) = x.decode(false);
+            let lower_limit = max(EMIN - PM1, e - PM1 - EMAX + 2);
+            let upper_limit = min(EMAX - PM1, e - PM1 - EMIN - 2);
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &(lower_limit..=upper_limit),
+            );
+            vec![x, y]
+        })
+        .random(n_ties, |rng| {
+            // x / y = k + ½, so that the parity of k decides the quotient
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &MODERATE_EXP_RANGE,
+            );
+            let y = FP237::new(Float::with_val(
+                P,
+                Float::with_val(P - TIE_BITS, &y.f),
+            ));
+            let k = rng.gen_range(0..1_u32 << (TIE_BITS - 1));
+            let x = FP237::new(Float::with_val(P, &y.f * (2 * k + 1)) >> 1);
+            vec![x, y]
+        })
+        .random(n_sub_normal, |rng| {
+            let x =
+                FP237::random_from_exp_range_with_rng(rng, &normal_exp_range);
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            );
+            vec![x, y]
+        })
+        .random(n_sub_normal, |rng| {
+            let x = FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            );
+            let y = FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            );
+            vec![x, y]
+        });
+    if args.boundaries {
+        builder = builder
+            .boundaries(&normal_exp_range)
+            .boundaries(&subnormal_exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    for item in builder.build() {
+        let (x, y) = (&item[0], &item[1]);
+        let (r, q) = x.remquo(y);
+        // x, y, remainder, low 31 bits of the quotient with its sign
+        let item = TestItem::new(
+            "remquo",
+            vec![x.into(), y.into()],
+            vec![(&r).into()],
+        )
+        .with_meta(Record::new().int(q));
+        out.write_item(&item).unwrap();
+    }
+    out.finish().unwrap();
+}
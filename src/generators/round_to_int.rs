@@ -0,0 +1,122 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::Float;
+
+use super::CommonArgs;
+use crate::{CorpusBuilder, Header, RoundingMode, EMAX, FP237, P, PM1};
+
+// 1 <= |n| < 2²³⁵, so that n + 1/2 is representable
+const HALF_EXP_RANGE: RangeInclusive<i32> = 0..=PM1 - 2;
+// 2⁻²³⁸ <= |d| < 2⁻¹⁰ for f = n + 1/2 + d
+const DELTA_EXP_RANGE: RangeInclusive<i32> = -PM1 - 2..=-10;
+// 1 <= |f| < 2²³⁶
+const INT_EXP_RANGE: RangeInclusive<i32> = 0..=PM1;
+// 2²³⁷ <= |f| <= f256::MAX
+const LARGE_EXP_RANGE: RangeInclusive<i32> = P as i32..=EMAX;
+// 2⁻²⁴⁰ <= |f| < 1
+const NEAR_ZERO_EXP_RANGE: RangeInclusive<i32> = -PM1 - 4..=-1;
+
+/// Generates test data for rounding to integral values.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// function: trunc floor ceil round round_ties_even fract
+    #[arg(short, long, default_value = "round")]
+    func: String,
+
+    /// Range of input value f: H = near n + 1/2, I = integral,
+    /// L = |f| >= 2²³⁷, Z = negative near zero
+    #[arg(long, default_value_t = 'H')]
+    range: char,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+}
+
+pub fn run(args: Args) {
+    let func = match args.func.as_str() {
+        "trunc" => |x: &FP237| x.round_to_int(RoundingMode::TowardZero),
+        "floor" => |x: &FP237| x.round_to_int(RoundingMode::Down),
+        "ceil" => |x: &FP237| x.round_to_int(RoundingMode::Up),
+        "round" => |x: &FP237| x.round_to_int(RoundingMode::NearestAway),
+        "round_ties_even" => {
+            |x: &FP237| x.round_to_int(RoundingMode::NearestEven)
+        }
+        "fract" => |x: &FP237| x.fract(),
+        _ => panic!("Unkown func"),
+    };
+    let range = args.range;
+    let seed = args.common.seed();
+
+    let mut builder = CorpusBuilder::new(1, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            let x = match range {
+                'H' => {
+                    // ±(n + 1/2) exactly or off by a tiny amount
+                    let n = FP237::random_from_exp_range_with_rng(
+                        rng,
+                        &HALF_EXP_RANGE,
+                    );
+                    let mut f = Float::with_val(P, n.abs().trunc().f + 0.5);
+                    if rng.gen_ratio(2, 3) {
+                        let d = FP237::random_from_exp_range_with_rng(
+                            rng,
+                            &DELTA_EXP_RANGE,
+                        );
+                        f += &d.f;
+                    }
+                    if rng.gen() {
+                        f = -f;
+                    }
+                    FP237::with_val_round(f, RoundingMode::NearestEven)
+                }
+                'I' => {
+                    FP237::random_from_exp_range_with_rng(rng, &INT_EXP_RANGE)
+                        .trunc()
+                }
+                'L' => FP237::random_from_exp_range_with_rng(
+                    rng,
+                    &LARGE_EXP_RANGE,
+                ),
+                'Z' => -FP237::random_from_exp_range_with_rng(
+                    rng,
+                    &NEAR_ZERO_EXP_RANGE,
+                )
+                .abs(),
+                _ => panic!("Unkown range"),
+            };
+            vec![x]
+        });
+    if args.specials {
+        builder = builder.specials();
+    }
+    let header =
+        Header::new(&args.func).seed(seed).exp_ranges(&match range {
+            'H' => vec![&HALF_EXP_RANGE, &DELTA_EXP_RANGE],
+            'I' => vec![&INT_EXP_RANGE],
+            'L' => vec![&LARGE_EXP_RANGE],
+            _ => vec![&NEAR_ZERO_EXP_RANGE],
+        });
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, &args.func, |ops| Some(vec![func(&ops[0])]))
+        .unwrap();
+    out.finish().unwrap();
+}
@@ -0,0 +1,157 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    cmp::{max, min},
+    ops::RangeInclusive,
+};
+
+use clap::{error::ErrorKind, Parser};
+use rand::prelude::*;
+
+use super::{usage_error, CommonArgs, ExpRangeArgs, EXP_UPPER_BOUND};
+use crate::{
+    CorpusBuilder, Header, RoundingMode, Sign, FP237, MIN_EXP_SUBNORMAL, P,
+    ROUNDING_MODES,
+};
+
+const EXP_LOWER_BOUND: i32 = MIN_EXP_SUBNORMAL;
+
+// f256::MIN_GT_ZERO <= |f| <= f256::MAX
+const EXP_RANGE: RangeInclusive<i32> = EXP_LOWER_BOUND..=EXP_UPPER_BOUND;
+
+/// Returns a random value with an exponent between `lower` and `upper`,
+/// both clamped to the exponent range of f256.
+fn random_clamped(rng: &mut StdRng, lower: i32, upper: i32) -> FP237 {
+    let lower = max(lower, EXP_LOWER_BOUND);
+    let upper = min(upper, EXP_UPPER_BOUND);
+    FP237::random_from_exp_range_with_rng(rng, &(lower..=upper))
+}
+
+/// Generates test data for the basic operations in all rounding modes.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Operation: add sub mul div sqrt fma
+    #[arg(short, long, default_value = "add")]
+    op: String,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    /// Add the boundary values of the exponent range
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    let exp_range = args.exp.exp_range(&EXP_RANGE);
+    let op = args.op.as_str();
+    let arity = match op {
+        "sqrt" => 1,
+        "add" | "sub" | "mul" | "div" => 2,
+        "fma" => 3,
+        _ => panic!("Unkown op"),
+    };
+    if op == "sqrt" && args.common.sign == Sign::Neg {
+        usage_error(
+            ErrorKind::ArgumentConflict,
+            "--sign neg gives NaN only for sqrt",
+        );
+    }
+    let seed = args.common.seed();
+
+    let mut builder = CorpusBuilder::new(arity, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            let x = FP237::random_from_exp_range_with_rng(rng, &exp_range);
+            // exponent of x like that of `random_from_exp_range`, i.e.
+            // 2ᵉ <= |x| < 2ᵉ⁺¹
+            let e = x.f.get_exp().unwrap() - 1;
+            // the exponent of the second operand is chosen so that the
+            // result lands near the representable range or beyond it
+            match op {
+                "sqrt" => vec![x.abs()],
+                "add" | "sub" => {
+                    let y = random_clamped(rng, e - P as i32, e + P as i32);
+                    vec![x, y]
+                }
+                "mul" => {
+                    let y = random_clamped(
+                        rng,
+                        EXP_LOWER_BOUND - e - 2,
+                        EXP_UPPER_BOUND - e + 1,
+                    );
+                    vec![x, y]
+                }
+                "div" => {
+                    let y = random_clamped(
+                        rng,
+                        e - EXP_UPPER_BOUND - 1,
+                        e - EXP_LOWER_BOUND + 2,
+                    );
+                    vec![x, y]
+                }
+                _ => {
+                    let y = random_clamped(
+                        rng,
+                        EXP_LOWER_BOUND - e,
+                        EXP_UPPER_BOUND - e,
+                    );
+                    let a = FP237::random_from_exp_range_with_rng(
+                        rng, &exp_range,
+                    );
+                    vec![x, y, a]
+                }
+            }
+        });
+    if args.boundaries {
+        builder = builder.boundaries(&exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        builder = builder.verify(&ROUNDING_MODES);
+    }
+    let header = Header::new(op)
+        .seed(seed)
+        .rounding("all")
+        .exp_ranges(&[&exp_range]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, op, |ops| {
+            let res = |rnd: RoundingMode| match op {
+                "sqrt" => ops[0].sqrt_round(rnd),
+                "add" => ops[0].add_round(&ops[1], rnd),
+                "sub" => ops[0].sub_round(&ops[1], rnd),
+                "mul" => ops[0].mul_round(&ops[1], rnd),
+                "div" => ops[0].div_round(&ops[1], rnd),
+                _ => ops[0].fma_round(&ops[1], &ops[2], rnd),
+            };
+            // one result column per rounding mode, in the order ne tz up
+            // dn na
+            Some(ROUNDING_MODES.iter().map(|rnd| res(*rnd)).collect())
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
@@ -0,0 +1,102 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    io::{BufRead, Write},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use rand::{prelude::*, rngs::StdRng};
+
+use super::{is_header, open_input};
+use crate::Output;
+
+/// Returns `n` items chosen uniformly at random from `items` by reservoir
+/// sampling driven by `seed`, in their original order.
+fn reservoir<T>(
+    items: impl Iterator<Item = T>,
+    n: usize,
+    seed: u64,
+) -> Vec<T> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut res: Vec<(usize, T)> = Vec::with_capacity(n);
+    for (i, item) in items.enumerate() {
+        if i < n {
+            res.push((i, item));
+        } else {
+            let j = rng.gen_range(0..=i);
+            if j < n {
+                res[j] = (i, item);
+            }
+        }
+    }
+    res.sort_by_key(|(i, _)| *i);
+    res.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Draws a deterministic random sample of the test cases of a corpus in
+/// TSV, CSV or JSON Lines format, e.g. to get a small corpus for smoke
+/// tests from a huge one. The header lines are kept, the test cases keep
+/// their order.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// File holding the corpus, decompressed if it ends with .gz
+    file: PathBuf,
+
+    /// Number of test cases to draw
+    #[arg(short, long, default_value_t = 1000)]
+    n: usize,
+
+    /// Seed for the random number generator
+    #[arg(short, long, default_value_t = 0)]
+    seed: u64,
+
+    /// Output file (default: stdout), compressed if it ends with .gz
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: Args) {
+    let inp = open_input(Some(&args.file));
+    let mut header = Vec::<String>::new();
+    let lines = inp.lines().map(Result::unwrap).filter(|line| {
+        if is_header(line) {
+            header.push(line.clone());
+        }
+        !(line.trim().is_empty() || is_header(line))
+    });
+    let sample = reservoir(lines, args.n, args.seed);
+    let mut out = match &args.output {
+        Some(path) => Output::create(path).unwrap_or_else(|e| {
+            panic!("Can't create {}: {e}", path.display())
+        }),
+        None => Output::stdout(),
+    };
+    for line in header.iter().chain(&sample) {
+        writeln!(out, "{line}").unwrap();
+    }
+    out.finish().unwrap();
+}
+
+#[cfg(test)]
+mod sample_tests {
+    use super::*;
+
+    #[test]
+    fn test_reservoir() {
+        let sample = reservoir(0..1000, 10, 7);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(sample, reservoir(0..1000, 10, 7));
+        assert_ne!(sample, reservoir(0..1000, 10, 8));
+        assert_eq!(reservoir(0..5, 10, 7), [0, 1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,287 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Search for inputs of a function whose exact results lie within 2⁻ᵐ ulp
+//! of a rounding boundary of f256, see `hard_cases`, within a time budget.
+//!
+//! Randomly drawn inputs are refined locally: near an input x the exact
+//! result, in units of ½ ulp, is nearly linear in the offset from x in
+//! ulps, so the offsets whose results come close to a boundary, i.e. to an
+//! integer, are predicted by stepping along that line. Only the predicted
+//! candidates are evaluated exactly. The window of offsets is narrowed
+//! until the deviation of the results from the line stays small, so no
+//! input of the window closer to a boundary than the threshold is missed,
+//! unless the exponent of the result changes within the window.
+
+use std::{
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::{Float, Integer};
+
+use super::{
+    hard_cases::{exact, hardness, Boundary, Func, HardCase},
+    oracle, OutputArgs,
+};
+use crate::{Header, Record, RoundingMode, TestItem, EMAX, EMIN, FP237, P};
+
+// working precision of the predictions
+const WP: u32 = 3 * P;
+
+/// Returns the exact value of `func` at `x` in units of ½ ulp of a result
+/// with exponent `exp`.
+fn scaled(func: Func, x: &FP237, exp: i32) -> Float {
+    exact(func, &x.f, WP).f.abs() << (P as i32 + 1 - exp)
+}
+
+/// Returns the inputs within `window` ulps of the positive normal `x`, in
+/// the binade of `x`, whose exact results under `func` lie closer than
+/// `threshold` to a rounding boundary, in units of 2⁻ᴾ·ulp like
+/// `hardness`, together with the number of inputs evaluated exactly.
+/// Inputs giving exact results are skipped.
+pub(crate) fn refine(
+    func: Func,
+    x: &FP237,
+    window: u32,
+    threshold: &Float,
+) -> (Vec<HardCase>, u32) {
+    let exp_x = x.f.get_exp().unwrap();
+    let Some(exp) = exact(func, &x.f, WP).f.get_exp() else {
+        return (vec![], 1);
+    };
+    let ulp = Float::with_val(WP, Float::i_exp(1, exp_x - P as i32));
+    let at = |k: i64| {
+        let d = Float::with_val(WP, &ulp * k);
+        FP237::new(Float::with_val(P, &x.f + &d))
+    };
+    // offsets k staying in the binade of x: 2ᴾ⁻¹ <= m + k < 2ᴾ
+    let m = Float::with_val(P, &x.f << (P as i32 - exp_x))
+        .to_integer()
+        .unwrap();
+    let below = &m - (Integer::from(1) << (P - 1));
+    let above = (Integer::from(1) << P) - m - 1_u32;
+    let bound = |i: Integer| i.to_i64().unwrap_or(i64::MAX);
+    let s0 = scaled(func, x, exp);
+    let slope = if above > 0 {
+        scaled(func, &at(1), exp) - &s0
+    } else {
+        Float::with_val(WP, &s0 - scaled(func, &at(-1), exp))
+    };
+    let mut evaluated = 3;
+    // narrow the window until the results at its ends deviate from the
+    // line by less than 1/16
+    let mut w = window.max(1) as i64;
+    let (k_min, k_max, dev) = loop {
+        let (k_min, k_max) =
+            (-w.min(bound(below.clone())), w.min(bound(above.clone())));
+        let dev = [k_min, k_max]
+            .iter()
+            .map(|&k| {
+                let line = Float::with_val(WP, &slope * k) + &s0;
+                (scaled(func, &at(k), exp) - line).abs()
+            })
+            .max_by(|a, b| a.total_cmp(b))
+            .unwrap();
+        evaluated += 2;
+        if dev < 0.0625 || w == 1 {
+            break (k_min, k_max, dev);
+        }
+        w /= 2;
+    };
+    // ½ ulp = 2^(P-1) units of 2⁻ᴾ·ulp
+    let limit = Float::with_val(WP, threshold >> (P - 1)) + dev * 2_u32;
+    let mut cases = Vec::new();
+    let mut t = Float::with_val(WP, &slope * k_min) + &s0;
+    for k in k_min..=k_max {
+        let nearest = Float::with_val(WP, t.round_ref());
+        let frac = Float::with_val(WP, &t - &nearest).abs();
+        if frac < limit {
+            let y = at(k);
+            evaluated += 1;
+            if let Some((distance, boundary)) = hardness(func, &y) {
+                if distance < *threshold {
+                    cases.push(HardCase {
+                        x: y,
+                        distance,
+                        boundary,
+                    });
+                }
+            }
+        }
+        t += &slope;
+    }
+    (cases, evaluated)
+}
+
+/// Searches inputs of a function whose exact results lie within 2^-m ulp of
+/// a rounding boundary, refining randomly drawn inputs locally, and emits
+/// them as a corpus, closest first, with the binary logarithm of the
+/// distance in ulps and the kind of the boundary.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Function
+    #[arg(short, long, value_enum)]
+    func: Func,
+
+    /// Keep the inputs with results closer than 2^-m ulp to a boundary
+    #[arg(short, default_value_t = 20)]
+    m: u32,
+
+    /// Time budget in seconds
+    #[arg(short, long, default_value_t = 10)]
+    budget: u64,
+
+    /// Maximal number of random inputs to be refined (default: as many as
+    /// the budget allows)
+    #[arg(short, long)]
+    centers: Option<u32>,
+
+    /// Number of ulps searched on each side of a random input
+    #[arg(short, long, default_value_t = 65536)]
+    window: u32,
+
+    /// Maximal number of inputs kept
+    #[arg(short, long, default_value_t = 100)]
+    keep: usize,
+
+    /// Seed for the random number generator
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    /// Lower bound of the exponent range of the inputs
+    #[arg(long, default_value_t = -8, allow_negative_numbers = true)]
+    exp_min: i32,
+
+    /// Upper bound of the exponent range of the inputs
+    #[arg(long, default_value_t = 8, allow_negative_numbers = true)]
+    exp_max: i32,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Returns the binary logarithm of `distance`, given in units of 2⁻ᴾ·ulp,
+/// in ulps.
+fn log2_ulps(distance: &Float) -> String {
+    let l = Float::with_val(64, distance.log2_ref()).to_f64() - P as f64;
+    format!("{l:.2}")
+}
+
+pub fn run(args: Args) {
+    let func = args.func;
+    let name = func.name();
+    let m = args.m;
+    assert!((1..=P).contains(&m), "m must be in 1..={P}");
+    let exp_range: RangeInclusive<i32> = args.exp_min..=args.exp_max;
+    assert!(
+        EMIN <= args.exp_min
+            && args.exp_min <= args.exp_max
+            && args.exp_max <= EMAX,
+        "Exponent range must be within {EMIN}..={EMAX}"
+    );
+    let seed = args.output.seed(args.seed);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let threshold = Float::with_val(WP, Float::i_exp(1, (P - m) as i32));
+    let budget = Duration::from_secs(args.budget);
+    let start = Instant::now();
+    let mut cases: Vec<HardCase> = Vec::new();
+    let (mut centers, mut evaluated) = (0_u32, 0_u64);
+    while start.elapsed() < budget
+        && args.centers.is_none_or(|n| centers < n)
+        && cases.len() < args.keep
+    {
+        let x =
+            FP237::random_from_exp_range_with_rng(&mut rng, &exp_range).abs();
+        centers += 1;
+        let (found, n) = refine(func, &x, args.window, &threshold);
+        evaluated += n as u64;
+        for c in found {
+            // windows may overlap
+            if !cases.iter().any(|d| d.x == c.x) {
+                cases.push(c);
+            }
+        }
+    }
+    cases.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    cases.truncate(args.keep);
+    if !args.output.quiet {
+        eprintln!(
+            "{name}: {} inputs within 2^-{m} ulp of a boundary, {centers} \
+             random inputs refined, {evaluated} evaluated exactly, {:.1} s",
+            cases.len(),
+            start.elapsed().as_secs_f64()
+        );
+    }
+
+    // the number of random inputs makes the run reproducible
+    let header = Header::new(name)
+        .seed(seed)
+        .param("m", m)
+        .param("window", args.window)
+        .param("centers", centers)
+        .exp_ranges(&[&exp_range]);
+    let mut out = args.output.meta_writer(&header);
+    for c in &cases {
+        let res = oracle::eval(
+            name,
+            std::slice::from_ref(&c.x),
+            RoundingMode::NearestEven,
+        );
+        let boundary = match c.boundary {
+            Boundary::Representable => "representable",
+            Boundary::Midpoint => "midpoint",
+        };
+        let item =
+            TestItem::new(name, vec![(&c.x).into()], vec![(&res).into()])
+                .with_meta(
+                    Record::new()
+                        .token(log2_ulps(&c.distance))
+                        .token(boundary),
+                );
+        out.write_item(&item).unwrap();
+    }
+    out.finish().unwrap();
+}
+
+#[cfg(test)]
+mod search_hard_cases_tests {
+    use super::*;
+
+    #[test]
+    fn test_refine() {
+        // all inputs of the window closer than 2⁻⁸ ulp to a boundary are
+        // found
+        let threshold = Float::with_val(WP, Float::i_exp(1, P as i32 - 8));
+        let x = FP237::from(3).sqrt();
+        let window = 300;
+        for func in [Func::Exp, Func::Ln, Func::Sin] {
+            let (cases, evaluated) = refine(func, &x, window, &threshold);
+            assert!(evaluated < 2 * window);
+            let ulp = &x.next_up() - &x;
+            let mut expected = Vec::new();
+            let mut y = &x - &(&FP237::from(window) * &ulp);
+            for _ in 0..=2 * window {
+                if let Some((d, _)) = hardness(func, &y) {
+                    if d < threshold {
+                        expected.push(y.clone());
+                    }
+                }
+                y = y.next_up();
+            }
+            assert!(!expected.is_empty(), "{func:?}");
+            let found: Vec<FP237> =
+                cases.iter().map(|c| c.x.clone()).collect();
+            assert_eq!(found, expected, "{func:?}");
+        }
+    }
+}
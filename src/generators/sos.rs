@@ -0,0 +1,144 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::Parser;
+use rand::prelude::*;
+use rug::Float;
+
+use super::{CommonArgs, ExpRangeArgs};
+use crate::{
+    CorpusBuilder, Header, RoundingMode, EMAX, EMIN, FP237,
+    MIN_EXP_SUBNORMAL, P, PM1,
+};
+
+const EXP_LOWER_BOUND: i32 = EMIN / 4 - 1;
+const EXP_UPPER_BOUND: i32 = EMAX / 4 + 1;
+const EXP_RANGE: RangeInclusive<i32> = EXP_LOWER_BOUND..=EXP_UPPER_BOUND;
+// 2⁻⁴⁷⁶ < y² / x² < 2⁻²³⁴, from about one ulp of x² down to far below it
+const NEGLIGIBLE_EXP_DIFF_RANGE: RangeInclusive<i32> =
+    P as i32 / 2..=P as i32;
+
+/// Rounds the exact value `f` to P bits only.
+fn round_p(f: Float) -> FP237 {
+    FP237::new(Float::with_val(P, f))
+}
+
+/// Returns a pair x, y so that x² + y² is within a few ulps of `r`.
+fn random_pair_near(rng: &mut StdRng, r: &Float) -> Vec<FP237> {
+    let u: f64 = rng.gen_range(0.0..1.0);
+    let x = round_p(Float::with_val(2 * P, r * u).sqrt());
+    let d = Float::with_val(4 * P, r - Float::with_val(2 * P, &x.f * &x.f));
+    let y = round_p(d.abs().sqrt());
+    let (x, y) = if rng.gen() { (x, y) } else { (-x, y) };
+    let (x, y) = if rng.gen() { (x, y) } else { (x, -y) };
+    if rng.gen() {
+        vec![x, y]
+    } else {
+        vec![y, x]
+    }
+}
+
+/// Generates test data for the sum of squares.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Mode: R = random exponents in a safe band, O = x² + y² near
+    /// overflow, U = x² + y² near total underflow, N = one square
+    /// negligible relative to the other
+    #[arg(short, long, default_value_t = 'R')]
+    mode: char,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    /// Add the boundary values of the exponent ranges
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    let exp_range = args.exp.exp_range(&EXP_RANGE);
+    let n = args.common.n_test_data;
+    let seed = args.common.seed();
+
+    let mut builder = CorpusBuilder::new(2, seed)
+        .sign(args.common.sign)
+        .random(n, |rng| {
+            match args.mode {
+                'R' => {
+                    let x = FP237::random_from_exp_range_with_rng(
+                        rng, &exp_range,
+                    );
+                    let y = FP237::random_from_exp_range_with_rng(
+                        rng, &exp_range,
+                    );
+                    vec![x, y]
+                }
+                'O' => {
+                    // f256::MAX ± a few ulps
+                    let d: f64 = rng.gen_range(-4.0..4.0);
+                    let r = Float::with_val(2 * P, Float::i_exp(1, P as i32));
+                    let r = Float::with_val(2 * P, r - 1 + d) << (EMAX - PM1);
+                    random_pair_near(rng, &r)
+                }
+                'U' => {
+                    // up to a few multiples of f256::MIN_GT_ZERO
+                    let d: f64 = rng.gen_range(0.0..4.0);
+                    let r = Float::with_val(2 * P, d) << MIN_EXP_SUBNORMAL;
+                    random_pair_near(rng, &r)
+                }
+                'N' => {
+                    let x = FP237::random_from_exp_range_with_rng(
+                        rng, &exp_range,
+                    );
+                    let (_, e, _) = x.decode(false);
+                    let k = rng.gen_range(NEGLIGIBLE_EXP_DIFF_RANGE);
+                    let t = e + PM1 - k;
+                    let y =
+                        FP237::random_from_exp_range_with_rng(rng, &(t..=t));
+                    if rng.gen() {
+                        vec![x, y]
+                    } else {
+                        vec![y, x]
+                    }
+                }
+                _ => panic!("Unkown mode"),
+            }
+        });
+    if args.boundaries {
+        builder = builder.boundaries(&exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        builder = builder.verify(&[RoundingMode::NearestEven]);
+    }
+    let header = Header::new("sos").seed(seed).exp_ranges(&[&exp_range]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "sos", |ops| Some(vec![ops[0].sos(&ops[1])]))
+        .unwrap();
+    out.finish().unwrap();
+}
@@ -0,0 +1,370 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use clap::{error::ErrorKind, Parser};
+use rand::prelude::*;
+use rug::{Float, Integer};
+
+use super::{
+    usage_error, CommonArgs, ExpRangeArgs, RoundArgs, NORMAL_EXP_RANGE,
+    SUBNORMAL_EXP_RANGE,
+};
+use crate::{
+    verify::{reference, VERIFY_PREC},
+    CorpusBuilder, DecodedFP, Header, RoundingMode, Sign, TestItem, EMAX,
+    EMIN, FP237, MIN_EXP_SUBNORMAL, P, ROUNDING_MODES,
+};
+
+// roots of exact squares: 2^(EMIN / 2) <= |r| < 2^(EMAX / 2)
+const ROOT_EXP_RANGE: RangeInclusive<i32> = EMIN / 2..=EMAX / 2 - 1;
+
+// significant bits needed by a rounded root of a non-square: the square of
+// a root z = s·2ᵘ with s odd of b bits is at least 2²ᵘ off the operand x,
+// so |√x - z| >= 2²ᵘ / (√x + z) > 2^(u - b - 1), which must be below
+// ulp(z) = 2^(u + b - P), hence 2b > P - 1
+const MIN_SHORT_ROOT_BITS: u32 = P.div_ceil(2);
+
+// Roots of non-squares may have short significands, even of at most 128
+// bits, see `subnormal_ties`, but not shorter than MIN_SHORT_ROOT_BITS.
+fn check_test_item(x: &FP237, z: &FP237) {
+    let (_, _, (h, l)) = z.decode(true);
+    let bits = if h != 0 {
+        256 - h.leading_zeros()
+    } else {
+        128 - l.leading_zeros()
+    };
+    assert!(
+        bits >= MIN_SHORT_ROOT_BITS,
+        "Root of {:?} unexpectedly has only {bits} significant bits.",
+        x.decode(false)
+    );
+}
+
+/// Counts of the subnormal inputs analyzed, by the number of significant
+/// bits of the roots they are built from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct TieStats {
+    /// Non-square inputs next to the square of a root
+    candidates: u64,
+    /// Inputs with a root of at most 128 significant bits in some mode
+    short: u64,
+    /// Results differing from the ones recomputed at 2P bits
+    mismatches: u64,
+}
+
+/// Returns the smallest value >= `lo` and the largest value <= `hi`
+/// congruent to 1 modulo `m`, if any.
+fn congruent_one(lo: &Integer, hi: &Integer, m: &Integer) -> Vec<Integer> {
+    let first =
+        Integer::from(lo - 1_u32).div_rem_ceil(m.clone()).0 * m + 1_u32;
+    let last =
+        Integer::from(hi - 1_u32).div_rem_floor(m.clone()).0 * m + 1_u32;
+    if first > *hi {
+        vec![]
+    } else if first == last {
+        vec![first]
+    } else {
+        vec![first, last]
+    }
+}
+
+/// Analyzes when the root of a subnormal value rounds to a value with a
+/// zero high word, i.e. with at most 128 significant bits.
+///
+/// The root of an exact square is exact; all other such roots are roots
+/// z = s·2ᵘ, s odd, of the subnormal values next to z², which is not a
+/// multiple of 2^MIN_EXP_SUBNORMAL. For each exponent t of the inputs in
+/// `exp_range` and each number b of significant bits of s, the inputs next
+/// to z² are built from the smallest and largest s of the binade having the
+/// square closest to the grid of subnormal values, i.e. s ≡ 1 modulo
+/// 2^(g - 1) with g = MIN_EXP_SUBNORMAL - 2u. Their roots are rounded
+/// according to `modes` and checked against the results recomputed at 2P
+/// bits.
+///
+/// Returns the inputs having a root of at most 128 significant bits in
+/// some mode, with the results in all modes, and the counts by b.
+fn subnormal_ties(
+    exp_range: &RangeInclusive<i32>,
+    modes: &[RoundingMode],
+) -> (Vec<TestItem>, BTreeMap<u32, TieStats>) {
+    let mut items = Vec::new();
+    let mut stats: BTreeMap<u32, TieStats> = BTreeMap::new();
+    let grid = Float::with_val(P, Float::i_exp(1, MIN_EXP_SUBNORMAL));
+    for t in exp_range.clone() {
+        // 2ᵗ <= x < 2ᵗ⁺¹ and 2ᵉ <= z < 2ᵉ⁺¹
+        let e = t.div_euclid(2);
+        let odd = t.rem_euclid(2) == 1;
+        for b in 1..=128_u32 {
+            // s² < 2²ᵇ⁻¹ for even t, s² >= 2²ᵇ⁻¹ for odd t
+            let sqrt_half = (Integer::from(1) << (2 * b - 1)) - 1_u32;
+            let sqrt_half = sqrt_half.sqrt();
+            let (lo, hi) = if odd {
+                (sqrt_half + 1_u32, (Integer::from(1) << b) - 1_u32)
+            } else {
+                (Integer::from(1) << (b - 1), sqrt_half)
+            };
+            let u = e - b as i32 + 1;
+            let g = MIN_EXP_SUBNORMAL - 2 * u;
+            if g <= 0 {
+                // all squares are subnormal values
+                continue;
+            }
+            let m = Integer::from(1) << (g - 1).max(1) as u32;
+            for s in congruent_one(&lo, &hi, &m) {
+                let square = Integer::from(s.square_ref());
+                let c = square >> g as u32;
+                for c in [c.clone(), c + 1_u32] {
+                    let x = FP237::new(Float::with_val(P, &grid * &c));
+                    if c == 0 || x.f.get_exp().unwrap() > EMIN {
+                        continue;
+                    }
+                    let st = stats.entry(b).or_default();
+                    st.candidates += 1;
+                    let results: Vec<FP237> =
+                        modes.iter().map(|rnd| x.sqrt_round(*rnd)).collect();
+                    let v = &reference(
+                        "sqrt",
+                        std::slice::from_ref(&x),
+                        VERIFY_PREC,
+                    )[0];
+                    st.mismatches += modes
+                        .iter()
+                        .zip(&results)
+                        .filter(|(rnd, z)| {
+                            z.to_bits() != v.to_fp237(**rnd).to_bits()
+                        })
+                        .count() as u64;
+                    if results.iter().any(|z| z.decode(true).2 .0 == 0) {
+                        st.short += 1;
+                        items.push(TestItem::new(
+                            "sqrt",
+                            vec![DecodedFP::from(&x)],
+                            results.iter().map(DecodedFP::from).collect(),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    (items, stats)
+}
+
+/// Generates test data for the square root.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    exp: ExpRangeArgs,
+
+    #[command(flatten)]
+    rounding: RoundArgs,
+
+    /// Add the boundary values of the exponent ranges
+    #[arg(short, long)]
+    boundaries: bool,
+
+    /// Add the special values
+    #[arg(short = 'x', long)]
+    specials: bool,
+
+    /// Give the results for all rounding modes (in the order ne tz up dn
+    /// na)
+    #[arg(short, long)]
+    all_modes: bool,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+
+    /// Instead of random data, emit the subnormal inputs whose roots round
+    /// to values with at most 128 significant bits, found by an exhaustive
+    /// analysis of the exponents of the inputs, and report the analysis on
+    /// stderr
+    #[arg(long)]
+    subnormal_ties: bool,
+}
+
+/// Runs the analysis selected by `--subnormal-ties`.
+fn run_subnormal_ties(args: &Args, modes: &[RoundingMode]) {
+    let exp_range = args.exp.exp_range(&SUBNORMAL_EXP_RANGE);
+    assert!(
+        *exp_range.end() < EMIN,
+        "Exponent range must be within {SUBNORMAL_EXP_RANGE:?}"
+    );
+    let (items, stats) = subnormal_ties(&exp_range, modes);
+    if !args.common.output.quiet {
+        eprintln!("# bits\tcandidates\tshort roots\tmismatches");
+        for (b, st) in stats.iter().filter(|(_, st)| st.short > 0) {
+            eprintln!(
+                "# {b}\t{}\t{}\t{}",
+                st.candidates, st.short, st.mismatches
+            );
+        }
+        let min_bits = stats.iter().find(|(_, st)| st.short > 0);
+        let mismatches: u64 = stats.values().map(|st| st.mismatches).sum();
+        eprintln!(
+            "# {} inputs with short roots, the shortest of {} bits (at \
+             least {MIN_SHORT_ROOT_BITS} needed), {mismatches} mismatches",
+            items.len(),
+            min_bits.map_or("-".to_string(), |(b, _)| b.to_string())
+        );
+    }
+    let header = Header::new("sqrt")
+        .rounding(if modes.len() > 1 {
+            "all".to_string()
+        } else {
+            modes[0].to_string()
+        })
+        .param("analysis", "subnormal-ties")
+        .exp_ranges(&[&exp_range]);
+    let mut out = args.common.output.writer(&header);
+    for item in &items {
+        out.write_item(item).unwrap();
+    }
+    out.finish().unwrap();
+}
+
+pub fn run(args: Args) {
+    if args.common.sign == Sign::Neg {
+        usage_error(
+            ErrorKind::ArgumentConflict,
+            "--sign neg gives NaN only for sqrt",
+        );
+    }
+    let normal_exp_range = args.exp.exp_range(&NORMAL_EXP_RANGE);
+    let subnormal_exp_range = args.exp.exp_range(&SUBNORMAL_EXP_RANGE);
+    let rnd = args.rounding.round;
+    assert!(
+        !args.all_modes || rnd == RoundingMode::NearestEven,
+        "Rounding mode {rnd} can't be combined with --all-modes"
+    );
+    if args.subnormal_ties {
+        let modes = if args.all_modes {
+            ROUNDING_MODES.to_vec()
+        } else {
+            vec![rnd]
+        };
+        run_subnormal_ties(&args, &modes);
+        return;
+    }
+    let n_sub_normal = args.common.n_test_data / 100 + 1;
+    let n_squares = args.common.n_test_data / 10 + 1;
+    let n_normal = args
+        .common
+        .n_test_data
+        .saturating_sub(n_sub_normal + n_squares);
+    let seed = args.common.seed();
+
+    let mut builder = CorpusBuilder::new(1, seed)
+        .sign(args.common.sign)
+        .random(n_normal, |rng| {
+            vec![FP237::random_from_exp_range_with_rng(
+                rng,
+                &normal_exp_range,
+            )
+            .abs()]
+        })
+        .random(n_sub_normal, |rng| {
+            vec![FP237::random_from_exp_range_with_rng(
+                rng,
+                &subnormal_exp_range,
+            )
+            .abs()]
+        })
+        .random(n_squares, |rng| {
+            // exact squares and their neighbours, where the root is closest
+            // to a representable value or to the midpoint between two
+            let r =
+                FP237::random_from_exp_range_with_rng(rng, &ROOT_EXP_RANGE);
+            let r = Float::with_val(P / 2, &r.f);
+            let x = FP237::new(Float::with_val(P, &r * &r)).abs();
+            vec![match rng.gen_range(0..3) {
+                0 => x,
+                1 => x.next_up(),
+                _ => x.next_down(),
+            }]
+        });
+    if args.boundaries {
+        builder = builder
+            .boundaries(&normal_exp_range)
+            .boundaries(&subnormal_exp_range);
+    }
+    if args.specials {
+        builder = builder.specials();
+    }
+    if args.verify {
+        let modes = if args.all_modes {
+            ROUNDING_MODES.to_vec()
+        } else {
+            vec![rnd]
+        };
+        builder = builder.verify(&modes);
+    }
+    let header = Header::new("sqrt")
+        .seed(seed)
+        .rounding(if args.all_modes {
+            "all".to_string()
+        } else {
+            rnd.to_string()
+        })
+        .exp_ranges(&[
+            &normal_exp_range,
+            &subnormal_exp_range,
+            &ROOT_EXP_RANGE,
+        ]);
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, "sqrt", |ops| {
+            let x = &ops[0];
+            let z = x.sqrt();
+            // exact squares and their neighbours have roots with short
+            // significands by nature
+            let d = (Float::with_val(2 * P, &z.f * &z.f) - &x.f).abs();
+            if x.f.is_finite() && d > (&x.next_up() - x).f {
+                check_test_item(x, &z);
+            }
+            if args.all_modes {
+                Some(
+                    ROUNDING_MODES
+                        .iter()
+                        .map(|rnd| x.sqrt_round(*rnd))
+                        .collect(),
+                )
+            } else {
+                Some(vec![x.sqrt_round(rnd)])
+            }
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
+
+#[cfg(test)]
+mod sqrt_tests {
+    use super::*;
+
+    #[test]
+    fn test_subnormal_ties() {
+        let exp_range = EMIN - 3..=EMIN - 1;
+        let (items, stats) = subnormal_ties(&exp_range, &ROUNDING_MODES);
+        assert!(!items.is_empty());
+        for (b, st) in &stats {
+            assert_eq!(st.mismatches, 0, "{b}");
+            assert_eq!(st.short > 0, *b >= MIN_SHORT_ROOT_BITS, "{b}");
+        }
+        let short: u64 = stats.values().map(|st| st.short).sum();
+        assert_eq!(items.len() as u64, short);
+    }
+}
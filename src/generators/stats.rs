@@ -0,0 +1,244 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::BufRead,
+    path::PathBuf,
+};
+
+use clap::Parser;
+
+use super::{
+    is_header, open_input,
+    oracle::{arity, parse_values},
+};
+use crate::{verify, FP237};
+
+/// Classes of values, in the order they are reported.
+const CLASSES: [&str; 5] = ["zero", "subnormal", "normal", "infinite", "nan"];
+
+/// Returns the index of the class of `f` in `CLASSES`.
+fn classify(f: &FP237) -> usize {
+    if f.f.is_zero() {
+        0
+    } else if f.f.is_nan() {
+        4
+    } else if f.f.is_infinite() {
+        3
+    } else if f.f.cmp_abs(&FP237::MIN_POSITIVE().f).unwrap().is_lt() {
+        1
+    } else {
+        2
+    }
+}
+
+/// Returns the operation named in a header line in TSV, CSV or JSON Lines
+/// format, or `None` if `line` is not a header line.
+fn header_op(line: &str) -> Option<String> {
+    if !is_header(line) {
+        None
+    } else if line.starts_with('#') {
+        line.split(' ')
+            .find_map(|kv| kv.strip_prefix("op="))
+            .map(str::to_string)
+    } else {
+        let (_, rest) = line.split_once("\"op\":\"")?;
+        rest.split_once('"').map(|(op, _)| op.to_string())
+    }
+}
+
+/// Parses the first `n` decode tuples of a data line in TSV, CSV or JSON
+/// Lines format (a test item object or an array).
+fn parse_operands(line: &str, n: usize) -> Result<Vec<FP237>, String> {
+    let line = match line.split_once("\"operands\":") {
+        Some((_, rest)) => rest.split("]]").next().unwrap_or_default(),
+        None => line,
+    };
+    let fields: Vec<&str> = line
+        .split(['\t', ','])
+        .map(|s| s.trim_matches(['[', ']']))
+        .collect();
+    if fields.len() < 4 * n {
+        return Err(format!(
+            "Expected at least {} fields, found {}",
+            4 * n,
+            fields.len()
+        ));
+    }
+    parse_values(&fields[..4 * n].join(" "), n)
+}
+
+/// Summary of the operand sets of a corpus.
+#[derive(Debug, Default)]
+struct Stats {
+    rows: u64,
+    duplicates: u64,
+    operands: u64,
+    negative: u64,
+    classes: [u64; CLASSES.len()],
+    exps: BTreeMap<i32, u64>,
+    exact: u64,
+    inexact: u64,
+}
+
+impl Stats {
+    /// Adds the operand set `ops`, counting the exponents of finite values
+    /// in bins of `bin_width`.
+    fn add(&mut self, ops: &[FP237], bin_width: i32) {
+        self.rows += 1;
+        for f in ops {
+            self.operands += 1;
+            if f.f.is_sign_negative() && !f.f.is_nan() {
+                self.negative += 1;
+            }
+            let class = classify(f);
+            self.classes[class] += 1;
+            if class == 1 || class == 2 {
+                let exp = f.f.get_exp().unwrap() - 1;
+                let bin = exp.div_euclid(bin_width) * bin_width;
+                *self.exps.entry(bin).or_default() += 1;
+            }
+        }
+    }
+
+    fn print(&self, bin_width: i32, with_exactness: bool) {
+        let pct = |k: u64, n: u64| {
+            format!("{k} ({:.2}%)", 100. * k as f64 / n.max(1) as f64)
+        };
+        println!("rows\t{}", self.rows);
+        println!("duplicates\t{}", pct(self.duplicates, self.rows));
+        println!("operands\t{}", self.operands);
+        let positive = self.operands - self.negative - self.classes[4];
+        println!("  positive\t{}", pct(positive, self.operands));
+        println!("  negative\t{}", pct(self.negative, self.operands));
+        for (name, k) in CLASSES.iter().zip(self.classes) {
+            println!("  {name}\t{}", pct(k, self.operands));
+        }
+        if with_exactness {
+            let n = self.exact + self.inexact;
+            println!("results\t{n}");
+            println!("  exact\t{}", pct(self.exact, n));
+            println!("  inexact\t{}", pct(self.inexact, n));
+        }
+        println!("exponents\t(bins of {bin_width})");
+        for (bin, k) in &self.exps {
+            println!(
+                "  {bin}..={}\t{}",
+                bin + bin_width - 1,
+                pct(*k, self.operands)
+            );
+        }
+    }
+}
+
+/// Summarizes the operands of a corpus in TSV, CSV or JSON Lines format:
+/// sign balance, proportions of zeros, subnormals, normals, infinities and
+/// NaNs, histogram of the exponents, number of duplicate operand sets and,
+/// for operations which can be recomputed, the number of exact and inexact
+/// results.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// File holding the corpus, decompressed if it ends with .gz
+    file: PathBuf,
+
+    /// Number of operands per test case (default: derived from the
+    /// operation given in the header)
+    #[arg(short = 'n', long)]
+    operands: Option<usize>,
+
+    /// Width of the bins of the exponent histogram
+    #[arg(short, long, default_value_t = 1 << 14)]
+    bin_width: i32,
+}
+
+pub fn run(args: Args) {
+    assert!(args.bin_width > 0, "Bin width must be positive");
+    let inp = open_input(Some(&args.file));
+    let mut op = None;
+    let mut n_operands = args.operands;
+    let mut seen = HashSet::<Vec<(u128, u128)>>::new();
+    let mut stats = Stats::default();
+    for (n, line) in inp.lines().enumerate() {
+        let line = line.unwrap();
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if is_header(line) {
+            if let Some(name) = header_op(line) {
+                n_operands = n_operands.or_else(|| arity(&name));
+                op = Some(name);
+            }
+            continue;
+        }
+        let Some(n_operands) = n_operands else {
+            panic!("Number of operands unknown, use -n to give it");
+        };
+        let ops = parse_operands(line, n_operands)
+            .unwrap_or_else(|e| panic!("Line {}: {e}", n + 1));
+        stats.add(&ops, args.bin_width);
+        if !seen.insert(ops.iter().map(FP237::to_bits).collect()) {
+            stats.duplicates += 1;
+        }
+        if let Some(op) = op.as_deref().filter(|op| verify::is_supported(op))
+        {
+            for exact in verify::exactness(op, &ops) {
+                if exact {
+                    stats.exact += 1;
+                } else {
+                    stats.inexact += 1;
+                }
+            }
+        }
+    }
+    println!("op\t{}", op.as_deref().unwrap_or("?"));
+    stats.print(
+        args.bin_width,
+        op.as_deref().is_some_and(verify::is_supported),
+    );
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+
+    #[test]
+    fn test_header_op() {
+        let tsv = "# version=0.1 op=div rounding=ne args=\"x op=y\"";
+        assert_eq!(header_op(tsv).as_deref(), Some("div"));
+        let jsonl = "{\"header\":{\"version\":\"0.1\",\"op\":\"sqrt\"}}";
+        assert_eq!(header_op(jsonl).as_deref(), Some("sqrt"));
+        let item = "{\"op\":\"sqrt\",\"operands\":[[0,0,0,1]]}";
+        assert_eq!(header_op(item), None);
+        assert_eq!(
+            parse_operands(item, 1).unwrap()[0].to_bits(),
+            FP237::ONE().to_bits()
+        );
+        assert_eq!(header_op("0\t0\t0\t1"), None);
+    }
+
+    #[test]
+    fn test_add() {
+        let ops = parse_operands("[0,0,0,1,1,-262378,0,1,0,0,0,0,\"x\"]", 3)
+            .unwrap();
+        let mut stats = Stats::default();
+        stats.add(&ops, 1 << 14);
+        stats.add(&[FP237::NAN(), -FP237::INFINITY()], 1 << 14);
+        assert_eq!(stats.rows, 2);
+        assert_eq!(stats.operands, 5);
+        assert_eq!(stats.negative, 2);
+        assert_eq!(stats.classes, [1, 1, 1, 1, 1]);
+        assert_eq!(
+            stats.exps.into_iter().collect::<Vec<_>>(),
+            [(-262144 - (1 << 14), 1), (0, 1)]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{ops::RangeInclusive, str::FromStr};
+
+use clap::{Parser, ValueEnum};
+use rand::prelude::*;
+
+use super::CommonArgs;
+use crate::{
+    max_10_exp, min_subnormal_10_exp, Header, Record, Sign, TestItem, EMAX,
+    FP237, MIN_EXP_SUBNORMAL,
+};
+
+const MAX_N_DIGITS: u32 = 80;
+
+/// Returns the decimal exponents of decimal literals, including values
+/// rounding to zero or overflowing.
+fn dec_exp_range() -> RangeInclusive<i32> {
+    min_subnormal_10_exp() - MAX_N_DIGITS as i32..=max_10_exp() + 1
+}
+// binary exponents of hex literals, including values rounding to zero or
+// overflowing
+const HEX_EXP_RANGE: RangeInclusive<i32> = MIN_EXP_SUBNORMAL - 4..=EMAX + 1;
+// 59 hex digits hold P bits, more digits need rounding
+const HEX_MAX_N_DIGITS: u32 = 72;
+
+const DIGITS: &[u8] = b"0123456789";
+const HEX_DIGITS: &[u8] = b"0123456789abcdefABCDEF";
+
+fn random_sign(rng: &mut StdRng) -> &'static str {
+    match rng.gen_range(0..=2) {
+        0 => "+",
+        1 => "-",
+        _ => "",
+    }
+}
+
+fn random_digits(rng: &mut StdRng, digits: &[u8], n: u32) -> String {
+    (0..n)
+        .map(|_| digits[rng.gen_range(0..digits.len())] as char)
+        .collect()
+}
+
+/// Returns a decimal literal like `-12.0345e-17`.
+fn gen_dec_literal(
+    rng: &mut StdRng,
+    dec_exp_range: &RangeInclusive<i32>,
+) -> String {
+    let sign = random_sign(rng);
+    let n_digits = rng.gen_range(1..=MAX_N_DIGITS);
+    let n_fract_digits = rng.gen_range(0..n_digits);
+    let digits = random_digits(rng, DIGITS, n_digits);
+    let (int_digits, fract_digits) =
+        digits.split_at((n_digits - n_fract_digits) as usize);
+    let exp = rng.gen_range(dec_exp_range.clone());
+    if n_fract_digits == 0 {
+        format!("{sign}{int_digits}e{exp}")
+    } else {
+        format!("{sign}{int_digits}.{fract_digits}e{exp}")
+    }
+}
+
+/// Returns a hex-float literal like `-0x1f.8p-3`.
+fn gen_hex_literal(rng: &mut StdRng) -> String {
+    let sign = random_sign(rng);
+    let n_digits = rng.gen_range(1..=HEX_MAX_N_DIGITS);
+    let n_fract_digits = rng.gen_range(0..n_digits);
+    let n_int_digits = n_digits - n_fract_digits;
+    let digits = random_digits(rng, HEX_DIGITS, n_digits);
+    let (int_digits, fract_digits) = digits.split_at(n_int_digits as usize);
+    // the value's exponent is roughly the literal's exponent plus four
+    // times the number of integral digits
+    let exp = rng.gen_range(HEX_EXP_RANGE) - 4 * n_int_digits as i32;
+    if n_fract_digits == 0 {
+        format!("{sign}0x{int_digits}p{exp}")
+    } else {
+        format!("{sign}0x{int_digits}.{fract_digits}p{exp}")
+    }
+}
+
+/// Re-parses `lit` and returns whether it gives exactly `f`.
+fn round_trips(lit: &str, f: &FP237) -> bool {
+    FP237::from_str(lit).unwrap().to_bits() == f.to_bits()
+}
+
+/// Types of literals to generate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum LitType {
+    /// Decimal literals
+    #[value(name = "D")]
+    Dec,
+    /// Hex literals
+    #[value(name = "H")]
+    Hex,
+    /// Decimal and hex literals
+    #[value(name = "M")]
+    Mixed,
+}
+
+/// Generates self-checked test data for parsing and formatting.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Type of literal
+    #[arg(short, long, value_enum, default_value_t = LitType::Mixed)]
+    type_of_lit: LitType,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+pub fn run(args: Args) {
+    let seed = args.common.seed();
+    let dec_exp_range = dec_exp_range();
+    let header = Header::new("from_str").seed(seed).exp_ranges(&match args
+        .type_of_lit
+    {
+        LitType::Dec => vec![&dec_exp_range],
+        LitType::Hex => vec![&HEX_EXP_RANGE],
+        LitType::Mixed => vec![&dec_exp_range, &HEX_EXP_RANGE],
+    });
+    let mut out = args.common.output.meta_writer(&header);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut n_failed = 0;
+    for _i in 0..args.common.n_test_data {
+        let hex = match args.type_of_lit {
+            LitType::Dec => false,
+            LitType::Hex => true,
+            LitType::Mixed => rng.gen(),
+        };
+        let lit = if hex {
+            gen_hex_literal(&mut rng)
+        } else {
+            gen_dec_literal(&mut rng, &dec_exp_range)
+        };
+        let lit = match args.common.sign {
+            Sign::Pos => lit.trim_start_matches('-').to_string(),
+            Sign::Neg if !lit.starts_with('-') => {
+                format!("-{}", lit.trim_start_matches('+'))
+            }
+            _ => lit,
+        };
+        let f = FP237::from_str(&lit).unwrap();
+        let shortest = f.to_shortest_string();
+        let exact = f.to_hex_string();
+        if round_trips(&shortest, &f) && round_trips(&exact, &f) {
+            // parsed value, then literal, shortest decimal and exact hex
+            let meta = Record::new().str(lit).str(shortest).str(exact);
+            let item = TestItem::new("from_str", vec![], vec![(&f).into()])
+                .with_meta(meta);
+            out.write_item(&item).unwrap();
+        } else {
+            n_failed += 1;
+            eprintln!("Round trip failed for \"{lit}\"");
+        }
+    }
+    if n_failed > 0 {
+        eprintln!(
+            "{n_failed} of {} literals failed",
+            args.common.n_test_data
+        );
+        // keep what was written so far in the temporary file
+        drop(out);
+        std::process::exit(1);
+    }
+    out.finish().unwrap();
+}
@@ -0,0 +1,211 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::{Parser, ValueEnum};
+use rug::Float;
+
+use super::{CommonArgs, RoundArgs};
+use crate::{CorpusBuilder, Header, RoundingMode, EMIN, FP237, P, PM1};
+
+// MIN_POSITIVE / 2⁴ <= |r| < MIN_POSITIVE * 2²
+const RESULT_EXP_RANGE: RangeInclusive<i32> = EMIN - 4..=EMIN + 1;
+// MIN_POSITIVE <= |f| < MIN_POSITIVE * 2⁴
+const NEAR_EMIN_NORMAL_EXP_RANGE: RangeInclusive<i32> = EMIN..=EMIN + 3;
+// MIN_POSITIVE / 2⁴ <= |f| < MIN_POSITIVE
+const NEAR_EMIN_SUBNORMAL_EXP_RANGE: RangeInclusive<i32> =
+    EMIN - 4..=EMIN - 1;
+// 2⁻¹⁰⁰ <= |f| < 2¹⁰⁰
+const MODERATE_EXP_RANGE: RangeInclusive<i32> = -100..=99;
+// 2 <= |f| < 2¹⁰, scaling subnormal values up
+const SCALE_EXP_RANGE: RangeInclusive<i32> = 1..=9;
+
+/// Rounds the exact value `f` to the nearest f256, so that operands derived
+/// from a target result are representable even if they fall below
+/// MIN_POSITIVE.
+fn to_f256(f: Float) -> FP237 {
+    FP237::with_val_round(f, RoundingMode::NearestEven)
+}
+
+/// Operations to generate test data for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Fma,
+}
+
+impl Op {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Sub => "sub",
+            Self::Mul => "mul",
+            Self::Div => "div",
+            Self::Fma => "fma",
+        }
+    }
+}
+
+/// Directions of crossing EMIN.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Direction {
+    /// Normal operands giving a result near or below MIN_POSITIVE
+    #[value(name = "N")]
+    ToSubnormal,
+    /// Subnormal operands giving a result near or above MIN_POSITIVE
+    #[value(name = "S")]
+    ToNormal,
+}
+
+/// Generates test data for results crossing MIN_POSITIVE.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Operation
+    #[arg(short, long, value_enum, default_value_t = Op::Add)]
+    op: Op,
+
+    /// Direction of crossing EMIN
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = Direction::ToSubnormal
+    )]
+    direction: Direction,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    rounding: RoundArgs,
+
+    /// Check the results against the results recomputed at higher
+    /// precision, abort on a mismatch
+    #[arg(long)]
+    verify: bool,
+}
+
+pub fn run(args: Args) {
+    let op = args.op;
+    let arity = match op {
+        Op::Fma => 3,
+        _ => 2,
+    };
+    let to_subnormal = args.direction == Direction::ToSubnormal;
+    let seed = args.common.seed();
+    let rnd = args.rounding.round;
+
+    let mut builder = CorpusBuilder::new(arity, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            let mut rnd = |range: &RangeInclusive<i32>| {
+                FP237::random_from_exp_range_with_rng(rng, range)
+            };
+            if to_subnormal {
+                // operands derived from a target result r around
+                // MIN_POSITIVE, rounded to f256, so the exact result is near
+                // r
+                let r = rnd(&RESULT_EXP_RANGE);
+                match op {
+                    Op::Add => {
+                        let x = rnd(&NEAR_EMIN_NORMAL_EXP_RANGE);
+                        let y = to_f256(Float::with_val(2 * P, &r.f - &x.f));
+                        vec![x, y]
+                    }
+                    Op::Sub => {
+                        let x = rnd(&NEAR_EMIN_NORMAL_EXP_RANGE);
+                        let y = to_f256(Float::with_val(2 * P, &x.f - &r.f));
+                        vec![x, y]
+                    }
+                    Op::Mul => {
+                        let x = rnd(&MODERATE_EXP_RANGE);
+                        let y = to_f256(Float::with_val(2 * P, &r.f / &x.f));
+                        vec![x, y]
+                    }
+                    Op::Div => {
+                        let y = rnd(&MODERATE_EXP_RANGE);
+                        let x = to_f256(Float::with_val(2 * P, &r.f * &y.f));
+                        vec![x, y]
+                    }
+                    _ => {
+                        let x = rnd(&MODERATE_EXP_RANGE);
+                        let (_, e, _) = x.decode(false);
+                        let t = EMIN + 2 - e - PM1;
+                        let y = rnd(&(t..=t + 2));
+                        let p = Float::with_val(2 * P, &x.f * &y.f);
+                        let a = to_f256(Float::with_val(3 * P, &r.f - &p));
+                        vec![x, y, a]
+                    }
+                }
+            } else {
+                // subnormal operands scaled or summed up to a normal result
+                let x = rnd(&NEAR_EMIN_SUBNORMAL_EXP_RANGE);
+                match op {
+                    Op::Add | Op::Sub => {
+                        let y = rnd(&NEAR_EMIN_SUBNORMAL_EXP_RANGE);
+                        let y = if (op == Op::Sub)
+                            == (x.f.is_sign_negative()
+                                == y.f.is_sign_negative())
+                        {
+                            -y
+                        } else {
+                            y
+                        };
+                        vec![x, y]
+                    }
+                    Op::Mul => vec![x, rnd(&SCALE_EXP_RANGE)],
+                    Op::Div => {
+                        let (lo, hi) = (
+                            *SCALE_EXP_RANGE.start(),
+                            *SCALE_EXP_RANGE.end(),
+                        );
+                        vec![x, rnd(&(-hi..=-lo))]
+                    }
+                    _ => {
+                        let y = rnd(&SCALE_EXP_RANGE);
+                        let a = rnd(&NEAR_EMIN_SUBNORMAL_EXP_RANGE);
+                        vec![x, y, a]
+                    }
+                }
+            }
+        });
+    if args.verify {
+        builder = builder.verify(&[rnd]);
+    }
+    let header = Header::new(op.name()).seed(seed).rounding(rnd).exp_ranges(
+        &if to_subnormal {
+            vec![
+                &RESULT_EXP_RANGE,
+                &NEAR_EMIN_NORMAL_EXP_RANGE,
+                &MODERATE_EXP_RANGE,
+            ]
+        } else {
+            vec![&NEAR_EMIN_SUBNORMAL_EXP_RANGE, &SCALE_EXP_RANGE]
+        },
+    );
+    let mut out = args.common.output.writer(&header);
+    builder
+        .jobs(args.common.jobs)
+        .write(&mut out, op.name(), |ops| {
+            Some(vec![match op {
+                Op::Add => ops[0].add_round(&ops[1], rnd),
+                Op::Sub => ops[0].sub_round(&ops[1], rnd),
+                Op::Mul => ops[0].mul_round(&ops[1], rnd),
+                Op::Div => ops[0].div_round(&ops[1], rnd),
+                _ => ops[0].fma_round(&ops[1], &ops[2], rnd),
+            }])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
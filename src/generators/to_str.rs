@@ -0,0 +1,90 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{io::Write, ops::RangeInclusive};
+
+use clap::Parser;
+
+use super::{
+    OutputArgs, EXP_UPPER_BOUND, NORMAL_EXP_LOWER_BOUND, SUBNORMAL_EXP_RANGE,
+};
+use crate::{
+    CorpusBuilder, DecodedFP, Header, Record, RecordWriter, TestItem, FP237,
+    PM1,
+};
+
+const FAST_LOWER_BOUND: i32 = 0;
+const FAST_LOWER_BOUND_MINUS_1: i32 = FAST_LOWER_BOUND - 1;
+const FAST_UPPER_BOUND: i32 = 511_i32;
+const FAST_UPPER_BOUND_PLUS_1: i32 = FAST_UPPER_BOUND + 1;
+
+// f256::MIN_POSITIVE <= |f| < 1
+const FRACT_EXP_RANGE: RangeInclusive<i32> =
+    NORMAL_EXP_LOWER_BOUND..=FAST_LOWER_BOUND_MINUS_1;
+// 1 <= |f| < 2²³⁶
+const SMALL_FLOAT_EXP_RANGE: RangeInclusive<i32> = FAST_LOWER_BOUND..=PM1;
+// 2²³⁶ <= |f| < 2⁵¹²
+const SMALL_INT_EXP_RANGE: RangeInclusive<i32> = PM1..=FAST_UPPER_BOUND;
+// 2⁵¹² <= |f| <= f256::MAX
+const LARGE_INT_EXP_RANGE: RangeInclusive<i32> =
+    FAST_UPPER_BOUND_PLUS_1..=EXP_UPPER_BOUND;
+
+fn print_test_item(out: &mut RecordWriter<impl Write>, f: FP237, lit: &str) {
+    let item = TestItem::new("to_string", vec![DecodedFP::from(&f)], vec![])
+        .with_meta(Record::new().str(lit));
+    out.write_item(&item).unwrap();
+}
+
+/// Generates test data for formatting.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Type of number: N = small float I = small int F = normal,
+    /// S = subnormal, X = large int
+    #[arg(short, long, default_value_t = 'N')]
+    type_of_num: char,
+
+    /// Number of test data to generate
+    #[arg(short, long, default_value_t = 10)]
+    n_test_data: u32,
+
+    /// Seed for the random number generator
+    #[arg(short, long)]
+    seed: Option<u64>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+pub fn run(args: Args) {
+    let exp_range = match args.type_of_num {
+        'N' => &SMALL_FLOAT_EXP_RANGE,
+        'I' => &SMALL_INT_EXP_RANGE,
+        'F' => &FRACT_EXP_RANGE,
+        'X' => &LARGE_INT_EXP_RANGE,
+        'S' => &SUBNORMAL_EXP_RANGE,
+        _ => panic!("Unkown type of number"),
+    };
+
+    let seed = args.output.seed(args.seed);
+    let header = Header::new("to_string").seed(seed).exp_ranges(&[exp_range]);
+    let mut out = args.output.meta_writer(&header);
+
+    let items = CorpusBuilder::new(1, seed)
+        .random(args.n_test_data, |rng| {
+            vec![FP237::random_from_exp_range_with_rng(rng, exp_range)]
+        })
+        .build();
+    for mut item in items {
+        let f = item.remove(0);
+        let s = format!("{f:.0}");
+        print_test_item(&mut out, f, &s);
+    }
+    out.finish().unwrap();
+}
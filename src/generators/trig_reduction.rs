@@ -0,0 +1,75 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::Parser;
+
+use super::CommonArgs;
+use crate::{CorpusBuilder, Header, Record, TestItem, EMAX, FP237, PM1};
+
+// 2³ <= |f| < 2²⁰
+const SMALL_EXP_RANGE: RangeInclusive<i32> = 3..=19;
+// 2²⁰ <= |f| < 2²³⁶
+const MEDIUM_EXP_RANGE: RangeInclusive<i32> = 20..=PM1 - 1;
+// 2²³⁶ <= |f| <= f256::MAX
+const LARGE_EXP_RANGE: RangeInclusive<i32> = PM1..=EMAX;
+
+/// Generates test data for the reduction modulo π/2.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Range of input value f: S = 2³..2²⁰, M = 2²⁰..2²³⁶, L = 2²³⁶..
+    #[arg(long, default_value_t = 'L')]
+    range: char,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    /// Add the boundary values of the exponent range
+    #[arg(short, long)]
+    boundaries: bool,
+}
+
+pub fn run(args: Args) {
+    let exp_range = match args.range {
+        'S' => &SMALL_EXP_RANGE,
+        'M' => &MEDIUM_EXP_RANGE,
+        'L' => &LARGE_EXP_RANGE,
+        _ => panic!("Unkown range"),
+    };
+    let seed = args.common.seed();
+    let header = Header::new("reduce_pi_2")
+        .seed(seed)
+        .exp_ranges(&[exp_range]);
+    let mut out = args.common.output.meta_writer(&header);
+
+    let mut builder = CorpusBuilder::new(1, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            vec![FP237::random_from_exp_range_with_rng(rng, exp_range)]
+        });
+    if args.boundaries {
+        builder = builder.boundaries(exp_range);
+    }
+    // x, reduced argument (hi, lo), sin(x), cos(x), quadrant
+    for item in builder.build() {
+        let x = &item[0];
+        let (k, hi, lo) = x.reduce_pi_2();
+        let expected = [hi, lo, x.sin(), x.cos()];
+        let item = TestItem::new(
+            "reduce_pi_2",
+            vec![x.into()],
+            expected.iter().map(Into::into).collect(),
+        )
+        .with_meta(Record::new().int(k));
+        out.write_item(&item).unwrap();
+    }
+    out.finish().unwrap();
+}
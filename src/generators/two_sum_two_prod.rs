@@ -0,0 +1,92 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::ops::RangeInclusive;
+
+use clap::{Parser, ValueEnum};
+use rand::prelude::*;
+
+use super::CommonArgs;
+use crate::{CorpusBuilder, Header, EMAX, FP237, P};
+
+// 2⁻¹³¹⁰⁷¹ <= |f| < 2¹³¹⁰⁷¹, so that neither the sum nor the product
+// nor the error term over- or underflows
+const EXP_RANGE: RangeInclusive<i32> = -EMAX / 2 + P as i32..=EMAX / 2 - 1;
+
+/// Returns the exponent gap between the operands: with equal probability
+/// a small gap, a gap around P (where the smaller operand starts to vanish
+/// in the sum) or an arbitrary gap up to 2P.
+fn exp_gap(rng: &mut StdRng) -> i32 {
+    let p = P as i32;
+    match rng.gen_range(0..3) {
+        0 => rng.gen_range(0..=3),
+        1 => rng.gen_range(p - 3..=p + 3),
+        _ => rng.gen_range(0..=2 * p),
+    }
+}
+
+/// Error-free transforms to generate test data for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Func {
+    #[value(name = "two_sum")]
+    TwoSum,
+    #[value(name = "two_prod")]
+    TwoProd,
+}
+
+impl Func {
+    fn name(self) -> &'static str {
+        match self {
+            Self::TwoSum => "two_sum",
+            Self::TwoProd => "two_prod",
+        }
+    }
+}
+
+/// Generates test data for two_sum and two_prod.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Error-free transform
+    #[arg(short, long, value_enum, default_value_t = Func::TwoSum)]
+    func: Func,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+pub fn run(args: Args) {
+    let func = match args.func {
+        Func::TwoSum => FP237::two_sum,
+        Func::TwoProd => FP237::two_prod,
+    };
+    let seed = args.common.seed();
+
+    let header = Header::new(args.func.name())
+        .seed(seed)
+        .exp_ranges(&[&EXP_RANGE]);
+
+    let mut out = args.common.output.writer(&header);
+    CorpusBuilder::new(2, seed)
+        .sign(args.common.sign)
+        .random(args.common.n_test_data, |rng| {
+            let x = FP237::random_from_exp_range_with_rng(rng, &EXP_RANGE);
+            let (_, e, _) = x.decode(false);
+            let t = e + P as i32 - 1 - exp_gap(rng);
+            let y = FP237::random_from_exp_range_with_rng(rng, &(t..=t));
+            vec![x, y]
+        })
+        .jobs(args.common.jobs)
+        .write(&mut out, args.func.name(), |ops| {
+            let (hi, lo) = func(&ops[0], &ops[1]);
+            Some(vec![hi, lo])
+        })
+        .unwrap();
+    out.finish().unwrap();
+}
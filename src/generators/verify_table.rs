@@ -0,0 +1,321 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{io::Read, path::PathBuf, process};
+
+use clap::{Parser, ValueEnum};
+use rug::{ops::Pow, Float, Integer};
+
+use super::{
+    calc_tables::{clenshaw, Func},
+    open_input,
+};
+use crate::{
+    reader::{parse_json, Json},
+    P,
+};
+
+/// Precision the polynomials and the functions are evaluated at.
+const VERIFY_PREC: u32 = 3 * P;
+
+/// Returns the maximum error of `approx` against `func` over [`lo`, `hi`]
+/// in ulps of `func` rounded to `prec` bits, sampled at `samples` + 1
+/// equidistant points and calculated with `wp` bits, together with the
+/// point it occurs at. Points where `func` is zero are skipped, as zero
+/// has no ulp.
+pub(crate) fn max_ulp_error<F>(
+    func: Func,
+    approx: F,
+    lo: &Float,
+    hi: &Float,
+    prec: u32,
+    samples: u32,
+    wp: u32,
+) -> (Float, Float)
+where
+    F: Fn(&Float) -> Float,
+{
+    let width = Float::with_val(wp, hi - lo);
+    let mut worst = (Float::with_val(64, 0), Float::with_val(wp, lo));
+    for i in 0..=samples {
+        let x = Float::with_val(wp, &width * i) / samples + lo;
+        let exact = func.eval(x.clone());
+        let Some(exp) = exact.get_exp() else {
+            continue;
+        };
+        let err = Float::with_val(wp, approx(&x) - &exact).abs();
+        let err = Float::with_val(64, err >> (exp - prec as i32));
+        if err > worst.0 {
+            worst = (err, x);
+        }
+    }
+    worst
+}
+
+/// Polynomial read from a table.
+#[derive(Clone, Debug)]
+enum Poly {
+    /// Σ cⱼ·Tⱼ(t) with x = (hi + lo) / 2 + t·(hi - lo) / 2
+    Chebyshev(Vec<Float>),
+    /// Σ cⱼ·x^kⱼ
+    Power(Vec<(u32, Float)>),
+}
+
+impl Poly {
+    fn eval(&self, x: &Float, lo: &Float, hi: &Float) -> Float {
+        let wp = x.prec();
+        match self {
+            Poly::Chebyshev(coeffs) => {
+                let sum = Float::with_val(wp, hi + lo);
+                let t = (Float::with_val(wp, x * 2_u32) - sum)
+                    / Float::with_val(wp, hi - lo);
+                clenshaw(coeffs, &t)
+            }
+            Poly::Power(terms) => {
+                let mut res = Float::new(wp);
+                for (k, c) in terms {
+                    res += Float::with_val(wp, x.pow(*k)) * c;
+                }
+                res
+            }
+        }
+    }
+}
+
+/// Segment [`lo`, `hi`] of a table, approximated by `poly`.
+#[derive(Clone, Debug)]
+struct Segment {
+    lo: Float,
+    hi: Float,
+    poly: Poly,
+}
+
+/// Returns the string `key` of `doc`.
+fn field<'a>(doc: &'a Json, key: &str) -> Result<&'a str, String> {
+    match doc.get(key) {
+        Some(Json::Str(s)) | Some(Json::Num(s)) => Ok(s),
+        _ => Err(format!("Missing field {key}")),
+    }
+}
+
+/// Returns `s` parsed as number with `VERIFY_PREC` bits.
+fn parse_float(s: &str) -> Result<Float, String> {
+    Float::parse(s)
+        .map(|f| Float::with_val(VERIFY_PREC, f))
+        .map_err(|e| format!("Invalid number {s}: {e}"))
+}
+
+/// Returns the value given by sign, exponent and significand of `prec`
+/// bits, as written by `TableWriter`.
+fn parse_value(value: &Json, prec: u32) -> Result<Float, String> {
+    let sign = field(value, "sign")?;
+    let exp: i32 =
+        field(value, "exp")?.parse().map_err(|e| format!("{e}"))?;
+    let signif = field(value, "signif")?;
+    let m = signif
+        .strip_prefix("0x")
+        .and_then(|hex| Integer::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| format!("Invalid significand {signif}"))?;
+    let f = Float::with_val(VERIFY_PREC, m) << (exp - prec as i32 + 1);
+    Ok(if sign == "1" { -f } else { f })
+}
+
+/// Returns the values of the array `item`.
+fn parse_array(item: &Json, prec: u32) -> Result<Vec<Float>, String> {
+    match item.get("values") {
+        Some(Json::Arr(values)) => {
+            values.iter().map(|v| parse_value(v, prec)).collect()
+        }
+        _ => Err(format!("{} is not an array", field(item, "name")?)),
+    }
+}
+
+/// Returns the function approximated by the table `doc`, written by
+/// `rug237 tables --target json`, the precision of its values and its
+/// segments.
+fn parse_table(doc: &Json) -> Result<(Func, u32, Vec<Segment>), String> {
+    let table = field(doc, "table")?;
+    let prec: u32 =
+        field(doc, "prec")?.parse().map_err(|e| format!("{e}"))?;
+    let Some(Json::Arr(items)) = doc.get("items") else {
+        return Err("Missing items".into());
+    };
+    let first = items.first().ok_or("No items")?;
+    let bounds = || -> Result<(Float, Float), String> {
+        Ok((
+            parse_float(field(doc, "lo")?)?,
+            parse_float(field(doc, "hi")?)?,
+        ))
+    };
+    let segments = match table {
+        "chebyshev" => {
+            let (lo, hi) = bounds()?;
+            let poly = Poly::Chebyshev(parse_array(first, prec)?);
+            vec![Segment { lo, hi, poly }]
+        }
+        "taylor" => {
+            let (lo, hi) = bounds()?;
+            let powers = field(doc, "powers")?
+                .split(',')
+                .map(|k| k.parse::<u32>().map_err(|e| format!("{e}")));
+            let terms = powers
+                .zip(parse_array(first, prec)?)
+                .map(|(k, c)| k.map(|k| (k, c)))
+                .collect::<Result<Vec<_>, _>>()?;
+            vec![Segment {
+                lo,
+                hi,
+                poly: Poly::Power(terms),
+            }]
+        }
+        "erf" => {
+            let breaks = field(doc, "breaks")?
+                .split(',')
+                .map(parse_float)
+                .collect::<Result<Vec<_>, _>>()?;
+            // the first array holds the breaks
+            if items.len() != breaks.len() {
+                return Err(format!(
+                    "Expected {} segments, found {}",
+                    breaks.len() - 1,
+                    items.len() - 1
+                ));
+            }
+            breaks
+                .windows(2)
+                .zip(&items[1..])
+                .map(|(b, item)| {
+                    Ok(Segment {
+                        lo: b[0].clone(),
+                        hi: b[1].clone(),
+                        poly: Poly::Chebyshev(parse_array(item, prec)?),
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?
+        }
+        _ => return Err(format!("Table {table} can't be verified")),
+    };
+    let func = Func::from_str(field(doc, "func")?, true)?;
+    Ok((func, prec, segments))
+}
+
+/// Re-checks a table of polynomial approximations written by `rug237
+/// tables --target json` (chebyshev, taylor or erf) against the function
+/// approximated, both evaluated at 3P bits, and prints the maximum error
+/// observed per segment in ulps of the function rounded to the precision
+/// of the table. Exits with status 1 if the error exceeds --max-ulp.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Number of equidistant samples per segment, less one
+    #[arg(short, long, default_value_t = 4096)]
+    samples: u32,
+
+    /// Maximum error in ulps accepted
+    #[arg(short, long)]
+    max_ulp: Option<f64>,
+
+    /// File holding the table (default: stdin), decompressed if it ends
+    /// with .gz
+    file: Option<PathBuf>,
+}
+
+pub fn run(args: Args) {
+    let mut json = String::new();
+    open_input(args.file.as_deref())
+        .read_to_string(&mut json)
+        .unwrap_or_else(|e| panic!("Can't read table: {e}"));
+    let doc = parse_json(&json).unwrap_or_else(|e| panic!("{e}"));
+    let (func, prec, segments) =
+        parse_table(&doc).unwrap_or_else(|e| panic!("{e}"));
+    let mut worst = Float::with_val(64, 0);
+    for Segment { lo, hi, poly } in &segments {
+        let (err, x) = max_ulp_error(
+            func,
+            |x| poly.eval(x, lo, hi),
+            lo,
+            hi,
+            prec,
+            args.samples,
+            VERIFY_PREC,
+        );
+        println!(
+            "[{}, {}]: max error {} ulp at x = {}",
+            lo.to_string_radix(10, Some(8)),
+            hi.to_string_radix(10, Some(8)),
+            err.to_string_radix(10, Some(6)),
+            x.to_string_radix(10, Some(20))
+        );
+        worst.max_mut(&err);
+    }
+    println!(
+        "# {}: {} segments, {} samples each, max error {} ulp",
+        func.name(),
+        segments.len(),
+        args.samples + 1,
+        worst.to_string_radix(10, Some(6))
+    );
+    if args.max_ulp.is_some_and(|max| worst > max) {
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod verify_table_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table() {
+        let json = r#"{"type": "FP64", "prec": 64, "table": "taylor",
+            "func": "exp", "powers": "0,1", "lo": "-0.5", "hi": "0.5",
+            "notes": [], "items": [{"name": "T", "values": [
+                {"sign": 0, "exp": 0, "signif": "0x8000000000000000"},
+                {"sign": 1, "exp": -1, "signif": "0x8000000000000000"}
+            ]}]}"#;
+        let (func, prec, segments) =
+            parse_table(&parse_json(json).unwrap()).unwrap();
+        assert_eq!((func, prec, segments.len()), (Func::Exp, 64, 1));
+        let seg = &segments[0];
+        assert_eq!(seg.lo, -0.5);
+        let x = Float::with_val(VERIFY_PREC, 0.25);
+        // 1 - x/2
+        assert_eq!(seg.poly.eval(&x, &seg.lo, &seg.hi), 0.875);
+        let json = json.replace("taylor", "atans");
+        assert!(parse_table(&parse_json(&json).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_max_ulp_error() {
+        let wp = VERIFY_PREC;
+        let (lo, hi) = (Float::with_val(wp, 1), Float::with_val(wp, 2));
+        // the rounded function has an error of at most ½ ulp
+        let (err, _) = max_ulp_error(
+            Func::Ln,
+            |x| Float::with_val(53, x.ln_ref()),
+            &lo,
+            &hi,
+            53,
+            64,
+            wp,
+        );
+        assert!(err <= 0.5 && err > 0.25, "{err}");
+        // (1 + x) / 2 for √x is exact at x = 1, worst at x = 2
+        let (err, x) = max_ulp_error(
+            Func::Sqrt,
+            |x| Float::with_val(wp, x + 1_u32) / 2,
+            &lo,
+            &hi,
+            53,
+            64,
+            wp,
+        );
+        assert_eq!(x, 2);
+        assert!(err > 1e14, "{err}");
+    }
+}
@@ -0,0 +1,291 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    env,
+    fmt::Display,
+    ops::RangeInclusive,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    reader::{parse_json, Json},
+    record::json_quoted,
+    Field, Format,
+};
+
+/// Describes how a corpus was produced: crate version, operation, rounding
+/// mode, exponent ranges, seed, creation time and command line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header(Vec<(String, Field)>);
+
+impl Header {
+    /// Returns a header for operation `op` with rounding mode ne (to
+    /// nearest, ties to even), stamped with the current time and command
+    /// line.
+    pub fn new(op: &str) -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let cmd_line: Vec<String> = env::args().collect();
+        Self(vec![
+            (
+                "version".to_string(),
+                Field::Token(env!("CARGO_PKG_VERSION").to_string()),
+            ),
+            ("op".to_string(), Field::Token(op.to_string())),
+            ("rounding".to_string(), Field::Token("ne".to_string())),
+            ("created".to_string(), Field::Token(utc_timestamp(secs))),
+            ("args".to_string(), Field::Str(cmd_line.join(" "))),
+        ])
+    }
+
+    /// Sets the value of `key` to `val`, keeping the position of an
+    /// existing entry.
+    fn set(mut self, key: &str, val: Field) -> Self {
+        match self.0.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = val,
+            None => {
+                // keep created and args last
+                let pos = self.0.len().saturating_sub(2);
+                self.0.insert(pos, (key.to_string(), val));
+            }
+        }
+        self
+    }
+
+    /// Sets the seed of the random number generator.
+    pub fn seed(self, seed: u64) -> Self {
+        self.set("seed", Field::Int(seed.to_string()))
+    }
+
+    /// Sets the rounding mode(s) the results are given for.
+    pub fn rounding(self, rnd: impl Display) -> Self {
+        self.set("rounding", Field::Token(rnd.to_string()))
+    }
+
+    /// Sets the exponent ranges the operands are drawn from.
+    pub fn exp_ranges(self, ranges: &[&RangeInclusive<i32>]) -> Self {
+        let ranges: Vec<String> =
+            ranges.iter().map(|r| format!("{r:?}")).collect();
+        self.set("exp_ranges", Field::Token(ranges.join(",")))
+    }
+
+    /// Adds a generator specific parameter.
+    pub fn param(self, key: &str, val: impl Display) -> Self {
+        self.set(key, Field::Token(val.to_string()))
+    }
+
+    /// Returns the value of `key`, if set.
+    pub fn get(&self, key: &str) -> Option<&Field> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Returns the name of the operation.
+    pub fn op(&self) -> &str {
+        match self.get("op") {
+            Some(Field::Token(op)) => op,
+            _ => "",
+        }
+    }
+
+    /// Returns the seed of the random number generator, if set.
+    pub fn get_seed(&self) -> Option<u64> {
+        match self.get("seed") {
+            Some(Field::Int(seed)) => seed.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn entries(&self) -> &[(String, Field)] {
+        &self.0
+    }
+
+    /// Returns `self` formatted as one line (without line break): a
+    /// comment line with `key=value` pairs in TSV, CSV, Rust and binary
+    /// format, a JSON object with key `header` in JSON Lines format.
+    pub fn to_line(&self, format: Format) -> String {
+        match format {
+            Format::Tsv | Format::Csv | Format::Rust | Format::Bin => {
+                let entries: Vec<String> = self
+                    .0
+                    .iter()
+                    .map(|(k, v)| match v {
+                        Field::Int(s) | Field::Token(s) => format!("{k}={s}"),
+                        Field::Str(s) => format!("{k}={}", json_quoted(s)),
+                    })
+                    .collect();
+                let prefix = if format == Format::Rust { "//" } else { "#" };
+                format!("{prefix} {}", entries.join(" "))
+            }
+            Format::Jsonl => {
+                let entries: Vec<String> = self
+                    .0
+                    .iter()
+                    .map(|(k, v)| match v {
+                        Field::Int(s) => format!("{}:{s}", json_quoted(k)),
+                        Field::Token(s) | Field::Str(s) => {
+                            format!("{}:{}", json_quoted(k), json_quoted(s))
+                        }
+                    })
+                    .collect();
+                format!("{{\"header\":{{{}}}}}", entries.join(","))
+            }
+        }
+    }
+}
+
+impl Header {
+    /// Parses a header line in any of the formats written by `to_line`,
+    /// returns `None` if `line` is not a header line. Bare numeric values
+    /// are read as integers.
+    pub fn from_line(line: &str) -> Option<Self> {
+        let entries = if let Some(rest) =
+            line.strip_prefix("# ").or_else(|| line.strip_prefix("// "))
+        {
+            let mut entries = Vec::new();
+            let mut rest = rest.trim_start();
+            while !rest.is_empty() {
+                let (key, tail) = rest.split_once('=')?;
+                let (val, tail) = if let Some(quoted) = tail.strip_prefix('"')
+                {
+                    // find the closing quote of the JSON string
+                    let mut escaped = false;
+                    let end = quoted.find(|c| {
+                        let end = c == '"' && !escaped;
+                        escaped = c == '\\' && !escaped;
+                        end
+                    })? + 2;
+                    let Json::Str(s) = parse_json(&tail[..end]).ok()? else {
+                        return None;
+                    };
+                    (Field::Str(s), &tail[end..])
+                } else {
+                    let (val, tail) =
+                        tail.split_once(' ').unwrap_or((tail, ""));
+                    let is_int = val.parse::<i128>().is_ok()
+                        || val.parse::<u128>().is_ok();
+                    if is_int {
+                        (Field::Int(val.to_string()), tail)
+                    } else {
+                        (Field::Token(val.to_string()), tail)
+                    }
+                };
+                entries.push((key.to_string(), val));
+                rest = tail.trim_start();
+            }
+            entries
+        } else if line.starts_with("{\"header\"") {
+            let Json::Obj(entries) =
+                parse_json(line).ok()?.get("header")?.clone()
+            else {
+                return None;
+            };
+            entries
+                .into_iter()
+                .map(|(k, v)| match v {
+                    Json::Num(s) => Some((k, Field::Int(s))),
+                    Json::Str(s) if k == "args" => Some((k, Field::Str(s))),
+                    Json::Str(s) => Some((k, Field::Token(s))),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?
+        } else {
+            return None;
+        };
+        let header = Self(entries);
+        header.get("op").is_some().then_some(header)
+    }
+}
+
+/// Returns `secs` since the Unix epoch as ISO 8601 UTC timestamp.
+fn utc_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let secs = secs % 86400;
+    // civil from days, see H. Hinnant, chrono-Compatible Low-Level Date
+    // Algorithms
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + (m <= 2) as i64;
+    format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod header_tests {
+    use super::*;
+
+    #[test]
+    fn test_utc_timestamp() {
+        assert_eq!(utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(utc_timestamp(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(utc_timestamp(1791459296), "2026-10-08T11:34:56Z");
+    }
+
+    #[test]
+    fn test_to_line() {
+        let hdr = Header::new("sqrt")
+            .seed(42)
+            .rounding("all")
+            .exp_ranges(&[&(-3..=5), &(7..=7)]);
+        let keys: Vec<&str> =
+            hdr.entries().iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "version",
+                "op",
+                "rounding",
+                "seed",
+                "exp_ranges",
+                "created",
+                "args"
+            ]
+        );
+        let version = env!("CARGO_PKG_VERSION");
+        let tsv = hdr.to_line(Format::Tsv);
+        assert!(tsv.starts_with(&format!(
+            "# version={version} op=sqrt rounding=all seed=42 \
+             exp_ranges=-3..=5,7..=7 created="
+        )));
+        let json = hdr.to_line(Format::Jsonl);
+        assert!(json.starts_with(&format!(
+            "{{\"header\":{{\"version\":\"{version}\",\"op\":\"sqrt\",\"\
+             rounding\":\"all\",\"seed\":42,"
+        )));
+        assert!(json.ends_with("}}"));
+        assert_eq!(hdr.get_seed(), Some(42));
+    }
+
+    #[test]
+    fn test_from_line() {
+        let hdr = Header::new("sqrt")
+            .seed(42)
+            .param("note", "a=b")
+            .exp_ranges(&[&(-3..=5)]);
+        for format in [Format::Tsv, Format::Rust, Format::Jsonl] {
+            assert_eq!(
+                Header::from_line(&hdr.to_line(format)),
+                Some(hdr.clone())
+            );
+        }
+        assert_eq!(Header::from_line("# just a comment"), None);
+        assert_eq!(Header::from_line("0\t0\t0\t1"), None);
+    }
+}
@@ -0,0 +1,375 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Intervals of f256 values with outward-rounded arithmetic, giving
+//! guaranteed enclosures of exact results: the lower bound of a result is
+//! rounded toward -∞, the upper bound toward +∞.
+//!
+//! The operands have to lie within the domain of a function, otherwise the
+//! bounds of its result are NaN. Products of zero and infinity are taken
+//! as zero.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use rug::{float::Constant, Float};
+
+use crate::{RoundingMode, FP237, P};
+
+const RD: RoundingMode = RoundingMode::Down;
+const RU: RoundingMode = RoundingMode::Up;
+
+/// Closed interval [lo, hi] of f256 values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Interval237 {
+    pub lo: FP237,
+    pub hi: FP237,
+}
+
+/// Returns the smaller of `x` and `y`, ignoring NaN.
+fn min(x: FP237, y: FP237) -> FP237 {
+    if y.f < x.f || x.f.is_nan() {
+        y
+    } else {
+        x
+    }
+}
+
+/// Returns the larger of `x` and `y`, ignoring NaN.
+fn max(x: FP237, y: FP237) -> FP237 {
+    if y.f > x.f || x.f.is_nan() {
+        y
+    } else {
+        x
+    }
+}
+
+impl Interval237 {
+    /// Returns [lo, hi].
+    ///
+    /// # Panics
+    ///
+    /// If lo > hi or a bound is NaN.
+    pub fn new(lo: FP237, hi: FP237) -> Self {
+        assert!(lo.f <= hi.f, "Invalid interval [{lo}, {hi}]");
+        Self { lo, hi }
+    }
+
+    /// Returns the interval holding `x` only.
+    pub fn point(x: &FP237) -> Self {
+        Self {
+            lo: x.clone(),
+            hi: x.clone(),
+        }
+    }
+
+    /// Returns the smallest interval enclosing `x`.
+    pub fn from_float(x: &Float) -> Self {
+        Self {
+            lo: FP237::with_val_round(x, RD),
+            hi: FP237::with_val_round(x, RU),
+        }
+    }
+
+    /// Returns [-∞, +∞].
+    pub fn entire() -> Self {
+        Self {
+            lo: -FP237::INFINITY(),
+            hi: FP237::INFINITY(),
+        }
+    }
+
+    pub fn contains(&self, x: &Float) -> bool {
+        self.lo.f <= *x && *x <= self.hi.f
+    }
+
+    /// Returns `true` if the interval holds a single value.
+    pub fn is_point(&self) -> bool {
+        self.lo.f == self.hi.f
+    }
+
+    /// Returns hi - lo, rounded up.
+    pub fn width(&self) -> FP237 {
+        self.hi.sub_round(&self.lo, RU)
+    }
+
+    /// Returns the image of `self` under the increasing function `f`,
+    /// which gets the rounding mode to apply.
+    fn increasing<F>(&self, f: F) -> Self
+    where
+        F: Fn(&FP237, RoundingMode) -> FP237,
+    {
+        Self {
+            lo: f(&self.lo, RD),
+            hi: f(&self.hi, RU),
+        }
+    }
+
+    /// Returns `true` if the interval holds a value (q + 4k)·π/2 for some
+    /// integer k, i.e. a point of phase q·π/2 of the period 2π. Like
+    /// `FP237::reduce_pi_2`, π is taken with enough bits to resolve the
+    /// distance of the bounds from the closest multiple of π/2.
+    fn hits_phase(&self, q: u32) -> bool {
+        if !self.lo.f.is_finite() || !self.hi.f.is_finite() {
+            return true;
+        }
+        if Float::with_val(64, &self.hi.f - &self.lo.f) >= 7 {
+            return true;
+        }
+        let e = [&self.lo.f, &self.hi.f]
+            .iter()
+            .filter_map(|f| f.get_exp())
+            .max()
+            .unwrap_or(0)
+            .max(0) as u32;
+        let prec = e + 3 * P + 64;
+        let pi_2 = Float::with_val(prec, Constant::Pi) >> 1;
+        // smallest k with (q + 4k)·π/2 >= lo
+        let t: Float = (Float::with_val(prec, &self.lo.f / &pi_2) - q) / 4;
+        let k = t.ceil();
+        let x = (k * 4_u32 + q) * pi_2;
+        x <= self.hi.f
+    }
+
+    pub fn sqrt(&self) -> Self {
+        self.increasing(|x, rnd| x.sqrt_round(rnd))
+    }
+
+    pub fn cbrt(&self) -> Self {
+        self.increasing(|x, rnd| FP237::with_val_round(x.f.cbrt_ref(), rnd))
+    }
+
+    pub fn ln(&self) -> Self {
+        self.increasing(|x, rnd| FP237::with_val_round(x.f.ln_ref(), rnd))
+    }
+
+    pub fn log2(&self) -> Self {
+        self.increasing(|x, rnd| FP237::with_val_round(x.f.log2_ref(), rnd))
+    }
+
+    pub fn log10(&self) -> Self {
+        self.increasing(|x, rnd| FP237::with_val_round(x.f.log10_ref(), rnd))
+    }
+
+    pub fn ln_1p(&self) -> Self {
+        self.increasing(|x, rnd| FP237::with_val_round(x.f.ln_1p_ref(), rnd))
+    }
+
+    pub fn exp(&self) -> Self {
+        self.increasing(|x, rnd| FP237::with_val_round(x.f.exp_ref(), rnd))
+    }
+
+    pub fn exp_m1(&self) -> Self {
+        self.increasing(|x, rnd| FP237::with_val_round(x.f.exp_m1_ref(), rnd))
+    }
+
+    pub fn sin(&self) -> Self {
+        let lo = if self.hits_phase(3) {
+            -FP237::ONE()
+        } else {
+            min(
+                FP237::with_val_round(self.lo.f.sin_ref(), RD),
+                FP237::with_val_round(self.hi.f.sin_ref(), RD),
+            )
+        };
+        let hi = if self.hits_phase(1) {
+            FP237::ONE()
+        } else {
+            max(
+                FP237::with_val_round(self.lo.f.sin_ref(), RU),
+                FP237::with_val_round(self.hi.f.sin_ref(), RU),
+            )
+        };
+        Self { lo, hi }
+    }
+
+    pub fn cos(&self) -> Self {
+        let lo = if self.hits_phase(2) {
+            -FP237::ONE()
+        } else {
+            min(
+                FP237::with_val_round(self.lo.f.cos_ref(), RD),
+                FP237::with_val_round(self.hi.f.cos_ref(), RD),
+            )
+        };
+        let hi = if self.hits_phase(0) {
+            FP237::ONE()
+        } else {
+            max(
+                FP237::with_val_round(self.lo.f.cos_ref(), RU),
+                FP237::with_val_round(self.hi.f.cos_ref(), RU),
+            )
+        };
+        Self { lo, hi }
+    }
+
+    /// Returns [-∞, +∞] if the interval holds a pole of tan.
+    pub fn tan(&self) -> Self {
+        if self.hits_phase(1) || self.hits_phase(3) {
+            Self::entire()
+        } else {
+            self.increasing(|x, rnd| {
+                FP237::with_val_round(x.f.tan_ref(), rnd)
+            })
+        }
+    }
+
+    /// Returns the hull of `op` applied to all pairs of bounds of `self`
+    /// and `rhs`, `op` being monotone in each operand.
+    fn hull<F>(&self, rhs: &Self, op: F) -> Self
+    where
+        F: Fn(&FP237, &FP237, RoundingMode) -> FP237,
+    {
+        let pairs = [
+            (&self.lo, &rhs.lo),
+            (&self.lo, &rhs.hi),
+            (&self.hi, &rhs.lo),
+            (&self.hi, &rhs.hi),
+        ];
+        let mut res = Self {
+            lo: FP237::NAN(),
+            hi: FP237::NAN(),
+        };
+        for (x, y) in pairs {
+            res.lo = min(res.lo, op(x, y, RD));
+            res.hi = max(res.hi, op(x, y, RU));
+        }
+        res
+    }
+}
+
+impl From<FP237> for Interval237 {
+    fn from(x: FP237) -> Self {
+        Self::point(&x)
+    }
+}
+
+impl Neg for Interval237 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl Add for &Interval237 {
+    type Output = Interval237;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Interval237 {
+            lo: self.lo.add_round(&rhs.lo, RD),
+            hi: self.hi.add_round(&rhs.hi, RU),
+        }
+    }
+}
+
+impl Sub for &Interval237 {
+    type Output = Interval237;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Interval237 {
+            lo: self.lo.sub_round(&rhs.hi, RD),
+            hi: self.hi.sub_round(&rhs.lo, RU),
+        }
+    }
+}
+
+impl Mul for &Interval237 {
+    type Output = Interval237;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.hull(rhs, |x, y, rnd| {
+            if x.f.is_zero() || y.f.is_zero() {
+                FP237::ZERO()
+            } else {
+                x.mul_round(y, rnd)
+            }
+        })
+    }
+}
+
+impl Div for &Interval237 {
+    type Output = Interval237;
+
+    /// Returns [-∞, +∞] if `rhs` holds zero.
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.contains(&Float::new(P)) {
+            Interval237::entire()
+        } else {
+            self.hull(rhs, |x, y, rnd| x.div_round(y, rnd))
+        }
+    }
+}
+
+#[cfg(test)]
+mod interval_tests {
+    use std::str::FromStr;
+
+    use rand::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_arith() {
+        let third = Float::with_val(4 * P, 3).recip();
+        let x = Interval237::from_float(&third);
+        assert!(x.contains(&third));
+        assert_eq!(x.hi.f, x.lo.next_up().f);
+        let one = Interval237::from(FP237::ONE());
+        let three = Interval237::from(FP237::from(3));
+        assert_eq!(&one / &three, x);
+        assert!((&x * &three).contains(&Float::with_val(P, 1)));
+        assert_eq!(&(&one - &three) + &three, one);
+        let y = Interval237::new(-FP237::from(2), FP237::from(3));
+        let z = &y * &Interval237::new(-FP237::from(5), FP237::from(4));
+        assert_eq!(z, Interval237::new(-FP237::from(15), FP237::from(12)));
+        assert_eq!(&one / &y, Interval237::entire());
+        assert_eq!(
+            -y.clone(),
+            Interval237::new(-FP237::from(3), FP237::from(2))
+        );
+    }
+
+    #[test]
+    fn test_functions() {
+        let mut rng = StdRng::seed_from_u64(201);
+        let wp = 4 * P;
+        for _ in 0..100 {
+            let x =
+                FP237::random_from_exp_range_with_rng(&mut rng, &(-8..=8));
+            let ix = Interval237::point(&x);
+            let y = x.f.clone().abs();
+            let iy = Interval237::point(&FP237::new(y.clone()));
+            assert!(ix.exp().contains(&Float::with_val(wp, x.f.exp_ref())));
+            assert!(iy.ln().contains(&Float::with_val(wp, y.ln_ref())));
+            assert!(iy.sqrt().contains(&Float::with_val(wp, y.sqrt_ref())));
+            let sin = ix.sin();
+            assert!(sin.contains(&Float::with_val(wp, x.f.sin_ref())));
+            assert_eq!(sin.hi.f, sin.lo.next_up().f);
+            assert!(ix.cos().contains(&Float::with_val(wp, x.f.cos_ref())));
+            assert!(ix.tan().contains(&Float::with_val(wp, x.f.tan_ref())));
+        }
+        // [1, 2] holds π/2
+        let x = Interval237::new(FP237::ONE(), FP237::from(2));
+        assert_eq!(x.sin().hi, FP237::ONE());
+        assert!(x.cos().lo.f < 0 && x.cos().hi.f > 0.5);
+        assert_eq!(x.tan(), Interval237::entire());
+        // [4, 5] holds 3π/2
+        let x = Interval237::new(FP237::from(4), FP237::from(5));
+        assert_eq!(x.sin().lo, -FP237::from(1));
+        let x = Interval237::new(-FP237::from(1), FP237::from(7));
+        assert_eq!(x.cos(), Interval237::new(-FP237::from(1), FP237::ONE()));
+        // huge argument
+        let x = FP237::from_str("1e1000").unwrap();
+        let exact = Float::with_val(2000 + 4 * P, &x.f).sin();
+        assert!(Interval237::point(&x).sin().contains(&exact));
+    }
+}
@@ -8,17 +8,22 @@
 // $Revision$
 
 use std::{
+    cell::RefCell,
     cmp::Ordering,
     fmt::{Display, Formatter, LowerExp},
+    iter::{Product, Sum},
     ops::{Add, Div, Mul, Neg, RangeInclusive, Rem, Sub},
     str::FromStr,
 };
 
 use rand::prelude::*;
 use rug::{
-    float::{Constant, ParseFloatError, Round},
-    ops::Pow,
-    Assign, Float, Integer,
+    float::{Constant, ParseFloatError, Round, Special},
+    ops::{
+        AddAssignRound, AssignRound, DivAssignRound, MulAssignRound,
+        NegAssign, Pow, RemAssignRound, SubAssignRound,
+    },
+    Assign, Float, Integer, Rational,
 };
 
 pub const P: u32 = 237;
@@ -27,13 +32,191 @@ pub const EMAX: i32 = 262143;
 pub const EMIN: i32 = 1 - EMAX;
 pub const MIN_EXP_SUBNORMAL: i32 = EMIN - PM1;
 
+const HI_HIDDEN_BIT: u128 = 1_u128 << 108;
+pub const HI_FRACTION_MASK: u128 = HI_HIDDEN_BIT - 1;
+pub const HI_EXP_MASK: u128 = (EMAX as u128 * 2 + 1) << 108;
+pub const HI_SIGN_BIT: u128 = 1_u128 << 127;
+pub const HI_QUIET_NAN_BIT: u128 = HI_HIDDEN_BIT >> 1;
+
+pub mod binary;
+mod checkpoint;
+mod complex;
+mod cont_frac;
+mod corpus;
+mod decimal;
+mod difftest;
+mod double_word;
+mod fp_dyn;
+pub mod generators;
+mod header;
+mod interval;
+mod linalg;
+mod output;
+mod poly;
+mod progress;
+mod q_format;
+mod reader;
+mod record;
+pub mod tables;
+mod u256;
+mod verify;
+
+pub use binary::{CorpusReader, CorpusWriter};
+pub use checkpoint::Checkpoint;
+pub use complex::C237;
+pub use cont_frac::ContinuedFraction;
+pub use corpus::{boundary_values, special_values, CorpusBuilder};
+pub use decimal::DecimalExact;
+pub use difftest::{DiffReport, DiffTest, ErrStats, Failure};
+pub use double_word::DW237;
+pub use fp_dyn::FPDyn;
+pub use header::Header;
+pub use interval::Interval237;
+pub use linalg::{Matrix237, Vector237};
+pub use output::Output;
+pub use poly::Poly237;
+pub use progress::Progress;
+pub use q_format::Qmn;
+pub use reader::RecordReader;
+pub use record::{
+    DecodedFP, Field, Format, Literals, Record, RecordWriter, TestItem,
+};
+pub use u256::U256;
+
+/// The rounding modes defined by IEEE 754.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round to nearest, ties to even
+    NearestEven,
+    /// Round toward zero
+    TowardZero,
+    /// Round toward +∞
+    Up,
+    /// Round toward -∞
+    Down,
+    /// Round to nearest, ties away from zero
+    NearestAway,
+}
+
+pub const ROUNDING_MODES: [RoundingMode; 5] = [
+    RoundingMode::NearestEven,
+    RoundingMode::TowardZero,
+    RoundingMode::Up,
+    RoundingMode::Down,
+    RoundingMode::NearestAway,
+];
+
+impl FromStr for RoundingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ne" => Ok(Self::NearestEven),
+            "tz" => Ok(Self::TowardZero),
+            "up" => Ok(Self::Up),
+            "dn" => Ok(Self::Down),
+            "na" => Ok(Self::NearestAway),
+            _ => Err(format!("Unknown rounding mode: {s}")),
+        }
+    }
+}
+
+impl Display for RoundingMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::NearestEven => "ne",
+            Self::TowardZero => "tz",
+            Self::Up => "up",
+            Self::Down => "dn",
+            Self::NearestAway => "na",
+        })
+    }
+}
+
+/// Signs of randomly drawn values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Sign {
+    /// Positive values only
+    Pos,
+    /// Negative values only
+    Neg,
+    /// Positive and negative values
+    #[default]
+    Both,
+}
+
+impl Sign {
+    /// Returns `f` with the selected sign, unchanged for `Both`.
+    pub fn apply<const P: u32, const EMAX: i32>(
+        self,
+        f: FP<P, EMAX>,
+    ) -> FP<P, EMAX> {
+        match self {
+            Self::Pos if f.f.is_sign_negative() => -f,
+            Self::Neg if f.f.is_sign_positive() => -f,
+            _ => f,
+        }
+    }
+}
+
+thread_local! {
+    /// Scratch values of the rounding to nearest, ties away from zero, see
+    /// `FP::assign_round`.
+    static NA_SCRATCH: RefCell<[Float; 4]> =
+        RefCell::new([(); 4].map(|_| Float::new(P)));
+}
+
+/// Binary floating point value with `P` bits precision (at most 256) and
+/// exponents in 1 - EMAX..=EMAX, including subnormal values, infinities
+/// and NaN, i.e. a value of an IEEE 754 binary interchange format.
 #[derive(Clone, Debug, PartialEq)]
-pub struct FP237 {
+pub struct FP<const P: u32, const EMAX: i32> {
     pub f: Float,
     pub(crate) o: Ordering,
 }
 
-impl FP237 {
+/// f256
+pub type FP237 = FP<P, EMAX>;
+/// f256's internal type with 255 bits precision and f256's exponent range
+pub type FP255 = FP<255, EMAX>;
+/// IEEE 754 binary128
+pub type FP113 = FP<113, 16383>;
+
+impl<const P: u32, const EMAX: i32> FP<P, EMAX> {
+    pub const PM1: i32 = P as i32 - 1;
+    pub const EMIN: i32 = 1 - EMAX;
+    pub const MIN_EXP_SUBNORMAL: i32 = Self::EMIN - Self::PM1;
+    /// ⌈P·log₁₀ 2⌉
+    const DEC_DIGITS: u32 = (P as u64 * 30103).div_ceil(100000) as u32;
+    /// Fraction bits of the significand, as (hi, lo) like in `decode`
+    const FRACTION_MASK: (u128, u128) = Self::split_signif(Self::PM1 as u32);
+    /// Significand of the quiet NaN, as (hi, lo) like in `decode`
+    const QUIET_NAN_SIGNIF: (u128, u128) = if P - 2 >= 128 {
+        (1 << (P - 2 - 128), 0)
+    } else {
+        (0, 1 << (P - 2))
+    };
+
+    /// Returns 2ⁿ - 1 as (hi, lo) with 2ⁿ - 1 = hi * 2¹²⁸ + lo.
+    const fn split_signif(n: u32) -> (u128, u128) {
+        assert!(P >= 2 && P <= 256, "Precision must be in 2..=256");
+        if n > 128 {
+            ((1 << (n - 128)) - 1, u128::MAX)
+        } else if n == 128 {
+            (0, u128::MAX)
+        } else {
+            (0, (1 << n) - 1)
+        }
+    }
+
+    /// Rounds `f` to a multiple of MIN_GT_ZERO if it's below MIN_POSITIVE,
+    /// see `Float::subnormalize_round`.
+    fn subnormalize(f: &mut Float, o: Ordering, rnd: Round) -> Ordering {
+        f.subnormalize_round(Self::EMIN + 1, o, rnd)
+    }
+}
+
+impl<const P: u32, const EMAX: i32> FP<P, EMAX> {
     #[allow(non_snake_case)]
     pub fn Log2() -> Self {
         Self {
@@ -66,6 +249,84 @@ impl FP237 {
         }
     }
 
+    /// 2/π split into hi + lo, see `split_hi_lo`.
+    #[allow(non_snake_case)]
+    pub fn FRAC_2_PI_SPLIT2() -> [Self; 2] {
+        let c = Float::with_val(4 * P, Constant::Pi).recip() * 2;
+        Self::split_hi_lo(&c, 2).try_into().unwrap()
+    }
+
+    /// π/2 split into hi + lo, see `split_hi_lo`.
+    #[allow(non_snake_case)]
+    pub fn FRAC_PI_2_SPLIT2() -> [Self; 2] {
+        let c = Float::with_val(4 * P, Constant::Pi) >> 1;
+        Self::split_hi_lo(&c, 2).try_into().unwrap()
+    }
+
+    /// π/2 split into hi + mid + lo, see `split_hi_lo`. The first two terms
+    /// are those of `FRAC_PI_2_SPLIT2`.
+    #[allow(non_snake_case)]
+    pub fn FRAC_PI_2_SPLIT3() -> [Self; 3] {
+        let c = Float::with_val(4 * P, Constant::Pi) >> 1;
+        Self::split_hi_lo(&c, 3).try_into().unwrap()
+    }
+
+    /// ln 2 split into hi + lo, see `split_hi_lo`.
+    #[allow(non_snake_case)]
+    pub fn LN_2_SPLIT2() -> [Self; 2] {
+        let c = Float::with_val(4 * P, Constant::Log2);
+        Self::split_hi_lo(&c, 2).try_into().unwrap()
+    }
+
+    /// ln 2 split into hi + mid + lo, see `split_hi_lo`. The first two
+    /// terms are those of `LN_2_SPLIT2`.
+    #[allow(non_snake_case)]
+    pub fn LN_2_SPLIT3() -> [Self; 3] {
+        let c = Float::with_val(4 * P, Constant::Log2);
+        Self::split_hi_lo(&c, 3).try_into().unwrap()
+    }
+
+    #[allow(non_snake_case)]
+    pub fn ZERO() -> Self {
+        Self::from(0)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn ONE() -> Self {
+        Self::from(1)
+    }
+
+    #[allow(non_snake_case)]
+    pub fn INFINITY() -> Self {
+        Self::new(Float::with_val(P, Special::Infinity))
+    }
+
+    #[allow(non_snake_case)]
+    pub fn NAN() -> Self {
+        Self::new(Float::with_val(P, Special::Nan))
+    }
+
+    /// Largest finite value: (2ᴾ - 1) * 2^(EMAX - P + 1), for f256
+    /// (2²³⁷ - 1) * 2²⁶¹⁹⁰⁷
+    #[allow(non_snake_case)]
+    pub fn MAX() -> Self {
+        let m = (Integer::from(1) << P) - 1_u32;
+        Self::new(Float::with_val(P, m) << (EMAX - Self::PM1))
+    }
+
+    /// Smallest positive normal value: 2^EMIN, for f256 2⁻²⁶²¹⁴²
+    #[allow(non_snake_case)]
+    pub fn MIN_POSITIVE() -> Self {
+        Self::encode(0, Self::EMIN, (0, 1))
+    }
+
+    /// Smallest positive subnormal value: 2^(EMIN - P + 1), for f256
+    /// 2⁻²⁶²³⁷⁸
+    #[allow(non_snake_case)]
+    pub fn MIN_GT_ZERO() -> Self {
+        Self::encode(0, Self::MIN_EXP_SUBNORMAL, (0, 1))
+    }
+
     pub fn new(val: Float) -> Self {
         Self {
             f: val,
@@ -73,6 +334,269 @@ impl FP237 {
         }
     }
 
+    /// Rounds `val` to a value representable in f256 format according to
+    /// `rnd`: to P bits, to a multiple of MIN_GT_ZERO below MIN_POSITIVE and
+    /// to ±MAX or ±∞ beyond MAX.
+    pub fn with_val_round<T>(val: T, rnd: RoundingMode) -> Self
+    where
+        Float: AssignRound<T, Round = Round, Ordering = Ordering>,
+    {
+        let mut res = Self::new(Float::new(P));
+        res.assign_round(val, rnd);
+        res
+    }
+
+    /// Assigns `val` rounded like `with_val_round` to `self`, reusing its
+    /// allocation, and returns the ternary value of the rounding.
+    pub fn assign_round<T>(&mut self, val: T, rnd: RoundingMode) -> Ordering
+    where
+        Float: AssignRound<T, Round = Round, Ordering = Ordering>,
+    {
+        if self.f.prec() != P {
+            self.f.set_prec(P);
+        }
+        let f = &mut self.f;
+        self.o = match rnd {
+            RoundingMode::NearestEven => {
+                Self::round_direct(f, val, Round::Nearest)
+            }
+            RoundingMode::TowardZero => {
+                Self::round_direct(f, val, Round::Zero)
+            }
+            RoundingMode::Up => Self::round_direct(f, val, Round::Up),
+            RoundingMode::Down => Self::round_direct(f, val, Round::Down),
+            RoundingMode::NearestAway => {
+                NA_SCRATCH.with_borrow_mut(|[t, scratch @ ..]| {
+                    // Rounding to odd at more than P + 1 bits keeps enough
+                    // information to round correctly a second time and to
+                    // detect exact ties.
+                    t.set_prec(4 * P);
+                    let o = t.assign_round(val, Round::Zero);
+                    Self::round_odd_nearest_away(f, t, o, scratch)
+                })
+            }
+        };
+        self.o
+    }
+
+    /// Assigns `t`, rounded toward zero with the ternary value `o`, to `f`
+    /// of P bits, rounded to nearest, ties away from zero, and returns the
+    /// ternary value of the rounding. `t` is rounded to odd first; the
+    /// values of `scratch` are overwritten.
+    fn round_odd_nearest_away(
+        f: &mut Float,
+        t: &mut Float,
+        o: Ordering,
+        [rz, ra, mid]: &mut [Float; 3],
+    ) -> Ordering {
+        Self::round_odd(t, o, rz);
+        rz.set_prec(P);
+        ra.set_prec(P);
+        Self::round_direct::<&Float>(rz, t, Round::Zero);
+        let o = Self::round_direct::<&Float>(ra, t, Round::AwayZero);
+        if rz != ra && ra.is_finite() {
+            mid.set_prec(2 * P);
+            mid.assign(&*rz + &*ra);
+            *mid >>= 1;
+            if mid == t {
+                f.assign(&*ra);
+                return o;
+            }
+        }
+        Self::round_direct::<&Float>(f, t, Round::Nearest)
+    }
+
+    /// Rounds `t`, rounded toward zero with the ternary value `o`, to odd,
+    /// i.e. makes its last bit 1 if it is inexact. The value of `scratch`
+    /// is overwritten.
+    fn round_odd(t: &mut Float, o: Ordering, scratch: &mut Float) {
+        if o != Ordering::Equal && t.is_finite() {
+            // t is even if it fits into one bit less
+            scratch.set_prec(t.prec() - 1);
+            if scratch.assign_round(&*t, Round::Zero) == Ordering::Equal {
+                if t.is_sign_positive() {
+                    t.next_up();
+                } else {
+                    t.next_down();
+                }
+            }
+        }
+    }
+
+    /// Assigns `val` rounded according to `rnd` to `f` of P bits, see
+    /// `with_val_round`, and returns the ternary value of the rounding.
+    fn round_direct<T>(f: &mut Float, val: T, rnd: Round) -> Ordering
+    where
+        Float: AssignRound<T, Round = Round, Ordering = Ordering>,
+    {
+        let o = f.assign_round(val, rnd);
+        Self::fit_range(f, o, rnd)
+    }
+
+    /// Adjusts `f` of P bits, rounded according to `rnd` with the ternary
+    /// value `o`, to the exponent range, see `with_val_round`, and returns
+    /// the resulting ternary value.
+    fn fit_range(f: &mut Float, o: Ordering, rnd: Round) -> Ordering {
+        let o = Self::subnormalize(f, o, rnd);
+        if !(f.is_normal() && f.get_exp().unwrap() > EMAX + 1) {
+            return o;
+        }
+        let neg = f.is_sign_negative();
+        let to_inf = match rnd {
+            Round::Zero => false,
+            Round::Up => !neg,
+            Round::Down => neg,
+            _ => true,
+        };
+        if to_inf {
+            f.assign(Special::Infinity);
+        } else {
+            f.assign(&Self::MAX().f);
+        }
+        if neg {
+            f.neg_assign();
+        }
+        if to_inf != neg {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        }
+    }
+
+    pub fn add_round(&self, rhs: &Self, rnd: RoundingMode) -> Self {
+        Self::with_val_round(&self.f + &rhs.f, rnd)
+    }
+
+    pub fn sub_round(&self, rhs: &Self, rnd: RoundingMode) -> Self {
+        Self::with_val_round(&self.f - &rhs.f, rnd)
+    }
+
+    pub fn mul_round(&self, rhs: &Self, rnd: RoundingMode) -> Self {
+        Self::with_val_round(&self.f * &rhs.f, rnd)
+    }
+
+    pub fn div_round(&self, rhs: &Self, rnd: RoundingMode) -> Self {
+        Self::with_val_round(&self.f / &rhs.f, rnd)
+    }
+
+    pub fn rem_round(&self, rhs: &Self, rnd: RoundingMode) -> Self {
+        Self::with_val_round(&self.f % &rhs.f, rnd)
+    }
+
+    pub fn sqrt_round(&self, rnd: RoundingMode) -> Self {
+        Self::with_val_round(self.f.sqrt_ref(), rnd)
+    }
+
+    pub fn fma_round(&self, m: &Self, a: &Self, rnd: RoundingMode) -> Self {
+        Self::with_val_round(&self.f * &m.f + &a.f, rnd)
+    }
+
+    /// Applies `op`, an in-place operation on `Float` rounding according to
+    /// the given `Round` and returning the ternary value, to `self`, so that
+    /// the result is rounded like `with_val_round`, and returns the ternary
+    /// value of the rounding.
+    fn apply_round<F>(&mut self, rnd: RoundingMode, op: F) -> Ordering
+    where
+        F: FnOnce(&mut Float, Round) -> Ordering,
+    {
+        let round = match rnd {
+            RoundingMode::NearestEven => Round::Nearest,
+            RoundingMode::TowardZero | RoundingMode::NearestAway => {
+                Round::Zero
+            }
+            RoundingMode::Up => Round::Up,
+            RoundingMode::Down => Round::Down,
+        };
+        if rnd != RoundingMode::NearestAway && self.f.prec() == P {
+            let o = op(&mut self.f, round);
+            self.o = Self::fit_range(&mut self.f, o, round);
+            return self.o;
+        }
+        let f = &mut self.f;
+        self.o = NA_SCRATCH.with_borrow_mut(|[t, scratch @ ..]| {
+            // The operation is applied at 4P bits, the result rounded to
+            // odd and then rounded a second time, like in `assign_round`.
+            t.set_prec(4 * P);
+            t.assign(&*f);
+            let o = op(t, Round::Zero);
+            f.set_prec(P);
+            if rnd == RoundingMode::NearestAway {
+                Self::round_odd_nearest_away(f, t, o, scratch)
+            } else {
+                Self::round_odd(t, o, &mut scratch[0]);
+                Self::round_direct::<&Float>(f, t, round)
+            }
+        });
+        self.o
+    }
+
+    /// Assigns `self` + `rhs`, rounded according to `rnd`, to `self` and
+    /// returns the ternary value of the rounding.
+    pub fn add_assign_round(
+        &mut self,
+        rhs: &Self,
+        rnd: RoundingMode,
+    ) -> Ordering {
+        self.apply_round(rnd, |f, r| f.add_assign_round(&rhs.f, r))
+    }
+
+    /// Assigns `self` - `rhs`, rounded according to `rnd`, to `self` and
+    /// returns the ternary value of the rounding.
+    pub fn sub_assign_round(
+        &mut self,
+        rhs: &Self,
+        rnd: RoundingMode,
+    ) -> Ordering {
+        self.apply_round(rnd, |f, r| f.sub_assign_round(&rhs.f, r))
+    }
+
+    /// Assigns `self` * `rhs`, rounded according to `rnd`, to `self` and
+    /// returns the ternary value of the rounding.
+    pub fn mul_assign_round(
+        &mut self,
+        rhs: &Self,
+        rnd: RoundingMode,
+    ) -> Ordering {
+        self.apply_round(rnd, |f, r| f.mul_assign_round(&rhs.f, r))
+    }
+
+    /// Assigns `self` / `rhs`, rounded according to `rnd`, to `self` and
+    /// returns the ternary value of the rounding.
+    pub fn div_assign_round(
+        &mut self,
+        rhs: &Self,
+        rnd: RoundingMode,
+    ) -> Ordering {
+        self.apply_round(rnd, |f, r| f.div_assign_round(&rhs.f, r))
+    }
+
+    /// Assigns the remainder of `self` / `rhs`, rounded according to `rnd`,
+    /// to `self` and returns the ternary value of the rounding.
+    pub fn rem_assign_round(
+        &mut self,
+        rhs: &Self,
+        rnd: RoundingMode,
+    ) -> Ordering {
+        self.apply_round(rnd, |f, r| f.rem_assign_round(&rhs.f, r))
+    }
+
+    /// Assigns the square root of `self`, rounded according to `rnd`, to
+    /// `self` and returns the ternary value of the rounding.
+    pub fn sqrt_assign_round(&mut self, rnd: RoundingMode) -> Ordering {
+        self.apply_round(rnd, |f, r| f.sqrt_round(r))
+    }
+
+    /// Assigns `self` * `m` + `a`, rounded once according to `rnd`, to
+    /// `self` and returns the ternary value of the rounding.
+    pub fn fma_assign_round(
+        &mut self,
+        m: &Self,
+        a: &Self,
+        rnd: RoundingMode,
+    ) -> Ordering {
+        self.apply_round(rnd, |f, r| f.mul_add_round(&m.f, &a.f, r))
+    }
+
     pub fn trunc(&self) -> Self {
         Self {
             f: self.f.clone().trunc(),
@@ -80,30 +604,185 @@ impl FP237 {
         }
     }
 
-    pub fn abs(self) -> Self {
+    /// Returns the fractional part of `self`, which has the sign of `self`,
+    /// or NaN for infinities.
+    pub fn fract(&self) -> Self {
+        if self.f.is_infinite() {
+            return Self::NAN();
+        }
         Self {
-            f: self.f.abs(),
+            f: self.f.clone().fract(),
             o: Ordering::Equal,
         }
     }
 
-    pub fn sqrt(self) -> Self {
-        Self {
-            f: self.f.sqrt(),
-            o: Ordering::Equal,
+    /// Returns the integer q rounded to P bits, so that `self` = q * `rhs` +
+    /// `self.rem_euclid(rhs)`, i.e. ⌊self / rhs⌋ for rhs > 0 and
+    /// ⌈self / rhs⌉ for rhs < 0. For finite `self` and infinite `rhs` the
+    /// result is ±0 or ±1 like that of f64::div_euclid.
+    pub fn div_euclid(&self, rhs: &Self) -> Self {
+        let (x, y) = (&self.f, &rhs.f);
+        if x.is_finite() && y.is_infinite() {
+            let q = Float::with_val(P, x / y);
+            let f = if x.is_sign_negative() && !x.is_zero() {
+                if y.is_sign_positive() {
+                    Float::with_val(P, &q - 1)
+                } else {
+                    Float::with_val(P, &q + 1)
+                }
+            } else {
+                q
+            };
+            return Self::new(f);
+        }
+        // the integer part of the quotient must not be rounded, unless it
+        // overflows anyway
+        let prec = match (x.get_exp(), y.get_exp()) {
+            (Some(ex), Some(ey)) if ex - ey <= EMAX + 1 => {
+                (ex - ey + 2).max(P as i32) as u32
+            }
+            _ => P,
+        };
+        let f = if y.is_sign_positive() {
+            Float::with_val_round(prec, x / y, Round::Down).0.floor()
+        } else {
+            Float::with_val_round(prec, x / y, Round::Up).0.ceil()
+        };
+        Self::with_val_round(f, RoundingMode::NearestEven)
+    }
+
+    /// Returns the least non-negative remainder of `self` / `rhs`, rounded
+    /// to nearest. Like f64::rem_euclid the result may round to |rhs|.
+    pub fn rem_euclid(&self, rhs: &Self) -> Self {
+        // the truncated remainder is always exact
+        let r = Float::with_val(P, &self.f % &rhs.f);
+        if r < 0 {
+            Self::with_val_round(
+                &r + &*rhs.f.as_abs(),
+                RoundingMode::NearestEven,
+            )
+        } else {
+            Self::new(r)
         }
     }
 
+    pub fn abs(&self) -> Self {
+        Self::new(Float::with_val(self.f.prec(), &*self.f.as_abs()))
+    }
+
+    pub fn sqrt(&self) -> Self {
+        self.sqrt_round(RoundingMode::NearestEven)
+    }
+
+    pub fn cbrt(&self) -> Self {
+        Self::with_val_round(self.f.cbrt_ref(), RoundingMode::NearestEven)
+    }
+
+    /// Returns the IEEE remainder of `self` / `rhs`, i.e. `self` - n * `rhs`
+    /// with n being the integer nearest to `self` / `rhs` (ties to even),
+    /// together with the low 31 bits of n, carrying the sign of n.
+    pub fn remquo(&self, rhs: &Self) -> (Self, i32) {
+        // the remainder is always exact
+        let (f, q) = self.f.clone().remainder_quo31(&rhs.f);
+        (Self::new(f), q)
+    }
+
+    /// Returns `self` raised to the integer power `n`, rounded once, with
+    /// x⁰ = 1 for any x, including NaN.
+    pub fn powi(&self, n: i32) -> Self {
+        Self::with_val_round((&self.f).pow(n), RoundingMode::NearestEven)
+    }
+
     pub fn fma(&self, m: &Self, a: &Self) -> Self {
         let f = &self.f * &m.f + &a.f;
         let (f, o) = Float::with_val_round(P, f, Round::Nearest);
         Self { f, o }
     }
 
+    /// Returns self * m - a, rounded once.
+    pub fn fms(&self, m: &Self, a: &Self) -> Self {
+        Self::with_val_round(&self.f * &m.f - &a.f, RoundingMode::NearestEven)
+    }
+
+    /// Returns -(self * m) + a, rounded once.
+    pub fn fnma(&self, m: &Self, a: &Self) -> Self {
+        let nx = -self.f.clone();
+        Self::with_val_round(&nx * &m.f + &a.f, RoundingMode::NearestEven)
+    }
+
+    /// Returns -(self * m) - a, rounded once.
+    pub fn fnms(&self, m: &Self, a: &Self) -> Self {
+        let nx = -self.f.clone();
+        Self::with_val_round(&nx * &m.f - &a.f, RoundingMode::NearestEven)
+    }
+
+    /// Returns (hi, lo) with hi = self + other rounded to nearest and lo
+    /// the rounding error, so that hi + lo = self + other exactly.
+    pub fn two_sum(&self, other: &Self) -> (Self, Self) {
+        let hi = self + other;
+        let nhi = -hi.f.clone();
+        let lo = Float::sum([&self.f, &other.f, &nhi].into_iter());
+        let (lo, o) = Float::with_val_round(P, lo, Round::Nearest);
+        (hi, Self { f: lo, o })
+    }
+
+    /// Returns (hi, lo) with hi = self * other rounded to nearest and lo
+    /// the rounding error, so that hi + lo = self * other exactly (unless
+    /// lo underflows).
+    pub fn two_prod(&self, other: &Self) -> (Self, Self) {
+        let hi = self * other;
+        let lo = self.fms(other, &hi);
+        (hi, lo)
+    }
+
+    /// Returns the sum of the products xs[i] * ys[i], rounded once.
+    pub fn dot(xs: &[Self], ys: &[Self]) -> Self {
+        assert_eq!(xs.len(), ys.len());
+        let values = xs.iter().zip(ys).map(|(x, y)| (&x.f, &y.f));
+        Self::with_val_round(Float::dot(values), RoundingMode::NearestEven)
+    }
+
+    /// Returns the sum of `values`, accumulated exactly and rounded once to
+    /// nearest, together with the ternary of the rounding. The empty sum is
+    /// +0.
+    pub fn exact_sum(values: &[Self]) -> (Self, Ordering) {
+        let terms = values.iter().map(|x| &x.f);
+        let s = Self::with_val_round(
+            Float::sum(terms),
+            RoundingMode::NearestEven,
+        );
+        let o = s.o;
+        (s, o)
+    }
+
+    /// Returns the product of `values`, computed exactly and rounded once
+    /// to nearest, together with the ternary of the rounding. The empty
+    /// product is 1.
+    pub fn exact_product(values: &[Self]) -> (Self, Ordering) {
+        let prec = values.iter().map(|x| x.f.prec()).sum::<u32>().max(P);
+        let mut f = Float::with_val(prec, 1);
+        for x in values {
+            f *= &x.f;
+        }
+        let p = Self::with_val_round(f, RoundingMode::NearestEven);
+        let o = p.o;
+        (p, o)
+    }
+
+    /// Returns self² + other², rounded once into the range of f256, i.e.
+    /// overflowing to ∞ and underflowing to subnormal values or zero.
     pub fn sos(&self, other: &Self) -> Self {
-        let f = self.f.clone().mul_add_mul(&self.f, &other.f, &other.f);
-        let (f, o) = Float::with_val_round(P, f, Round::Nearest);
-        Self { f, o }
+        Self::with_val_round(
+            &self.f * &self.f + &other.f * &other.f,
+            RoundingMode::NearestEven,
+        )
+    }
+
+    pub fn hypot(&self, other: &Self) -> Self {
+        Self::with_val_round(
+            self.f.hypot_ref(&other.f),
+            RoundingMode::NearestEven,
+        )
     }
 
     pub fn sin(&self) -> Self {
@@ -130,12 +809,138 @@ impl FP237 {
         Self { f, o }
     }
 
+    /// Reduces `self` modulo π/2: returns the quadrant k mod 4 and the
+    /// reduced argument r = self - k * π/2 with |r| <= π/4, split into a
+    /// head and a tail (r ≈ hi + lo).
+    pub fn reduce_pi_2(&self) -> (u32, Self, Self) {
+        assert!(self.f.is_finite());
+        // enough bits to keep P + guard bits of r after cancelling out the
+        // integral part of self * 2 / π
+        let e = self.f.get_exp().unwrap_or(0).max(0) as u32;
+        let prec = e + 3 * P + 64;
+        let pi_2 = Float::with_val(prec, Constant::Pi) >> 1;
+        let q = Float::with_val(prec, &self.f / &pi_2).round_even();
+        let k = q.to_integer().unwrap();
+        let r = Float::with_val(prec, &self.f - &q * &pi_2);
+        let hi = Self::with_val_round(&r, RoundingMode::NearestEven);
+        let lo = Self::with_val_round(
+            Float::with_val(prec, &r - &hi.f),
+            RoundingMode::NearestEven,
+        );
+        (k.mod_u(4), hi, lo)
+    }
+
+    /// Splits `value` into `n_terms` (2 or 3) values, each rounded to
+    /// nearest from what the previous ones leave of `value`, so that their
+    /// exact sum matches `value` to about `n_terms` · P bits.
+    ///
+    /// # Panics
+    ///
+    /// If `n_terms` is not 2 or 3 or `value` has less than `n_terms` · P
+    /// bits.
+    pub fn split_hi_lo(value: &Float, n_terms: usize) -> Vec<Self> {
+        assert!(
+            (2..=3).contains(&n_terms),
+            "Number of terms must be 2 or 3, got {n_terms}"
+        );
+        let prec = n_terms as u32 * P;
+        assert!(
+            value.prec() >= prec,
+            "{n_terms} terms need at least {prec} bits, got {}",
+            value.prec()
+        );
+        let mut rest = value.clone();
+        (0..n_terms)
+            .map(|_| {
+                let t =
+                    Self::with_val_round(&rest, RoundingMode::NearestEven);
+                rest -= &t.f;
+                t
+            })
+            .collect()
+    }
+
+    pub fn ln(&self) -> Self {
+        Self::with_val_round(self.f.ln_ref(), RoundingMode::NearestEven)
+    }
+
+    pub fn log2(&self) -> Self {
+        Self::with_val_round(self.f.log2_ref(), RoundingMode::NearestEven)
+    }
+
+    pub fn log10(&self) -> Self {
+        Self::with_val_round(self.f.log10_ref(), RoundingMode::NearestEven)
+    }
+
+    pub fn ln_1p(&self) -> Self {
+        Self::with_val_round(self.f.ln_1p_ref(), RoundingMode::NearestEven)
+    }
+
+    pub fn exp(&self) -> Self {
+        Self::with_val_round(self.f.exp_ref(), RoundingMode::NearestEven)
+    }
+
+    pub fn exp_m1(&self) -> Self {
+        Self::with_val_round(self.f.exp_m1_ref(), RoundingMode::NearestEven)
+    }
+
+    pub fn gamma(&self) -> Self {
+        Self::with_val_round(self.f.gamma_ref(), RoundingMode::NearestEven)
+    }
+
+    /// Returns ln(|Γ(self)|).
+    pub fn ln_gamma(&self) -> Self {
+        let mut f = Float::new(P);
+        let mut sign = Ordering::Equal;
+        (&mut f, &mut sign)
+            .assign_round(self.f.ln_abs_gamma_ref(), Round::Nearest);
+        // f has P bits and can't be subnormal, only overflow is left to be
+        // handled
+        Self::with_val_round(&f, RoundingMode::NearestEven)
+    }
+
+    /// Splits `self` into a fraction m with 0.5 <= |m| < 1 and an exponent
+    /// e with self = m * 2ᵉ. Zero, infinities and NaN are returned
+    /// unchanged, together with 0.
+    pub fn frexp(&self) -> (Self, i32) {
+        match self.f.get_exp() {
+            Some(e) => (Self::new(Float::with_val(P, &self.f >> e)), e),
+            None => (self.clone(), 0),
+        }
+    }
+
+    /// Returns self * 2ⁿ, rounded to nearest.
+    pub fn ldexp(&self, n: i32) -> Self {
+        Self::with_val_round(&self.f << n, RoundingMode::NearestEven)
+    }
+
+    /// Returns ⌊log₂(|self|)⌋ as integer, i32::MIN for zero and NaN and
+    /// i32::MAX for infinities.
+    pub fn ilogb(&self) -> i32 {
+        match self.f.get_exp() {
+            Some(e) => e - 1,
+            None if self.f.is_infinite() => i32::MAX,
+            None => i32::MIN,
+        }
+    }
+
+    /// Returns ⌊log₂(|self|)⌋, -∞ for zero, +∞ for infinities and NaN for
+    /// NaN.
+    pub fn logb(&self) -> Self {
+        match self.f.get_exp() {
+            Some(e) => Self::new(Float::with_val(P, e - 1)),
+            None if self.f.is_zero() => -Self::INFINITY(),
+            None if self.f.is_infinite() => Self::INFINITY(),
+            None => Self::NAN(),
+        }
+    }
+
     pub fn decode(&self, reduce: bool) -> (u32, i32, (u128, u128)) {
         let b: Integer = Integer::from(u128::MAX) + 1;
         match self.f.to_integer_exp() {
             Some((mut i, mut e)) => {
                 let s = self.f.is_sign_negative() as u32;
-                if e > EMAX - PM1 {
+                if e > EMAX - Self::PM1 {
                     return (s, EMAX + 1, (0, 0));
                 }
                 i.abs_mut();
@@ -145,19 +950,27 @@ impl FP237 {
                         e += 1;
                     }
                 }
-                if e < MIN_EXP_SUBNORMAL {
-                    let shift = MIN_EXP_SUBNORMAL - e;
+                if e < Self::MIN_EXP_SUBNORMAL {
+                    let shift = Self::MIN_EXP_SUBNORMAL - e;
                     let mask = (Integer::from(1) << shift) - 1;
                     let tie = Integer::from(1) << (shift - 1);
                     let rem = &i & mask;
                     i >>= shift;
+                    // at a tie the magnitude has been rounded down to P
+                    // bits, if the value has been rounded toward zero
+                    let truncated = if s == 1 {
+                        self.o == Ordering::Greater
+                    } else {
+                        self.o == Ordering::Less
+                    };
                     if rem > tie
                         || rem == tie
-                            && (self.o != Ordering::Greater || i.is_odd())
+                            && (truncated
+                                || self.o == Ordering::Equal && i.is_odd())
                     {
                         i += 1;
                     }
-                    e = MIN_EXP_SUBNORMAL;
+                    e = Self::MIN_EXP_SUBNORMAL;
                 }
                 if i == 0 {
                     // println!("Near 0: {:?}", self.f.to_integer_exp());
@@ -167,81 +980,453 @@ impl FP237 {
                 let l = Integer::from(&i % &b).to_u128().unwrap();
                 (s, e, (h, l))
             }
-            _ => panic!("Value is NaN or infinite."),
+            _ if self.f.is_infinite() => {
+                (self.f.is_sign_negative() as u32, EMAX + 1, (0, 0))
+            }
+            _ => (0, EMAX + 1, Self::QUIET_NAN_SIGNIF),
         }
     }
 
-    pub fn random_from_exp_range(exp_range: &RangeInclusive<i32>) -> Self {
-        const HI_HIDDEN_BIT: u128 = 1_u128 << 108;
-        const HI_MAX: u128 = HI_HIDDEN_BIT - 1;
-        let mut rng = thread_rng();
-        let s = rng.gen_range(0..=1_u32);
-        let mut t: i32 = rng.gen_range(exp_range.clone());
-        let mut h = rng.gen_range(0..=HI_MAX);
-        let l = rng.gen_range(0..=u128::MAX);
-        let mut prec = P;
-        if t >= EMIN {
-            t -= PM1;
-            h += HI_HIDDEN_BIT;
-        } else {
-            let msb = if h != 0 {
-                128 - h.leading_zeros()
+    /// Inverse of `decode`: creates the value (-1)ˢ * (h * 2¹²⁸ + l) * 2ᵉ.
+    /// The tuple (s, EMAX + 1, (0, 0)) gives an infinity, any other tuple
+    /// with e = EMAX + 1 gives NaN.
+    pub fn encode(s: u32, e: i32, (h, l): (u128, u128)) -> Self {
+        let f = if e == EMAX + 1 {
+            if h == 0 && l == 0 {
+                Float::with_val(P, Special::Infinity)
             } else {
-                256 - l.leading_zeros()
-            };
-            prec = msb;
-        }
-        let mut c = (Integer::from(h) << 128) + l;
-        let (mut f, o) = if t < 0 {
-            let mut p = Float::new(P);
-            p.assign(Float::i_exp(2, t));
-            let (fr, o) =
-                Float::with_val_round(prec, &c * &p, Round::Nearest);
-            (Float::with_val(P, fr), o)
+                Float::with_val(P, Special::Nan)
+            }
         } else {
-            let p = Integer::from(2).pow(t as u32);
-            c *= p;
-            Float::with_val_round(P, &c, Round::Nearest)
+            let m: Integer = (Integer::from(h) << 128) + l;
+            Float::with_val(P, m) << e
         };
-        if s == 1 {
-            f = -f;
-        }
-        Self { f, o }
-    }
-
-    // pub fn recip_factorial(n: u32) -> Self {
-    //     let mut f = Self::new(Float::with_val(P, 1));
-    //     for i in 2..=n {
-    //         f /= Self::new(Float::with_val(P, i));
-    //     }
-    //     f
-    // }
-}
-
-impl Default for FP237 {
-    fn default() -> Self {
-        FP237::from(0)
+        Self::new(if s == 1 { -f } else { f })
     }
-}
 
-impl From<u32> for FP237 {
-    fn from(value: u32) -> Self {
-        FP237 {
-            f: Float::with_val(P, value),
-            o: Ordering::Equal,
+    /// Returns the least value greater than `self`.
+    pub fn next_up(&self) -> Self {
+        if self.f.is_nan()
+            || self.f.is_infinite() && self.f.is_sign_positive()
+        {
+            return self.clone();
+        }
+        if self.f.is_infinite() {
+            return -Self::MAX();
         }
+        if self.f == Self::MAX().f {
+            return Self::INFINITY();
+        }
+        let mut f = self.f.clone();
+        if f.clone().abs() <= Self::MIN_POSITIVE().f {
+            // the spacing of subnormal values and of the smallest normal
+            // ones is MIN_GT_ZERO, which keeps the sum exact
+            f += Self::MIN_GT_ZERO().f;
+            // the successor of -MIN_GT_ZERO is -0
+            if f.is_zero() {
+                f = -f;
+            }
+        } else {
+            f.next_up();
+        }
+        Self::new(f)
     }
-}
 
-impl FromStr for FP237 {
-    type Err = ParseFloatError;
+    /// Returns the greatest value less than `self`.
+    pub fn next_down(&self) -> Self {
+        -(-self.clone()).next_up()
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    /// Returns the shortest decimal string in scientific notation (like
+    /// `1.5e-7`) which, when parsed with rounding to nearest, gives `self`
+    /// again. Among candidates with equally few digits the one closest to
+    /// `self` is chosen.
+    pub fn to_shortest_string(&self) -> String {
+        // ⌈P * log₁₀(2)⌉ + 1 digits always suffice
+        let max_n_digits = Self::DEC_DIGITS as usize + 1;
+        let sign = if self.f.is_sign_negative() { "-" } else { "" };
+        if self.f.is_nan() {
+            return "NaN".to_string();
+        }
+        if self.f.is_infinite() {
+            return format!("{sign}inf");
+        }
+        if self.f.is_zero() {
+            return format!("{sign}0e0");
+        }
+        let round_trips = |digits: &str, exp: i32| {
+            Self::from_str(&format!("{sign}0.{digits}e{exp}"))
+                .unwrap()
+                .f
+                == self.f
+        };
+        for n in 1..=max_n_digits {
+            let mut candidates = vec![];
+            for rnd in [Round::Nearest, Round::Down, Round::Up] {
+                let (_, digits, exp) =
+                    self.f.to_sign_string_exp_round(10, Some(n), rnd);
+                candidates.push((digits, exp.unwrap()));
+            }
+            if let Some((digits, exp)) =
+                candidates.iter().find(|(d, e)| round_trips(d, *e))
+            {
+                let digits = digits.trim_end_matches('0');
+                let (first, rest) = digits.split_at(1);
+                let exp = *exp - 1;
+                return if rest.is_empty() {
+                    format!("{sign}{first}e{exp}")
+                } else {
+                    format!("{sign}{first}.{rest}e{exp}")
+                };
+            }
+        }
+        unreachable!()
+    }
+
+    /// Returns the exact value of `self` as hex-float literal like
+    /// "-0x1.f8p-3", normalized to a leading digit 1 even for subnormal
+    /// values, or as "0x0p0", "-0x0p0", "inf", "-inf" or "NaN".
+    pub fn to_hex_string(&self) -> String {
+        let sign = if self.f.is_sign_negative() { "-" } else { "" };
+        if self.f.is_nan() {
+            return "NaN".to_string();
+        }
+        if self.f.is_infinite() {
+            return format!("{sign}inf");
+        }
+        if self.f.is_zero() {
+            return format!("{sign}0x0p0");
+        }
+        let (m, e) = self.f.to_integer_exp().unwrap();
+        let m = m.abs();
+        let n = m.significant_bits() as i32;
+        let exp = e + n - 1;
+        // fractional bits, padded to full hex digits
+        let k = (n - 1 + 3) / 4 * 4;
+        let fract =
+            (m - (Integer::from(1) << (n - 1) as u32)) << (k - n + 1) as u32;
+        let digits =
+            format!("{:0>1$}", fract.to_string_radix(16), k as usize / 4);
+        let digits = digits.trim_end_matches('0');
+        if digits.is_empty() {
+            format!("{sign}0x1p{exp}")
+        } else {
+            format!("{sign}0x1.{digits}p{exp}")
+        }
+    }
+
+    /// Parses the part of a hex-float literal following "0x" (with the sign
+    /// already stripped) and rounds its value to nearest.
+    fn from_hex_str(neg: bool, h: &str) -> Result<Self, ParseFloatError> {
+        let (m, e) = h.split_once(['p', 'P']).unwrap_or((h, "0"));
+        let (int_digits, fract_digits) = m.split_once('.').unwrap_or((m, ""));
+        let digits = format!("{int_digits}{fract_digits}");
+        let m = Float::parse_radix(&digits, 16)?;
+        // the exponent must be a decimal integer, anything else (fractions,
+        // nested exponents, inf or nan) is rejected
+        let exp = match e.parse::<Integer>() {
+            Ok(i) => i.to_i32().unwrap_or(if i.is_negative() {
+                i32::MIN
+            } else {
+                i32::MAX
+            }),
+            // there is no public constructor of ParseFloatError, so take
+            // the one reported for a literal without digits
+            Err(_) => return Err(Float::parse("").unwrap_err()),
+        };
+        let mut f = Float::with_val(4 * digits.len() as u32, m);
+        f <<= exp.saturating_sub(4 * fract_digits.len() as i32);
+        if neg {
+            f = -f;
+        }
+        Ok(Self::with_val_round(f, RoundingMode::NearestEven))
+    }
+
+    /// Rounds `self` to an integral value according to `rnd`, keeping the
+    /// sign of zero results.
+    pub fn round_to_int(&self, rnd: RoundingMode) -> Self {
+        let f = self.f.clone();
+        Self::new(match rnd {
+            RoundingMode::NearestEven => f.round_even(),
+            RoundingMode::TowardZero => f.trunc(),
+            RoundingMode::Up => f.ceil(),
+            RoundingMode::Down => f.floor(),
+            RoundingMode::NearestAway => f.round(),
+        })
+    }
+
+    /// Converts `self` to f64, rounded according to `rnd`.
+    pub fn to_f64_round(&self, rnd: RoundingMode) -> f64 {
+        self.round_to_binary(rnd, |f, r| f.to_f64_round(r))
+    }
+
+    /// Converts `self` to f32, rounded according to `rnd`.
+    pub fn to_f32_round(&self, rnd: RoundingMode) -> f32 {
+        self.round_to_binary(rnd, |f, r| f.to_f32_round(r) as f64) as f32
+    }
+
+    fn round_to_binary<C>(&self, rnd: RoundingMode, conv: C) -> f64
+    where
+        C: Fn(&Float, Round) -> f64,
+    {
+        match rnd {
+            RoundingMode::NearestEven => conv(&self.f, Round::Nearest),
+            RoundingMode::TowardZero => conv(&self.f, Round::Zero),
+            RoundingMode::Up => conv(&self.f, Round::Up),
+            RoundingMode::Down => conv(&self.f, Round::Down),
+            RoundingMode::NearestAway => {
+                let z = conv(&self.f, Round::Zero);
+                let a = conv(&self.f, Round::AwayZero);
+                if z != a && a.is_finite() {
+                    let mid = (Float::with_val(64, z) + a) >> 1;
+                    if mid == self.f {
+                        return a;
+                    }
+                }
+                conv(&self.f, Round::Nearest)
+            }
+        }
+    }
+
+    /// Returns the exact decimal representation of `self` in fixed
+    /// notation with `n` fractional digits, rounded half to even, like
+    /// `format!("{:.n}", f)`.
+    pub fn to_fixed_string(&self, n: usize) -> String {
+        let sign = if self.f.is_sign_negative() { "-" } else { "" };
+        if self.f.is_nan() {
+            return "NaN".to_string();
+        }
+        if self.f.is_infinite() {
+            return format!("{sign}inf");
+        }
+        let (m, e) = self.f.to_integer_exp().unwrap();
+        let mut q = m.abs() * Integer::from(10).pow(n as u32);
+        if e >= 0 {
+            q <<= e as u32;
+        } else {
+            let sh = e.unsigned_abs();
+            let r = Integer::from(q.keep_bits_ref(sh));
+            q >>= sh;
+            let half = Integer::from(1) << (sh - 1);
+            if r > half || r == half && q.is_odd() {
+                q += 1;
+            }
+        }
+        let mut digits = q.to_string();
+        if digits.len() <= n {
+            digits.insert_str(0, &"0".repeat(n + 1 - digits.len()));
+        }
+        if n > 0 {
+            digits.insert(digits.len() - n, '.');
+        }
+        format!("{sign}{digits}")
+    }
+
+    pub fn random_from_exp_range(exp_range: &RangeInclusive<i32>) -> Self {
+        Self::random_from_exp_range_with_rng(&mut thread_rng(), exp_range)
+    }
+
+    pub fn random_from_exp_range_with_rng<R: Rng + ?Sized>(
+        rng: &mut R,
+        exp_range: &RangeInclusive<i32>,
+    ) -> Self {
+        let (hi_max, lo_max) = Self::FRACTION_MASK;
+        let s = rng.gen_range(0..=1_u32);
+        let mut t: i32 = rng.gen_range(exp_range.clone());
+        let h = rng.gen_range(0..=hi_max);
+        let l = rng.gen_range(0..=lo_max);
+        let mut c: Integer;
+        if t >= Self::EMIN {
+            t -= Self::PM1;
+            c = (Integer::from(h) << 128) + l;
+            c.set_bit(Self::PM1 as u32, true);
+        } else {
+            // the significand of a subnormal value with exponent t has
+            // t - MIN_EXP_SUBNORMAL + 1 bits
+            let n = (t - Self::MIN_EXP_SUBNORMAL + 1) as u32;
+            c = ((Integer::from(h) << 128) + l) >> (Self::PM1 as u32 - n);
+            c.set_bit(n - 1, true);
+            t = Self::MIN_EXP_SUBNORMAL;
+        }
+        let (mut f, o) = if t < 0 {
+            let mut p = Float::new(P);
+            p.assign(Float::i_exp(1, t));
+            Float::with_val_round(P, &c * &p, Round::Nearest)
+        } else {
+            let p = Integer::from(2).pow(t as u32);
+            c *= p;
+            Float::with_val_round(P, &c, Round::Nearest)
+        };
+        if s == 1 {
+            f = -f;
+        }
+        Self { f, o }
+    }
+
+    // pub fn recip_factorial(n: u32) -> Self {
+    //     let mut f = Self::new(Float::with_val(P, 1));
+    //     for i in 2..=n {
+    //         f /= Self::new(Float::with_val(P, i));
+    //     }
+    //     f
+    // }
+}
+
+impl FP237 {
+    /// Returns the bit pattern of the value in f256 layout as (hi, lo):
+    /// 1 sign bit, 19 bits biased exponent, 236 bits fraction.
+    pub fn to_bits(&self) -> (u128, u128) {
+        let (s, mut e, (h, l)) = self.decode(false);
+        let sign = if s == 1 { HI_SIGN_BIT } else { 0 };
+        if e == EMAX + 1 {
+            return (sign | HI_EXP_MASK | h, l);
+        }
+        let mut m: Integer = (Integer::from(h) << 128) + l;
+        if m == 0 {
+            return (sign, 0);
+        }
+        // normalize the significand to P bits as far as the exponent
+        // range allows
+        let n_bits = m.significant_bits() as i32;
+        let shift = (P as i32 - n_bits).min(e - MIN_EXP_SUBNORMAL);
+        if shift > 0 {
+            m <<= shift as u32;
+            e -= shift;
+        }
+        let biased_exp = if m.significant_bits() == P {
+            m -= Integer::from(1) << PM1 as u32;
+            (e + PM1 + EMAX) as u128
+        } else {
+            0
+        };
+        let b: Integer = Integer::from(u128::MAX) + 1;
+        let h = Integer::from(&m / &b).to_u128().unwrap();
+        let l = Integer::from(&m % &b).to_u128().unwrap();
+        (sign | (biased_exp << 108) | h, l)
+    }
+
+    /// Inverse of `to_bits`.
+    pub fn from_bits((hi, lo): (u128, u128)) -> Self {
+        let s = (hi >> 127) as u32;
+        let biased_exp = ((hi & HI_EXP_MASK) >> 108) as i32;
+        let h = hi & HI_FRACTION_MASK;
+        match biased_exp {
+            0 => Self::encode(s, MIN_EXP_SUBNORMAL, (h, lo)),
+            t if t == 2 * EMAX + 1 => Self::encode(s, EMAX + 1, (h, lo)),
+            t => Self::encode(s, t - EMAX - PM1, (h | HI_HIDDEN_BIT, lo)),
+        }
+    }
+
+    /// Returns the continued-fraction expansion of the exact value into
+    /// at most `max_terms` terms.
+    ///
+    /// # Panics
+    ///
+    /// If `self` is infinite or NaN.
+    pub fn to_continued_fraction(
+        &self,
+        max_terms: usize,
+    ) -> ContinuedFraction {
+        ContinuedFraction::from_float(&self.f, max_terms)
+    }
+
+    /// Returns the rational number closest to `self` among those with a
+    /// denominator not exceeding `max_den`.
+    ///
+    /// # Panics
+    ///
+    /// If `self` is infinite or NaN or `max_den` < 1.
+    pub fn best_rational(&self, max_den: &Integer) -> Rational {
+        self.to_continued_fraction(usize::MAX)
+            .best_approximation(max_den)
+    }
+}
+
+impl FP113 {
+    const BIAS: i32 = 16383;
+    const EXP_MASK: u128 = 0x7fff << 112;
+    const HIDDEN_BIT: u128 = 1 << 112;
+
+    /// Returns the bit pattern of the value in binary128 layout: 1 sign
+    /// bit, 15 bits biased exponent, 112 bits fraction.
+    pub fn to_bits(&self) -> u128 {
+        let (s, mut e, (_, mut m)) = self.decode(false);
+        let sign = (s as u128) << 127;
+        if e == Self::BIAS + 1 {
+            return sign | Self::EXP_MASK | m;
+        }
+        if m == 0 {
+            return sign;
+        }
+        // normalize the significand to 113 bits as far as the exponent
+        // range allows
+        let n_bits = 128 - m.leading_zeros() as i32;
+        let shift = (113 - n_bits).min(e - Self::MIN_EXP_SUBNORMAL);
+        if shift > 0 {
+            m <<= shift;
+            e -= shift;
+        }
+        let biased_exp = if m >= Self::HIDDEN_BIT {
+            m -= Self::HIDDEN_BIT;
+            (e + Self::PM1 + Self::BIAS) as u128
+        } else {
+            0
+        };
+        sign | (biased_exp << 112) | m
+    }
+
+    /// Inverse of `to_bits`.
+    pub fn from_bits(bits: u128) -> Self {
+        let s = (bits >> 127) as u32;
+        let biased_exp = ((bits & Self::EXP_MASK) >> 112) as i32;
+        let m = bits & (Self::HIDDEN_BIT - 1);
+        match biased_exp {
+            0 => Self::encode(s, Self::MIN_EXP_SUBNORMAL, (0, m)),
+            0x7fff => Self::encode(s, Self::BIAS + 1, (0, m)),
+            t => Self::encode(
+                s,
+                t - Self::BIAS - Self::PM1,
+                (0, m | Self::HIDDEN_BIT),
+            ),
+        }
+    }
+}
+
+impl<const P: u32, const EMAX: i32> Default for FP<P, EMAX> {
+    fn default() -> Self {
+        Self::from(0)
+    }
+}
+
+impl<const P: u32, const EMAX: i32> From<u32> for FP<P, EMAX> {
+    fn from(value: u32) -> Self {
+        Self {
+            f: Float::with_val(P, value),
+            o: Ordering::Equal,
+        }
+    }
+}
+
+impl<const P: u32, const EMAX: i32> FromStr for FP<P, EMAX> {
+    type Err = ParseFloatError;
+
+    /// Parses a decimal literal or a hex-float literal like `-0x1f.8p-3`,
+    /// rounding it to nearest.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let t = s.trim();
+        let (neg, u) = match t.strip_prefix('-') {
+            Some(u) => (true, u),
+            None => (false, t.strip_prefix('+').unwrap_or(t)),
+        };
+        if let Some(h) = u.strip_prefix("0x").or_else(|| u.strip_prefix("0X"))
+        {
+            return Self::from_hex_str(neg, h);
+        }
         match Float::parse(s) {
             Ok(p) => {
                 let (mut f, mut o) =
                     Float::with_val_round(P, p, Round::Nearest);
-                o = f.subnormalize_ieee_round(o, Round::Nearest);
+                o = Self::subnormalize(&mut f, o, Round::Nearest);
                 Ok(Self { f, o })
             }
             Err(e) => Err(e),
@@ -249,14 +1434,14 @@ impl FromStr for FP237 {
     }
 }
 
-impl Display for FP237 {
+impl<const P: u32, const EMAX: i32> Display for FP<P, EMAX> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         if self.f.is_integer() {
             let mut i = self.f.to_integer().unwrap();
             let mut s = i.to_string();
             let n = s.len() as u32;
-            if n > 72 {
-                let d = Integer::from(10).pow(n - 72);
+            if n > Self::DEC_DIGITS {
+                let d = Integer::from(10).pow(n - Self::DEC_DIGITS);
                 let mut t = Integer::new();
                 t.assign(&d >> 1);
                 let qr = i.div_rem_ref(&d);
@@ -276,19 +1461,19 @@ impl Display for FP237 {
     }
 }
 
-impl LowerExp for FP237 {
+impl<const P: u32, const EMAX: i32> LowerExp for FP<P, EMAX> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         LowerExp::fmt(&self.f, f)
     }
 }
 
-impl PartialOrd for FP237 {
+impl<const P: u32, const EMAX: i32> PartialOrd for FP<P, EMAX> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         self.f.partial_cmp(&other.f)
     }
 }
 
-impl Neg for FP237 {
+impl<const P: u32, const EMAX: i32> Neg for FP<P, EMAX> {
     type Output = Self;
 
     fn neg(self) -> Self::Output {
@@ -299,8 +1484,8 @@ impl Neg for FP237 {
     }
 }
 
-impl Add for &FP237 {
-    type Output = FP237;
+impl<const P: u32, const EMAX: i32> Add for &FP<P, EMAX> {
+    type Output = FP<P, EMAX>;
 
     fn add(self, rhs: Self) -> Self::Output {
         let f = &self.f + &rhs.f;
@@ -309,8 +1494,8 @@ impl Add for &FP237 {
     }
 }
 
-impl Sub for &FP237 {
-    type Output = FP237;
+impl<const P: u32, const EMAX: i32> Sub for &FP<P, EMAX> {
+    type Output = FP<P, EMAX>;
 
     fn sub(self, rhs: Self) -> Self::Output {
         let f = &self.f - &rhs.f;
@@ -319,8 +1504,8 @@ impl Sub for &FP237 {
     }
 }
 
-impl Mul for &FP237 {
-    type Output = FP237;
+impl<const P: u32, const EMAX: i32> Mul for &FP<P, EMAX> {
+    type Output = FP<P, EMAX>;
 
     fn mul(self, rhs: Self) -> Self::Output {
         let f = &self.f * &rhs.f;
@@ -329,8 +1514,8 @@ impl Mul for &FP237 {
     }
 }
 
-impl Div for &FP237 {
-    type Output = FP237;
+impl<const P: u32, const EMAX: i32> Div for &FP<P, EMAX> {
+    type Output = FP<P, EMAX>;
 
     fn div(self, rhs: Self) -> Self::Output {
         let f = &self.f / &rhs.f;
@@ -339,8 +1524,8 @@ impl Div for &FP237 {
     }
 }
 
-impl Rem for &FP237 {
-    type Output = FP237;
+impl<const P: u32, const EMAX: i32> Rem for &FP<P, EMAX> {
+    type Output = FP<P, EMAX>;
 
     fn rem(self, rhs: Self) -> Self::Output {
         let f = &self.f % &rhs.f;
@@ -349,6 +1534,252 @@ impl Rem for &FP237 {
     }
 }
 
+/// Sums are accumulated exactly and rounded once, see `exact_sum`.
+impl<const P: u32, const EMAX: i32> Sum for FP<P, EMAX> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self::exact_sum(&iter.collect::<Vec<_>>()).0
+    }
+}
+
+impl<'a, const P: u32, const EMAX: i32> Sum<&'a Self> for FP<P, EMAX> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+
+/// Products are computed exactly and rounded once, see `exact_product`.
+impl<const P: u32, const EMAX: i32> Product for FP<P, EMAX> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self::exact_product(&iter.collect::<Vec<_>>()).0
+    }
+}
+
+impl<'a, const P: u32, const EMAX: i32> Product<&'a Self> for FP<P, EMAX> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.cloned().product()
+    }
+}
+
+/// Returns the decimal exponent of f256::MAX in scientific notation, i.e.
+/// the largest n with 10ⁿ ≤ MAX (78913).
+pub fn max_10_exp() -> i32 {
+    let max = FP237::MAX().f;
+    let log10 = Float::with_val(2 * P, max.log10_ref()).floor();
+    log10.to_i32_saturating().unwrap()
+}
+
+/// Returns the smallest n with 10ⁿ being a normal f256 (-78912).
+pub fn min_10_exp() -> i32 {
+    let min = FP237::MIN_POSITIVE().f;
+    let log10 = Float::with_val(2 * P, min.log10_ref()).ceil();
+    log10.to_i32_saturating().unwrap()
+}
+
+/// Returns the decimal exponent of f256::MIN_GT_ZERO in scientific
+/// notation, i.e. the largest n with 10ⁿ ≤ MIN_GT_ZERO (-78984).
+pub fn min_subnormal_10_exp() -> i32 {
+    let min = FP237::MIN_GT_ZERO().f;
+    let log10 = Float::with_val(2 * P, min.log10_ref()).floor();
+    log10.to_i32_saturating().unwrap()
+}
+
+/// Returns the number of significant decimal digits sufficient for any
+/// f256 to round-trip through a decimal literal, ⌈P·log₁₀ 2⌉ + 1 (73).
+pub fn max_sig_digits() -> u32 {
+    let log10_2 = Float::with_val(2 * P, 2).log10();
+    let digits = Float::with_val(2 * P, log10_2 * P).ceil();
+    digits.to_u32_saturating().unwrap() + 1
+}
+
+/// Returns the largest number of significant decimal digits of the exact
+/// value of an f256, i.e. the number of digits of (2ᴾ - 1)·5⁻ᵉ with e
+/// being the exponent of MIN_GT_ZERO (183466).
+pub fn max_exact_digits() -> u32 {
+    let m = (Integer::from(1) << P) - 1_u32;
+    let exact =
+        m * Integer::from(Integer::u_pow_u(5, -MIN_EXP_SUBNORMAL as u32));
+    exact.to_string_radix(10).len() as u32
+}
+
+/// Returns the position of `f` in the ordered sequence of f256 values,
+/// with -0 and +0 being neighbours. The bit patterns of non-negative values
+/// are ordered like the values themselves, across the boundary between
+/// subnormal and normal values and up to ∞.
+fn ordinal(f: &FP237) -> Integer {
+    let (hi, lo) = f.to_bits();
+    let mag: Integer = (Integer::from(hi & !HI_SIGN_BIT) << 128) + lo;
+    if hi & HI_SIGN_BIT != 0 {
+        -mag - 1
+    } else {
+        mag
+    }
+}
+
+/// Returns the signed number of steps from `b` to `a` in the ordered
+/// sequence of f256 values, i.e. the distance in ulps, positive if `a` is
+/// greater than `b`, or `None` if one of them is NaN. -0 and +0 count as
+/// different values one step apart, ∞ as the value following MAX.
+pub fn ulp_diff(a: &FP237, b: &FP237) -> Option<Integer> {
+    if a.f.is_nan() || b.f.is_nan() {
+        None
+    } else {
+        Some(ordinal(a) - ordinal(b))
+    }
+}
+
+/// Returns |`approx` - `exact`| / |`exact`|, computed exactly and rounded
+/// once to nearest. The error is 0 for equal values, including equal
+/// infinities, NaN if one of the values is NaN and ∞ for an exact value of
+/// zero or ∞ differing from `approx`.
+pub fn rel_err(approx: &FP237, exact: &Float) -> FP237 {
+    if approx.f.is_nan() || exact.is_nan() {
+        return FP237::NAN();
+    }
+    if approx.f == *exact {
+        return FP237::ZERO();
+    }
+    match (approx.f.to_rational(), exact.to_rational()) {
+        (Some(a), Some(e)) if e != 0 => {
+            let err = ((a - &e) / e).abs();
+            FP237::with_val_round(&err, RoundingMode::NearestEven)
+        }
+        _ => FP237::INFINITY(),
+    }
+}
+
+/// Classes of errors measured in ulps of the exact value, see `ulp_class`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UlpClass {
+    /// At most half an ulp, as for results rounded to nearest
+    HalfUlp,
+    /// More than half an ulp, but at most one
+    OneUlp,
+    /// More than one ulp
+    Worse,
+}
+
+/// Returns |`approx` - `exact`| measured in ulps of `exact` in the f256
+/// format, computed exactly, or `None` if a value is not finite.
+pub fn ulp_err(approx: &FP237, exact: &Float) -> Option<Rational> {
+    let a = approx.f.to_rational()?;
+    let e = exact.to_rational()?;
+    // ulp of values in [2ᵗ⁻¹, 2ᵗ)
+    let ulp_exp = exact
+        .get_exp()
+        .map_or(MIN_EXP_SUBNORMAL, |t| (t - P as i32).max(MIN_EXP_SUBNORMAL));
+    let ulp = Float::with_val(P, Float::i_exp(1, ulp_exp))
+        .to_rational()
+        .unwrap();
+    Some((a - e).abs() / ulp)
+}
+
+/// Returns the class of the error of `approx` measured in ulps of `exact`
+/// in the f256 format, see `ulp_err`. Equal values, including equal
+/// infinities and NaNs, are in class `HalfUlp`, other non-finite values
+/// in class `Worse`.
+pub fn ulp_class(approx: &FP237, exact: &Float) -> UlpClass {
+    if approx.f == *exact || (approx.f.is_nan() && exact.is_nan()) {
+        return UlpClass::HalfUlp;
+    }
+    match ulp_err(approx, exact) {
+        Some(err) if err <= Rational::from((1, 2)) => UlpClass::HalfUlp,
+        Some(err) if err <= 1 => UlpClass::OneUlp,
+        _ => UlpClass::Worse,
+    }
+}
+
+#[cfg(test)]
+mod rel_err_tests {
+    use super::*;
+
+    #[test]
+    fn test_rel_err() {
+        let wp = 4 * P;
+        let third = Float::with_val(wp, 3).recip();
+        let x = FP237::with_val_round(&third, RoundingMode::NearestEven);
+        // 1/3 rounded to nearest is off by 1/3 ulp
+        let err = rel_err(&x, &third);
+        let bound = Float::with_val(wp, Float::i_exp(1, -(P as i32)));
+        assert!(err.f < bound && err.f > 0);
+        assert_eq!(ulp_class(&x, &third), UlpClass::HalfUlp);
+        assert_eq!(ulp_class(&x.next_up(), &third), UlpClass::OneUlp);
+        assert_eq!(ulp_class(&x.next_down(), &third), UlpClass::Worse);
+        assert_eq!(rel_err(&FP237::from(3), &Float::with_val(P, 2)).f, 0.5);
+        assert!(rel_err(&x, &Float::new(P)).f.is_infinite());
+        assert!(rel_err(&FP237::NAN(), &third).f.is_nan());
+        assert!(rel_err(
+            &FP237::INFINITY(),
+            &Float::with_val(P, Special::Infinity)
+        )
+        .f
+        .is_zero());
+        // ulps of subnormal values
+        let tiny = FP237::MIN_GT_ZERO();
+        let half_tiny = Float::with_val(P, &tiny.f / 2_u32);
+        assert_eq!(ulp_class(&tiny, &half_tiny), UlpClass::HalfUlp);
+        assert_eq!(ulp_class(&FP237::ZERO(), &tiny.f), UlpClass::OneUlp);
+        let two_tiny = Float::with_val(P, &tiny.f * 2_u32);
+        assert_eq!(ulp_class(&FP237::ZERO(), &two_tiny), UlpClass::Worse);
+    }
+}
+
+#[cfg(test)]
+mod ulp_diff_tests {
+    use super::*;
+
+    #[test]
+    fn test_ulp_diff() {
+        let d = |a: &FP237, b: &FP237| ulp_diff(a, b).unwrap();
+        let one = FP237::ONE();
+        assert_eq!(d(&one.next_up().next_up(), &one), 2);
+        assert_eq!(d(&one, &one.next_up().next_up()), -2);
+        assert_eq!(d(&FP237::from(2), &one), Integer::from(1) << PM1);
+        assert_eq!(d(&-one.clone(), &one), -(ordinal(&one) * 2_u32 + 1_u32));
+        // subnormal/normal boundary
+        let min_pos = FP237::MIN_POSITIVE();
+        assert_eq!(d(&min_pos, &min_pos.next_down()), 1);
+        assert_eq!(d(&min_pos.next_up(), &min_pos.next_down()), 2);
+        assert_eq!(
+            d(&min_pos, &FP237::MIN_GT_ZERO()),
+            (Integer::from(1) << PM1) - 1
+        );
+        assert_eq!(d(&FP237::ZERO(), &-FP237::ZERO()), 1);
+        assert_eq!(d(&FP237::MIN_GT_ZERO(), &-FP237::MIN_GT_ZERO()), 3);
+        assert_eq!(d(&FP237::INFINITY(), &FP237::MAX()), 1);
+        assert!(ulp_diff(&FP237::NAN(), &FP237::NAN()).is_none());
+        assert!(ulp_diff(&one, &FP237::NAN()).is_none());
+    }
+}
+
+#[cfg(test)]
+mod decimal_limits_tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_limits() {
+        assert_eq!(max_10_exp(), 78913);
+        assert_eq!(min_10_exp(), -78912);
+        assert_eq!(min_subnormal_10_exp(), -78984);
+        assert_eq!(max_sig_digits(), 73);
+        // 10^max_10_exp fits, 10^(max_10_exp + 1) overflows
+        let ten = Float::with_val(P, 10);
+        let max = FP237::MAX().f;
+        assert!(Float::with_val(P, (&ten).pow(max_10_exp())) <= max);
+        assert!(Float::with_val(P, (&ten).pow(max_10_exp() + 1)) > max);
+    }
+
+    #[test]
+    fn test_max_exact_digits() {
+        let n = max_exact_digits();
+        assert_eq!(n, 183466);
+        // the exact value of MIN_GT_ZERO has fewer digits
+        let min = FP237::MIN_GT_ZERO().f;
+        let s = min.to_string_radix(10, Some(n as usize + 10));
+        let digits = s.split('e').next().unwrap().trim_end_matches('0');
+        assert!(digits.len() < n as usize, "{}", digits.len());
+    }
+}
+
 #[cfg(test)]
 mod decode_tests {
     use super::*;
@@ -390,113 +1821,631 @@ mod decode_tests {
     }
 
     #[test]
-    fn test_subnormal_near_zero() {
-        let s = "-21.75e-78985";
-        let f = FP237::from_str(s).unwrap();
-        assert_eq!(f.decode(true), (1, -262378, (0, 1)));
+    fn test_subnormal_near_zero() {
+        let s = "-21.75e-78985";
+        let f = FP237::from_str(s).unwrap();
+        assert_eq!(f.decode(true), (1, -262378, (0, 1)));
+    }
+
+    #[test]
+    fn test_subnormal_tie() {
+        let ulp = FP237::MIN_GT_ZERO().f;
+        let half = Float::with_val(P, &ulp >> 1);
+        let three_halves = Float::with_val(P, &ulp * 3) >> 1;
+        let tie =
+            |f: &Float, o: Ordering| FP237 { f: f.clone(), o }.decode(true);
+        // exact ties round to even
+        assert_eq!(tie(&half, Ordering::Equal), (0, 0, (0, 0)));
+        assert_eq!(tie(&three_halves, Ordering::Equal), (0, -262378, (0, 2)));
+        // otherwise the direction of the first rounding decides
+        assert_eq!(tie(&half, Ordering::Less), (0, -262378, (0, 1)));
+        assert_eq!(
+            tie(&three_halves, Ordering::Greater),
+            (0, -262378, (0, 1))
+        );
+        assert_eq!(
+            tie(&-half.clone(), Ordering::Greater),
+            (1, -262378, (0, 1))
+        );
+        assert_eq!(tie(&-half, Ordering::Less), (1, 0, (0, 0)));
+    }
+
+    #[test]
+    fn test_max() {
+        let e = Float::with_val(P, Float::parse("262144.").unwrap());
+        let a = e.exp2();
+        let e = Float::with_val(P, Float::parse("261907.").unwrap());
+        let b = e.exp2();
+        let t = a - b;
+        let f = FP237 {
+            f: t.clone(),
+            o: Ordering::Equal,
+        };
+        // println!("{f}");
+        assert_eq!(
+            f.decode(true),
+            (0, 261907, ((1 << (237 - 128)) - 1, u128::MAX))
+        );
+    }
+
+    #[test]
+    fn test_normal_gt1() {
+        let s = "320.1000009";
+        let f = FP237::from_str(s).unwrap();
+        // println!("{}", f);
+        assert_eq!(
+            f.decode(true),
+            (
+                0,
+                -228,
+                (
+                    405774958273941771624501157387890,
+                    164981958326160731124041003267846608813
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn test_normal_2_pow_275() {
+        const HI_HIDDEN_BIT: u128 = 1_u128 << 108;
+        let t = 275;
+        let mut c = Integer::from(HI_HIDDEN_BIT) << 128;
+        let p = Integer::from(2).pow(t as u32);
+        c *= p;
+        // println!("{c}");
+        let (f, o) = Float::with_val_round(P, &c, Round::Nearest);
+        // println!("{f:.0}");
+        let f = FP237 { f, o };
+        assert_eq!(f.f.prec(), P);
+        let (s, e, (h, _)) = f.decode(false);
+        assert!(s == 0 || s == 1);
+        assert_eq!(h.leading_zeros(), (256 - P));
+        assert_eq!(e, 275);
+    }
+
+    #[test]
+    fn test_normal_1() {
+        let (e, (h, l)) = (
+            -131198,
+            (
+                531439310060859797527772502089072_u128,
+                50579904501390594736454450167121974314_u128,
+            ),
+        );
+        let mut m = Integer::from(h);
+        m <<= 128;
+        m += Integer::from(l);
+        let t = Float::with_val(P, e).exp2();
+        let f = FP237 {
+            f: m * t,
+            o: Ordering::Equal,
+        };
+        assert_eq!(f.decode(false), (0_u32, e, (h, l)));
+        println!("{:?}", f.decode(false));
+        println!("{:?}", f.decode(true));
+    }
+}
+
+#[cfg(test)]
+mod rnd_tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_lt1() {
+        let exp_range: RangeInclusive<i32> = -304..=-236;
+        let f = FP237::random_from_exp_range(&exp_range);
+        assert_eq!(f.f.prec(), P);
+        let (s, e, (h, _)) = f.decode(true);
+        assert!(s == 0 || s == 1);
+        assert!(exp_range.contains(&(e + PM1)));
+        assert!(h.leading_zeros() >= 256 - P);
+    }
+
+    #[test]
+    fn test_subnormal() {
+        let exp_range: RangeInclusive<i32> = MIN_EXP_SUBNORMAL..=EMIN - 1;
+        for _ in 0..100 {
+            let f = FP237::random_from_exp_range(&exp_range).abs();
+            assert!(f >= FP237::MIN_GT_ZERO() && f < FP237::MIN_POSITIVE());
+        }
+        let f = FP237::random_from_exp_range(&(EMIN - 1..=EMIN - 1));
+        assert_eq!(f.f.get_exp(), Some(EMIN));
+    }
+
+    #[test]
+    fn test_normal_2_pow_275() {
+        let exp_range: RangeInclusive<i32> = 275..=275;
+        let f = FP237::random_from_exp_range(&exp_range);
+        assert_eq!(f.f.prec(), P);
+        let (s, e, (h, _)) = f.decode(false);
+        assert!(s == 0 || s == 1);
+        assert_eq!(h.leading_zeros(), (256 - P));
+        assert_eq!(e + PM1, 275);
+    }
+}
+
+#[cfg(test)]
+mod next_up_down_tests {
+    use super::*;
+
+    #[test]
+    fn test_next_up_down() {
+        assert_eq!(FP237::ZERO().next_up(), FP237::MIN_GT_ZERO());
+        assert_eq!(FP237::MAX().next_up(), FP237::INFINITY());
+        assert_eq!((-FP237::INFINITY()).next_up(), -FP237::MAX());
+        assert_eq!(
+            FP237::MIN_POSITIVE().next_down().next_up(),
+            FP237::MIN_POSITIVE()
+        );
+        assert_eq!(
+            (-FP237::ONE()).next_down().to_bits(),
+            ((1_u128 << 127) | (0x3ffff_u128 << 108), 1)
+        );
+    }
+}
+
+#[cfg(test)]
+mod generic_tests {
+    use super::*;
+
+    type FP64 = FP<53, 1023>;
+
+    #[test]
+    fn test_limits() {
+        assert_eq!(FP64::MAX().f, f64::MAX);
+        assert_eq!(FP64::MIN_POSITIVE().f, f64::MIN_POSITIVE);
+        assert_eq!(FP64::MIN_GT_ZERO().f, f64::from_bits(1));
+        assert_eq!(FP64::MAX().decode(false), (0, 971, (0, (1 << 53) - 1)));
+        assert_eq!(FP64::NAN().decode(false), (0, 1024, (0, 1 << 51)));
+        type FP16 = FP<11, 15>;
+        assert_eq!(FP16::MAX().f, 65504);
+        assert_eq!(FP16::MIN_GT_ZERO().f, Float::with_val(11, -24).exp2());
+        assert_eq!(FP255::MAX().f.prec(), 255);
+        assert_eq!(FP255::MAX().f.get_exp(), FP237::MAX().f.get_exp());
+    }
+
+    #[test]
+    fn test_arith() {
+        let mut rng = StdRng::seed_from_u64(237);
+        let exp_range = FP64::MIN_EXP_SUBNORMAL..=1023;
+        for _ in 0..1000 {
+            let x =
+                FP64::random_from_exp_range_with_rng(&mut rng, &exp_range);
+            let y =
+                FP64::random_from_exp_range_with_rng(&mut rng, &exp_range);
+            let (a, b) = (x.f.to_f64(), y.f.to_f64());
+            assert_eq!(x.f, a);
+            let rnd = RoundingMode::NearestEven;
+            assert_eq!(x.add_round(&y, rnd).f, a + b);
+            assert_eq!(x.mul_round(&y, rnd).f, a * b);
+            assert_eq!(x.div_round(&y, rnd).f, a / b);
+            assert_eq!(x.next_up().f, a.next_up());
+            assert_eq!(x.next_down().f, a.next_down());
+            let (s, e, (h, l)) = x.decode(true);
+            assert_eq!(FP64::encode(s, e, (h, l)).f, a);
+        }
+    }
+
+    #[test]
+    fn test_next_up_f256() {
+        let mut rng = StdRng::seed_from_u64(237);
+        let exp_range = MIN_EXP_SUBNORMAL..=EMAX;
+        for _ in 0..1000 {
+            let x =
+                FP237::random_from_exp_range_with_rng(&mut rng, &exp_range);
+            let (hi, lo) = x.to_bits();
+            let bits = if x.f.is_sign_positive() {
+                (hi + (lo == u128::MAX) as u128, lo.wrapping_add(1))
+            } else {
+                (hi - (lo == 0) as u128, lo.wrapping_sub(1))
+            };
+            assert_eq!(x.next_up().to_bits(), bits);
+        }
+        let y = (-FP237::MIN_GT_ZERO()).next_up();
+        assert!(y.f.is_zero() && y.f.is_sign_negative());
+    }
+
+    #[test]
+    fn test_assign_round() {
+        let mut rng = StdRng::seed_from_u64(217);
+        let exp_range = MIN_EXP_SUBNORMAL..=EMAX;
+        // a scratch value of another precision is adjusted
+        let mut z = FP237::new(Float::new(64));
+        for _ in 0..200 {
+            let x =
+                FP237::random_from_exp_range_with_rng(&mut rng, &exp_range);
+            let y =
+                FP237::random_from_exp_range_with_rng(&mut rng, &(-8..=8));
+            for rnd in ROUNDING_MODES {
+                let o = z.assign_round(&x.f * &y.f, rnd);
+                let expected = x.mul_round(&y, rnd);
+                assert_eq!(
+                    (z.to_bits(), o),
+                    (expected.to_bits(), expected.o)
+                );
+            }
+        }
+        // ties away from zero at both precisions, sharing the scratch
+        // values
+        let na = RoundingMode::NearestAway;
+        let tie =
+            |p: i32| Float::with_val(2 * P, Float::i_exp(1, -p)) + 1_u32;
+        let mut z64 = FP::<53, 1023>::ZERO();
+        for _ in 0..2 {
+            assert_eq!(z.assign_round(&tie(P as i32), na), Ordering::Greater);
+            assert_eq!(z.f, FP237::ONE().next_up().f);
+            assert_eq!(z64.assign_round(-tie(53), na), Ordering::Less);
+            assert_eq!(z64.f, -1.0_f64.next_up());
+        }
+    }
+
+    #[test]
+    fn test_assign_ops() {
+        let mut rng = StdRng::seed_from_u64(218);
+        let exp_range = MIN_EXP_SUBNORMAL..=EMAX;
+        for _ in 0..100 {
+            let x =
+                FP237::random_from_exp_range_with_rng(&mut rng, &exp_range);
+            let y =
+                FP237::random_from_exp_range_with_rng(&mut rng, &(-8..=8));
+            let a =
+                FP237::random_from_exp_range_with_rng(&mut rng, &exp_range);
+            for rnd in ROUNDING_MODES {
+                let check = |z: FP237, o: Ordering, expected: FP237| {
+                    assert_eq!(
+                        (z.to_bits(), o, z.o),
+                        (expected.to_bits(), expected.o, expected.o),
+                        "{rnd}"
+                    );
+                };
+                let mut z = x.clone();
+                let o = z.add_assign_round(&y, rnd);
+                check(z, o, x.add_round(&y, rnd));
+                let mut z = x.clone();
+                let o = z.sub_assign_round(&y, rnd);
+                check(z, o, x.sub_round(&y, rnd));
+                let mut z = x.clone();
+                let o = z.mul_assign_round(&y, rnd);
+                check(z, o, x.mul_round(&y, rnd));
+                let mut z = x.clone();
+                let o = z.div_assign_round(&y, rnd);
+                check(z, o, x.div_round(&y, rnd));
+                let mut z = y.clone();
+                let o = z.rem_assign_round(&x, rnd);
+                check(z, o, y.rem_round(&x, rnd));
+                let mut z = x.abs();
+                let o = z.sqrt_assign_round(rnd);
+                check(z, o, x.abs().sqrt_round(rnd));
+                let mut z = x.clone();
+                let o = z.fma_assign_round(&y, &a, rnd);
+                check(z, o, x.fma_round(&y, &a, rnd));
+            }
+        }
+        // a value of another precision is rounded once
+        let x = Float::with_val(2 * P, Float::i_exp(1, -(P as i32))) + 1_u32;
+        for rnd in ROUNDING_MODES {
+            let mut z = FP237::new(x.clone());
+            let o = z.mul_assign_round(&FP237::from(3), rnd);
+            let expected = FP237::with_val_round(&x * 3_u32, rnd);
+            assert_eq!((z.f, o), (expected.f, expected.o), "{rnd}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod fp113_tests {
+    use super::*;
+
+    #[test]
+    fn test_bits() {
+        assert_eq!(FP113::ONE().to_bits(), 0x3fff << 112);
+        assert_eq!(FP113::MAX().to_bits(), (0x7fff << 112) - 1);
+        assert_eq!(FP113::MIN_POSITIVE().to_bits(), 1 << 112);
+        assert_eq!(FP113::MIN_GT_ZERO().to_bits(), 1);
+        assert_eq!((-FP113::INFINITY()).to_bits(), 0xffff << 112);
+        assert_eq!(FP113::NAN().to_bits(), 0xffff << 111);
+        assert_eq!((-FP113::ZERO()).to_bits(), 1 << 127);
+        let x = FP113::from_bits(0x4000_8000 << 96);
+        assert_eq!(x.f, 3);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = StdRng::seed_from_u64(113);
+        let exp_range = FP113::MIN_EXP_SUBNORMAL..=16383;
+        for _ in 0..1000 {
+            let bits: u128 = rng.gen();
+            let x = FP113::from_bits(bits);
+            if !x.f.is_nan() {
+                assert_eq!(x.to_bits(), bits);
+            }
+            let x =
+                FP113::random_from_exp_range_with_rng(&mut rng, &exp_range);
+            assert_eq!(FP113::from_bits(x.to_bits()).f, x.f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod shortest_tests {
+    use super::*;
+
+    #[test]
+    fn test_specials() {
+        assert_eq!(FP237::ZERO().to_shortest_string(), "0e0");
+        assert_eq!((-FP237::ZERO()).to_shortest_string(), "-0e0");
+        assert_eq!(FP237::ONE().to_shortest_string(), "1e0");
+        assert_eq!((-FP237::INFINITY()).to_shortest_string(), "-inf");
+        assert_eq!(FP237::NAN().to_shortest_string(), "NaN");
+    }
+
+    #[test]
+    fn test_short_literals() {
+        for lit in ["1e-1", "-2.5e3", "1.2345678901234567890123e-78900"] {
+            let f = FP237::from_str(lit).unwrap();
+            assert_eq!(f.to_shortest_string(), lit);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for f in [FP237::MAX(), FP237::MIN_POSITIVE(), FP237::MIN_GT_ZERO()] {
+            let s = f.to_shortest_string();
+            assert_eq!(FP237::from_str(&s).unwrap().f, f.f);
+        }
+        assert_eq!(FP237::MIN_GT_ZERO().to_shortest_string(), "2e-78984");
+    }
+}
+
+#[cfg(test)]
+mod hex_tests {
+    use super::*;
+
+    #[test]
+    fn test_to_hex_string() {
+        assert_eq!(FP237::ONE().to_hex_string(), "0x1p0");
+        assert_eq!((-FP237::ZERO()).to_hex_string(), "-0x0p0");
+        let f = FP237::from_str("-0.24609375").unwrap();
+        assert_eq!(f.to_hex_string(), "-0x1.f8p-3");
+        assert_eq!(FP237::MIN_GT_ZERO().to_hex_string(), "0x1p-262378");
+        assert_eq!(FP237::NAN().to_hex_string(), "NaN");
+    }
+
+    #[test]
+    fn test_from_hex_str() {
+        let f = FP237::from_str("-0x1f.8p-7").unwrap();
+        assert_eq!(f.f, -0.24609375);
+        let f = FP237::from_str("+0X1P-262379").unwrap();
+        assert!(f.f.is_zero());
+        assert!(FP237::from_str("0x1p262144").unwrap().f.is_infinite());
+        assert!(FP237::from_str("0x1g").is_err());
+        assert!(FP237::from_str("0x1p").is_err());
+        assert_eq!(FP237::from_str("0x1p+3").unwrap().f, 8);
+        assert!(FP237::from_str("0x1p99999999999").unwrap().f.is_infinite());
+        assert!(FP237::from_str("0x1p-99999999999").unwrap().f.is_zero());
+        for s in ["0x1pnan", "0x1p1.5", "0x1p1e3", "0x1pinf", "0x1p-"] {
+            assert!(FP237::from_str(s).is_err());
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for f in [FP237::MAX(), FP237::MIN_POSITIVE(), -FP237::Pi()] {
+            let s = f.to_hex_string();
+            assert_eq!(FP237::from_str(&s).unwrap().f, f.f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod fixed_tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed() {
+        let f = FP237::from_str("2.5").unwrap();
+        assert_eq!(f.to_fixed_string(0), "2");
+        assert_eq!((-f.clone()).to_fixed_string(3), "-2.500");
+        let f = FP237::from_str("0.375").unwrap();
+        assert_eq!(f.to_fixed_string(2), "0.38");
+        assert_eq!(f.to_fixed_string(0), "0");
+        assert_eq!((-FP237::ZERO()).to_fixed_string(1), "-0.0");
+        assert_eq!(FP237::MIN_GT_ZERO().to_fixed_string(5), "0.00000");
+        assert_eq!(
+            FP237::encode(0, 300, (0, 1)).to_fixed_string(0).len(),
+            91
+        );
+    }
+}
+
+#[cfg(test)]
+mod gamma_tests {
+    use super::*;
+
+    #[test]
+    fn test_gamma() {
+        let x = FP237::from_str("5").unwrap();
+        assert_eq!(x.gamma().f, 24);
+        let x = FP237::from_str("-3").unwrap();
+        assert!(x.gamma().f.is_nan());
+        let x = FP237::from_str("30000").unwrap();
+        assert!(x.gamma().f.is_infinite());
+    }
+
+    #[test]
+    fn test_ln_gamma() {
+        assert!(FP237::ONE().ln_gamma().f.is_zero());
+        let x = FP237::from_str("3").unwrap();
+        assert_eq!(x.ln_gamma().f, FP237::Log2().f);
+        assert!(FP237::MAX().ln_gamma().f.is_infinite());
     }
+}
+
+#[cfg(test)]
+mod frexp_ldexp_tests {
+    use super::*;
 
     #[test]
-    fn test_max() {
-        let e = Float::with_val(P, Float::parse("262144.").unwrap());
-        let a = e.exp2();
-        let e = Float::with_val(P, Float::parse("261907.").unwrap());
-        let b = e.exp2();
-        let t = a - b;
-        let f = FP237 {
-            f: t.clone(),
-            o: Ordering::Equal,
-        };
-        // println!("{f}");
+    fn test_frexp() {
+        let (m, e) = FP237::from_str("-12").unwrap().frexp();
+        assert_eq!((m.f, e), (Float::with_val(P, -0.75), 4));
+        let (m, e) = FP237::MIN_GT_ZERO().frexp();
         assert_eq!(
-            f.decode(true),
-            (0, 261907, ((1 << (237 - 128)) - 1, u128::MAX))
+            (m.f, e),
+            (Float::with_val(P, 0.5), MIN_EXP_SUBNORMAL + 1)
         );
+        assert_eq!(FP237::ZERO().frexp(), (FP237::ZERO(), 0));
     }
 
     #[test]
-    fn test_normal_gt1() {
-        let s = "320.1000009";
-        let f = FP237::from_str(s).unwrap();
-        // println!("{}", f);
+    fn test_ldexp() {
+        let x = FP237::MIN_POSITIVE();
+        assert_eq!(x.ldexp(-PM1).f, FP237::MIN_GT_ZERO().f);
+        assert!(x.ldexp(-PM1 - 2).f.is_zero());
+        assert!(FP237::MAX().ldexp(1).f.is_infinite());
         assert_eq!(
-            f.decode(true),
-            (
-                0,
-                -228,
-                (
-                    405774958273941771624501157387890,
-                    164981958326160731124041003267846608813
-                )
-            )
+            FP237::MIN_GT_ZERO().ldexp(EMAX - MIN_EXP_SUBNORMAL).f,
+            Float::with_val(P, Float::i_exp(1, EMAX))
         );
     }
 
     #[test]
-    fn test_normal_2_pow_275() {
-        const HI_HIDDEN_BIT: u128 = 1_u128 << 108;
-        let t = 275;
-        let mut c = Integer::from(HI_HIDDEN_BIT) << 128;
-        let p = Integer::from(2).pow(t as u32);
-        c *= p;
-        // println!("{c}");
-        let (f, o) = Float::with_val_round(P, &c, Round::Nearest);
-        // println!("{f:.0}");
-        let f = FP237 { f, o };
-        assert_eq!(f.f.prec(), P);
-        let (s, e, (h, _)) = f.decode(false);
-        assert!(s == 0 || s == 1);
-        assert_eq!(h.leading_zeros(), (256 - P));
-        assert_eq!(e, 275);
+    fn test_ilogb_logb() {
+        assert_eq!(FP237::MIN_GT_ZERO().ilogb(), MIN_EXP_SUBNORMAL);
+        assert_eq!(FP237::MAX().ilogb(), EMAX);
+        assert_eq!(FP237::ZERO().ilogb(), i32::MIN);
+        assert_eq!(FP237::ONE().logb(), FP237::ZERO());
+        assert_eq!(FP237::ZERO().logb(), -FP237::INFINITY());
     }
+}
+
+#[cfg(test)]
+mod round_to_int_tests {
+    use super::*;
 
     #[test]
-    fn test_normal_1() {
-        let (e, (h, l)) = (
-            -131198,
-            (
-                531439310060859797527772502089072_u128,
-                50579904501390594736454450167121974314_u128,
-            ),
+    fn test_round_to_int() {
+        let x = FP237::from_str("-2.5").unwrap();
+        let res: Vec<FP237> = ROUNDING_MODES
+            .iter()
+            .map(|rnd| x.round_to_int(*rnd))
+            .collect();
+        let exp: Vec<FP237> = ["-2", "-2", "-2", "-3", "-3"]
+            .iter()
+            .map(|s| FP237::from_str(s).unwrap())
+            .collect();
+        assert_eq!(res, exp);
+        let x = FP237::from_str("-0.25").unwrap();
+        let z = x.round_to_int(RoundingMode::Up);
+        assert!(z.f.is_zero() && z.f.is_sign_negative());
+    }
+
+    #[test]
+    fn test_fract() {
+        let x = FP237::from_str("-2.75").unwrap();
+        assert_eq!(x.fract().f, -0.75);
+        let z = FP237::from_str("-2").unwrap().fract();
+        assert!(z.f.is_zero() && z.f.is_sign_negative());
+        assert!(FP237::INFINITY().fract().f.is_nan());
+    }
+}
+
+#[cfg(test)]
+mod to_binary_tests {
+    use super::*;
+
+    #[test]
+    fn test_to_f64() {
+        let one = FP237::ONE();
+        let e = FP237::encode(0, -53, (0, 1));
+        let x = &one + &e;
+        assert_eq!(x.to_f64_round(RoundingMode::NearestEven), 1.0);
+        assert_eq!(
+            x.to_f64_round(RoundingMode::NearestAway),
+            1.0 + f64::EPSILON
         );
-        let mut m = Integer::from(h);
-        m <<= 128;
-        m += Integer::from(l);
-        let t = Float::with_val(P, e).exp2();
-        let f = FP237 {
-            f: m * t,
-            o: Ordering::Equal,
-        };
-        assert_eq!(f.decode(false), (0_u32, e, (h, l)));
-        println!("{:?}", f.decode(false));
-        println!("{:?}", f.decode(true));
+        assert_eq!(x.to_f64_round(RoundingMode::Down), 1.0);
+        assert_eq!(x.to_f64_round(RoundingMode::Up), 1.0 + f64::EPSILON);
+        let x = FP237::encode(0, 1024, (0, 1));
+        assert_eq!(x.to_f64_round(RoundingMode::TowardZero), f64::MAX);
+        assert_eq!(x.to_f64_round(RoundingMode::NearestAway), f64::INFINITY);
+        let x = FP237::encode(1, -1075, (0, 1));
+        assert_eq!(x.to_f64_round(RoundingMode::NearestEven), -0.0);
+        assert_eq!(x.to_f64_round(RoundingMode::NearestAway), -4.9e-324);
+    }
+
+    #[test]
+    fn test_to_f32() {
+        let x = FP237::encode(0, -150, (0, 3));
+        assert_eq!(x.to_f32_round(RoundingMode::NearestEven), 2.8e-45);
+        assert_eq!(x.to_f32_round(RoundingMode::TowardZero), 1.4e-45);
     }
 }
 
 #[cfg(test)]
-mod rnd_tests {
+mod round_tests {
     use super::*;
 
     #[test]
-    fn test_normal_lt1() {
-        let exp_range: RangeInclusive<i32> = -304..=-236;
-        let f = FP237::random_from_exp_range(&exp_range);
-        assert_eq!(f.f.prec(), P);
-        let (s, e, (h, _)) = f.decode(true);
-        assert!(s == 0 || s == 1);
-        assert!(exp_range.contains(&(e + PM1)));
-        assert!(h.leading_zeros() >= 256 - P);
+    fn test_overflow() {
+        let x = FP237::MAX();
+        let y = FP237::MIN_POSITIVE();
+        assert_eq!(
+            x.add_round(&x, RoundingMode::TowardZero).f,
+            FP237::MAX().f
+        );
+        assert_eq!(
+            x.add_round(&x, RoundingMode::NearestEven).f,
+            FP237::INFINITY().f
+        );
+        assert_eq!(x.add_round(&y, RoundingMode::Up).f, FP237::INFINITY().f);
+        assert_eq!(
+            x.add_round(&y, RoundingMode::NearestEven).f,
+            FP237::MAX().f
+        );
+        let z = (-x.clone()).sub_round(&x, RoundingMode::Up);
+        assert_eq!(z.f, (-FP237::MAX()).f);
+        assert_eq!(z.o, Ordering::Greater);
     }
 
     #[test]
-    fn test_normal_2_pow_275() {
-        let exp_range: RangeInclusive<i32> = 275..=275;
-        let f = FP237::random_from_exp_range(&exp_range);
-        assert_eq!(f.f.prec(), P);
-        let (s, e, (h, _)) = f.decode(false);
-        assert!(s == 0 || s == 1);
-        assert_eq!(h.leading_zeros(), (256 - P));
-        assert_eq!(e + PM1, 275);
+    fn test_subnormal() {
+        let x = FP237::MIN_GT_ZERO();
+        let h = FP237::from_str("0.5").unwrap();
+        assert_eq!(
+            x.mul_round(&h, RoundingMode::NearestEven).f,
+            FP237::ZERO().f
+        );
+        assert_eq!(x.mul_round(&h, RoundingMode::Up).f, x.f);
+        assert_eq!(x.mul_round(&h, RoundingMode::Down).f, FP237::ZERO().f);
+        let z = (-x.clone()).mul_round(&h, RoundingMode::Down);
+        assert_eq!(z.f, (-x.clone()).f);
+        let y = &x + &x;
+        let t = FP237::from_str("0.75").unwrap();
+        assert_eq!(y.mul_round(&t, RoundingMode::NearestEven).f, y.f);
+        assert_eq!(y.mul_round(&t, RoundingMode::TowardZero).f, x.f);
+    }
+
+    #[test]
+    fn test_ties() {
+        let one = FP237::ONE();
+        let e = FP237::encode(0, -PM1 - 1, (0, 1));
+        let x = &one + &(&e + &e);
+        let t = x.add_round(&e, RoundingMode::NearestEven);
+        assert_eq!(t.f, (&x + &(&e + &e)).f);
+        let t = x.add_round(&e, RoundingMode::NearestAway);
+        assert_eq!(t.f, (&x + &(&e + &e)).f);
+        let t = one.add_round(&e, RoundingMode::NearestEven);
+        assert_eq!(t.f, one.f);
+        let t = one.add_round(&e, RoundingMode::NearestAway);
+        assert_eq!(t.f, (&one + &(&e + &e)).f);
+        let t = (-one.clone()).sub_round(&e, RoundingMode::NearestAway);
+        assert_eq!(t.f, (-(&one + &(&e + &e))).f);
+        let x = FP237::MIN_GT_ZERO();
+        let h = FP237::from_str("0.5").unwrap();
+        let t = x.mul_round(&h, RoundingMode::NearestAway);
+        assert_eq!(t.f, x.f);
     }
 }
 
@@ -620,7 +2569,7 @@ mod mul_tests {
         println!("{:?}", z.decode(false));
         assert_eq!(
             z.decode(false),
-            ((0, -23862, (424661712810566800616627487375360, 0)))
+            (0, -23862, (424661712810566800616627487375360, 0))
         );
     }
 
@@ -638,7 +2587,7 @@ mod mul_tests {
         println!("{:?}", y.decode(false));
         let z = &x * &y;
         println!("{:?}", z.decode(false));
-        assert_eq!(z.decode(false), ((0, 262144, (0, 0))));
+        assert_eq!(z.decode(false), (0, 262144, (0, 0)));
     }
 }
 
@@ -666,14 +2615,14 @@ mod div_tests {
         // println!("{:?}", z.decode(true));
         assert_eq!(
             z.decode(false),
-            ((
+            (
                 0,
                 -238,
                 (
                     370544523143478119304928052297435,
                     56225130705079841269183023087285862379
                 )
-            ))
+            )
         );
     }
 
@@ -691,7 +2640,7 @@ mod div_tests {
         println!("{:?}", y.decode(false));
         let z = &x * &y;
         println!("{:?}", z.decode(false));
-        assert_eq!(z.decode(false), ((0, 262144, (0, 0))));
+        assert_eq!(z.decode(false), (0, 262144, (0, 0)));
     }
 }
 
@@ -732,6 +2681,119 @@ mod rem_tests {
     }
 }
 
+#[cfg(test)]
+mod remquo_tests {
+    use super::*;
+
+    #[test]
+    fn test_ties_to_even() {
+        let y = FP237::from(2);
+        for (x, r, q) in [(5, 1, 2), (7, -1, 4), (3, -1, 2), (1, 1, 0)] {
+            let (z, n) = FP237::from(x).remquo(&y);
+            assert_eq!((z.f, n), (Float::with_val(P, r), q));
+        }
+        let (z, n) = (-FP237::from(7)).remquo(&y);
+        assert_eq!((z.f, n), (Float::with_val(P, 1), -4));
+    }
+
+    #[test]
+    fn test_low_bits() {
+        // n = 2⁴⁰ + 3
+        let y = FP237::from(3);
+        let x = FP237::new(Float::with_val(P, (1_u64 << 40) + 3) * 3);
+        let (z, n) = x.remquo(&y);
+        assert!(z.f.is_zero());
+        assert_eq!(n, 3);
+    }
+}
+
+#[cfg(test)]
+mod euclid_tests {
+    use super::*;
+
+    fn fp(f: f64) -> FP237 {
+        FP237::new(Float::with_val(P, f))
+    }
+
+    #[test]
+    fn test_mixed_signs() {
+        for (x, y) in [(7.5, 2.0), (-7.5, 2.0), (7.5, -2.0), (-7.5, -2.0)] {
+            let (x, y) = (fp(x), fp(y));
+            let q = x.div_euclid(&y);
+            let r = x.rem_euclid(&y);
+            assert_eq!(q.f, f64::div_euclid(x.f.to_f64(), y.f.to_f64()));
+            assert_eq!(r.f, f64::rem_euclid(x.f.to_f64(), y.f.to_f64()));
+            assert_eq!(Float::with_val(2 * P, &q.f * &y.f) + &r.f, x.f);
+        }
+    }
+
+    #[test]
+    fn test_rem_rounds_to_rhs() {
+        let x = -FP237::MIN_GT_ZERO();
+        let y = FP237::ONE();
+        assert_eq!(x.rem_euclid(&y).f, 1);
+        assert_eq!(x.div_euclid(&y).f, -1);
+    }
+
+    #[test]
+    fn test_large_quotient() {
+        // the integer part of 2¹⁰⁰⁰ / 3 has 999 bits, it is rounded once
+        let x = FP237::new(Float::with_val(P, Float::i_exp(1, 1000)));
+        let y = fp(3.0);
+        let exact: Float = Float::with_val(1024, Float::i_exp(1, 1000)) / 3;
+        let q = x.div_euclid(&y);
+        assert_eq!(q.f, Float::with_val(P, exact.floor()));
+    }
+
+    #[test]
+    fn test_infinite_rhs() {
+        let inf = FP237::INFINITY();
+        assert_eq!(fp(-1.5).div_euclid(&inf).f, -1);
+        assert_eq!(fp(-1.5).div_euclid(&-inf.clone()).f, 1);
+        assert!(fp(1.5).div_euclid(&inf).f.is_zero());
+        assert_eq!(fp(-1.5).rem_euclid(&inf).f, inf.f);
+    }
+}
+
+#[cfg(test)]
+mod powi_tests {
+    use super::*;
+
+    fn pow2(t: i32) -> FP237 {
+        FP237::new(Float::with_val(P, Float::i_exp(1, t)))
+    }
+
+    #[test]
+    fn test_normal() {
+        let x = FP237::from(3);
+        assert_eq!(x.powi(5).f, 243);
+        assert_eq!(x.powi(-1).f, Float::with_val(P, 3).recip());
+        let z = x.powi(200);
+        assert_eq!(z.f, Float::with_val(P, Integer::from(3).pow(200)));
+    }
+
+    #[test]
+    fn test_unit_exps() {
+        for x in [FP237::ZERO(), -FP237::MAX(), FP237::NAN()] {
+            assert_eq!(x.powi(0).f, 1);
+        }
+        assert!(FP237::NAN().powi(1).f.is_nan());
+        let z = (-FP237::ZERO()).powi(-1);
+        assert!(z.f.is_infinite() && z.f.is_sign_negative());
+        assert_eq!(FP237::MIN_GT_ZERO().powi(-1).f, FP237::INFINITY().f);
+    }
+
+    #[test]
+    fn test_overflow_underflow() {
+        assert_eq!(pow2(-2623).powi(100).f, pow2(-262300).f);
+        assert!(pow2(-2623).powi(101).f.is_zero());
+        assert_eq!(pow2(2623).powi(-100).f, pow2(-262300).f);
+        assert_eq!(pow2(-2623).powi(-100).f, FP237::INFINITY().f);
+        assert_eq!(FP237::from(2).powi(EMAX).f, pow2(EMAX).f);
+        assert!(FP237::from(2).powi(EMAX + 1).f.is_infinite());
+    }
+}
+
 #[cfg(test)]
 mod sqrt_tests {
     use rug::{ops::CompleteRound, Complete};
@@ -751,7 +2813,7 @@ mod sqrt_tests {
     fn test_normal_1() {
         let (f, o) = Float::with_val_round(P, 7., Round::Nearest);
         let x = FP237 { f, o };
-        let z = x.clone().sqrt();
+        let z = x.sqrt();
         println!("√{x:e} = {z:e}");
         println!("{:?}", x.decode(false));
         println!("{:?}", z.decode(false));
@@ -767,7 +2829,7 @@ mod sqrt_tests {
         let t = e.exp2();
         let (f, o) = Float::with_val_round(P, &t * &c, Round::Nearest);
         let x = FP237 { f, o };
-        let z = x.clone().sqrt();
+        let z = x.sqrt();
         println!("√{x:e} = {z:e}");
         println!("{:?}", x.decode(true));
         println!("{:?}", z.decode(true));
@@ -786,7 +2848,7 @@ mod sqrt_tests {
             f: m * t,
             o: Ordering::Equal,
         };
-        let r = f.clone().sqrt();
+        let r = f.sqrt();
         println!("√{f:e} = {r:e}");
         println!("{:?}", f.decode(true));
         println!("{:?}", f.decode(false));
@@ -808,7 +2870,7 @@ mod sqrt_tests {
             f: m * t,
             o: Ordering::Equal,
         };
-        let r = f.clone().sqrt();
+        let r = f.sqrt();
         println!("√{f:e} = {r:e}");
         println!("{:?}", f.decode(true));
         println!("{:?}", f.decode(false));
@@ -825,7 +2887,7 @@ mod sqrt_tests {
         let t = e.exp2();
         let (f, o) = Float::with_val_round(P, &t * &c, Round::Nearest);
         let x = FP237 { f, o };
-        let z = x.clone().sqrt();
+        let z = x.sqrt();
         println!("√{x:e} = {z:e}");
         println!("{:?}", x.decode(true));
         println!("{:?}", z.decode(true));
@@ -908,6 +2970,125 @@ mod fma_tests {
     }
 }
 
+#[cfg(test)]
+mod fms_fnma_fnms_tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_signs() {
+        let z = FP237::ZERO();
+        let nz = -FP237::ZERO();
+        let one = FP237::ONE();
+        // x * y = +0, a = +0
+        assert!(z.fms(&one, &z).f.is_sign_positive());
+        assert!(z.fnma(&one, &z).f.is_sign_positive());
+        assert!(z.fnms(&one, &z).f.is_sign_negative());
+        // x * y = -0, a = +0
+        assert!(nz.fms(&one, &z).f.is_sign_negative());
+        assert!(nz.fnma(&one, &z).f.is_sign_positive());
+        assert!(nz.fnms(&one, &z).f.is_sign_positive());
+        // x * y = a = 1
+        assert!(one.fms(&one, &one).f.is_sign_positive());
+        assert!(one.fnma(&one, &one).f.is_sign_positive());
+        assert_eq!(one.fnms(&one, &one).f, -2);
+    }
+
+    #[test]
+    fn test_range_edges() {
+        let max = FP237::MAX();
+        let one = FP237::ONE();
+        let two = FP237::from(2);
+        assert_eq!(max.fms(&two, &one).f, FP237::INFINITY().f);
+        assert_eq!(max.fnma(&two, &one).f, -FP237::INFINITY().f);
+        assert_eq!(max.fnms(&two, &one).f, -FP237::INFINITY().f);
+        // ½ · MIN_GT_ZERO is a tie between 0 and MIN_GT_ZERO
+        let x = FP237::MIN_GT_ZERO();
+        let z = FP237::ZERO();
+        assert!(x.fms(&FP237::from_str("0.5").unwrap(), &z).f.is_zero());
+        let y = FP237::from_str("0.75").unwrap();
+        assert_eq!(x.fms(&y, &z).f, x.f);
+        assert_eq!(x.fnma(&y, &z).f, -x.f.clone());
+    }
+}
+
+#[cfg(test)]
+mod two_sum_two_prod_tests {
+    use super::*;
+
+    #[test]
+    fn test_two_sum() {
+        let one = FP237::ONE();
+        let e = FP237::encode(0, -300, (0, 3));
+        let (hi, lo) = one.two_sum(&e);
+        assert_eq!((hi.f, lo.f), (one.f, e.f));
+    }
+
+    #[test]
+    fn test_two_prod() {
+        let x = &FP237::ONE() + &FP237::encode(0, -PM1, (0, 1));
+        let (hi, lo) = x.two_prod(&x);
+        assert_eq!(hi.f, (&FP237::ONE() + &FP237::encode(0, -235, (0, 1))).f);
+        assert_eq!(lo.f, FP237::encode(0, -472, (0, 1)).f);
+    }
+}
+
+#[cfg(test)]
+mod dot_tests {
+    use super::*;
+
+    #[test]
+    fn test_dot() {
+        let big = FP237::encode(0, 300, (0, 1));
+        let one = FP237::ONE();
+        let xs = [big.clone(), one.clone(), -big.clone()];
+        let ys = [big.clone(), one.clone(), big.clone()];
+        assert_eq!(FP237::dot(&xs, &ys).f, 1);
+        assert!(FP237::dot(&[], &[]).f.is_zero());
+    }
+
+    #[test]
+    fn test_exact_sum() {
+        let big = FP237::encode(0, 300, (0, 1));
+        let tiny = FP237::MIN_GT_ZERO();
+        let one = FP237::ONE();
+        let xs = [big.clone(), one.clone(), tiny.clone(), -big.clone()];
+        // 1 + tiny rounds down
+        let (s, o) = FP237::exact_sum(&xs);
+        assert_eq!((s.f, o), (one.f.clone(), Ordering::Less));
+        let s: FP237 = xs.iter().sum();
+        assert_eq!(s.f, 1);
+        let (s, o) = FP237::exact_sum(&[]);
+        assert!(s.f.is_zero() && s.f.is_sign_positive());
+        assert_eq!(o, Ordering::Equal);
+        // the result underflows to subnormal values like a single addition
+        let min_pos = FP237::MIN_POSITIVE();
+        let d = [min_pos.clone(), -tiny.clone(), -tiny.clone()];
+        assert_eq!(FP237::exact_sum(&d).0, &(&min_pos - &tiny) - &tiny);
+    }
+
+    #[test]
+    fn test_exact_product() {
+        let three = FP237::from(3);
+        let third = &FP237::ONE() / &three;
+        // 3·(1/3)·3 is not 3 when rounded stepwise
+        let xs = [three.clone(), third.clone(), three.clone()];
+        let (p, o) = FP237::exact_product(&xs);
+        assert_eq!(p.f, xs.iter().cloned().product::<FP237>().f);
+        let exact = Float::with_val(3 * P, &third.f * 9_u32);
+        assert_eq!(
+            p,
+            FP237::with_val_round(exact, RoundingMode::NearestEven)
+        );
+        assert_ne!(o, Ordering::Equal);
+        let (one, _) = FP237::exact_product(&[]);
+        assert_eq!(one.f, 1);
+        // no intermediate overflow
+        let half = FP237::encode(0, -1, (0, 1));
+        let xs = [FP237::MAX(), FP237::from(2), half];
+        assert_eq!(FP237::exact_product(&xs).0, FP237::MAX());
+    }
+}
+
 #[cfg(test)]
 mod sos_tests {
     use rug::ops::CompleteRound;
@@ -948,6 +3129,147 @@ mod sos_tests {
         // );
         assert_eq!(z.f, r.f);
     }
+
+    #[test]
+    fn test_range_edges() {
+        let x = FP237::MAX().sqrt();
+        assert_eq!(x.sos(&x).f, FP237::INFINITY().f);
+        let x = FP237::new(Float::with_val(P, Float::i_exp(1, -131190)));
+        // 2 * 2⁻²⁶²³⁸⁰ is a tie between 0 and MIN_GT_ZERO
+        assert!(x.sos(&x).f.is_zero());
+        let y = x.next_up();
+        assert_eq!(x.sos(&y).f, FP237::MIN_GT_ZERO().f);
+    }
+}
+
+#[cfg(test)]
+mod hypot_tests {
+    use super::*;
+
+    #[test]
+    fn test_range_edges() {
+        let x = FP237::MAX();
+        assert_eq!(x.hypot(&x).f, FP237::INFINITY().f);
+        // √2 · MIN_GT_ZERO rounds to MIN_GT_ZERO
+        let x = FP237::MIN_GT_ZERO();
+        assert_eq!(x.hypot(&x).f, x.f);
+    }
+}
+
+#[cfg(test)]
+mod exp_tests {
+    use super::*;
+
+    #[test]
+    fn test_range_edges() {
+        let x = FP237::from(190000);
+        assert_eq!(x.exp().f, FP237::INFINITY().f);
+        assert_eq!(x.exp_m1().f, FP237::INFINITY().f);
+        // e⁻¹⁹⁰⁰⁰⁰ < 2⁻²⁷⁴⁰⁰⁰ is below ½ · MIN_GT_ZERO
+        let x = FP237::from(-190000);
+        assert!(x.exp().f.is_zero());
+        assert_eq!(x.exp_m1().f, -1);
+        // e⁻¹⁸¹⁸⁰⁰ ≈ 2⁻²⁶²²⁸² is subnormal, i.e. a multiple of MIN_GT_ZERO
+        let z = FP237::from(-181800).exp();
+        assert!(z.f.get_exp().unwrap() < EMIN);
+        let m = Float::with_val(P, &z.f >> MIN_EXP_SUBNORMAL);
+        assert!(m.is_integer());
+    }
+}
+
+#[cfg(test)]
+mod ln_tests {
+    use super::*;
+
+    #[test]
+    fn test_range_edges() {
+        let x = FP237::MIN_GT_ZERO();
+        assert_eq!(x.log2().f, MIN_EXP_SUBNORMAL);
+        assert!(x.ln().f < 0 && x.log10().f < 0);
+        assert!(FP237::ZERO().ln().f.is_infinite());
+        // ln(1 + x) = x - x²/2 + … rounds to the subnormal x
+        let x = FP237::new(Float::with_val(P, 3) << MIN_EXP_SUBNORMAL);
+        assert_eq!(x.ln_1p().f, x.f);
+        assert_eq!((-x.clone()).ln_1p().f, -x.f.clone());
+        assert_eq!(FP237::MAX().ln_1p().f, FP237::MAX().ln().f);
+    }
+}
+
+#[cfg(test)]
+mod reduce_pi_2_tests {
+    use super::*;
+
+    #[test]
+    fn test_reduce_small() {
+        let x = FP237::from_str("0.5").unwrap();
+        let (k, hi, lo) = x.reduce_pi_2();
+        assert_eq!((k, hi.f, lo.f), (0, x.f, Float::new(P)));
+        let x = FP237::from_str("-3").unwrap();
+        let (k, hi, lo) = x.reduce_pi_2();
+        assert_eq!(k, 2);
+        let r = Float::with_val(2 * P, Constant::Pi) - 3;
+        assert_eq!(hi.f, Float::with_val(P, &r));
+        assert!(lo.f.abs() < Float::with_val(P, Float::i_exp(1, -PM1)));
+    }
+
+    #[test]
+    fn test_reduce_large() {
+        let x = FP237::MAX();
+        let (k, hi, lo) = x.reduce_pi_2();
+        let s = FP237::new(Float::with_val(3 * P, &hi.f + &lo.f));
+        let s = match k {
+            0 => s.sin(),
+            1 => s.cos(),
+            2 => -s.sin(),
+            _ => -s.cos(),
+        };
+        assert_eq!(s.f, x.sin().f);
+    }
+}
+
+#[cfg(test)]
+mod split_hi_lo_tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let c = Float::with_val(4 * P, Constant::Pi).recip() * 2;
+        let terms = FP237::split_hi_lo(&c, 2);
+        assert_eq!(terms[0].f, Float::with_val(P, &c));
+        let ulp = Float::with_val(P, Float::i_exp(1, terms[0].ilogb() - PM1));
+        assert!(terms[1].f.clone().abs() <= ulp / 2);
+        for n in 2..=3 {
+            let terms = FP237::split_hi_lo(&c, n);
+            let mut rest = c.clone();
+            for t in &terms {
+                rest -= &t.f;
+            }
+            let bound = Float::with_val(P, &c >> (n as u32 * P - 2));
+            assert!(rest.abs() < bound, "{n} terms");
+        }
+    }
+
+    #[test]
+    fn test_split_consts() {
+        let [hi, lo] = FP237::FRAC_2_PI_SPLIT2();
+        let c = Float::with_val(4 * P, Constant::Pi).recip() * 2;
+        assert_eq!(hi.f, Float::with_val(P, &c));
+        assert_eq!(lo.f, Float::with_val(P, c - &hi.f));
+        let [hi, lo] = FP237::FRAC_PI_2_SPLIT2();
+        assert_eq!(FP237::FRAC_PI_2_SPLIT3()[..2], [hi, lo]);
+        let [hi, mid, lo] = FP237::LN_2_SPLIT3();
+        assert_eq!(FP237::LN_2_SPLIT2(), [hi.clone(), mid.clone()]);
+        assert_eq!(hi.f, FP237::Log2().f);
+        let exact = Float::with_val(4 * P, Constant::Log2);
+        let rest = Float::with_val(4 * P, exact - &hi.f - &mid.f - &lo.f);
+        assert!(rest.abs() < Float::with_val(P, Float::i_exp(1, -3 * PM1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "3 terms need at least 711 bits")]
+    fn test_split_prec() {
+        FP237::split_hi_lo(&Float::with_val(2 * P, Constant::Pi), 3);
+    }
 }
 
 #[cfg(test)]
@@ -976,12 +3298,7 @@ mod sin_tests {
         println!("{:?}", a.sin().decode(false));
         println!("{:?}", r.cos().decode(false));
         // println!("{:e}\n{:e}\n{:e}", a.f, ph.f, r.f);
-        let c = Integer::from(2).div(Float::with_val(250, Constant::Pi));
-        let (f, o) = Float::with_val_round(P, &c, Round::Nearest);
-        let ch = FP237 { f, o };
-        let (f, o) = Float::with_val_round(P, &c - &ch.f, Round::Nearest);
-        let cl = FP237 { f, o };
-        println!("{}", c);
+        let [ch, cl] = FP237::FRAC_2_PI_SPLIT2();
         println!("{}", ch.f);
         println!("{}", cl.f);
         println!("{:?}", ch.decode(true));
@@ -0,0 +1,269 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Vectors and matrices of f256 values, for generating test data for
+//! numerical kernels built on f256.
+//!
+//! Sums of products are accumulated exactly and rounded once to nearest,
+//! ties to even, so the results don't depend on the order of the terms.
+
+use rug::{Float, Rational};
+
+use crate::{RoundingMode, FP237, P};
+
+/// Column vector of f256 values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vector237 {
+    pub elems: Vec<FP237>,
+}
+
+/// Matrix of f256 values, stored row by row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix237 {
+    pub n_rows: usize,
+    pub n_cols: usize,
+    pub elems: Vec<FP237>,
+}
+
+impl Vector237 {
+    pub fn new(elems: Vec<FP237>) -> Self {
+        Self { elems }
+    }
+
+    pub fn len(&self) -> usize {
+        self.elems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elems.is_empty()
+    }
+
+    /// Returns the sum of the products of the elements of `self` and
+    /// `other`, rounded once.
+    ///
+    /// # Panics
+    ///
+    /// If the vectors differ in length.
+    pub fn dot(&self, other: &Self) -> FP237 {
+        FP237::dot(&self.elems, &other.elems)
+    }
+
+    /// Returns the Euclidean norm, rounded once.
+    pub fn norm(&self) -> FP237 {
+        if self.elems.iter().any(|x| x.f.is_nan()) {
+            return FP237::NAN();
+        }
+        if self.elems.iter().any(|x| x.f.is_infinite()) {
+            return FP237::INFINITY();
+        }
+        let exps: Vec<i32> =
+            self.elems.iter().filter_map(|x| x.f.get_exp()).collect();
+        let (Some(e_min), Some(e_max)) =
+            (exps.iter().min(), exps.iter().max())
+        else {
+            return FP237::ZERO();
+        };
+        // the squares have 2P bits at most, so their sum is exact at prec
+        // bits
+        let n_bits = usize::BITS - self.len().leading_zeros();
+        let prec = 2 * (e_max - e_min) as u32 + 2 * P + n_bits;
+        let squares = self.elems.iter().map(|x| (&x.f, &x.f));
+        let sum = Float::with_val(prec, Float::dot(squares));
+        FP237::with_val_round(sum.sqrt_ref(), RoundingMode::NearestEven)
+    }
+}
+
+impl From<Vec<FP237>> for Vector237 {
+    fn from(elems: Vec<FP237>) -> Self {
+        Self::new(elems)
+    }
+}
+
+impl Matrix237 {
+    /// Returns the `n_rows` x `n_cols` matrix with the given elements,
+    /// stored row by row.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements doesn't fit the dimensions.
+    pub fn new(n_rows: usize, n_cols: usize, elems: Vec<FP237>) -> Self {
+        assert_eq!(elems.len(), n_rows * n_cols, "Invalid dimensions");
+        Self {
+            n_rows,
+            n_cols,
+            elems,
+        }
+    }
+
+    /// Returns the matrix with the given rows.
+    ///
+    /// # Panics
+    ///
+    /// If the rows differ in length.
+    pub fn from_rows(rows: Vec<Vec<FP237>>) -> Self {
+        let n_rows = rows.len();
+        let n_cols = rows.first().map_or(0, Vec::len);
+        Self::new(n_rows, n_cols, rows.into_iter().flatten().collect())
+    }
+
+    /// Returns the `n` x `n` identity matrix.
+    pub fn identity(n: usize) -> Self {
+        let elems = (0..n * n)
+            .map(|k| {
+                if k % (n + 1) == 0 {
+                    FP237::ONE()
+                } else {
+                    FP237::ZERO()
+                }
+            })
+            .collect();
+        Self::new(n, n, elems)
+    }
+
+    /// Returns the `n` x `n` Hilbert matrix, 1 / (i + j + 1), each element
+    /// rounded to nearest.
+    pub fn hilbert(n: usize) -> Self {
+        let elems = (0..n * n)
+            .map(|k| {
+                let d = (k / n + k % n + 1) as u32;
+                FP237::with_val_round(
+                    Rational::from((1, d)),
+                    RoundingMode::NearestEven,
+                )
+            })
+            .collect();
+        Self::new(n, n, elems)
+    }
+
+    pub fn row(&self, i: usize) -> &[FP237] {
+        &self.elems[i * self.n_cols..(i + 1) * self.n_cols]
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> &FP237 {
+        &self.row(i)[j]
+    }
+
+    /// Returns the product of `self` and `x`, each element rounded once.
+    ///
+    /// # Panics
+    ///
+    /// If the length of `x` doesn't match the number of columns.
+    pub fn mul_vec(&self, x: &Vector237) -> Vector237 {
+        assert_eq!(x.len(), self.n_cols, "Invalid dimensions");
+        (0..self.n_rows)
+            .map(|i| FP237::dot(self.row(i), &x.elems))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    /// Returns the exact solution x of `self` · x = `b`, each element
+    /// rounded once, or `None` if `self` is singular or not square or an
+    /// element is not finite.
+    pub fn solve(&self, b: &Vector237) -> Option<Vector237> {
+        let n = self.n_rows;
+        if self.n_cols != n || b.len() != n {
+            return None;
+        }
+        // augmented matrix [A | b] of exact values
+        let mut m: Vec<Vec<Rational>> = (0..n)
+            .map(|i| {
+                self.row(i)
+                    .iter()
+                    .chain([&b.elems[i]])
+                    .map(|x| x.f.to_rational())
+                    .collect()
+            })
+            .collect::<Option<_>>()?;
+        // Gauss-Jordan elimination
+        for k in 0..n {
+            let p = (k..n).find(|&i| m[i][k] != 0)?;
+            m.swap(k, p);
+            let pivot = m[k][k].clone();
+            for v in m[k][k..].iter_mut() {
+                *v /= &pivot;
+            }
+            let pivot_row = m[k].clone();
+            for i in (0..n).filter(|&i| i != k) {
+                let f = m[i][k].clone();
+                if f == 0 {
+                    continue;
+                }
+                for (v, p) in m[i][k..].iter_mut().zip(&pivot_row[k..]) {
+                    *v -= Rational::from(&f * p);
+                }
+            }
+        }
+        Some(
+            m.iter()
+                .map(|row| {
+                    FP237::with_val_round(&row[n], RoundingMode::NearestEven)
+                })
+                .collect::<Vec<_>>()
+                .into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod linalg_tests {
+    use super::*;
+
+    fn v(xs: &[u32]) -> Vector237 {
+        xs.iter()
+            .map(|&x| FP237::from(x))
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    #[test]
+    fn test_vector() {
+        let x = v(&[3, 4]);
+        assert_eq!(x.norm().f, 5);
+        assert_eq!(x.dot(&v(&[2, 1])).f, 10);
+        assert_eq!(v(&[]).norm().f, 0);
+        // exact accumulation: 2⁴⁰⁰ + 1 - 2⁴⁰⁰ = 1
+        let big = FP237::new(Float::with_val(P, Float::i_exp(1, 400)));
+        let y = Vector237::new(vec![big.clone(), FP237::ONE(), -big]);
+        assert_eq!(y.dot(&v(&[1, 1, 1])).f, 1);
+        // √(1 + 2⁻⁶⁰⁰) rounds to 1
+        let tiny = FP237::new(Float::with_val(P, Float::i_exp(1, -300)));
+        let z = Vector237::new(vec![FP237::ONE(), tiny]);
+        assert_eq!(z.norm().f, 1);
+    }
+
+    #[test]
+    fn test_matrix() {
+        let a =
+            Matrix237::from_rows(vec![v(&[2, 1]).elems, v(&[1, 3]).elems]);
+        assert_eq!(a.get(1, 0).f, 1);
+        let x = v(&[1, 2]);
+        let b = a.mul_vec(&x);
+        assert_eq!(b, v(&[4, 7]));
+        assert_eq!(a.solve(&b), Some(x.clone()));
+        assert_eq!(Matrix237::identity(2).mul_vec(&x), x);
+        let singular =
+            Matrix237::from_rows(vec![v(&[1, 2]).elems, v(&[2, 4]).elems]);
+        assert_eq!(singular.solve(&b), None);
+    }
+
+    #[test]
+    fn test_hilbert() {
+        let n = 6;
+        let h = Matrix237::hilbert(n);
+        assert_eq!(h.get(1, 2).f, Float::with_val(P, 4).recip());
+        let x = v(&[1; 6]);
+        // b = H·x is rounded, so the solution is close to, but not x
+        let y = h.solve(&h.mul_vec(&x)).unwrap();
+        assert_ne!(y, x);
+        for yi in &y.elems {
+            let err = Float::with_val(P, &yi.f - 1_u32).abs();
+            assert!(err < 1e-50);
+        }
+    }
+}
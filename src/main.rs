@@ -0,0 +1,37 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use clap::{CommandFactory, Parser};
+use rug237::generators::{catalogue, Command};
+
+/// Generates test data for f256.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+#[command(
+    arg_required_else_help = true,
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    /// Print a JSON description of the operations, their operands,
+    /// rounding modes and columns
+    #[arg(long)]
+    list_ops: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    match cli.command {
+        Some(command) => command.run(),
+        None if cli.list_ops => println!("{}", catalogue::to_json()),
+        None => Cli::command().print_help().unwrap(),
+    }
+}
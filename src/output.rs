@@ -0,0 +1,176 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    fs::{rename, File, OpenOptions},
+    io::{
+        stdout, BufWriter, Error, ErrorKind, Result, Seek, SeekFrom,
+        StdoutLock, Write,
+    },
+    path::{Path, PathBuf},
+};
+
+use flate2::{write::GzEncoder, Compression};
+
+/// Destination of the generated test data.
+///
+/// A file is written under a temporary name in the same directory and only
+/// renamed to its final name by `finish`, so that an aborted run never
+/// leaves a truncated file under that name. The temporary file is kept for
+/// inspection in that case, and an uncompressed one can be continued by
+/// `resume`. Files with extension `gz` are compressed.
+pub enum Output {
+    Stdout(StdoutLock<'static>),
+    File {
+        tmp: PathBuf,
+        path: PathBuf,
+        out: BufWriter<File>,
+    },
+    Gzip {
+        tmp: PathBuf,
+        path: PathBuf,
+        out: GzEncoder<BufWriter<File>>,
+    },
+}
+
+impl Output {
+    pub fn stdout() -> Self {
+        Self::Stdout(stdout().lock())
+    }
+
+    /// Creates the temporary file for `path`.
+    pub fn create(path: &Path) -> Result<Self> {
+        let tmp = part_path(path);
+        let out = BufWriter::new(File::create(&tmp)?);
+        let path = path.to_path_buf();
+        Ok(if path.extension().is_some_and(|ext| ext == "gz") {
+            let out = GzEncoder::new(out, Compression::default());
+            Self::Gzip { tmp, path, out }
+        } else {
+            Self::File { tmp, path, out }
+        })
+    }
+
+    /// Reopens the temporary file left by an aborted run for `path`,
+    /// truncated to its first `len` bytes, to append to it.
+    pub fn resume(path: &Path, len: u64) -> Result<Self> {
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "Compressed output can't be resumed",
+            ));
+        }
+        let tmp = part_path(path);
+        let mut file = OpenOptions::new().write(true).open(&tmp)?;
+        if file.metadata()?.len() < len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("{} is shorter than expected", tmp.display()),
+            ));
+        }
+        file.set_len(len)?;
+        file.seek(SeekFrom::End(0))?;
+        Ok(Self::File {
+            tmp,
+            path: path.to_path_buf(),
+            out: BufWriter::new(file),
+        })
+    }
+
+    /// Flushes all data and moves a temporary file to its final name.
+    pub fn finish(self) -> Result<()> {
+        match self {
+            Self::Stdout(mut out) => out.flush(),
+            Self::File { tmp, path, out } => {
+                out.into_inner()?.sync_all()?;
+                rename(tmp, path)
+            }
+            Self::Gzip { tmp, path, out } => {
+                out.finish()?.into_inner()?.sync_all()?;
+                rename(tmp, path)
+            }
+        }
+    }
+}
+
+/// Returns the name of the temporary file for `path`.
+fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        match self {
+            Self::Stdout(out) => out.write(buf),
+            Self::File { out, .. } => out.write(buf),
+            Self::Gzip { out, .. } => out.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self {
+            Self::Stdout(out) => out.flush(),
+            Self::File { out, .. } => out.flush(),
+            Self::Gzip { out, .. } => out.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod output_tests {
+    use std::{env::temp_dir, fs, io::Read, process};
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+
+    #[test]
+    fn test_file_is_renamed_on_finish() {
+        let path = temp_dir().join(format!("rug237-{}.tsv", process::id()));
+        let mut out = Output::create(&path).unwrap();
+        out.write_all(b"1\t2\n").unwrap();
+        assert!(!path.exists());
+        out.finish().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\t2\n");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_resume() {
+        let path =
+            temp_dir().join(format!("rug237-{}-resume.tsv", process::id()));
+        let mut out = Output::create(&path).unwrap();
+        out.write_all(b"1\t2\n3\t").unwrap();
+        out.flush().unwrap();
+        drop(out);
+        let mut out = Output::resume(&path, 4).unwrap();
+        out.write_all(b"5\t6\n").unwrap();
+        out.finish().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\t2\n5\t6\n");
+        fs::remove_file(&path).unwrap();
+        assert!(Output::resume(&path, 0).is_err());
+    }
+
+    #[test]
+    fn test_gzip() {
+        let path =
+            temp_dir().join(format!("rug237-{}.tsv.gz", process::id()));
+        let mut out = Output::create(&path).unwrap();
+        out.write_all(b"1\t2\n").unwrap();
+        out.finish().unwrap();
+        let mut s = String::new();
+        GzDecoder::new(File::open(&path).unwrap())
+            .read_to_string(&mut s)
+            .unwrap();
+        assert_eq!(s, "1\t2\n");
+        fs::remove_file(&path).unwrap();
+    }
+}
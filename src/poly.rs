@@ -0,0 +1,305 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Polynomials with exact rational coefficients, evaluated exactly or at a
+//! given precision, with root finding by bisection and Newton iteration.
+
+use std::{
+    cmp::Ordering,
+    ops::{Add, Mul, Sub},
+};
+
+use rug::{Float, Rational};
+
+use crate::{RoundingMode, FP237, P};
+
+/// Polynomial c₀ + c₁·x + c₂·x² + …, without trailing zero coefficients.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Poly237 {
+    pub coeffs: Vec<Rational>,
+}
+
+/// Returns the exact value of `x`.
+///
+/// # Panics
+///
+/// If `x` is infinite or NaN.
+fn exact(x: &Float) -> Rational {
+    x.to_rational()
+        .unwrap_or_else(|| panic!("{x} has no rational value"))
+}
+
+impl Poly237 {
+    /// Returns the polynomial with the coefficients `coeffs`, lowest degree
+    /// first.
+    pub fn new(mut coeffs: Vec<Rational>) -> Self {
+        while coeffs.last().is_some_and(|c| *c == 0) {
+            coeffs.pop();
+        }
+        Self { coeffs }
+    }
+
+    /// Returns the polynomial with the exact values of `coeffs`, lowest
+    /// degree first.
+    ///
+    /// # Panics
+    ///
+    /// If a coefficient is infinite or NaN.
+    pub fn from_fp237(coeffs: &[FP237]) -> Self {
+        Self::new(coeffs.iter().map(|c| exact(&c.f)).collect())
+    }
+
+    /// Returns the degree, which is 0 for the zero polynomial.
+    pub fn degree(&self) -> usize {
+        self.coeffs.len().saturating_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// Returns the exact value at `x`.
+    pub fn eval(&self, x: &Rational) -> Rational {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(Rational::new(), |acc, c| acc * x + c)
+    }
+
+    /// Returns the exact value at `x`, rounded once according to `rnd`.
+    ///
+    /// # Panics
+    ///
+    /// If `x` is infinite or NaN.
+    pub fn eval_fp237(&self, x: &FP237, rnd: RoundingMode) -> FP237 {
+        FP237::with_val_round(&self.eval(&exact(&x.f)), rnd)
+    }
+
+    /// Returns the value at `x` by Horner's scheme, each step rounded to
+    /// `prec` bits.
+    pub fn eval_float(&self, x: &Float, prec: u32) -> Float {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(Float::new(prec), |acc, c| acc * x + c)
+    }
+
+    pub fn derivative(&self) -> Self {
+        Self::new(
+            self.coeffs
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(k, c)| Rational::from(c * k as u32))
+                .collect(),
+        )
+    }
+
+    /// Returns the sign of the exact value at `x`.
+    fn sign_at(&self, x: &Float) -> Ordering {
+        self.eval(&exact(x)).cmp0()
+    }
+
+    /// Returns adjacent values a <= b of `prec` bits enclosing a root in
+    /// [`lo`, `hi`], found by bisection, or `None` if the signs of the
+    /// values at `lo` and `hi` don't differ. A root being hit exactly
+    /// gives a = b.
+    pub fn bisect(
+        &self,
+        lo: &Float,
+        hi: &Float,
+        prec: u32,
+    ) -> Option<(Float, Float)> {
+        let mut a = Float::with_val(prec, lo);
+        let mut b = Float::with_val(prec, hi);
+        let sa = self.sign_at(&a);
+        let sb = self.sign_at(&b);
+        if sa == Ordering::Equal {
+            return Some((a.clone(), a));
+        }
+        if sb == Ordering::Equal {
+            return Some((b.clone(), b));
+        }
+        if sa == sb {
+            return None;
+        }
+        loop {
+            let m = Float::with_val(prec, &a + &b) / 2;
+            if m == a || m == b {
+                return Some((a, b));
+            }
+            match self.sign_at(&m) {
+                Ordering::Equal => return Some((m.clone(), m)),
+                s if s == sa => a = m,
+                _ => b = m,
+            }
+        }
+    }
+
+    /// Returns the root reached by Newton iteration from `x0` at `prec`
+    /// bits, or `None` if the derivative vanishes or the iteration doesn't
+    /// settle within `max_iter` steps.
+    pub fn newton(
+        &self,
+        x0: &Float,
+        prec: u32,
+        max_iter: u32,
+    ) -> Option<Float> {
+        let d = self.derivative();
+        let mut x = Float::with_val(prec, x0);
+        for _ in 0..max_iter {
+            let dy = d.eval_float(&x, prec);
+            if dy.is_zero() {
+                return None;
+            }
+            let dx = self.eval_float(&x, prec) / dy;
+            // stop when the step is below an ulp of x
+            if dx.is_zero()
+                || dx.get_exp().unwrap() < x.get_exp()? - prec as i32
+            {
+                return Some(x);
+            }
+            x -= dx;
+        }
+        None
+    }
+
+    /// Returns the root in [`lo`, `hi`] rounded according to `rnd`, or
+    /// `None` if the signs of the values at `lo` and `hi` don't differ.
+    /// The root is located exactly between adjacent values of P + 1 bits,
+    /// which include all rounding boundaries of f256, so the result is
+    /// correctly rounded.
+    pub fn root(
+        &self,
+        lo: &FP237,
+        hi: &FP237,
+        rnd: RoundingMode,
+    ) -> Option<FP237> {
+        let (a, b) = self.bisect(&lo.f, &hi.f, P + 1)?;
+        let inner = if a == b {
+            a
+        } else {
+            // any value strictly between a and b rounds the same way
+            Float::with_val(P + 2, &a + &b) / 2
+        };
+        Some(FP237::with_val_round(&inner, rnd))
+    }
+
+    /// Returns the polynomial with the coefficients `op` applied to the
+    /// coefficients of `self` and `other` of equal degree.
+    fn zip_with<F>(&self, other: &Self, op: F) -> Self
+    where
+        F: Fn(&Rational, &Rational) -> Rational,
+    {
+        let n = self.coeffs.len().max(other.coeffs.len());
+        let zero = Rational::new();
+        Self::new(
+            (0..n)
+                .map(|k| {
+                    op(
+                        self.coeffs.get(k).unwrap_or(&zero),
+                        other.coeffs.get(k).unwrap_or(&zero),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Add for &Poly237 {
+    type Output = Poly237;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |x, y| Rational::from(x + y))
+    }
+}
+
+impl Sub for &Poly237 {
+    type Output = Poly237;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |x, y| Rational::from(x - y))
+    }
+}
+
+impl Mul for &Poly237 {
+    type Output = Poly237;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return Poly237::default();
+        }
+        let mut coeffs =
+            vec![Rational::new(); self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, x) in self.coeffs.iter().enumerate() {
+            for (j, y) in rhs.coeffs.iter().enumerate() {
+                coeffs[i + j] += Rational::from(x * y);
+            }
+        }
+        Poly237::new(coeffs)
+    }
+}
+
+#[cfg(test)]
+mod poly_tests {
+    use super::*;
+    use crate::ROUNDING_MODES;
+
+    fn poly(coeffs: &[i32]) -> Poly237 {
+        Poly237::new(coeffs.iter().map(|&c| Rational::from(c)).collect())
+    }
+
+    #[test]
+    fn test_arith() {
+        let p = poly(&[1, 1]);
+        let q = poly(&[-1, 1]);
+        assert_eq!(&p * &q, poly(&[-1, 0, 1]));
+        assert_eq!(&p - &p, Poly237::default());
+        assert_eq!((&p + &q).degree(), 1);
+        assert_eq!(poly(&[5, 0, 0]).degree(), 0);
+        let r = poly(&[3, -2, 0, 4]);
+        assert_eq!(r.derivative(), poly(&[-2, 0, 12]));
+        assert_eq!(r.eval(&Rational::from((1, 2))), Rational::from((5, 2)));
+    }
+
+    #[test]
+    fn test_eval() {
+        // (x - 1)³ vanishes at 1 and cancels badly near 1
+        let p = poly(&[-1, 3, -3, 1]);
+        let x = FP237::ONE().next_up();
+        let ulp = &x - &FP237::ONE();
+        let expected = &(&ulp * &ulp) * &ulp;
+        let ne = RoundingMode::NearestEven;
+        assert_eq!(p.eval_fp237(&x, ne).f, expected.f);
+    }
+
+    #[test]
+    fn test_roots() {
+        let p = poly(&[-2, 0, 1]);
+        let (one, two) = (FP237::ONE(), FP237::from(2));
+        for rnd in ROUNDING_MODES {
+            let r = p.root(&one, &two, rnd).unwrap();
+            assert_eq!(r.f, two.sqrt_round(rnd).f, "{rnd}");
+        }
+        assert_eq!(p.root(&two, &FP237::from(3), RoundingMode::Up), None);
+        // exact root
+        let q = poly(&[-3, 1]);
+        let r = q.root(&one, &FP237::from(4), RoundingMode::Down).unwrap();
+        assert_eq!(r.f, 3);
+        let prec = 4 * P;
+        let x = p.newton(&Float::with_val(prec, 1), prec, 20).unwrap();
+        let sqrt2 = Float::with_val(prec, 2).sqrt();
+        // within two ulps
+        let err = Float::with_val(prec, &x - &sqrt2).abs();
+        assert!(
+            err <= Float::with_val(prec, Float::i_exp(1, 2 - prec as i32))
+        );
+        assert_eq!(poly(&[1, 0, 1]).newton(&x, prec, 20), None);
+    }
+}
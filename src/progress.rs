@@ -0,0 +1,96 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    io::{stderr, IsTerminal, Write},
+    time::{Duration, Instant},
+};
+
+/// Periodic status line on stderr, giving the number of items written so
+/// far and the rate. On a terminal the line is updated in place several
+/// times a second, otherwise a new line is written every ten seconds.
+pub struct Progress {
+    label: String,
+    n: u64,
+    start: Instant,
+    last: Instant,
+    interval: Duration,
+    in_place: bool,
+    shown: bool,
+}
+
+impl Progress {
+    pub fn new(label: &str) -> Self {
+        let in_place = stderr().is_terminal();
+        let now = Instant::now();
+        Self {
+            label: label.to_string(),
+            n: 0,
+            start: now,
+            last: now,
+            interval: if in_place {
+                Duration::from_millis(250)
+            } else {
+                Duration::from_secs(10)
+            },
+            in_place,
+            shown: false,
+        }
+    }
+
+    /// Returns the number of items counted so far.
+    pub fn count(&self) -> u64 {
+        self.n
+    }
+
+    /// Counts one item, showing the status if due.
+    pub fn inc(&mut self) {
+        self.n += 1;
+        // checking the time only every 64 items keeps the overhead low
+        if self.n.is_multiple_of(64) && self.last.elapsed() >= self.interval {
+            self.last = Instant::now();
+            self.show();
+        }
+    }
+
+    fn status(&self) -> String {
+        let secs = self.start.elapsed().as_secs_f64();
+        let rate = if secs > 0.0 {
+            self.n as f64 / secs
+        } else {
+            0.0
+        };
+        format!(
+            "{}: {} items, {:.0} items/s, {:.1} s",
+            self.label, self.n, rate, secs
+        )
+    }
+
+    fn show(&mut self) {
+        let mut err = stderr().lock();
+        // errors writing to stderr are of no concern for the corpus
+        let _ = if self.in_place {
+            write!(err, "\r{}\x1b[K", self.status())
+        } else {
+            writeln!(err, "{}", self.status())
+        };
+        self.shown = true;
+    }
+
+    /// Shows the final status, if any status has been shown before.
+    pub fn finish(&mut self) {
+        if self.shown {
+            let _ = if self.in_place {
+                writeln!(stderr(), "\r{}\x1b[K", self.status())
+            } else {
+                writeln!(stderr(), "{}", self.status())
+            };
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Signed 256-bit fixed-point values in Qm.n format, m + n = 255, i.e. a
+//! two's complement integer v holding the value v·2⁻ⁿ, for cross-checking
+//! the fixed-point intermediate values of f256's CORDIC iterations step
+//! by step.
+//!
+//! Like the integer arithmetic of f256, addition, subtraction and
+//! multiplication wrap around modulo 2²⁵⁶, and shifts to the right are
+//! arithmetic, i.e. they round toward -∞. Products are truncated the same
+//! way.
+
+use std::ops::{Add, Mul, Neg, Shr, Sub};
+
+use rug::{Float, Integer};
+
+use crate::{RoundingMode, FP237};
+
+/// Number of bits of the two's complement representation.
+const N_BITS: u32 = 256;
+
+/// Fixed-point value v·2⁻ⁿ with n = `frac_bits`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Qmn {
+    pub v: Integer,
+    pub frac_bits: u32,
+}
+
+/// Returns `v` reduced modulo 2²⁵⁶ to the range of a signed 256-bit
+/// integer.
+fn wrap(mut v: Integer) -> Integer {
+    v.keep_bits_mut(N_BITS);
+    if v.get_bit(N_BITS - 1) {
+        v -= Integer::from(1) << N_BITS;
+    }
+    v
+}
+
+impl Qmn {
+    /// Returns the value `v`·2⁻ⁿ, `v` wrapped to 256 bits.
+    ///
+    /// # Panics
+    ///
+    /// If `frac_bits` > 255.
+    pub fn new(v: Integer, frac_bits: u32) -> Self {
+        assert!(frac_bits < N_BITS, "Invalid number of fraction bits");
+        Self {
+            v: wrap(v),
+            frac_bits,
+        }
+    }
+
+    /// Returns `x` rounded to a multiple of 2⁻ⁿ according to `rnd`, or
+    /// `None` if the result doesn't fit into 256 bits or `x` is not
+    /// finite.
+    pub fn from_fp237(
+        x: &FP237,
+        frac_bits: u32,
+        rnd: RoundingMode,
+    ) -> Option<Self> {
+        if !x.f.is_finite() {
+            return None;
+        }
+        let scaled =
+            FP237::new(Float::with_val(x.f.prec(), &x.f << frac_bits));
+        let v = scaled.round_to_int(rnd).f.to_integer().unwrap();
+        let limit = Integer::from(1) << (N_BITS - 1);
+        if v >= limit || v < -limit {
+            return None;
+        }
+        Some(Self::new(v, frac_bits))
+    }
+
+    /// Returns the two's complement representation as (hi, lo), the high
+    /// and the low 128 bits, as used by f256.
+    pub fn to_bits(&self) -> (u128, u128) {
+        let mut u = self.v.clone();
+        u.keep_bits_mut(N_BITS);
+        let lo = Integer::from(u.keep_bits_ref(128)).to_u128().unwrap();
+        u >>= 128;
+        (u.to_u128().unwrap(), lo)
+    }
+
+    /// Returns the value given by its two's complement representation
+    /// (hi, lo).
+    pub fn from_bits((hi, lo): (u128, u128), frac_bits: u32) -> Self {
+        Self::new((Integer::from(hi) << 128) + lo, frac_bits)
+    }
+
+    /// Returns the exact value.
+    pub fn to_float(&self) -> Float {
+        let prec = self.v.significant_bits().max(1);
+        Float::with_val(prec, &self.v) >> self.frac_bits
+    }
+
+    /// Returns the value rounded to f256 according to `rnd`.
+    pub fn to_fp237(&self, rnd: RoundingMode) -> FP237 {
+        FP237::with_val_round(self.to_float(), rnd)
+    }
+
+    fn check_format(&self, rhs: &Self) {
+        assert_eq!(
+            self.frac_bits, rhs.frac_bits,
+            "Operands with different formats"
+        );
+    }
+}
+
+impl Neg for &Qmn {
+    type Output = Qmn;
+
+    fn neg(self) -> Self::Output {
+        Qmn::new(Integer::from(-&self.v), self.frac_bits)
+    }
+}
+
+impl Add for &Qmn {
+    type Output = Qmn;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.check_format(rhs);
+        Qmn::new(Integer::from(&self.v + &rhs.v), self.frac_bits)
+    }
+}
+
+impl Sub for &Qmn {
+    type Output = Qmn;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.check_format(rhs);
+        Qmn::new(Integer::from(&self.v - &rhs.v), self.frac_bits)
+    }
+}
+
+impl Mul for &Qmn {
+    type Output = Qmn;
+
+    /// Returns the product truncated toward -∞ to n fraction bits.
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.check_format(rhs);
+        let p = Integer::from(&self.v * &rhs.v) >> self.frac_bits;
+        Qmn::new(p, self.frac_bits)
+    }
+}
+
+impl Shr<u32> for &Qmn {
+    type Output = Qmn;
+
+    /// Returns `self`·2⁻ᵏ, rounded toward -∞.
+    fn shr(self, k: u32) -> Self::Output {
+        Qmn::new(Integer::from(&self.v >> k), self.frac_bits)
+    }
+}
+
+#[cfg(test)]
+mod q_format_tests {
+    use rug::float::Constant;
+
+    use super::*;
+    use crate::P;
+
+    #[test]
+    fn test_conversions() {
+        let rn = RoundingMode::NearestEven;
+        let x = -(&FP237::from(3) / &FP237::from(4));
+        let q = Qmn::from_fp237(&x, 253, rn).unwrap();
+        assert_eq!(q.v, -(Integer::from(3) << 251_u32));
+        assert_eq!(q.to_fp237(rn), x);
+        let (hi, lo) = q.to_bits();
+        assert_eq!(hi, u128::MAX - (3 << 123) + 1);
+        assert_eq!(lo, 0);
+        assert_eq!(Qmn::from_bits((hi, lo), 253), q);
+        // 1/3 with 2 fraction bits
+        let third = &FP237::ONE() / &FP237::from(3);
+        let dn = Qmn::from_fp237(&third, 2, RoundingMode::Down).unwrap();
+        let up = Qmn::from_fp237(&third, 2, RoundingMode::Up).unwrap();
+        assert_eq!((dn.v, up.v), (Integer::from(1), Integer::from(2)));
+        assert!(Qmn::from_fp237(&FP237::from(4), 253, rn).is_none());
+        assert!(Qmn::from_fp237(&FP237::INFINITY(), 0, rn).is_none());
+    }
+
+    #[test]
+    fn test_arith() {
+        let n = 200;
+        let one = Qmn::new(Integer::from(1) << n, n);
+        let half = &one >> 1;
+        assert_eq!(&half + &half, one);
+        assert_eq!(&(&half * &half) - &(&one >> 2), Qmn::new(0.into(), n));
+        // shifts round toward -∞
+        let tiny = Qmn::new((-1).into(), n);
+        assert_eq!(&tiny >> 3, tiny);
+        assert_eq!((-&one).v, -(Integer::from(1) << n));
+        // wrap around
+        let max = Qmn::new((Integer::from(1) << 255) - 1, n);
+        let min = &max + &Qmn::new(1.into(), n);
+        assert_eq!(min.v, -(Integer::from(1) << 255_u32));
+    }
+
+    #[test]
+    fn test_cordic_rotation() {
+        // rotating (1/K, 0) by z gives (cos z, sin z)
+        let (n, iters) = (236, 238);
+        let rn = RoundingMode::NearestEven;
+        let wp = 2 * P;
+        let q = |f: Float| {
+            Qmn::from_fp237(&FP237::with_val_round(f, rn), n, rn).unwrap()
+        };
+        let mut gain = Float::with_val(wp, 1);
+        for i in 0..iters {
+            let f = Float::with_val(wp, Float::i_exp(1, -2 * i as i32));
+            gain *= (f + 1_u32).sqrt();
+        }
+        let z0: Float = Float::with_val(wp, Constant::Pi) / 5;
+        let (mut x, mut y, mut z) =
+            (q(gain.recip()), q(Float::new(wp)), q(z0.clone()));
+        for i in 0..iters {
+            let atan =
+                q(Float::with_val(wp, Float::i_exp(1, -(i as i32))).atan());
+            let (dx, dy) = (&y >> i, &x >> i);
+            if z.v >= 0 {
+                (x, y, z) = (&x - &dx, &y + &dy, &z - &atan);
+            } else {
+                (x, y, z) = (&x + &dx, &y - &dy, &z + &atan);
+            }
+        }
+        // each iteration adds at most two ulps of rounding error
+        let bound =
+            Float::with_val(wp, Float::i_exp(1, -(n as i32))) * 2 * iters;
+        let (sin, cos) = z0.sin_cos(Float::new(wp));
+        let err_cos = Float::with_val(wp, x.to_float() - cos).abs();
+        let err_sin = Float::with_val(wp, y.to_float() - sin).abs();
+        assert!(err_cos < bound && err_sin < bound);
+    }
+}
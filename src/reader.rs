@@ -0,0 +1,679 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    io::{BufRead, Error, ErrorKind, Result},
+    iter::Peekable,
+    str::Chars,
+};
+
+use crate::{DecodedFP, Format, Header, Record, TestItem, EMAX, FP237, U256};
+
+fn invalid_data(msg: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidData, msg.into())
+}
+
+/// A JSON value, numbers kept as given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Json {
+    Bool(bool),
+    Num(String),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Returns the value of `key`, if `self` is an object holding it.
+    pub(crate) fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Obj(entries) => {
+                entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parses a JSON document holding numbers, strings, arrays and objects
+/// only, as written by `RecordWriter`.
+pub(crate) fn parse_json(s: &str) -> std::result::Result<Json, String> {
+    let mut chars = s.chars().peekable();
+    let res = json_value(&mut chars)?;
+    skip_ws(&mut chars);
+    match chars.next() {
+        None => Ok(res),
+        Some(c) => Err(format!("Unexpected '{c}' after JSON value")),
+    }
+}
+
+fn skip_ws(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn json_value(
+    chars: &mut Peekable<Chars>,
+) -> std::result::Result<Json, String> {
+    skip_ws(chars);
+    match chars.peek() {
+        Some('"') => json_string(chars).map(Json::Str),
+        Some('[') => {
+            chars.next();
+            let mut elems = Vec::new();
+            skip_ws(chars);
+            if chars.next_if_eq(&']').is_some() {
+                return Ok(Json::Arr(elems));
+            }
+            loop {
+                elems.push(json_value(chars)?);
+                skip_ws(chars);
+                match chars.next() {
+                    Some(',') => {}
+                    Some(']') => return Ok(Json::Arr(elems)),
+                    _ => return Err("Expected ',' or ']'".to_string()),
+                }
+            }
+        }
+        Some('{') => {
+            chars.next();
+            let mut entries = Vec::new();
+            skip_ws(chars);
+            if chars.next_if_eq(&'}').is_some() {
+                return Ok(Json::Obj(entries));
+            }
+            loop {
+                skip_ws(chars);
+                let key = json_string(chars)?;
+                skip_ws(chars);
+                if chars.next() != Some(':') {
+                    return Err("Expected ':'".to_string());
+                }
+                entries.push((key, json_value(chars)?));
+                skip_ws(chars);
+                match chars.next() {
+                    Some(',') => {}
+                    Some('}') => return Ok(Json::Obj(entries)),
+                    _ => return Err("Expected ',' or '}'".to_string()),
+                }
+            }
+        }
+        Some('t' | 'f') => {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(char::is_ascii_alphabetic) {
+                word.push(c);
+            }
+            match word.as_str() {
+                "true" => Ok(Json::Bool(true)),
+                "false" => Ok(Json::Bool(false)),
+                _ => Err(format!("Unexpected '{word}'")),
+            }
+        }
+        Some(c) if *c == '-' || c.is_ascii_digit() => {
+            let mut num = String::new();
+            while let Some(c) = chars.next_if(|c| {
+                c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')
+            }) {
+                num.push(c);
+            }
+            Ok(Json::Num(num))
+        }
+        Some(c) => Err(format!("Unexpected '{c}'")),
+        None => Err("Unexpected end of JSON value".to_string()),
+    }
+}
+
+fn json_string(
+    chars: &mut Peekable<Chars>,
+) -> std::result::Result<String, String> {
+    if chars.next() != Some('"') {
+        return Err("Expected '\"'".to_string());
+    }
+    let mut res = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(res),
+            Some('\\') => match chars.next() {
+                Some('n') => res.push('\n'),
+                Some('r') => res.push('\r'),
+                Some('t') => res.push('\t'),
+                Some('b') => res.push('\u{8}'),
+                Some('f') => res.push('\u{c}'),
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    let c = u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or(format!("Invalid escape \\u{hex}"))?;
+                    res.push(c);
+                }
+                Some(c) => res.push(c),
+                None => break,
+            },
+            Some(c) => res.push(c),
+            None => break,
+        }
+    }
+    Err("Unterminated string".to_string())
+}
+
+/// Returns true if `s` is an integral number.
+fn is_int(s: &str) -> bool {
+    s.parse::<i128>().is_ok() || s.parse::<u128>().is_ok()
+}
+
+/// A column read from a line: a decode tuple (Rust source only) or a
+/// field.
+#[derive(Clone, Debug)]
+enum Col {
+    Fp(DecodedFP),
+    Int(String),
+    Token(String),
+    Str(String),
+}
+
+impl Col {
+    /// Returns a string read from a format not distinguishing tokens from
+    /// strings, as token if it would have been written as such.
+    fn text(s: String) -> Self {
+        if s.is_empty() || s.contains(|c: char| c.is_whitespace() || c == '"')
+        {
+            Col::Str(s)
+        } else {
+            Col::Token(s)
+        }
+    }
+
+    /// Returns a bare field as integer or token.
+    fn bare(s: &str) -> Self {
+        if is_int(s) {
+            Col::Int(s.to_string())
+        } else {
+            Col::Token(s.to_string())
+        }
+    }
+}
+
+fn tsv_cols(line: &str) -> Vec<Col> {
+    line.split('\t')
+        .map(|s| {
+            match s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                Some(s) => Col::Str(tsv_unescaped(s)),
+                None => Col::bare(s),
+            }
+        })
+        .collect()
+}
+
+/// Returns the string field `s` with the escapes written by `Record` for
+/// backslashes, tabs and line breaks resolved.
+fn tsv_unescaped(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => res.push('\t'),
+            Some('n') => res.push('\n'),
+            Some('r') => res.push('\r'),
+            Some(c) => res.push(c),
+            None => res.push('\\'),
+        }
+    }
+    res
+}
+
+fn csv_cols(line: &str) -> Vec<Col> {
+    let mut cols = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        if chars.next_if_eq(&'"').is_some() {
+            let mut s = String::new();
+            while let Some(c) = chars.next() {
+                if c == '"' && chars.next_if_eq(&'"').is_none() {
+                    break;
+                }
+                s.push(c);
+            }
+            cols.push(Col::Str(s));
+            // skip anything up to the separator
+            while chars.next_if(|c| *c != ',').is_some() {}
+        } else {
+            let s: String =
+                std::iter::from_fn(|| chars.next_if(|c| *c != ',')).collect();
+            cols.push(Col::bare(&s));
+        }
+        if chars.next().is_none() {
+            return cols;
+        }
+    }
+}
+
+fn json_cols(elems: &[Json]) -> std::result::Result<Vec<Col>, String> {
+    elems
+        .iter()
+        .map(|elem| match elem {
+            Json::Num(s) => Ok(Col::Int(s.clone())),
+            Json::Str(s) => Ok(Col::text(s.clone())),
+            _ => Err("Unexpected nested JSON value".to_string()),
+        })
+        .collect()
+}
+
+/// Parses a row of Rust source as written by `RecordWriter`, e.g.
+/// `((0, 0, 0, 1), 3, "x"),`.
+fn rust_cols(line: &str) -> std::result::Result<Vec<Col>, String> {
+    let inner = line
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix("),"))
+        .ok_or("Not a row of Rust source")?;
+    let mut cols = Vec::new();
+    let mut chars = inner.chars().peekable();
+    loop {
+        skip_ws(&mut chars);
+        match chars.peek() {
+            None => return Ok(cols),
+            Some('(') => {
+                chars.next();
+                let tuple: String =
+                    std::iter::from_fn(|| chars.next_if(|c| *c != ')'))
+                        .collect();
+                chars.next();
+                let parts: Vec<&str> =
+                    tuple.split(',').map(str::trim).collect();
+                let [s, e, h, l] = parts[..] else {
+                    return Err(format!("Invalid decode tuple ({tuple})"));
+                };
+                let err = |_| format!("Invalid decode tuple ({tuple})");
+                cols.push(Col::Fp(DecodedFP {
+                    sign: s.parse().map_err(err)?,
+                    exp: e.parse().map_err(err)?,
+                    signif: U256::new(
+                        h.parse().map_err(err)?,
+                        l.parse().map_err(err)?,
+                    ),
+                }));
+            }
+            // Rust string literals written by `{:?}` use the JSON escapes
+            // and \u{..}
+            Some('"') => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('r') => s.push('\r'),
+                            Some('t') => s.push('\t'),
+                            Some('0') => s.push('\0'),
+                            Some('u') => {
+                                let hex: String = chars
+                                    .by_ref()
+                                    .skip(1)
+                                    .take_while(|c| *c != '}')
+                                    .collect();
+                                let c = u32::from_str_radix(&hex, 16)
+                                    .ok()
+                                    .and_then(char::from_u32)
+                                    .ok_or(format!(
+                                        "Invalid escape \\u{{{hex}}}"
+                                    ))?;
+                                s.push(c);
+                            }
+                            Some(c) => s.push(c),
+                            None => {
+                                return Err("Unterminated string".to_string())
+                            }
+                        },
+                        Some(c) => s.push(c),
+                        None => return Err("Unterminated string".to_string()),
+                    }
+                }
+                cols.push(Col::text(s));
+            }
+            Some(_) => {
+                let s: String =
+                    std::iter::from_fn(|| chars.next_if(|c| *c != ','))
+                        .collect();
+                let s = s.trim();
+                // hex tokens are written as integer literals
+                let is_hex = s
+                    .strip_prefix("0x")
+                    .is_some_and(|hex| u128::from_str_radix(hex, 16).is_ok());
+                if !is_int(s) && !is_hex {
+                    return Err(format!("Unexpected {s}"));
+                }
+                cols.push(Col::bare(s));
+            }
+        }
+        skip_ws(&mut chars);
+        if chars.next().is_none() {
+            return Ok(cols);
+        }
+    }
+}
+
+/// Returns `d` after checking that it denotes a value representable as
+/// f256, i.e. that encoding and decoding it gives the same value.
+fn checked(d: DecodedFP) -> std::result::Result<DecodedFP, String> {
+    if d.sign > 1 {
+        return Err(format!("Invalid sign in {d:?}"));
+    }
+    let decoded =
+        DecodedFP::from(&FP237::encode(d.sign, d.exp, d.signif.into()));
+    let mut reduced = d;
+    if d.exp <= EMAX {
+        if d.signif.is_zero() {
+            reduced.exp = 0;
+        } else {
+            let tz = d.signif.trailing_zeros();
+            reduced.exp += tz as i32;
+            reduced.signif = d.signif >> tz;
+        }
+    }
+    if reduced == decoded {
+        Ok(d)
+    } else {
+        Err(format!("{d:?} is not representable as f256"))
+    }
+}
+
+/// Reads test items from a corpus in TSV, CSV, JSON Lines or Rust format,
+/// as written by `RecordWriter`.
+///
+/// The leading header lines are read by `new`. Test items in JSON Lines
+/// format are self-describing, for all other formats the number of
+/// operands and results per item has to be given by `with_arity`; the
+/// remaining columns are read as additional data. Each decode tuple is
+/// checked to denote a value representable as f256.
+///
+/// As the formats don't all distinguish tokens from strings, a string is
+/// read as token unless it is empty or contains white space or quotes.
+pub struct RecordReader<R: BufRead> {
+    inp: R,
+    format: Format,
+    header: Option<Header>,
+    arity: Option<(usize, usize)>,
+    pending: Option<String>,
+    line_no: usize,
+}
+
+impl<R: BufRead> RecordReader<R> {
+    /// Reads the header lines of a corpus in `format`, which must not be
+    /// the binary format (see `CorpusReader`).
+    pub fn new(inp: R, format: Format) -> Result<Self> {
+        if format == Format::Bin {
+            return Err(Error::new(
+                ErrorKind::Unsupported,
+                "Binary corpora are read by CorpusReader",
+            ));
+        }
+        let mut rdr = Self {
+            inp,
+            format,
+            header: None,
+            arity: None,
+            pending: None,
+            line_no: 0,
+        };
+        while let Some(line) = rdr.next_line()? {
+            if let Some(header) = Header::from_line(&line) {
+                rdr.header.get_or_insert(header);
+            } else if !line.starts_with('#') && !line.starts_with("//") {
+                rdr.pending = Some(line);
+                break;
+            }
+        }
+        Ok(rdr)
+    }
+
+    /// Sets the number of operands and results per item.
+    pub fn with_arity(mut self, n_operands: usize, n_results: usize) -> Self {
+        self.arity = Some((n_operands, n_results));
+        self
+    }
+
+    /// Returns the header of the corpus, if any.
+    pub fn header(&self) -> Option<&Header> {
+        self.header.as_ref()
+    }
+
+    /// Returns the next non-empty line.
+    fn next_line(&mut self) -> Result<Option<String>> {
+        if let Some(line) = self.pending.take() {
+            return Ok(Some(line));
+        }
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.inp.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            self.line_no += 1;
+            let trimmed = line.trim();
+            if !trimmed.is_empty() {
+                return Ok(Some(trimmed.to_string()));
+            }
+        }
+    }
+
+    /// Reads the next item, returns `None` at the end of the corpus.
+    pub fn read_item(&mut self) -> Result<Option<TestItem>> {
+        loop {
+            let Some(line) = self.next_line()? else {
+                return Ok(None);
+            };
+            let skip = match self.format {
+                Format::Rust => {
+                    line.starts_with("//")
+                        || line.starts_with("pub const")
+                        || line == "];"
+                }
+                _ => line.starts_with('#'),
+            };
+            if !skip {
+                return self.parse_item(&line).map(Some).map_err(|e| {
+                    invalid_data(format!("Line {}: {e}", self.line_no))
+                });
+            }
+        }
+    }
+
+    fn parse_item(
+        &self,
+        line: &str,
+    ) -> std::result::Result<TestItem, String> {
+        let op = self.header.as_ref().map_or("", Header::op).to_string();
+        let cols = match self.format {
+            Format::Tsv => tsv_cols(line),
+            Format::Csv => csv_cols(line),
+            Format::Rust => rust_cols(line)?,
+            _ => match parse_json(line)? {
+                Json::Arr(elems) => json_cols(&elems)?,
+                obj => return json_item(&obj, &op),
+            },
+        };
+        let Some((n_operands, n_results)) = self.arity else {
+            return Err("Number of operands and results unknown".to_string());
+        };
+        // decode tuples are given as four integers except in Rust source
+        let mut cols = cols.into_iter().peekable();
+        let mut values = Vec::with_capacity(n_operands + n_results);
+        for _ in 0..n_operands + n_results {
+            let d = match cols.next() {
+                Some(Col::Fp(d)) => d,
+                Some(Col::Int(s)) => {
+                    let mut ints = vec![s];
+                    for _ in 0..3 {
+                        match cols.next() {
+                            Some(Col::Int(i)) => ints.push(i),
+                            _ => {
+                                return Err(
+                                    "Incomplete decode tuple".to_string()
+                                )
+                            }
+                        }
+                    }
+                    let err = |_| format!("Invalid decode tuple {ints:?}");
+                    DecodedFP {
+                        sign: ints[0].parse().map_err(err)?,
+                        exp: ints[1].parse().map_err(err)?,
+                        signif: U256::new(
+                            ints[2].parse().map_err(err)?,
+                            ints[3].parse().map_err(err)?,
+                        ),
+                    }
+                }
+                _ => return Err("Expected a decode tuple".to_string()),
+            };
+            values.push(checked(d)?);
+        }
+        let mut meta = Record::new();
+        for col in cols {
+            meta = match col {
+                Col::Int(s) => meta.int(s),
+                Col::Token(s) => meta.token(s),
+                Col::Str(s) => meta.str(s),
+                Col::Fp(_) => {
+                    return Err("Unexpected decode tuple".to_string())
+                }
+            };
+        }
+        let expected = values.split_off(n_operands);
+        Ok(TestItem::new(&op, values, expected).with_meta(meta))
+    }
+}
+
+/// Returns the test item given as JSON object, see `TestItem::to_json`.
+fn json_item(obj: &Json, op: &str) -> std::result::Result<TestItem, String> {
+    let op = match obj.get("op") {
+        Some(Json::Str(op)) => op.as_str(),
+        _ => op,
+    };
+    let decoded = |key: &str| -> std::result::Result<Vec<DecodedFP>, String> {
+        let Some(Json::Arr(elems)) = obj.get(key) else {
+            return Err(format!("Missing {key}"));
+        };
+        let invalid = || format!("Invalid decode tuple in {key}");
+        elems
+            .iter()
+            .map(|elem| {
+                let Json::Arr(t) = elem else {
+                    return Err(invalid());
+                };
+                let [Json::Num(s), Json::Num(e), Json::Num(h), Json::Num(l)] =
+                    &t[..]
+                else {
+                    return Err(invalid());
+                };
+                checked(DecodedFP {
+                    sign: s.parse().map_err(|_| invalid())?,
+                    exp: e.parse().map_err(|_| invalid())?,
+                    signif: U256::new(
+                        h.parse().map_err(|_| invalid())?,
+                        l.parse().map_err(|_| invalid())?,
+                    ),
+                })
+            })
+            .collect()
+    };
+    let mut meta = Record::new();
+    if let Some(Json::Arr(elems)) = obj.get("meta") {
+        for col in json_cols(elems)? {
+            meta = match col {
+                Col::Int(s) => meta.int(s),
+                Col::Token(s) => meta.token(s),
+                Col::Str(s) => meta.str(s),
+                Col::Fp(_) => unreachable!(),
+            };
+        }
+    }
+    Ok(
+        TestItem::new(op, decoded("operands")?, decoded("expected")?)
+            .with_meta(meta),
+    )
+}
+
+impl<R: BufRead> Iterator for RecordReader<R> {
+    type Item = Result<TestItem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_item().transpose()
+    }
+}
+
+#[cfg(test)]
+mod reader_tests {
+    use super::*;
+    use crate::{special_values, RecordWriter, MIN_EXP_SUBNORMAL};
+
+    #[test]
+    fn test_round_trip() {
+        let header = Header::new("div").seed(3);
+        let values: Vec<DecodedFP> =
+            special_values().iter().map(DecodedFP::from).collect();
+        let items: Vec<TestItem> = values
+            .chunks_exact(3)
+            .map(|c| {
+                TestItem::new("div", c[..2].to_vec(), c[2..].to_vec())
+                    .with_meta(
+                        Record::new().int(-7).token("0x1f").str("a, b"),
+                    )
+            })
+            .collect();
+        for format in [Format::Tsv, Format::Csv, Format::Jsonl, Format::Rust]
+        {
+            let mut wrt = RecordWriter::new(Vec::<u8>::new(), format);
+            wrt.write_header(&header).unwrap();
+            for item in &items {
+                wrt.write_item(item).unwrap();
+            }
+            let written = wrt.into_inner().unwrap();
+            let rdr = RecordReader::new(&written[..], format)
+                .unwrap()
+                .with_arity(2, 1);
+            assert_eq!(rdr.header(), Some(&header));
+            let read: Vec<TestItem> = rdr.map(Result::unwrap).collect();
+            assert_eq!(read, items, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_tsv_escapes() {
+        let s = "a\tb\nc\\d \"e\"";
+        let line = Record::new().str(s).int(1).to_line(Format::Tsv);
+        let cols = tsv_cols(&line);
+        assert_eq!(cols.len(), 2);
+        assert!(matches!(&cols[0], Col::Str(t) if t == s));
+    }
+
+    #[test]
+    fn test_checked() {
+        let one = DecodedFP::from(&FP237::ONE());
+        assert_eq!(checked(one), Ok(one));
+        let unreduced = DecodedFP::unreduced(&FP237::ONE());
+        assert_eq!(checked(unreduced), Ok(unreduced));
+        let too_long = DecodedFP {
+            sign: 0,
+            exp: 0,
+            signif: U256::MAX,
+        };
+        assert!(checked(too_long).is_err());
+        let too_small = DecodedFP {
+            sign: 0,
+            exp: MIN_EXP_SUBNORMAL - 1,
+            signif: U256::from(1),
+        };
+        assert!(checked(too_small).is_err());
+        assert!(checked(DecodedFP { sign: 2, ..one }).is_err());
+    }
+}
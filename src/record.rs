@@ -0,0 +1,900 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::{binary, Checkpoint, Header, Output, Progress, FP237, U256};
+
+/// Time between two checkpoints.
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Output format of the test data.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Tab-separated values
+    #[default]
+    Tsv,
+    /// Comma-separated values (RFC 4180)
+    Csv,
+    /// One JSON array per line
+    Jsonl,
+    /// Rust source defining a constant `TEST_CASES`, to be `include!`d
+    Rust,
+    /// Fixed-size binary records, see module `binary`
+    Bin,
+}
+
+/// Decimal literals in scientific notation added to test items, see
+/// `RecordWriter::with_literals`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Literals {
+    /// The shortest literal giving the value again, see
+    /// `FP237::to_shortest_string`
+    Shortest,
+    /// The value rounded to the given number of significant digits
+    Digits(usize),
+}
+
+impl Literals {
+    /// Returns `f` as literal.
+    pub fn format(&self, f: &FP237) -> String {
+        match self {
+            Literals::Shortest => f.to_shortest_string(),
+            Literals::Digits(n) => format!("{f:.*e}", n),
+        }
+    }
+}
+
+impl Display for Literals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literals::Shortest => f.write_str("shortest"),
+            Literals::Digits(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+impl FromStr for Literals {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shortest" => Ok(Literals::Shortest),
+            _ => match s.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(Literals::Digits(n)),
+                _ => Err(format!(
+                    "Expected 'shortest' or a number of digits, found '{s}'"
+                )),
+            },
+        }
+    }
+}
+
+/// A single column of a record.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Field {
+    /// Integral number, written as is in all formats.
+    Int(String),
+    /// Bare token like a hex number or a flag, written as JSON string.
+    Token(String),
+    /// String literal, always written quoted.
+    Str(String),
+}
+
+/// One line of test data, i.e. a sequence of fields.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Record(Vec<Field>);
+
+impl Record {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the reduced decode tuple of `f` as four integral fields.
+    pub fn fp(self, f: &FP237) -> Self {
+        self.decoded(f.decode(true))
+    }
+
+    /// Appends the non-reduced decode tuple of `f` as four integral
+    /// fields.
+    pub fn fp_unreduced(self, f: &FP237) -> Self {
+        self.decoded(f.decode(false))
+    }
+
+    /// Appends the non-reduced decode tuple of `f`, with the significand
+    /// given as two hex tokens.
+    pub fn fp_hex(self, f: &FP237) -> Self {
+        let (s, e, (h, l)) = f.decode(false);
+        self.int(s)
+            .int(e)
+            .token(format!("0x{:032x}", h))
+            .token(format!("0x{:032x}", l))
+    }
+
+    fn decoded(self, (s, e, (h, l)): (u32, i32, (u128, u128))) -> Self {
+        self.int(s).int(e).int(h).int(l)
+    }
+
+    /// Appends an integral field.
+    pub fn int(mut self, i: impl Display) -> Self {
+        self.0.push(Field::Int(i.to_string()));
+        self
+    }
+
+    /// Appends a bare token.
+    pub fn token(mut self, t: impl Display) -> Self {
+        self.0.push(Field::Token(t.to_string()));
+        self
+    }
+
+    /// Appends a string literal.
+    pub fn str(mut self, s: impl Display) -> Self {
+        self.0.push(Field::Str(s.to_string()));
+        self
+    }
+
+    pub fn fields(&self) -> &[Field] {
+        &self.0
+    }
+
+    /// Returns `self` formatted as one line (without line break).
+    pub fn to_line(&self, format: Format) -> String {
+        match format {
+            Format::Tsv => self
+                .0
+                .iter()
+                .map(|f| match f {
+                    Field::Int(s) | Field::Token(s) => s.clone(),
+                    Field::Str(s) => tsv_quoted(s),
+                })
+                .collect::<Vec<String>>()
+                .join("\t"),
+            Format::Csv => self
+                .0
+                .iter()
+                .map(|f| match f {
+                    Field::Int(s) => s.clone(),
+                    Field::Token(s)
+                        if !s.contains([',', '"', '\n', '\r']) =>
+                    {
+                        s.clone()
+                    }
+                    Field::Token(s) | Field::Str(s) => csv_quoted(s),
+                })
+                .collect::<Vec<String>>()
+                .join(","),
+            Format::Jsonl => json_array(&self.0),
+            Format::Bin => panic!("Records can't be given in binary format"),
+            Format::Rust => {
+                let cols: Vec<String> =
+                    self.0.iter().map(Field::to_rust).collect();
+                rust_tuple(&cols)
+            }
+        }
+    }
+}
+
+impl Field {
+    /// Returns `self` as Rust literal: a string literal for non-numeric
+    /// tokens and for string literals, the field as is otherwise.
+    fn to_rust(&self) -> String {
+        match self {
+            Field::Int(s) => s.clone(),
+            Field::Token(s) if rust_int(self).is_some() => s.clone(),
+            Field::Token(s) | Field::Str(s) => format!("{s:?}"),
+        }
+    }
+}
+
+/// The decode tuple of a FP237 value: sign, exponent and the significand
+/// as 256-bit integer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DecodedFP {
+    pub sign: u32,
+    pub exp: i32,
+    pub signif: U256,
+}
+
+impl DecodedFP {
+    /// Returns the non-reduced decode tuple of `f`.
+    pub fn unreduced(f: &FP237) -> Self {
+        Self::from(f.decode(false))
+    }
+}
+
+impl From<(u32, i32, (u128, u128))> for DecodedFP {
+    fn from((sign, exp, signif): (u32, i32, (u128, u128))) -> Self {
+        Self {
+            sign,
+            exp,
+            signif: signif.into(),
+        }
+    }
+}
+
+/// Returns the reduced decode tuple of `f`.
+impl From<&FP237> for DecodedFP {
+    fn from(f: &FP237) -> Self {
+        Self::from(f.decode(true))
+    }
+}
+
+/// A test case for operation `op`: the operands, the expected results and
+/// additional data like exception flags, integral operands or results and
+/// literals. All corpora use this column order.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TestItem {
+    pub op: String,
+    pub operands: Vec<DecodedFP>,
+    pub expected: Vec<DecodedFP>,
+    pub meta: Record,
+}
+
+impl TestItem {
+    pub fn new(
+        op: &str,
+        operands: Vec<DecodedFP>,
+        expected: Vec<DecodedFP>,
+    ) -> Self {
+        Self {
+            op: op.to_string(),
+            operands,
+            expected,
+            meta: Record::new(),
+        }
+    }
+
+    /// Returns `self` with `meta` as additional data.
+    pub fn with_meta(mut self, meta: Record) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    /// Returns the columns of `self` (without the operation).
+    pub fn to_record(&self) -> Record {
+        let mut rec = Record::new();
+        for d in self.operands.iter().chain(self.expected.iter()) {
+            rec = rec.decoded((d.sign, d.exp, d.signif.into()));
+        }
+        rec.0.extend(self.meta.0.iter().cloned());
+        rec
+    }
+
+    pub fn to_tsv(&self) -> String {
+        self.to_record().to_line(Format::Tsv)
+    }
+
+    /// Returns `self` as JSON object with keys op, operands, expected and
+    /// meta, each decode tuple given as array.
+    pub fn to_json(&self) -> String {
+        let decoded = |ds: &[DecodedFP]| {
+            let elems: Vec<String> = ds
+                .iter()
+                .map(|d| {
+                    json_array(
+                        &Record::new()
+                            .decoded((d.sign, d.exp, d.signif.into()))
+                            .0,
+                    )
+                })
+                .collect();
+            format!("[{}]", elems.join(","))
+        };
+        format!(
+            "{{\"op\":{},\"operands\":{},\"expected\":{},\"meta\":{}}}",
+            json_quoted(&self.op),
+            decoded(&self.operands),
+            decoded(&self.expected),
+            json_array(&self.meta.0)
+        )
+    }
+}
+
+/// One column of a row of Rust source output.
+#[derive(Clone, Debug)]
+enum RustCol {
+    Fp(DecodedFP),
+    Field(Field),
+}
+
+/// Returns the value of an integral field or a hex token.
+fn rust_int(field: &Field) -> Option<Result<i128, u128>> {
+    let parsed = match field {
+        Field::Int(s) => s.parse::<i128>().map_err(|_| s.parse::<u128>()),
+        Field::Token(s) => {
+            let hex = s.strip_prefix("0x")?;
+            i128::from_str_radix(hex, 16)
+                .map_err(|_| u128::from_str_radix(hex, 16))
+        }
+        Field::Str(_) => return None,
+    };
+    match parsed {
+        Ok(i) => Some(Ok(i)),
+        Err(Ok(u)) => Some(Err(u)),
+        Err(Err(_)) => None,
+    }
+}
+
+/// Returns the smallest integer type holding all `fields`, or `&str` if
+/// there is none.
+fn rust_type(fields: &[&Field]) -> &'static str {
+    let vals: Option<Vec<Result<i128, u128>>> =
+        fields.iter().map(|f| rust_int(f)).collect();
+    let Some(vals) = vals else {
+        return "&str";
+    };
+    let fits = |min: i128, max: i128| {
+        vals.iter()
+            .all(|v| matches!(v, Ok(i) if (min..=max).contains(i)))
+    };
+    if fits(i32::MIN as i128, i32::MAX as i128) {
+        "i32"
+    } else if fits(i64::MIN as i128, i64::MAX as i128) {
+        "i64"
+    } else if fits(i128::MIN, i128::MAX) {
+        "i128"
+    } else if vals.iter().all(|v| !matches!(v, Ok(i) if *i < 0)) {
+        "u128"
+    } else {
+        "&str"
+    }
+}
+
+fn rust_tuple(elems: &[String]) -> String {
+    match elems.len() {
+        1 => format!("({},)", elems[0]),
+        _ => format!("({})", elems.join(", ")),
+    }
+}
+
+/// Returns `rows` as definition of a constant `TEST_CASES`, each row given
+/// as tuple with decode tuples of type `(u32, i32, u128, u128)`.
+fn rust_source(rows: &[Vec<RustCol>]) -> String {
+    let n_cols = rows.first().map_or(0, Vec::len);
+    assert!(
+        rows.iter().all(|row| row.len() == n_cols),
+        "Rows of different length can't be given as Rust source"
+    );
+    let types: Vec<String> = (0..n_cols)
+        .map(|i| {
+            let fields: Option<Vec<&Field>> = rows
+                .iter()
+                .map(|row| match &row[i] {
+                    RustCol::Fp(_) => None,
+                    RustCol::Field(f) => Some(f),
+                })
+                .collect();
+            match fields {
+                Some(fields) => rust_type(&fields).to_string(),
+                None => {
+                    assert!(
+                        rows.iter()
+                            .all(|row| matches!(row[i], RustCol::Fp(_))),
+                        "Column {i} mixes decode tuples and other fields"
+                    );
+                    "(u32, i32, u128, u128)".to_string()
+                }
+            }
+        })
+        .collect();
+    let mut res =
+        format!("pub const TEST_CASES: &[{}] = &[\n", rust_tuple(&types));
+    for row in rows {
+        let cols: Vec<String> = row
+            .iter()
+            .zip(&types)
+            .map(|(col, ty)| match col {
+                RustCol::Fp(d) => format!(
+                    "({}, {}, {}, {})",
+                    d.sign, d.exp, d.signif.hi, d.signif.lo
+                ),
+                RustCol::Field(Field::Int(s) | Field::Token(s))
+                    if ty == "&str" =>
+                {
+                    format!("{s:?}")
+                }
+                RustCol::Field(f) => f.to_rust(),
+            })
+            .collect();
+        res.push_str(&format!("    {},\n", rust_tuple(&cols)));
+    }
+    res.push_str("];\n");
+    res
+}
+
+/// Returns `s` quoted, with backslashes, tabs and line breaks escaped, so
+/// that it stays within its column and line.
+fn tsv_quoted(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        match c {
+            '\\' => res.push_str("\\\\"),
+            '\t' => res.push_str("\\t"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+fn csv_quoted(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+pub(crate) fn json_quoted(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                res.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+fn json_array(fields: &[Field]) -> String {
+    let cols: Vec<String> = fields
+        .iter()
+        .map(|f| match f {
+            Field::Int(s) => s.clone(),
+            Field::Token(s) | Field::Str(s) => json_quoted(s),
+        })
+        .collect();
+    format!("[{}]", cols.join(","))
+}
+
+/// Writes records to `out` in the selected format.
+///
+/// In Rust format the rows are collected and written as a whole by
+/// `into_inner` or `finish`, as the type of the constant depends on all of
+/// them. In binary format the corpus header is written together with the
+/// first item, as it holds the number of operands and results.
+///
+/// With a checkpoint file the state of the output is saved periodically,
+/// so that an interrupted run can be resumed, see `with_checkpoint`.
+pub struct RecordWriter<W: Write> {
+    out: W,
+    format: Format,
+    rows: Vec<Vec<RustCol>>,
+    op: String,
+    info: String,
+    arity: Option<(usize, usize)>,
+    seen: Option<HashSet<Record>>,
+    progress: Option<Progress>,
+    literals: Option<Literals>,
+    state: Checkpoint,
+    skip: u64,
+    checkpoint: Option<(PathBuf, Instant)>,
+}
+
+impl<W: Write> RecordWriter<W> {
+    pub fn new(out: W, format: Format) -> Self {
+        Self {
+            out,
+            format,
+            rows: Vec::new(),
+            op: String::new(),
+            info: String::new(),
+            arity: None,
+            seen: None,
+            progress: None,
+            literals: None,
+            state: Checkpoint::default(),
+            skip: 0,
+            checkpoint: None,
+        }
+    }
+
+    /// Returns `self` reporting the number of records and items written
+    /// to `progress`.
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Returns `self` adding a literal for each operand and result to the
+    /// additional data of the test items written.
+    pub fn with_literals(mut self, literals: Literals) -> Self {
+        self.literals = Some(literals);
+        self
+    }
+
+    /// Returns `self` skipping records and items equal to one written
+    /// before. As decode tuples are unique, this removes duplicate operand
+    /// sets (compared by bit pattern) also for generators not using a
+    /// `CorpusBuilder`.
+    pub fn dedup(mut self) -> Self {
+        self.seen = Some(HashSet::new());
+        self
+    }
+
+    /// Returns `self` saving its state to `path` every few seconds. The
+    /// underlying writer must be positioned after `state.bytes` bytes and
+    /// the first `state.items` items must already have been written to it,
+    /// so these are skipped when written again. To resume an interrupted
+    /// run, the data must be generated exactly as before, i.e. with the
+    /// seed saved in `state`.
+    pub fn with_checkpoint(mut self, path: &Path, state: Checkpoint) -> Self {
+        self.skip = state.items;
+        self.state = state;
+        self.checkpoint = Some((path.to_path_buf(), Instant::now()));
+        self
+    }
+
+    /// Returns true if the next item has already been written before the
+    /// run was resumed and counts it as written. Generators may call this
+    /// to omit evaluating an item which is certain to be written. If
+    /// duplicates are skipped, false is returned, as the item is needed to
+    /// detect later duplicates.
+    pub fn skip_next(&mut self) -> bool {
+        if self.skip == 0 || self.seen.is_some() {
+            return false;
+        }
+        self.skip -= 1;
+        if let Some(progress) = &mut self.progress {
+            progress.inc();
+        }
+        true
+    }
+
+    /// Returns true if `rec` has been written before, either in this run
+    /// or before it was resumed, otherwise counts it as written.
+    fn is_dup(&mut self, rec: &Record) -> bool {
+        if let Some(seen) = &mut self.seen {
+            if !seen.insert(rec.clone()) {
+                return true;
+            }
+        }
+        if let Some(progress) = &mut self.progress {
+            progress.inc();
+        }
+        if self.skip > 0 {
+            self.skip -= 1;
+            return true;
+        }
+        false
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        self.out.write_all(line.as_bytes())?;
+        self.out.write_all(b"\n")?;
+        self.state.bytes += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// Counts an item written as line, saving a checkpoint if due.
+    fn item_written(&mut self) -> std::io::Result<()> {
+        self.state.items += 1;
+        if let Some((path, saved)) = &mut self.checkpoint {
+            if saved.elapsed() >= CHECKPOINT_INTERVAL {
+                self.out.flush()?;
+                self.state.save(path)?;
+                *saved = Instant::now();
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes `header` as one line, see `Header::to_line`. In binary
+    /// format the line is kept as info text of the corpus header.
+    pub fn write_header(&mut self, header: &Header) -> std::io::Result<()> {
+        if self.format == Format::Bin {
+            self.info = header.to_line(Format::Tsv);
+            self.op = header.op().to_string();
+            return Ok(());
+        }
+        self.write_line(&header.to_line(self.format))
+    }
+
+    /// Writes `rec` as one line.
+    pub fn write(&mut self, rec: &Record) -> std::io::Result<()> {
+        if self.format == Format::Bin {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "The binary format holds test items only",
+            ));
+        }
+        if self.is_dup(rec) {
+            return Ok(());
+        }
+        if self.format == Format::Rust {
+            self.rows
+                .push(rec.0.iter().cloned().map(RustCol::Field).collect());
+            return Ok(());
+        }
+        self.write_line(&rec.to_line(self.format))?;
+        self.item_written()
+    }
+
+    /// Writes `item` as one line. In JSON Lines format the item is written
+    /// as object, see `TestItem::to_json`.
+    pub fn write_item(&mut self, item: &TestItem) -> std::io::Result<()> {
+        let mut rec = item.to_record();
+        if self.is_dup(&rec) {
+            return Ok(());
+        }
+        let with_literals;
+        let item = match self.literals {
+            Some(literals) => {
+                let mut meta = item.meta.clone();
+                for d in item.operands.iter().chain(item.expected.iter()) {
+                    let f = FP237::encode(d.sign, d.exp, d.signif.into());
+                    meta = meta.str(literals.format(&f));
+                }
+                with_literals = item.clone().with_meta(meta);
+                rec = with_literals.to_record();
+                &with_literals
+            }
+            None => item,
+        };
+        match self.format {
+            Format::Jsonl => {
+                self.write_line(&item.to_json())?;
+                self.item_written()
+            }
+            Format::Rust => {
+                let row = item
+                    .operands
+                    .iter()
+                    .chain(item.expected.iter())
+                    .map(|d| RustCol::Fp(*d))
+                    .chain(item.meta.0.iter().cloned().map(RustCol::Field))
+                    .collect();
+                self.rows.push(row);
+                Ok(())
+            }
+            Format::Bin => {
+                let (n_operands, n_results) = match self.arity {
+                    Some(arity) => arity,
+                    None => {
+                        let arity =
+                            (item.operands.len(), item.expected.len());
+                        binary::write_header(
+                            &mut self.out,
+                            &item.op,
+                            arity.0,
+                            arity.1,
+                            &self.info,
+                        )?;
+                        self.arity = Some(arity);
+                        arity
+                    }
+                };
+                binary::check_item(item, n_operands, n_results)?;
+                for d in item.operands.iter().chain(item.expected.iter()) {
+                    binary::write_value(&mut self.out, d)?;
+                }
+                Ok(())
+            }
+            _ => {
+                self.write_line(&rec.to_line(self.format))?;
+                self.item_written()
+            }
+        }
+    }
+
+    /// Writes pending rows and returns the underlying writer.
+    pub fn into_inner(mut self) -> std::io::Result<W> {
+        if let Some(progress) = &mut self.progress {
+            progress.finish();
+        }
+        match self.format {
+            Format::Rust => {
+                self.out.write_all(rust_source(&self.rows).as_bytes())?
+            }
+            Format::Bin if self.arity.is_none() => binary::write_header(
+                &mut self.out,
+                &self.op,
+                0,
+                0,
+                &self.info,
+            )?,
+            _ => {}
+        }
+        Ok(self.out)
+    }
+}
+
+impl RecordWriter<Output> {
+    /// Completes the output, see `Output::finish`, and removes the
+    /// checkpoint file, if any.
+    pub fn finish(mut self) -> std::io::Result<()> {
+        let checkpoint = self.checkpoint.take();
+        self.into_inner()?.finish()?;
+        match checkpoint {
+            Some((path, _)) if path.exists() => fs::remove_file(path),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod record_writer_tests {
+    use super::*;
+
+    fn written(format: Format, rec: &Record) -> String {
+        let mut wrt = RecordWriter::new(Vec::<u8>::new(), format);
+        wrt.write(rec).unwrap();
+        String::from_utf8(wrt.into_inner().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_formats() {
+        let rec = Record::new()
+            .fp(&FP237::ONE())
+            .int(-7)
+            .token("0x1f")
+            .str("a \"b\", c");
+        assert_eq!(
+            written(Format::Tsv, &rec),
+            "0\t0\t0\t1\t-7\t0x1f\t\"a \"b\", c\"\n"
+        );
+        assert_eq!(
+            written(Format::Csv, &rec),
+            "0,0,0,1,-7,0x1f,\"a \"\"b\"\", c\"\n"
+        );
+        assert_eq!(
+            written(Format::Jsonl, &rec),
+            "[0,0,0,1,-7,\"0x1f\",\"a \\\"b\\\", c\"]\n"
+        );
+        let rec = Record::new().str("a\tb\nc\\");
+        assert_eq!(written(Format::Tsv, &rec), "\"a\\tb\\nc\\\\\"\n");
+    }
+
+    #[test]
+    fn test_item() {
+        let one = DecodedFP::from(&FP237::ONE());
+        let item = TestItem::new("powi", vec![one], vec![one])
+            .with_meta(Record::new().int(3));
+        assert_eq!(item.to_tsv(), "0\t0\t0\t1\t0\t0\t0\t1\t3");
+        assert_eq!(
+            item.to_json(),
+            "{\"op\":\"powi\",\"operands\":[[0,0,0,1]],\"expected\":[[0,0,0,\
+             1]],\"meta\":[3]}"
+        );
+        let mut wrt = RecordWriter::new(Vec::<u8>::new(), Format::Csv);
+        wrt.write_item(&item).unwrap();
+        assert_eq!(wrt.into_inner().unwrap(), b"0,0,0,1,0,0,0,1,3\n");
+    }
+
+    #[test]
+    fn test_literals() {
+        let item = TestItem::new(
+            "div",
+            vec![(0, 0, (0, 1)).into(), (0, 0, (0, 3)).into()],
+            vec![DecodedFP::from(&(&FP237::ONE() / &FP237::from(3)))],
+        )
+        .with_meta(Record::new().int(0));
+        let written = |literals: &str| {
+            let mut wrt = RecordWriter::new(Vec::<u8>::new(), Format::Tsv)
+                .with_literals(literals.parse().unwrap());
+            wrt.write_item(&item).unwrap();
+            String::from_utf8(wrt.into_inner().unwrap()).unwrap()
+        };
+        assert!(written("shortest").ends_with(&format!(
+            "\t0\t\"1e0\"\t\"3e0\"\t\"3.{}e-1\"\n",
+            "3".repeat(71)
+        )));
+        assert!(
+            written("3").ends_with("\"1.00e0\"\t\"3.00e0\"\t\"3.33e-1\"\n")
+        );
+        assert!("0".parse::<Literals>().is_err());
+    }
+
+    #[test]
+    fn test_dedup() {
+        let one = DecodedFP::from(&FP237::ONE());
+        let neg_one = DecodedFP::from(&-FP237::ONE());
+        let mut wrt =
+            RecordWriter::new(Vec::<u8>::new(), Format::Tsv).dedup();
+        for d in [one, neg_one, one] {
+            wrt.write_item(&TestItem::new("abs", vec![d], vec![one]))
+                .unwrap();
+        }
+        let buf = wrt.into_inner().unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    fn test_resume() {
+        let path = std::env::temp_dir()
+            .join(format!("rug237-{}-writer.ckpt", std::process::id()));
+        let items: Vec<TestItem> = (0..5_u32)
+            .map(|i| {
+                let d = DecodedFP::from(&FP237::from(i));
+                TestItem::new("neg", vec![d], vec![])
+            })
+            .collect();
+        let mut wrt = RecordWriter::new(Vec::<u8>::new(), Format::Tsv);
+        wrt.write_header(&Header::new("neg")).unwrap();
+        for item in &items {
+            wrt.write_item(item).unwrap();
+        }
+        let all = wrt.into_inner().unwrap();
+        // an interrupted run having written the header and two items
+        let n_bytes = all
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b == b'\n')
+            .nth(2)
+            .unwrap()
+            .0 as u64
+            + 1;
+        let state = Checkpoint {
+            items: 2,
+            bytes: n_bytes,
+            seed: None,
+        };
+        let mut wrt =
+            RecordWriter::new(all[..n_bytes as usize].to_vec(), Format::Tsv)
+                .with_checkpoint(&path, state);
+        assert!(wrt.skip_next());
+        wrt.write_item(&items[1]).unwrap();
+        assert!(!wrt.skip_next());
+        for item in &items[2..] {
+            wrt.write_item(item).unwrap();
+        }
+        assert_eq!(wrt.state.bytes, all.len() as u64);
+        assert_eq!(wrt.into_inner().unwrap(), all);
+    }
+
+    #[test]
+    fn test_bin() {
+        let one = DecodedFP::from(&FP237::ONE());
+        let mut wrt = RecordWriter::new(Vec::<u8>::new(), Format::Bin);
+        wrt.write_header(&Header::new("sqrt")).unwrap();
+        wrt.write_item(&TestItem::new("sqrt", vec![one], vec![one]))
+            .unwrap();
+        assert!(wrt.write(&Record::new().int(1)).is_err());
+        let buf = wrt.into_inner().unwrap();
+        let mut rdr = crate::CorpusReader::new(buf.as_slice()).unwrap();
+        assert_eq!(rdr.op(), "sqrt");
+        assert!(rdr.info().starts_with("# version="));
+        let item = rdr.read_item().unwrap().unwrap();
+        assert_eq!((item.operands, item.expected), (vec![one], vec![one]));
+        assert!(rdr.read_item().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rust_source() {
+        let one = DecodedFP::from(&FP237::ONE());
+        let mut wrt = RecordWriter::new(Vec::<u8>::new(), Format::Rust);
+        for (n, flag) in [(3_i64, "-"), (1 << 40, "x")] {
+            let item = TestItem::new("powi", vec![one], vec![])
+                .with_meta(Record::new().int(n).token(flag));
+            wrt.write_item(&item).unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(wrt.into_inner().unwrap()).unwrap(),
+            "pub const TEST_CASES: &[((u32, i32, u128, u128), i64, &str)] = \
+             &[\n    ((0, 0, 0, 1), 3, \"-\"),\n    ((0, 0, 0, 1), \
+             1099511627776, \"x\"),\n];\n"
+        );
+    }
+}
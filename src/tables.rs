@@ -0,0 +1,669 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Emission of constants and tables as Rust source for f256 itself and
+//! for its internal fixed-width float types like `FP255`, or as JSON or
+//! packed binary, see `Target`.
+
+use std::cmp::Ordering;
+
+use rug::{Float, Integer};
+
+use crate::{record::json_quoted, RoundingMode, EMAX, FP237, P, PM1};
+
+/// Size of a single value in the packed binary output.
+pub const PACKED_VALUE_SIZE: usize = 40;
+
+/// Returns the definition of the f256 constant `name` holding `exact`
+/// rounded to nearest, preceded by comments giving the rounded value, its
+/// error in ulps and the ternary value of the rounding.
+///
+/// # Panics
+///
+/// If `exact` is zero or not finite or the rounding error exceeds ½ ulp,
+/// i.e. `exact` has too few bits to be rounded correctly.
+pub fn f256_constant(name: &str, exact: &Float) -> String {
+    assert!(exact.is_normal(), "Can't emit {exact}");
+    let f = FP237::with_val_round(exact, RoundingMode::NearestEven);
+    let ulp = Float::with_val(exact.prec(), Float::i_exp(1, f.ilogb() - PM1));
+    let err = Float::with_val(exact.prec(), &f.f - exact) / ulp;
+    assert!(
+        err.clone().abs() <= 0.5,
+        "Rounding error of {name} exceeds ½ ulp: {err}"
+    );
+    let (ternary, dir) = match f.o {
+        Ordering::Less => (-1, "rounded down"),
+        Ordering::Equal => (0, "exact"),
+        Ordering::Greater => (1, "rounded up"),
+    };
+    format!(
+        "// {}\n// error {:+.4} ulp, ternary {ternary} ({dir})\npub const \
+         {name}: f256 = {};",
+        f.f.to_string_radix(10, Some(72)),
+        err.to_f64(),
+        f256_literal(&f)
+    )
+}
+
+/// Returns `f` as f256 literal, i.e. its bit pattern in f256's struct
+/// syntax.
+pub fn f256_literal(f: &FP237) -> String {
+    let (hi, lo) = f.to_bits();
+    format!("f256 {{ bits: u256::new(0x{hi:032x}, 0x{lo:032x}) }}")
+}
+
+/// Formats values rounded to `prec` bits as literals of type `FP<prec>`
+/// (or the type given by `with_type_name`), i.e. sign, exponent and the
+/// significand as `u256` with the hidden bit being the most significant
+/// one of `prec` bits.
+#[derive(Clone, Debug)]
+pub struct ConstWriter {
+    prec: u32,
+    emax: i32,
+    type_name: Option<String>,
+}
+
+impl ConstWriter {
+    /// # Panics
+    ///
+    /// If the significand of `prec` bits doesn't fit into a `u256`.
+    pub fn new(prec: u32, emax: i32) -> Self {
+        assert!(
+            (2..=256).contains(&prec),
+            "Precision must be in 2..=256, got {prec}"
+        );
+        Self {
+            prec,
+            emax,
+            type_name: None,
+        }
+    }
+
+    /// Sets the name of the type of the literals.
+    pub fn with_type_name(mut self, name: &str) -> Self {
+        self.type_name = Some(name.to_string());
+        self
+    }
+
+    pub fn prec(&self) -> u32 {
+        self.prec
+    }
+
+    /// Returns the name of the type of the literals.
+    pub fn type_name(&self) -> String {
+        match &self.type_name {
+            Some(name) => name.clone(),
+            None => format!("FP{}", self.prec),
+        }
+    }
+
+    /// Returns `f` rounded to nearest as literal. Zero is emitted with
+    /// exponent 0 and an all-zero significand.
+    ///
+    /// # Panics
+    ///
+    /// If `f` is not finite or its exponent is beyond `-emax..=emax`.
+    pub fn literal(&self, f: &Float) -> String {
+        let (sign, exp, (hi, lo)) = self.parts(f);
+        format!(
+            "{} {{ sign: {sign}, exp: {exp}, signif: u256::new(0x{hi:032x}, \
+             0x{lo:032x}) }}",
+            self.type_name()
+        )
+    }
+
+    /// Returns sign, exponent and the high and low half of the significand
+    /// of `f` rounded to nearest, as given by `literal`.
+    ///
+    /// # Panics
+    ///
+    /// If `f` is not finite or its exponent is beyond `-emax..=emax`.
+    pub fn parts(&self, f: &Float) -> (u32, i32, (u128, u128)) {
+        let f = Float::with_val(self.prec, f);
+        if f.is_zero() {
+            return (0, 0, (0, 0));
+        }
+        let Some((m, e)) = f.to_integer_exp() else {
+            panic!("Can't emit {f}");
+        };
+        let exp = e + self.prec as i32 - 1;
+        assert!(
+            exp.abs() <= self.emax,
+            "Exponent {exp} of {f} beyond {}",
+            self.emax
+        );
+        let sign = m.is_negative() as u32;
+        let m = m.abs();
+        debug_assert_eq!(m.significant_bits(), self.prec);
+        let (hi, lo) = m.div_rem(Integer::from(1) << 128);
+        (sign, exp, (hi.to_u128_wrapping(), lo.to_u128_wrapping()))
+    }
+
+    /// Returns the definition of the constant `name` holding `f`, preceded
+    /// by a comment giving its value.
+    pub fn constant(&self, name: &str, f: &Float) -> String {
+        format!(
+            "// {}\npub(crate) const {name}: {} = {};",
+            Float::with_val(self.prec, f),
+            self.type_name(),
+            self.literal(f)
+        )
+    }
+
+    /// Returns the definition of the array `name` holding `values`, each
+    /// preceded by a comment giving its value.
+    pub fn array(&self, name: &str, values: &[Float]) -> String {
+        let values: Vec<(Float, String)> =
+            values.iter().map(|f| (f.clone(), String::new())).collect();
+        self.annotated_array(name, &values)
+    }
+
+    /// Returns the definition of the array `name` holding the first
+    /// components of `values`, each preceded by a comment giving its value
+    /// followed by the second component.
+    pub fn annotated_array(
+        &self,
+        name: &str,
+        values: &[(Float, String)],
+    ) -> String {
+        let mut res = format!(
+            "pub(crate) const {name}: [{}; {}] = [\n",
+            self.type_name(),
+            values.len()
+        );
+        for (f, note) in values {
+            res += &format!(
+                "    // {}{note}\n    {},\n",
+                Float::with_val(self.prec, f),
+                self.literal(f)
+            );
+        }
+        res + "];"
+    }
+}
+
+/// Element of a table holding other values than those of type FP<PREC>.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// f256 value, emitted as f256 literal
+    F256(FP237),
+    /// Integer of the Rust type `ty`, one of u8, u16, u32, u64, u128 and
+    /// i32, emitted in hex with all digits of the type if `hex` is true
+    Int {
+        ty: &'static str,
+        value: Integer,
+        hex: bool,
+    },
+}
+
+impl Value {
+    /// Returns the integer `value` of type `ty` emitted in decimal.
+    pub fn int(ty: &'static str, value: impl Into<Integer>) -> Self {
+        Self::Int {
+            ty,
+            value: value.into(),
+            hex: false,
+        }
+    }
+
+    /// Returns the integer `value` of type `ty` emitted in hex.
+    pub fn hex(ty: &'static str, value: impl Into<Integer>) -> Self {
+        Self::Int {
+            ty,
+            value: value.into(),
+            hex: true,
+        }
+    }
+
+    /// Returns the name of the Rust type.
+    pub fn ty(&self) -> &'static str {
+        match self {
+            Value::F256(_) => "f256",
+            Value::Int { ty, .. } => ty,
+        }
+    }
+
+    /// Returns the number of bits of an integer type.
+    fn int_bits(ty: &str) -> u32 {
+        ty[1..]
+            .parse()
+            .unwrap_or_else(|_| panic!("Unknown integer type {ty}"))
+    }
+
+    /// Returns the value as Rust literal.
+    pub fn to_rust(&self) -> String {
+        match self {
+            Value::F256(f) => f256_literal(f),
+            Value::Int {
+                ty,
+                value,
+                hex: true,
+            } => {
+                let width = Self::int_bits(ty) as usize / 4;
+                format!("0x{value:0width$x}")
+            }
+            Value::Int { value, .. } => value.to_string(),
+        }
+    }
+
+    /// Returns the value as JSON object, an f256 value given like the
+    /// values of type FP<PREC> with PREC = P, an integer as string.
+    fn to_json(&self) -> String {
+        match self {
+            Value::F256(f) => {
+                let (sign, exp, (hi, lo)) = f256_writer().parts(&f.f);
+                format!(
+                    "{{\"type\":\"f256\",\"sign\":{sign},\"exp\":{exp},\"\
+                     signif\":\"0x{hi:032x}{lo:032x}\",\"value\":\"{}\"}}",
+                    f.f
+                )
+            }
+            Value::Int { ty, .. } => format!(
+                "{{\"type\":{},\"value\":{}}}",
+                json_quoted(ty),
+                json_quoted(&self.to_rust())
+            ),
+        }
+    }
+
+    /// Appends the packed value to `bin`, see `Target::Bin`.
+    fn pack(&self, bin: &mut Vec<u8>) {
+        match self {
+            Value::F256(f) => pack(bin, f256_writer().parts(&f.f)),
+            Value::Int { ty, value, .. } => {
+                let n = Self::int_bits(ty) as usize / 8;
+                bin.extend(&value.to_u128_wrapping().to_le_bytes()[..n]);
+            }
+        }
+    }
+}
+
+/// Returns the writer giving the parts of f256 values.
+fn f256_writer() -> ConstWriter {
+    ConstWriter::new(P, EMAX)
+}
+
+/// Appends sign, exponent and significand to `bin`, see `Target::Bin`.
+fn pack(bin: &mut Vec<u8>, (sign, exp, (hi, lo)): (u32, i32, (u128, u128))) {
+    bin.extend(lo.to_le_bytes());
+    bin.extend(hi.to_le_bytes());
+    bin.extend(exp.to_le_bytes());
+    bin.extend(sign.to_le_bytes());
+}
+
+/// Output targets of the tables.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Target {
+    /// Rust source, see `ConstWriter`
+    #[default]
+    Rust,
+    /// A single JSON object holding the comments and the constants and
+    /// arrays, each value given by sign, exponent, significand (hex) and
+    /// its decimal value
+    Json,
+    /// The values of the constants and arrays in the order of their
+    /// definition, without any header, to be `include_bytes!`d: per float
+    /// value the significand as `u256`, the exponent as `i32` and the sign
+    /// as `u32`, all little-endian (the low half of the significand
+    /// first), 40 bytes in total, with f256 values given like values of
+    /// type FP<P>; per integer its little-endian bytes
+    Bin,
+}
+
+/// Collects the comments, constants and arrays of a table and renders
+/// them for a `Target`, so that the values are calculated only once for
+/// all targets.
+#[derive(Clone, Debug)]
+pub struct TableWriter {
+    wrt: ConstWriter,
+    target: Target,
+    rust: Vec<String>,
+    meta: Vec<String>,
+    notes: Vec<String>,
+    items: Vec<String>,
+    bin: Vec<u8>,
+}
+
+impl TableWriter {
+    pub fn new(wrt: ConstWriter, target: Target) -> Self {
+        Self {
+            wrt,
+            target,
+            rust: Vec::new(),
+            meta: Vec::new(),
+            notes: Vec::new(),
+            items: Vec::new(),
+            bin: Vec::new(),
+        }
+    }
+
+    /// Adds the field `key` with string `value` to the JSON object, e.g.
+    /// the parameters the table has been calculated for. Other targets
+    /// ignore it.
+    pub fn meta(&mut self, key: &str, value: &str) {
+        self.meta.push(format!(
+            "{}:{}",
+            json_quoted(key),
+            json_quoted(value)
+        ));
+    }
+
+    /// Adds the comment `text`, which may span several lines. Comments
+    /// are dropped from the binary output.
+    pub fn comment(&mut self, text: &str) {
+        for line in text.lines() {
+            self.rust.push(format!("// {line}"));
+            self.notes.push(json_quoted(line));
+        }
+    }
+
+    /// Returns `f` as JSON object, with `note` if not empty.
+    fn json_value(&self, f: &Float, note: &str) -> String {
+        let (sign, exp, (hi, lo)) = self.wrt.parts(f);
+        let note = match note {
+            "" => String::new(),
+            note => format!(",\"note\":{}", json_quoted(note.trim())),
+        };
+        format!(
+            "{{\"sign\":{sign},\"exp\":{exp},\"signif\":\"0x{hi:032x}{lo:\
+             032x}\",\"value\":\"{}\"{note}}}",
+            Float::with_val(self.wrt.prec(), f)
+        )
+    }
+
+    fn pack(&mut self, f: &Float) {
+        pack(&mut self.bin, self.wrt.parts(f));
+    }
+
+    /// Adds the constant `name` holding `f`, see `ConstWriter::constant`.
+    pub fn constant(&mut self, name: &str, f: &Float) {
+        self.rust.push(self.wrt.constant(name, f));
+        self.items.push(format!(
+            "{{\"name\":{},\"value\":{}}}",
+            json_quoted(name),
+            self.json_value(f, "")
+        ));
+        self.pack(f);
+    }
+
+    /// Adds the array `name` holding `values`, see `ConstWriter::array`.
+    pub fn array(&mut self, name: &str, values: &[Float]) {
+        let values: Vec<(Float, String)> =
+            values.iter().map(|f| (f.clone(), String::new())).collect();
+        self.annotated_array(name, &values);
+    }
+
+    /// Adds the array `name` holding the first components of `values`,
+    /// annotated by the second ones, see `ConstWriter::annotated_array`.
+    pub fn annotated_array(
+        &mut self,
+        name: &str,
+        values: &[(Float, String)],
+    ) {
+        self.rust.push(self.wrt.annotated_array(name, values));
+        let elems: Vec<String> = values
+            .iter()
+            .map(|(f, note)| self.json_value(f, note))
+            .collect();
+        self.items.push(format!(
+            "{{\"name\":{},\"values\":[\n{}\n]}}",
+            json_quoted(name),
+            elems.join(",\n")
+        ));
+        for (f, _) in values {
+            self.pack(f);
+        }
+    }
+
+    /// Adds the f256 constant `name` holding `exact` rounded to nearest,
+    /// see `f256_constant`.
+    pub fn f256_constant(&mut self, name: &str, exact: &Float) {
+        self.rust.push(f256_constant(name, exact));
+        let value = Value::F256(FP237::with_val_round(
+            exact,
+            RoundingMode::NearestEven,
+        ));
+        self.items.push(format!(
+            "{{\"name\":{},\"value\":{}}}",
+            json_quoted(name),
+            value.to_json()
+        ));
+        value.pack(&mut self.bin);
+    }
+
+    /// Adds the constant `name` holding `value`, without comment.
+    pub fn value_constant(&mut self, name: &str, value: &Value) {
+        self.rust.push(format!(
+            "pub(crate) const {name}: {} = {};",
+            value.ty(),
+            value.to_rust()
+        ));
+        self.items.push(format!(
+            "{{\"name\":{},\"value\":{}}}",
+            json_quoted(name),
+            value.to_json()
+        ));
+        value.pack(&mut self.bin);
+    }
+
+    /// Adds the array `name` holding the first components of `rows`, each
+    /// a single value or a tuple of values of the same types in all rows,
+    /// preceded by a comment giving the second component.
+    pub fn value_array(&mut self, name: &str, rows: &[(Vec<Value>, String)]) {
+        let tuple = |values: Vec<String>| match values.len() {
+            1 => values[0].clone(),
+            _ => format!("({})", values.join(", ")),
+        };
+        let ty = rows.first().map_or(String::new(), |(values, _)| {
+            tuple(values.iter().map(|v| v.ty().to_string()).collect())
+        });
+        let mut res =
+            format!("pub(crate) const {name}: [{ty}; {}] = [\n", rows.len());
+        let mut elems = Vec::with_capacity(rows.len());
+        for (values, note) in rows {
+            res += &format!(
+                "    // {note}\n    {},\n",
+                tuple(values.iter().map(Value::to_rust).collect())
+            );
+            let values: Vec<String> =
+                values.iter().map(Value::to_json).collect();
+            elems.push(format!(
+                "{{\"elems\":[{}],\"note\":{}}}",
+                values.join(","),
+                json_quoted(note)
+            ));
+        }
+        self.rust.push(res + "];");
+        self.items.push(format!(
+            "{{\"name\":{},\"values\":[\n{}\n]}}",
+            json_quoted(name),
+            elems.join(",\n")
+        ));
+        for (values, _) in rows {
+            for value in values {
+                value.pack(&mut self.bin);
+            }
+        }
+    }
+
+    /// Returns the output for the selected target.
+    pub fn finish(self) -> Vec<u8> {
+        match self.target {
+            Target::Rust => self
+                .rust
+                .iter()
+                .flat_map(|s| [s.as_bytes(), b"\n"].concat())
+                .collect(),
+            Target::Json => {
+                let head = format!(
+                    "\"type\":{},\"prec\":{},{}\"notes\":[{}]",
+                    json_quoted(&self.wrt.type_name()),
+                    self.wrt.prec(),
+                    self.meta
+                        .iter()
+                        .map(|m| m.clone() + ",")
+                        .collect::<String>(),
+                    self.notes.join(",")
+                );
+                let items = self.items.join(",\n");
+                format!("{{{head},\"items\":[\n{items}\n]}}\n").into_bytes()
+            }
+            Target::Bin => self.bin,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tables_tests {
+    use super::*;
+    use crate::EMAX;
+
+    #[test]
+    fn test_literal() {
+        let wrt = ConstWriter::new(255, EMAX);
+        assert_eq!(wrt.type_name(), "FP255");
+        assert_eq!(
+            wrt.literal(&Float::with_val(300, -3)),
+            format!(
+                "FP255 {{ sign: 1, exp: 1, signif: u256::new(0x{:032x}, \
+                 0x{:032x}) }}",
+                3_u128 << 125,
+                0
+            )
+        );
+        let half = Float::with_val(8, 0.5);
+        assert!(wrt.literal(&half).contains("sign: 0, exp: -1,"));
+        assert!(wrt
+            .literal(&Float::new(8))
+            .contains("exp: 0, signif: u256::new(0x0000"));
+        let wrt = ConstWriter::new(64, 16383).with_type_name("F64x");
+        assert_eq!(wrt.type_name(), "F64x");
+        let wrt = ConstWriter::new(64, 16383);
+        assert_eq!(
+            wrt.literal(&Float::with_val(64, 1)),
+            format!(
+                "FP64 {{ sign: 0, exp: 0, signif: u256::new(0x{:032x}, \
+                 0x{:032x}) }}",
+                0,
+                1_u128 << 63
+            )
+        );
+    }
+
+    #[test]
+    fn test_f256_constant() {
+        let exact = Float::with_val(4 * crate::P, 1.5);
+        let (hi, lo) =
+            FP237::with_val_round(&exact, RoundingMode::NearestEven)
+                .to_bits();
+        assert_eq!(
+            f256_constant("THREE_HALVES", &exact),
+            format!(
+                "// 1.5{}\n// error +0.0000 ulp, ternary 0 (exact)\npub \
+                 const THREE_HALVES: f256 = f256 {{ bits: \
+                 u256::new(0x{hi:032x}, 0x{lo:032x}) }};",
+                "0".repeat(70)
+            )
+        );
+        let third = Float::with_val(4 * crate::P, 1) / 3;
+        let def = f256_constant("FRAC_1_3", &third);
+        assert!(def.contains("ulp, ternary -1 (rounded down)"), "{def}");
+    }
+
+    #[test]
+    fn test_table_writer() {
+        let wrt = ConstWriter::new(64, 16383);
+        let one = Float::with_val(64, 1);
+        let half = Float::with_val(64, 0.5);
+        let targets = [Target::Rust, Target::Json, Target::Bin];
+        let [rust, json, bin] = targets.map(|target| {
+            let mut tw = TableWriter::new(wrt.clone(), target);
+            tw.comment("two\nlines");
+            tw.meta("func", "exp");
+            tw.constant("ONE", &one);
+            tw.annotated_array("A", &[(-half.clone(), " (x^1)".into())]);
+            tw.finish()
+        });
+        let rust = String::from_utf8(rust).unwrap();
+        assert!(rust.starts_with("// two\n// lines\n// 1.0"), "{rust}");
+        assert!(
+            rust.ends_with(
+                "(x^1)\n    FP64 { sign: 1, exp: -1, signif: \
+                 u256::new(0x00000000000000000000000000000000, \
+                 0x00000000000000008000000000000000) },\n];\n"
+            ),
+            "{rust}"
+        );
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.starts_with(
+            "{\"type\":\"FP64\",\"prec\":64,\"func\":\"exp\",\"notes\":[\"\
+             two\",\"lines\"],"
+        ));
+        assert!(json.contains(
+            "{\"name\":\"A\",\"values\":[\n{\"sign\":1,\"exp\":-1,"
+        ));
+        assert!(json.contains("\"note\":\"(x^1)\"}"));
+        assert_eq!(bin.len(), 2 * PACKED_VALUE_SIZE);
+        assert_eq!(bin[7], 0x80);
+        assert_eq!(bin[32..40], [0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(bin[72..80], [0xff, 0xff, 0xff, 0xff, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_table_writer_values() {
+        let wrt = ConstWriter::new(64, 16383);
+        let one = FP237::with_val_round(
+            &Float::with_val(P, 1),
+            RoundingMode::NearestEven,
+        );
+        let targets = [Target::Rust, Target::Json, Target::Bin];
+        let [rust, json, bin] = targets.map(|target| {
+            let mut tw = TableWriter::new(wrt.clone(), target);
+            tw.value_constant("BITS", &Value::int("u32", 19));
+            tw.value_array(
+                "T",
+                &[(
+                    vec![Value::hex("u16", 0xab), Value::F256(one.clone())],
+                    "0: one".into(),
+                )],
+            );
+            tw.finish()
+        });
+        let rust = String::from_utf8(rust).unwrap();
+        assert_eq!(
+            rust,
+            format!(
+                "pub(crate) const BITS: u32 = 19;\npub(crate) const T: \
+                 [(u16, f256); 1] = [\n    // 0: one\n    (0x00ab, \
+                 {}),\n];\n",
+                f256_literal(&one)
+            )
+        );
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains(
+            "{\"name\":\"BITS\",\"value\":{\"type\":\"u32\",\"value\":\"19\"\
+             }}"
+        ));
+        let elems = "{\"elems\":[{\"type\":\"u16\",\"value\":\"0x00ab\"},";
+        assert!(json.contains(
+            &(elems.to_owned() + "{\"type\":\"f256\",\"sign\":0,\"exp\":0,")
+        ));
+        assert_eq!(bin.len(), 4 + 2 + PACKED_VALUE_SIZE);
+        assert_eq!(bin[..6], [19, 0, 0, 0, 0xab, 0]);
+        // the hidden bit of the f256 value is bit 236 = 8·29 + 4
+        assert_eq!(bin[6 + 29], 0x10);
+    }
+
+    #[test]
+    #[should_panic(expected = "beyond 16383")]
+    fn test_emax() {
+        let wrt = ConstWriter::new(64, 16383);
+        wrt.literal(&Float::with_val(64, Float::i_exp(1, 16384)));
+    }
+}
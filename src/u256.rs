@@ -0,0 +1,213 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::{
+    fmt::{self, Display, Formatter, LowerHex, UpperHex},
+    ops::{Add, Shl, Shr, Sub},
+};
+
+use rug::Integer;
+
+/// Unsigned 256-bit integer, split into its high and low 128 bits like the
+/// significands of f256. Comparisons follow the numeric order, addition and
+/// subtraction wrap around modulo 2²⁵⁶.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct U256 {
+    pub hi: u128,
+    pub lo: u128,
+}
+
+impl U256 {
+    pub const ZERO: Self = Self::new(0, 0);
+    pub const MAX: Self = Self::new(u128::MAX, u128::MAX);
+
+    pub const fn new(hi: u128, lo: u128) -> Self {
+        Self { hi, lo }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.hi == 0 && self.lo == 0
+    }
+
+    pub fn leading_zeros(&self) -> u32 {
+        if self.hi == 0 {
+            128 + self.lo.leading_zeros()
+        } else {
+            self.hi.leading_zeros()
+        }
+    }
+
+    pub fn trailing_zeros(&self) -> u32 {
+        if self.lo == 0 {
+            128 + self.hi.trailing_zeros()
+        } else {
+            self.lo.trailing_zeros()
+        }
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(lo: u128) -> Self {
+        Self::new(0, lo)
+    }
+}
+
+impl From<(u128, u128)> for U256 {
+    fn from((hi, lo): (u128, u128)) -> Self {
+        Self::new(hi, lo)
+    }
+}
+
+impl From<U256> for (u128, u128) {
+    fn from(u: U256) -> Self {
+        (u.hi, u.lo)
+    }
+}
+
+impl From<U256> for Integer {
+    fn from(u: U256) -> Self {
+        (Integer::from(u.hi) << 128) + u.lo
+    }
+}
+
+impl TryFrom<&Integer> for U256 {
+    type Error = String;
+
+    fn try_from(i: &Integer) -> Result<Self, Self::Error> {
+        if *i < 0 || i.significant_bits() > 256 {
+            return Err(format!("{i} doesn't fit into 256 bits"));
+        }
+        let lo = i.to_u128_wrapping();
+        let hi = Integer::from(i >> 128).to_u128().unwrap();
+        Ok(Self::new(hi, lo))
+    }
+}
+
+impl Add for U256 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let (lo, carry) = self.lo.overflowing_add(rhs.lo);
+        let hi = self.hi.wrapping_add(rhs.hi).wrapping_add(carry as u128);
+        Self::new(hi, lo)
+    }
+}
+
+impl Sub for U256 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let (lo, borrow) = self.lo.overflowing_sub(rhs.lo);
+        let hi = self.hi.wrapping_sub(rhs.hi).wrapping_sub(borrow as u128);
+        Self::new(hi, lo)
+    }
+}
+
+impl Shl<u32> for U256 {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// If `n` >= 256.
+    fn shl(self, n: u32) -> Self::Output {
+        assert!(n < 256, "Shift by {n} bits overflows");
+        match n {
+            0 => self,
+            1..=127 => {
+                Self::new(self.hi << n | self.lo >> (128 - n), self.lo << n)
+            }
+            _ => Self::new(self.lo << (n - 128), 0),
+        }
+    }
+}
+
+impl Shr<u32> for U256 {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// If `n` >= 256.
+    fn shr(self, n: u32) -> Self::Output {
+        assert!(n < 256, "Shift by {n} bits overflows");
+        match n {
+            0 => self,
+            1..=127 => {
+                Self::new(self.hi >> n, self.lo >> n | self.hi << (128 - n))
+            }
+            _ => Self::new(0, self.hi >> (n - 128)),
+        }
+    }
+}
+
+impl Display for U256 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &Integer::from(*self).to_string())
+    }
+}
+
+impl LowerHex for U256 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = if self.hi == 0 {
+            format!("{:x}", self.lo)
+        } else {
+            format!("{:x}{:032x}", self.hi, self.lo)
+        };
+        f.pad_integral(true, "0x", &s)
+    }
+}
+
+impl UpperHex for U256 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = if self.hi == 0 {
+            format!("{:X}", self.lo)
+        } else {
+            format!("{:X}{:032X}", self.hi, self.lo)
+        };
+        f.pad_integral(true, "0x", &s)
+    }
+}
+
+#[cfg(test)]
+mod u256_tests {
+    use super::*;
+
+    #[test]
+    fn test_arith() {
+        let one = U256::from(1);
+        let x = U256::new(1, u128::MAX);
+        assert_eq!(x + one, U256::new(2, 0));
+        assert_eq!(U256::new(2, 0) - one, x);
+        assert_eq!(U256::MAX + one, U256::ZERO);
+        assert_eq!(U256::ZERO - one, U256::MAX);
+        assert!(U256::new(1, 0) > U256::new(0, u128::MAX));
+        assert_eq!(one << 200, U256::new(1 << 72, 0));
+        assert_eq!((one << 200) >> 199, U256::from(2));
+        assert_eq!(x << 4, U256::new(0x1f, u128::MAX - 0xf));
+        assert_eq!(x >> 4, U256::new(0, u128::MAX >> 3));
+        assert_eq!((one << 255).leading_zeros(), 0);
+        assert_eq!(one.leading_zeros(), 255);
+        assert_eq!((one << 130).trailing_zeros(), 130);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let x = U256::new(0xab, 0xcd);
+        let i = Integer::from(x);
+        assert_eq!(i, (Integer::from(0xab) << 128) + 0xcd);
+        assert_eq!(U256::try_from(&i), Ok(x));
+        assert!(U256::try_from(&Integer::from(-1)).is_err());
+        assert!(U256::try_from(&(Integer::from(1) << 256)).is_err());
+        assert_eq!(<(u128, u128)>::from(x), (0xab, 0xcd));
+        assert_eq!(format!("{x:x}"), format!("ab{:032x}", 0xcd));
+        assert_eq!(format!("{:#X}", U256::from(255)), "0xFF");
+        assert_eq!(format!("{:>5}", U256::from(42)), "   42");
+    }
+}
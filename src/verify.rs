@@ -0,0 +1,177 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+//! Cross-check of the oracle: results computed at P bits are compared to
+//! results recomputed at 2P bits and then rounded to f256. Recomputing
+//! rounds to odd, so that the second rounding is exact in all rounding
+//! modes and any mismatch is an error of the oracle.
+
+use std::cmp::Ordering;
+
+use rug::{float::Round, ops::AssignRound, Float};
+
+use crate::{DecodedFP, FPDyn, RoundingMode, FP237, P};
+
+/// Precision the results are recomputed at.
+pub(crate) const VERIFY_PREC: u32 = 2 * P;
+
+/// Returns true if the results of `op` can be recomputed by `check`.
+pub(crate) fn is_supported(op: &str) -> bool {
+    matches!(
+        op,
+        "add"
+            | "sub"
+            | "mul"
+            | "div"
+            | "sqrt"
+            | "cbrt"
+            | "fma"
+            | "fms_fnma_fnms"
+            | "hypot"
+            | "sos"
+            | "dot"
+            | "ln"
+            | "log2"
+            | "log10"
+            | "ln_1p"
+            | "gamma"
+            | "ln_gamma"
+    )
+}
+
+/// Returns the results of `op` applied to `ops`, rounded to odd at `prec`
+/// bits, with unbounded exponent range.
+pub(crate) fn reference(op: &str, ops: &[FP237], prec: u32) -> Vec<FPDyn> {
+    let x = &ops[0].f;
+    match op {
+        "add" => vec![FPDyn::with_val_odd(prec, x + &ops[1].f)],
+        "sub" => vec![FPDyn::with_val_odd(prec, x - &ops[1].f)],
+        "mul" => vec![FPDyn::with_val_odd(prec, x * &ops[1].f)],
+        "div" => vec![FPDyn::with_val_odd(prec, x / &ops[1].f)],
+        "sqrt" => vec![FPDyn::with_val_odd(prec, x.sqrt_ref())],
+        "cbrt" => vec![FPDyn::with_val_odd(prec, x.cbrt_ref())],
+        "fma" => vec![FPDyn::with_val_odd(prec, x * &ops[1].f + &ops[2].f)],
+        "fms_fnma_fnms" => {
+            let (y, a) = (&ops[1].f, &ops[2].f);
+            // the product of two values of P bits is exact
+            let xy = Float::with_val(2 * P, x * y);
+            let FPDyn { f, o } = FPDyn::with_val_odd(prec, &xy + a);
+            vec![
+                FPDyn::with_val_odd(prec, &xy - a),
+                FPDyn::with_val_odd(prec, a - &xy),
+                FPDyn {
+                    f: -f,
+                    o: o.reverse(),
+                },
+            ]
+        }
+        "hypot" => vec![FPDyn::with_val_odd(prec, x.hypot_ref(&ops[1].f))],
+        "sos" => {
+            let y = &ops[1].f;
+            vec![FPDyn::with_val_odd(prec, x * x + y * y)]
+        }
+        "dot" => {
+            let values = ops.chunks(2).map(|xy| (&xy[0].f, &xy[1].f));
+            vec![FPDyn::with_val_odd(prec, Float::dot(values))]
+        }
+        "ln" => vec![FPDyn::with_val_odd(prec, x.ln_ref())],
+        "log2" => vec![FPDyn::with_val_odd(prec, x.log2_ref())],
+        "log10" => vec![FPDyn::with_val_odd(prec, x.log10_ref())],
+        "ln_1p" => vec![FPDyn::with_val_odd(prec, x.ln_1p_ref())],
+        "gamma" => vec![FPDyn::with_val_odd(prec, x.gamma_ref())],
+        "ln_gamma" => {
+            let mut f = Float::new(prec);
+            let mut sign = Ordering::Equal;
+            let o = (&mut f, &mut sign)
+                .assign_round(x.ln_abs_gamma_ref(), Round::Zero);
+            vec![FPDyn { f, o }.to_odd()]
+        }
+        _ => panic!("Results of {op} can't be verified"),
+    }
+}
+
+/// Checks `results` of `op` applied to `ops` against the results
+/// recomputed at `VERIFY_PREC` bits and rounded to f256 by the modes in
+/// `rnd`, one result per mode for each value of `op`.
+pub(crate) fn check(
+    op: &str,
+    ops: &[FP237],
+    results: &[FP237],
+    rnd: &[RoundingMode],
+) -> Result<(), String> {
+    let values = reference(op, ops, VERIFY_PREC);
+    assert_eq!(
+        values.len() * rnd.len(),
+        results.len(),
+        "Unexpected number of results of {op}"
+    );
+    for (i, res) in results.iter().enumerate() {
+        let (v, m) = (&values[i / rnd.len()], rnd[i % rnd.len()]);
+        let expected = v.to_fp237(m);
+        // compare bit patterns, as values rounded to subnormals may decode
+        // to different tuples
+        if res.to_bits() != expected.to_bits() {
+            let operands: Vec<DecodedFP> =
+                ops.iter().map(DecodedFP::from).collect();
+            return Err(format!(
+                "Result {i} of {op}{operands:?} is {:?}, but {:?} at \
+                 {VERIFY_PREC} bits",
+                DecodedFP::from(res),
+                DecodedFP::from(&expected)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Returns for each value of `op` applied to `ops` whether it is exactly
+/// representable as f256, i.e. whether rounding it is exact.
+pub(crate) fn exactness(op: &str, ops: &[FP237]) -> Vec<bool> {
+    reference(op, ops, VERIFY_PREC)
+        .iter()
+        .map(|v| {
+            // a value rounded to odd is exact iff it is representable
+            v.to_fp237(RoundingMode::TowardZero).to_bits()
+                == v.to_fp237(RoundingMode::Up).to_bits()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod verify_tests {
+    use super::*;
+    use crate::ROUNDING_MODES;
+
+    #[test]
+    fn test_check() {
+        let x = [FP237::from(2)];
+        let z = [x[0].sqrt()];
+        let ne = &ROUNDING_MODES[..1];
+        assert!(check("sqrt", &x, &z, ne).is_ok());
+        let results: Vec<FP237> = ROUNDING_MODES
+            .iter()
+            .map(|rnd| x[0].sqrt_round(*rnd))
+            .collect();
+        assert!(check("sqrt", &x, &results, &ROUNDING_MODES).is_ok());
+        let err = check("sqrt", &x, &[z[0].next_up()], ne).unwrap_err();
+        assert!(err.contains("at 474 bits"));
+    }
+
+    #[test]
+    fn test_exactness() {
+        let x = [FP237::from(2)];
+        assert_eq!(exactness("sqrt", &x), [false]);
+        assert_eq!(exactness("sqrt", &[FP237::from(4)]), [true]);
+        let ops = [FP237::ONE(), FP237::from(3)];
+        assert_eq!(exactness("div", &ops), [false]);
+        assert_eq!(exactness("add", &ops), [true]);
+        let tiny = [FP237::MIN_GT_ZERO(), FP237::from(2)];
+        assert_eq!(exactness("div", &tiny), [false]);
+    }
+}
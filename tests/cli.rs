@@ -0,0 +1,23 @@
+// ---------------------------------------------------------------------------
+// Copyright:   (c) 2022 ff. Michael Amrhein (michael@adrhinum.de)
+// License:     This program is part of a larger application. For license
+//              details please read the file LICENSE.TXT provided together
+//              with the application.
+// ---------------------------------------------------------------------------
+// $Source$
+// $Revision$
+
+use std::process::Command;
+
+#[test]
+fn test_bin_format_with_meta_columns() {
+    let out = Command::new(env!("CARGO_BIN_EXE_rug237"))
+        .args(["powi", "-n", "3", "--format", "bin", "--quiet"])
+        .output()
+        .unwrap();
+    // clap's exit code for usage errors, a panic exits with 101
+    assert_eq!(out.status.code(), Some(2));
+    assert!(out.stdout.is_empty());
+    let err = String::from_utf8(out.stderr).unwrap();
+    assert!(err.contains("binary format"), "{err}");
+}